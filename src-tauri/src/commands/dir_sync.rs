@@ -0,0 +1,52 @@
+//! 目录同步命令
+//!
+//! 先通过 sftp_sync_plan 生成同步计划供用户确认，再通过 sftp_sync_apply 执行
+
+use crate::commands::sftp::SftpManagerState;
+use crate::error::Result;
+use crate::sftp::dir_sync::{self, SyncOptions, SyncPlan, SyncResult};
+use tauri::{Manager, State};
+
+/// 比较本地和远程目录，生成同步计划
+///
+/// # 参数
+/// - `connection_id`: SSH 连接 ID
+/// - `options`: 同步方向、两端目录、比较方式和过滤规则
+/// - `task_id`: 任务 ID，扫描期间可通过 sftp_sync_cancel 取消
+#[tauri::command]
+pub async fn sftp_sync_plan(
+    manager: State<'_, SftpManagerState>,
+    connection_id: String,
+    options: SyncOptions,
+    task_id: String,
+) -> Result<SyncPlan> {
+    tracing::info!("Building sync plan for task {} on connection {}", task_id, connection_id);
+    dir_sync::plan_task(manager.inner(), &connection_id, &task_id, &options).await
+}
+
+/// 执行同步计划
+///
+/// 进度通过 `sftp-sync-progress` 事件发送
+///
+/// # 参数
+/// - `connection_id`: SSH 连接 ID
+/// - `plan`: sftp_sync_plan 返回的同步计划
+/// - `task_id`: 任务 ID
+#[tauri::command]
+pub async fn sftp_sync_apply(
+    manager: State<'_, SftpManagerState>,
+    connection_id: String,
+    plan: SyncPlan,
+    task_id: String,
+    window: tauri::Window,
+) -> Result<SyncResult> {
+    tracing::info!("Applying sync plan for task {} on connection {}", task_id, connection_id);
+    dir_sync::apply_task(manager.inner(), window.app_handle(), &connection_id, &task_id, &plan).await
+}
+
+/// 取消正在扫描或执行的同步任务
+#[tauri::command]
+pub async fn sftp_sync_cancel(manager: State<'_, SftpManagerState>, task_id: String) -> Result<()> {
+    tracing::info!("Cancelling sync task {}", task_id);
+    manager.cancel_task(&task_id).await
+}
@@ -12,7 +12,10 @@ pub mod sync;
 pub mod user_profile;
 pub mod app_settings;
 pub mod ssh_session;
+pub mod ssh_key;
 pub mod records;
+pub mod transfer_queue;
+pub mod dir_sync;
 
 pub use session::*;
 pub use terminal::*;
@@ -27,7 +30,10 @@ pub use sync::*;
 pub use user_profile::*;
 pub use app_settings::*;
 pub use ssh_session::*;
+pub use ssh_key::*;
 pub use records::*;
+pub use transfer_queue::*;
+pub use dir_sync::*;
 
 // 导出 AI 配置相关的类型（用于 Tauri 命令序列化）
 #[allow(unused_imports)]
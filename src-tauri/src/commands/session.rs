@@ -1,9 +1,12 @@
 use crate::error::Result;
 use crate::ssh::manager::SSHManager;
-use crate::ssh::session::{SessionConfig, SessionConfigUpdate, AuthMethod};
+use crate::ssh::session::{SessionConfig, SessionConfigUpdate, AuthMethod, LocalShellConfig, SerialConfig, SerialPortEntry};
+use crate::ssh::forwarding::{PortForwardRule, PortForwardStatus};
+use crate::ssh::exec::{ExecRequest, ExecResult};
+use crate::ssh::screen::OutputReplay;
 use crate::database::repositories::{SshSessionRepository, UserAuthRepository};
 use crate::services::CryptoService;
-use crate::models::ssh_session::AuthMethod as DbAuthMethod;
+use crate::models::ssh_session::{AuthMethod as DbAuthMethod, SshSession};
 use std::sync::Arc;
 use tauri::State;
 
@@ -35,10 +38,23 @@ fn convert_db_auth_method(db_auth: &DbAuthMethod) -> AuthMethod {
             private_key_path,
             passphrase,
             key_data: _,
+            certificate_path,
         } => {
             AuthMethod::PublicKey {
                 private_key_path: private_key_path.clone(),
                 passphrase: passphrase.clone(),
+                certificate_path: certificate_path.clone(),
+            }
+        }
+        DbAuthMethod::Agent { identity } => {
+            AuthMethod::Agent {
+                identity: identity.clone(),
+            }
+        }
+        DbAuthMethod::ManagedKey { key_id, passphrase } => {
+            AuthMethod::ManagedKey {
+                key_id: key_id.clone(),
+                passphrase: passphrase.clone(),
             }
         }
     }
@@ -57,19 +73,26 @@ async fn load_session_from_db(
         Err(e) => return Err(format!("Failed to find session: {}", e)),
     };
 
-    // 解密认证信息
     let (_, device_id) = get_current_user_info(pool)?;
+    session_config_from_db(session, &device_id).map(Some)
+}
+
+/// 将数据库中的会话转换为会话配置（使用 device_id 解密认证信息）
+pub(crate) fn session_config_from_db(
+    session: SshSession,
+    device_id: &str,
+) -> std::result::Result<SessionConfig, String> {
     let auth_method_json = CryptoService::decrypt_password(
         &session.auth_method_encrypted,
         &session.auth_nonce,
-        &device_id,
+        device_id,
     )
         .map_err(|e| format!("Failed to decrypt auth method: {}", e))?;
 
     let db_auth_method: DbAuthMethod = serde_json::from_str(&auth_method_json)
         .map_err(|e| format!("Failed to parse auth method: {}", e))?;
 
-    Ok(Some(SessionConfig {
+    Ok(SessionConfig {
         name: session.name,
         host: session.host,
         port: session.port,
@@ -81,7 +104,39 @@ async fn load_session_from_db(
         rows: session.rows,
         strict_host_key_checking: true,
         keep_alive_interval: 30,
-    }))
+        jump_hosts: session.jump_hosts,
+        port_forwards: session.port_forwards,
+        reconnect: session.reconnect,
+        connection_type: session.connection_type,
+        persistent_shell: session.persistent_shell,
+    })
+}
+
+/// 确保会话配置在内存中，不在时从数据库加载
+async fn ensure_session_config_loaded(
+    manager: &SSHManager,
+    pool: &crate::database::DbPool,
+    session_id: &str,
+) -> Result<()> {
+    if manager.get_session_config(session_id).await.is_ok() {
+        println!("[session_connect] ✅ Session config found in memory");
+        return Ok(());
+    }
+
+    // 不在内存中，尝试从数据库加载
+    println!("[session_connect] ⚠️  Session config not in memory, loading from database: {}", session_id);
+
+    match load_session_from_db(pool, session_id).await {
+        Ok(Some(config)) => {
+            println!("[session_connect] ✅ Loaded from database: {}", config.name);
+            // 创建内存会话配置
+            manager.create_session_with_id(Some(session_id.to_string()), config).await?;
+            Ok(())
+        }
+        // 会话在数据库中也不存在
+        Ok(None) => Err(crate::error::SSHError::SessionNotFound(session_id.to_string())),
+        Err(e) => Err(crate::error::SSHError::Storage(format!("Failed to load session from database: {}", e))),
+    }
 }
 
 /// 创建会话配置
@@ -113,6 +168,50 @@ pub async fn session_create_temp(
     manager.create_temporary_connection(config).await
 }
 
+/// 创建本地终端连接
+///
+/// 返回连接实例ID，随后通过 session_connect 启动 shell
+#[tauri::command]
+pub async fn session_create_local(
+    manager: State<'_, SSHManagerState>,
+    name: Option<String>,
+    shell: LocalShellConfig,
+) -> Result<String> {
+    let name = name.unwrap_or_else(|| {
+        shell.shell.as_deref()
+            .and_then(|program| std::path::Path::new(program).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Local".to_string())
+    });
+    manager.create_temporary_connection(SessionConfig::local(name, shell)).await
+}
+
+/// 创建串口控制台连接
+///
+/// 返回连接实例ID，随后通过 session_connect 打开串口
+#[tauri::command]
+pub async fn session_create_serial(
+    manager: State<'_, SSHManagerState>,
+    name: Option<String>,
+    serial: SerialConfig,
+) -> Result<String> {
+    let name = name.unwrap_or_else(|| serial.path.clone());
+    manager.create_temporary_connection(SessionConfig::serial(name, serial)).await
+}
+
+/// 列出可用的串口设备
+#[tauri::command]
+pub async fn session_serial_ports() -> Result<Vec<SerialPortEntry>> {
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::ssh::backends::serial::available_ports()
+    }
+    #[cfg(target_os = "android")]
+    {
+        Err(crate::error::SSHError::NotSupported("串口".to_string()))
+    }
+}
+
 /// 连接会话
 #[tauri::command]
 pub async fn session_connect(
@@ -121,39 +220,74 @@ pub async fn session_connect(
     session_id: String,
 ) -> Result<String> {
     // 检查是否是已存在的连接实例
-    let is_connection = manager.get_connection(&session_id).await.is_ok();
-
-    if !is_connection {
-        // 不是连接实例，检查会话配置是否在内存中
-        let session_exists = manager.get_session_config(&session_id).await.is_ok();
-
-        if !session_exists {
-            // 不在内存中，尝试从数据库加载
-            println!("[session_connect] ⚠️  Session config not in memory, loading from database: {}", session_id);
-
-            match load_session_from_db(&pool, &session_id).await {
-                Ok(Some(config)) => {
-                    println!("[session_connect] ✅ Loaded from database: {}", config.name);
-                    // 创建内存会话配置
-                    manager.create_session_with_id(Some(session_id.clone()), config).await?;
-                }
-                Ok(None) => {
-                    // 会话在数据库中也不存在
-                    return Err(crate::error::SSHError::SessionNotFound(session_id));
-                }
-                Err(e) => {
-                    return Err(crate::error::SSHError::Storage(format!("Failed to load session from database: {}", e)));
-                }
-            }
-        } else {
-            println!("[session_connect] ✅ Session config found in memory");
+    let config = match manager.get_connection(&session_id).await {
+        Ok(connection) => connection.config,
+        Err(_) => {
+            // 不是连接实例，确保会话配置已在内存中
+            ensure_session_config_loaded(&manager, &pool, &session_id).await?;
+            manager.get_session_config(&session_id).await?
         }
+    };
+
+    // 跳板机的会话配置同样需要加载
+    for jump_id in &config.jump_hosts {
+        ensure_session_config_loaded(&manager, &pool, jump_id).await?;
     }
 
     let connection_id = manager.connect_session(&session_id).await?;
     Ok(connection_id)
 }
 
+/// 复制连接（如复制标签页），复用已认证的 SSH 连接打开新的终端
+///
+/// 返回新的连接实例ID
+#[tauri::command]
+pub async fn session_duplicate(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+) -> Result<String> {
+    manager.duplicate_connection(&connection_id).await
+}
+
+/// 回放连接的输出
+///
+/// since 为前端已写入终端的输出位置（`ssh-output` 事件的 offset 加数据长度），
+/// 为空时回放当前屏幕；前端发现输出位置不连续或重连成功后自动调用，补齐错过的输出
+#[tauri::command]
+pub async fn session_replay_output(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+    since: Option<u64>,
+) -> Result<OutputReplay> {
+    manager.replay_output(&connection_id, since).await
+}
+
+/// 回复键盘交互认证提示（如 TOTP/OTP 验证码）
+///
+/// responses 与 `ssh-keyboard-interactive-<connectionId>` 事件中的 prompts 一一对应，
+/// 传 null 表示取消认证
+#[tauri::command]
+pub async fn session_keyboard_interactive_respond(
+    manager: State<'_, SSHManagerState>,
+    request_id: String,
+    responses: Option<Vec<String>>,
+) -> Result<()> {
+    manager.respond_keyboard_interactive(&request_id, responses)
+}
+
+/// 信任严格模式下被拒绝的未知主机密钥
+///
+/// fingerprint 为用户确认过的指纹（来自连接失败的错误信息），成功后重新连接即可
+#[tauri::command]
+pub async fn session_trust_host_key(
+    manager: State<'_, SSHManagerState>,
+    host: String,
+    port: u16,
+    fingerprint: String,
+) -> Result<()> {
+    manager.trust_host_key(&host, port, &fingerprint)
+}
+
 /// 断开会话
 #[tauri::command]
 pub async fn session_disconnect(
@@ -199,3 +333,67 @@ pub async fn session_update(
 ) -> Result<()> {
     manager.update_session(&session_id, updates).await
 }
+
+/// 列出连接上的端口转发及其流量统计
+#[tauri::command]
+pub async fn session_port_forward_list(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+) -> Result<Vec<PortForwardStatus>> {
+    manager.list_port_forwards(&connection_id).await
+}
+
+/// 在已连接的会话上添加端口转发
+///
+/// 状态变化通过 `ssh-port-forward-<connectionId>` 事件通知
+#[tauri::command]
+pub async fn session_port_forward_add(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+    rule: PortForwardRule,
+) -> Result<()> {
+    manager.add_port_forward(&connection_id, rule).await
+}
+
+/// 移除端口转发
+#[tauri::command]
+pub async fn session_port_forward_remove(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+    rule_id: String,
+) -> Result<()> {
+    manager.remove_port_forward(&connection_id, &rule_id).await
+}
+
+/// 启用或停用端口转发
+#[tauri::command]
+pub async fn session_port_forward_toggle(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+    rule_id: String,
+    enabled: bool,
+) -> Result<()> {
+    manager.set_port_forward_enabled(&connection_id, &rule_id, enabled).await
+}
+
+/// 在连接上执行命令（独立的 exec channel，不影响终端）
+///
+/// stdout/stderr 通过 `ssh-exec-<execId>` 事件实时推送，命令结束后返回退出码和输出
+#[tauri::command]
+pub async fn session_exec(
+    manager: State<'_, SSHManagerState>,
+    connection_id: String,
+    exec_id: String,
+    request: ExecRequest,
+) -> Result<ExecResult> {
+    manager.exec_command(&connection_id, &exec_id, request).await
+}
+
+/// 取消正在执行的命令
+#[tauri::command]
+pub async fn session_exec_cancel(
+    manager: State<'_, SSHManagerState>,
+    exec_id: String,
+) -> Result<()> {
+    manager.cancel_exec(&exec_id)
+}
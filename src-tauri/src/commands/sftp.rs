@@ -2,13 +2,15 @@
 //!
 //! 前端调用的 SFTP 操作命令
 
-use crate::database::repositories::UserAuthRepository;
+use crate::commands::transfer_queue::TransferQueueState;
+use crate::database::repositories::{UploadRecord, UploadRecordsRepository, UserAuthRepository};
 use crate::database::DbPool;
 use crate::error::Result;
-use crate::sftp::{SftpFileInfo, SftpManager, UploadDirectoryResult};
+use crate::sftp::queue::TransferRequest;
+use crate::sftp::{SftpFileInfo, SftpManager, TransferSource, UploadDirectoryResult};
 use std::sync::Arc;
 use std::path::Path;
-use tauri::{State, Emitter};
+use tauri::{Emitter, Manager, State};
 
 /// SFTP Manager 状态
 pub type SftpManagerState = Arc<SftpManager>;
@@ -26,6 +28,20 @@ fn get_current_user_id(pool: &DbPool) -> String {
     }
 }
 
+/// 远程传输端点（会话 ID 由队列根据连接补全）
+fn remote_endpoint(connection_id: String, path: String) -> TransferSource {
+    TransferSource::Remote { connection_id, session_id: None, path }
+}
+
+/// 获取上传记录
+fn find_upload_record(pool: &DbPool, task_id: &str) -> Result<UploadRecord> {
+    let conn = pool.get()
+        .map_err(|e| crate::error::SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+    UploadRecordsRepository::find_by_task_id(&conn, task_id)
+        .map_err(|e| crate::error::SSHError::Storage(e.to_string()))?
+        .ok_or_else(|| crate::error::SSHError::NotFound(format!("上传记录不存在: {}", task_id)))
+}
+
 /// 列出目录内容
 ///
 /// # 参数
@@ -308,306 +324,58 @@ pub async fn local_drive_root(drive: String) -> Result<String> {
     }
 }
 
-/// 上传文件（完整实现）
+/// 上传文件
+///
+/// 通过传输队列执行（受并发限制，可在队列中暂停/恢复），任务结束后返回
 ///
 /// # 参数
-/// - `manager`: SFTP Manager
-/// - `pool`: 数据库连接池
+/// - `queue`: 传输队列
 /// - `connection_id`: SSH 连接 ID
 /// - `local_path`: 本地文件路径
 /// - `remote_path`: 远程保存路径
-/// - `window`: Tauri 窗口实例（用于发送进度事件）
 ///
 /// # 返回
 /// 传输的字节数
 #[tauri::command]
 pub async fn sftp_upload_file(
-    manager: State<'_, SftpManagerState>,
-    pool: State<'_, DbPool>,
+    queue: State<'_, TransferQueueState>,
     connection_id: String,
     local_path: String,
     remote_path: String,
-    window: tauri::Window,
 ) -> Result<u64> {
     tracing::info!("=== Upload File Start ===");
     tracing::info!("Connection ID: {}", connection_id);
     tracing::info!("Local path: {}", local_path);
     tracing::info!("Remote path: {}", remote_path);
 
-    // 检查本地文件是否存在
-    let local_path_obj = std::path::Path::new(&local_path);
-    tracing::info!("Local file exists: {}", local_path_obj.exists());
-
-    if !local_path_obj.exists() {
-        tracing::error!("Local file does not exist: {}", local_path);
-        return Err(crate::error::SSHError::NotFound(format!("本地文件不存在: {}", local_path)));
-    }
-
-    // 获取当前用户 ID
-    let user_id = get_current_user_id(&pool);
-
     // 生成任务 ID
     let task_id = format!("upload-file-{}-{}", connection_id, uuid::Uuid::new_v4().to_string().split('-').next().unwrap_or(""));
 
-    // 获取文件大小
-    let file_size = local_path_obj.metadata()
-        .map_err(|e| crate::error::SSHError::Io(format!("无法获取文件元数据: {}", e)))?
-        .len();
-
-    // 创建上传记录
-    let now = chrono::Utc::now().timestamp();
-    let upload_record = crate::database::repositories::UploadRecord {
-        id: 0, // 数据库会自动生成
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        user_id: user_id.clone(),
-        local_path: local_path.clone(),
-        remote_path: remote_path.clone(),
-        total_files: 1,
-        total_dirs: 0,
-        total_size: file_size as i64,
-        status: "pending".to_string(),
-        bytes_transferred: 0,
-        files_completed: 0,
-        started_at: now,
-        completed_at: None,
-        elapsed_ms: None,
-        error_message: None,
-        created_at: now,
-        updated_at: now,
-    };
-
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::UploadRecordsRepository::create(&conn, &upload_record);
-    }
-
-    // 更新状态为 uploading
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::UploadRecordsRepository::update_status(
-            &conn,
-            &task_id,
-            crate::database::repositories::UploadStatus::Uploading,
-            None,
-        );
-    }
-
-    // 获取取消令牌
-    let cancellation_token = manager.get_cancellation_token(&task_id).await;
-
-    // 🔥 为任务创建独立的 SFTP Client
-    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
-    let mut client_guard = sftp_client.lock().await;
-
-    // 获取文件大小
-    let file_size = local_path_obj.metadata()
-        .map_err(|e| crate::error::SSHError::Io(format!("无法获取文件元数据: {}", e)))?
-        .len();
-
-    // 记录任务开始时间
-    let start_time = chrono::Utc::now().timestamp_millis() as u64;
-
-    // 发送开始进度事件
-    let start_event = crate::sftp::UploadProgressEvent {
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        current_file: local_path.clone(),
-        current_dir: local_path_obj.parent()
-            .and_then(|p| p.to_str())
-            .unwrap_or("")
-            .to_string(),
-        files_completed: 0,
-        total_files: 1,
-        bytes_transferred: 0,
-        total_bytes: file_size,
-        speed_bytes_per_sec: 0,
-        start_time,
-        completed_time: start_time,
-        upload_name: local_path_obj.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or_else(|| local_path.as_str())
-            .to_string(),
-    };
-    let _ = window.emit("sftp-upload-progress", &start_event);
-
-    // 流式上传文件
-    let task_id_for_callback = task_id.clone();
-    let connection_id_for_callback = connection_id.clone();
-    let local_path_for_callback = local_path.clone();
-    let local_dir = local_path_obj.parent()
-        .and_then(|p| p.to_str())
-        .unwrap_or("")
-        .to_string();
-    let start_time_for_callback = start_time;
-
-    // 使用 Arc<Mutex> 来跟踪已传输字节数，用于计算速度
-    let transferred_bytes = std::sync::Arc::new(std::sync::Mutex::new(0u64));
-    let transferred_bytes_for_callback = transferred_bytes.clone();
-
-    // 添加节流机制（参考目录传输）
-    let last_emit_time = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
-    let last_emit_time_for_callback = last_emit_time.clone();
-
-    let window_for_callback = window.clone();
-    let result = client_guard.upload_file_stream(
-        &local_path,
-        &remote_path,
-        &cancellation_token,
-        move |transferred, total| {
-            // 使用节流机制：每 200ms 最多发送一次事件
-            let now = std::time::Instant::now();
-            let should_emit = {
-                let mut last = last_emit_time_for_callback.lock().unwrap();
-                if now.duration_since(*last) >= std::time::Duration::from_millis(200) {
-                    *last = now;
-                    true
-                } else {
-                    false
-                }
-            };
-
-            if should_emit {
-                // 计算传输速度
-                let current_time = chrono::Utc::now().timestamp_millis() as u64;
-                let elapsed_ms = if current_time > start_time_for_callback {
-                    current_time - start_time_for_callback
-                } else {
-                    1
-                };
-                let speed_bytes_per_sec = if elapsed_ms > 0 {
-                    (transferred * 1000) / elapsed_ms
-                } else {
-                    0
-                };
-
-                // 更新已传输字节数
-                if let Ok(mut bytes) = transferred_bytes_for_callback.lock() {
-                    *bytes = transferred;
-                }
-
-                // 发送进度事件（前端显示用）
-                let progress_event = crate::sftp::UploadProgressEvent {
-                    task_id: task_id_for_callback.clone(),
-                    connection_id: connection_id_for_callback.clone(),
-                    current_file: local_path_for_callback.clone(),
-                    current_dir: local_dir.clone(),
-                    files_completed: if transferred >= total { 1 } else { 0 },
-                    total_files: 1,
-                    bytes_transferred: transferred,
-                    total_bytes: total,
-                    speed_bytes_per_sec,
-                    start_time: start_time_for_callback,
-                    completed_time: current_time,
-                    upload_name: local_path_for_callback.rsplit('/')
-                        .next()
-                        .or_else(|| local_path_for_callback.rsplit('\\').next())
-                        .unwrap_or(local_path_for_callback.as_str())
-                        .to_string(),
-                };
-                let _ = window_for_callback.emit("sftp-upload-progress", &progress_event);
-            }
-        },
-        false,
-    ).await;
-
-    // 🔥 清理任务 SFTP Client 和取消令牌（无论成功或失败）
-    // 注意：先清理 client，再清理取消令牌，确保传输函数已经返回
-    manager.cleanup_task_client(&task_id).await;
-    manager.cleanup_cancellation_token(&task_id).await;
-
-    // 返回上传结果
-    match result {
-        Ok(transferred) => {
-            tracing::info!("Upload completed: {} bytes", transferred);
-
-            // 标记上传完成
-            let elapsed = chrono::Utc::now().timestamp() - now;
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::UploadRecordsRepository::mark_completed(
-                    &conn,
-                    &task_id,
-                    elapsed * 1000, // 转换为毫秒
-                    transferred as i64,
-                    1, // 单文件上传，files_completed = 1
-                );
-                
-                // 发送状态变更事件
-                let _ = window.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: "completed".to_string(),
-                    bytes_transferred: transferred as i64,
-                    files_completed: 1,
-                    total_files: 1,
-                    error_message: None,
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Ok(transferred)
-        }
-        Err(e) => {
-            tracing::error!("Upload failed: {}", e);
-
-            // 判断是取消还是失败
-            let error_msg = e.to_string();
-            let status = if error_msg.contains("已取消") {
-                tracing::info!("Upload cancelled by user: {}", task_id);
-                crate::database::repositories::UploadStatus::Cancelled
-            } else {
-                tracing::error!("Upload failed with error: {}", e);
-                crate::database::repositories::UploadStatus::Failed
-            };
-
-            // 获取状态字符串（在移动 status 之前）
-            let status_str = status.as_str();
-
-            // 更新数据库状态
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::UploadRecordsRepository::update_status(
-                    &conn,
-                    &task_id,
-                    status,
-                    Some(error_msg.clone()),
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: status_str.to_string(),
-                    bytes_transferred: 0,
-                    files_completed: 0,
-                    total_files: 1,
-                    error_message: Some(error_msg),
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Err(e)
-        }
-    }
+    queue.submit(task_id, TransferRequest {
+        source: TransferSource::Local { path: local_path },
+        destination: remote_endpoint(connection_id, remote_path),
+        priority: 0,
+    }).await
 }
 
-/// 下载文件（完整实现）
+/// 下载文件
+///
+/// 通过传输队列执行（受并发限制，可在队列中暂停/恢复），任务结束后返回
 ///
 /// # 参数
-/// - `manager`: SFTP Manager
-/// - `pool`: 数据库连接池
+/// - `queue`: 传输队列
 /// - `connection_id`: SSH 连接 ID
 /// - `remote_path`: 远程文件路径
 /// - `local_path`: 本地保存路径
-/// - `window`: Tauri 窗口实例（用于发送进度事件）
 ///
 /// # 返回
 /// 传输的字节数
 #[tauri::command]
 pub async fn sftp_download_file(
-    manager: State<'_, SftpManagerState>,
-    pool: State<'_, DbPool>,
+    queue: State<'_, TransferQueueState>,
     connection_id: String,
     remote_path: String,
     local_path: String,
-    window: tauri::Window,
 ) -> Result<u64> {
     tracing::info!("=== Download File Start ===");
     tracing::info!("Connection ID: {}", connection_id);
@@ -624,255 +392,38 @@ pub async fn sftp_download_file(
         }
     }
 
-    // 获取当前用户 ID
-    let user_id = get_current_user_id(&pool);
-
     // 生成任务 ID
     let task_id = format!("download-file-{}-{}", connection_id, uuid::Uuid::new_v4().to_string().split('-').next().unwrap_or(""));
 
-    // 创建下载记录
-    let now = chrono::Utc::now().timestamp();
-    let download_record = crate::database::repositories::DownloadRecord {
-        id: 0, // 数据库会自动生成
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        user_id: user_id.clone(),
-        remote_path: remote_path.clone(),
-        local_path: local_path.clone(),
-        total_files: 1,
-        total_dirs: 0,
-        total_size: 0, // 下载前不知道大小
-        status: "pending".to_string(),
-        bytes_transferred: 0,
-        files_completed: 0,
-        started_at: now,
-        completed_at: None,
-        elapsed_ms: None,
-        error_message: None,
-        created_at: now,
-        updated_at: now,
-    };
-
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::DownloadRecordsRepository::create(&conn, &download_record);
-    }
-
-    // 更新状态为 downloading
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
-            &conn,
-            &task_id,
-            crate::database::repositories::DownloadStatus::Downloading,
-            None,
-        );
-    }
-
-    // 获取取消令牌
-    let cancellation_token = manager.get_cancellation_token(&task_id).await;
-
-    // 🔥 为任务创建独立的 SFTP Client
-    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
-    let client_guard = sftp_client.lock().await;
-
-    // 提取文件名和目录信息
-    let file_name = remote_path.rsplit('/').next().unwrap_or(&remote_path).to_string();
-    let current_dir = remote_path.rsplit('/')
-        .skip(1)
-        .next()
-        .unwrap_or("")
-        .to_string();
-
-    // 记录任务开始时间
-    let start_time = chrono::Utc::now().timestamp_millis() as u64;
-
-    // 发送开始进度事件
-    let start_event = crate::sftp::DownloadProgressEvent {
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        current_file: file_name.clone(),
-        current_dir: current_dir.clone(),
-        files_completed: 0,
-        total_files: 1,
-        bytes_transferred: 0,
-        total_bytes: 0, // 初始为0，会在第一次进度回调时更新
-        speed_bytes_per_sec: 0,
-        start_time,
-        completed_time: start_time,
-    };
-    let _ = window.emit("sftp-download-progress", &start_event);
-
-    // 流式下载文件
-    let task_id_for_callback = task_id.clone();
-    let connection_id_for_callback = connection_id.clone();
-    let file_name_for_callback = file_name.clone();
-    let current_dir_for_callback = current_dir.clone();
-    let start_time_for_callback = start_time;
-
-    // 使用 Arc<Mutex> 来跟踪已传输字节数，用于计算速度
-    let transferred_bytes = std::sync::Arc::new(std::sync::Mutex::new(0u64));
-    let transferred_bytes_for_callback = transferred_bytes.clone();
-
-    // 添加节流机制（参考目录传输）
-    let last_emit_time = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
-    let last_emit_time_for_callback = last_emit_time.clone();
-
-    let window_for_callback = window.clone();
-    let result = client_guard.download_file_stream(
-        &remote_path,
-        &local_path,
-        &cancellation_token,
-        move |transferred, total| {
-            // 使用节流机制：每 200ms 最多发送一次事件
-            let now = std::time::Instant::now();
-            let should_emit = {
-                let mut last = last_emit_time_for_callback.lock().unwrap();
-                if now.duration_since(*last) >= std::time::Duration::from_millis(200) {
-                    *last = now;
-                    true
-                } else {
-                    false
-                }
-            };
-
-            if should_emit {
-                // 计算传输速度
-                let current_time = chrono::Utc::now().timestamp_millis() as u64;
-                let elapsed_ms = if current_time > start_time_for_callback {
-                    current_time - start_time_for_callback
-                } else {
-                    1
-                };
-                let speed_bytes_per_sec = if elapsed_ms > 0 {
-                    (transferred * 1000) / elapsed_ms
-                } else {
-                    0
-                };
-
-                // 更新已传输字节数
-                if let Ok(mut bytes) = transferred_bytes_for_callback.lock() {
-                    *bytes = transferred;
-                }
-
-                // 发送进度事件（前端显示用）
-                let progress_event = crate::sftp::DownloadProgressEvent {
-                    task_id: task_id_for_callback.clone(),
-                    connection_id: connection_id_for_callback.clone(),
-                    current_file: file_name_for_callback.clone(),
-                    current_dir: current_dir_for_callback.clone(),
-                    files_completed: if transferred >= total { 1 } else { 0 },
-                    total_files: 1,
-                    bytes_transferred: transferred,
-                    total_bytes: total,
-                    speed_bytes_per_sec,
-                    start_time: start_time_for_callback,
-                    completed_time: current_time,
-                };
-                let _ = window_for_callback.emit("sftp-download-progress", &progress_event);
-            }
-        }
-    ).await;
-
-    // 🔥 清理任务 SFTP Client 和取消令牌（无论成功或失败）
-    // 注意：先清理 client，再清理取消令牌，确保传输函数已经返回
-    manager.cleanup_task_client(&task_id).await;
-    manager.cleanup_cancellation_token(&task_id).await;
-
-    // 返回下载结果
-    match result {
-        Ok(transferred) => {
-            tracing::info!("Download completed: {} bytes", transferred);
-
-            // 标记下载完成
-            let elapsed = chrono::Utc::now().timestamp() - now;
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::DownloadRecordsRepository::mark_completed(
-                    &conn,
-                    &task_id,
-                    elapsed * 1000, // 转换为毫秒
-                    transferred as i64,
-                    1, // 单文件下载，files_completed = 1
-                );
-                
-                // 发送状态变更事件
-                let _ = window.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: "completed".to_string(),
-                    bytes_transferred: transferred as i64,
-                    files_completed: 1,
-                    total_files: 1,
-                    error_message: None,
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Ok(transferred)
-        }
-        Err(e) => {
-            tracing::error!("Download failed: {}", e);
-
-            // 判断是取消还是失败
-            let error_msg = e.to_string();
-            let status = if error_msg.contains("已取消") {
-                tracing::info!("Download cancelled by user: {}", task_id);
-                crate::database::repositories::DownloadStatus::Cancelled
-            } else {
-                tracing::error!("Download failed with error: {}", e);
-                crate::database::repositories::DownloadStatus::Failed
-            };
-
-            // 获取状态字符串（在移动 status 之前）
-            let status_str = status.as_str();
-
-            // 更新数据库状态
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
-                    &conn,
-                    &task_id,
-                    status,
-                    Some(error_msg.clone()),
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: status_str.to_string(),
-                    bytes_transferred: 0,
-                    files_completed: 0,
-                    total_files: 1,
-                    error_message: Some(error_msg),
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Err(e)
-        }
-    }
+    queue.submit(task_id, TransferRequest {
+        source: remote_endpoint(connection_id, remote_path),
+        destination: TransferSource::Local { path: local_path },
+        priority: 0,
+    }).await
 }
 
 /// 上传目录及其所有子目录和文件
 ///
+/// 通过传输队列执行（受并发限制，可在队列中暂停/恢复），任务结束后返回
+///
 /// # 参数
-/// - `manager`: SFTP Manager
+/// - `queue`: 传输队列
 /// - `pool`: 数据库连接池
 /// - `connection_id`: SSH 连接 ID
 /// - `local_dir_path`: 本地目录路径
 /// - `remote_dir_path`: 远程目录路径
 /// - `task_id`: 上传任务的唯一 ID
-/// - `window`: Tauri 窗口实例（用于发送进度事件）
 ///
 /// # 返回
 /// 上传结果统计信息
 #[tauri::command]
 pub async fn sftp_upload_directory(
-    manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
     pool: State<'_, DbPool>,
     connection_id: String,
     local_dir_path: String,
     remote_dir_path: String,
     task_id: String,
-    window: tauri::Window,
 ) -> Result<UploadDirectoryResult> {
     tracing::info!("=== Upload Directory Start ===");
     tracing::info!("Task ID: {}", task_id);
@@ -894,168 +445,20 @@ pub async fn sftp_upload_directory(
         ));
     }
 
-    // 获取当前用户 ID
-    let user_id = get_current_user_id(&pool);
-
-    // 创建上传记录
-    let now = chrono::Utc::now().timestamp();
-    let upload_record = crate::database::repositories::UploadRecord {
-        id: 0,
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        user_id: user_id.clone(),
-        local_path: local_dir_path.clone(),
-        remote_path: remote_dir_path.clone(),
-        total_files: 0,
-        total_dirs: 0,
-        total_size: 0,
-        status: "pending".to_string(),
-        bytes_transferred: 0,
-        files_completed: 0,
-        started_at: now,
-        completed_at: None,
-        elapsed_ms: None,
-        error_message: None,
-        created_at: now,
-        updated_at: now,
-    };
-
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::UploadRecordsRepository::create(&conn, &upload_record);
-    }
-
-    // 更新状态为 uploading
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::UploadRecordsRepository::update_status(
-            &conn,
-            &task_id,
-            crate::database::repositories::UploadStatus::Uploading,
-            None,
-        );
-    }
-
-    // 获取取消令牌（基于 task_id）
-    let cancellation_token = manager.get_cancellation_token(&task_id).await;
-
-    // 🔥 为任务创建独立的 SFTP Client
-    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
-    let mut client_guard = sftp_client.lock().await;
-
-    // 执行上传操作
-    let result = client_guard.upload_directory_recursive(
-        &local_dir_path,
-        &remote_dir_path,
-        &window,
-        &connection_id,
-        &task_id,
-        &cancellation_token
-    ).await;
-
-    // 🔥 清理任务 SFTP Client 和取消令牌
-    manager.cleanup_task_client(&task_id).await;
-    manager.cleanup_cancellation_token(&task_id).await;
-
-    // 处理上传结果
-    match result {
-        Ok(upload_result) => {
-            tracing::info!("Upload directory completed: {:?}", upload_result);
-
-            // 发送最终完成事件
-            let completed_event = crate::sftp::UploadProgressEvent {
-                task_id: task_id.clone(),
-                connection_id: connection_id.clone(),
-                current_file: "".to_string(),
-                current_dir: remote_dir_path.clone(),
-                files_completed: upload_result.total_files,
-                total_files: upload_result.total_files,
-                bytes_transferred: upload_result.total_size,
-                total_bytes: upload_result.total_size,
-                speed_bytes_per_sec: 0,
-                start_time: chrono::Utc::now().timestamp_millis() as u64,
-                completed_time: chrono::Utc::now().timestamp_millis() as u64,
-                upload_name: Path::new(&local_dir_path)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or_else(|| {
-                        local_dir_path.rsplit('/')
-                            .next()
-                            .or_else(|| local_dir_path.rsplit('\\').next())
-                            .unwrap_or(&local_dir_path)
-                    })
-                    .to_string(),
-            };
-            let _ = window.emit("sftp-upload-progress", &completed_event);
-
-            // 标记上传完成（包含统计信息）
-            let elapsed = chrono::Utc::now().timestamp() - now;
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::UploadRecordsRepository::mark_completed_with_stats(
-                    &conn,
-                    &task_id,
-                    elapsed * 1000,
-                    upload_result.total_size as i64,
-                    upload_result.total_files as i64,
-                    upload_result.total_files as i64,
-                    upload_result.total_dirs as i64,
-                    upload_result.total_size as i64,
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: "completed".to_string(),
-                    bytes_transferred: upload_result.total_size as i64,
-                    files_completed: upload_result.total_files as i64,
-                    total_files: upload_result.total_files as i64,
-                    error_message: None,
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Ok(upload_result)
-        }
-        Err(e) => {
-            tracing::error!("Upload directory failed: {}", e);
-
-            // 判断是取消还是失败
-            let error_msg = e.to_string();
-            let status = if error_msg.contains("已取消") {
-                tracing::info!("Upload directory cancelled by user: {}", task_id);
-                crate::database::repositories::UploadStatus::Cancelled
-            } else {
-                tracing::error!("Upload directory failed with error: {}", e);
-                crate::database::repositories::UploadStatus::Failed
-            };
-
-            // 获取状态字符串（在移动 status 之前）
-            let status_str = status.as_str();
-
-            // 更新数据库状态
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::UploadRecordsRepository::update_status(
-                    &conn,
-                    &task_id,
-                    status,
-                    Some(error_msg.clone()),
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: status_str.to_string(),
-                    bytes_transferred: 0,
-                    files_completed: 0,
-                    total_files: 0,
-                    error_message: Some(error_msg),
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Err(e)
-        }
-    }
+    queue.submit(task_id.clone(), TransferRequest {
+        source: TransferSource::Local { path: local_dir_path },
+        destination: remote_endpoint(connection_id, remote_dir_path),
+        priority: 0,
+    }).await?;
+
+    // 统计信息在任务结束时写入上传记录
+    let record = find_upload_record(&pool, &task_id)?;
+    Ok(UploadDirectoryResult {
+        total_files: record.total_files as u64,
+        total_dirs: record.total_dirs as u64,
+        total_size: record.total_size as u64,
+        elapsed_time_ms: record.elapsed_ms.unwrap_or(0) as u64,
+    })
 }
 
 /// 取消上传操作
@@ -1065,14 +468,19 @@ pub async fn sftp_upload_directory(
 #[tauri::command]
 pub async fn sftp_cancel_upload(
     manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
     pool: State<'_, DbPool>,
     task_id: String,
     window: tauri::Window,
 ) -> Result<()> {
     tracing::info!("Cancelling upload for task {}", task_id);
     
-    // 取消任务
-    manager.cancel_task(&task_id).await?;
+    // 取消任务（队列中的任务由队列取消，避免被重新调度）
+    if queue.contains(&task_id) {
+        queue.cancel(&task_id).await?;
+    } else {
+        manager.cancel_task(&task_id).await?;
+    }
     
     // 更新数据库状态为 cancelled
     if let Ok(conn) = pool.get() {
@@ -1099,28 +507,121 @@ pub async fn sftp_cancel_upload(
     Ok(())
 }
 
+/// 续传失败或已取消的单文件上传
+///
+/// 以目标端已有的部分文件为起点继续传输，目录上传任务不支持续传
+///
+/// # 参数
+/// - `task_id`: 上传记录的任务 ID
+/// - `connection_id`: 原连接已断开时使用的新连接 ID（可选）
+/// - `verify`: 是否先校验已有部分的 SHA-256（默认只比较大小）
+///
+/// # 返回
+/// 文件总字节数
+#[tauri::command]
+pub async fn sftp_resume_upload(
+    manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
+    pool: State<'_, DbPool>,
+    task_id: String,
+    connection_id: Option<String>,
+    verify: Option<bool>,
+    window: tauri::Window,
+) -> Result<u64> {
+    tracing::info!("Resuming upload for task {}", task_id);
+
+    let user_id = get_current_user_id(&pool);
+    let mut record = {
+        let conn = pool.get()
+            .map_err(|e| crate::error::SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+        crate::database::repositories::UploadRecordsRepository::find_by_task_id(&conn, &task_id)
+            .map_err(|e| crate::error::SSHError::Storage(e.to_string()))?
+            .filter(|record| record.user_id == user_id)
+            .ok_or_else(|| crate::error::SSHError::NotFound(format!("上传记录不存在: {}", task_id)))?
+    };
+
+    // 应用异常退出时记录可能停留在进行中，只要任务实际未运行即可续传
+    if crate::database::repositories::UploadStatus::from(record.status.as_str()) == crate::database::repositories::UploadStatus::Completed {
+        return Err(crate::error::SSHError::NotSupported("任务已完成".to_string()));
+    }
+    if manager.is_task_active(&task_id).await {
+        return Err(crate::error::SSHError::NotSupported("任务正在进行中".to_string()));
+    }
+    if record.total_files != 1 || record.total_dirs != 0 {
+        return Err(crate::error::SSHError::NotSupported("目录上传任务不支持续传".to_string()));
+    }
+
+    // 通过队列执行的任务重新排队
+    if queue.contains(&task_id) {
+        return queue.retry(&task_id, connection_id, verify.unwrap_or(false)).await;
+    }
+
+    if let Some(connection_id) = connection_id {
+        record.connection_id = connection_id;
+    }
+
+    crate::sftp::transfer::upload_file(manager.inner(), pool.inner(), window.app_handle(), &record, Some(verify.unwrap_or(false))).await
+}
+
+/// 在两个连接之间直接传输文件或目录（不经过本地磁盘）
+///
+/// 通过传输队列执行（同时占用两端连接的并发名额），任务结束后返回；
+/// 以上传记录保存到目标连接（`local_path` 为源连接上的路径），进度和状态通过上传事件发送，
+/// 单文件任务可通过 `sftp_resume_upload` 续传
+///
+/// # 参数
+/// - `queue`: 传输队列
+/// - `source_connection_id`: 源 SSH 连接 ID
+/// - `source_path`: 源文件或目录路径
+/// - `connection_id`: 目标 SSH 连接 ID
+/// - `remote_path`: 目标保存路径
+/// - `task_id`: 任务的唯一 ID
+///
+/// # 返回
+/// 传输的字节数
+#[tauri::command]
+pub async fn sftp_copy_remote(
+    queue: State<'_, TransferQueueState>,
+    source_connection_id: String,
+    source_path: String,
+    connection_id: String,
+    remote_path: String,
+    task_id: String,
+) -> Result<u64> {
+    tracing::info!("=== Remote Copy Start ===");
+    tracing::info!("Task ID: {}", task_id);
+    tracing::info!("Source: {}:{}", source_connection_id, source_path);
+    tracing::info!("Target: {}:{}", connection_id, remote_path);
+
+    queue.submit(task_id, TransferRequest {
+        source: remote_endpoint(source_connection_id, source_path),
+        destination: remote_endpoint(connection_id, remote_path),
+        priority: 0,
+    }).await
+}
+
 /// 下载目录及其所有子目录和文件
 ///
+/// 通过传输队列执行（受并发限制，可在队列中暂停/恢复），任务结束后返回
+///
 /// # 参数
-/// - `manager`: SFTP Manager
+/// - `queue`: 传输队列
 /// - `pool`: 数据库连接池
 /// - `connection_id`: SSH 连接 ID
 /// - `remote_dir_path`: 远程目录路径
 /// - `local_dir_path`: 本地保存路径
 /// - `task_id`: 下载任务的唯一 ID
-/// - `window`: Tauri 窗口实例（用于发送进度事件）
 ///
 /// # 返回
 /// 下载结果统计信息
 #[tauri::command]
 pub async fn sftp_download_directory(
-    manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
     pool: State<'_, DbPool>,
     connection_id: String,
     remote_dir_path: String,
     local_dir_path: String,
     task_id: String,
-    window: tauri::Window,
 ) -> Result<crate::sftp::DownloadDirectoryResult> {
     tracing::info!("=== Download Directory Start ===");
     tracing::info!("Task ID: {}", task_id);
@@ -1128,154 +629,24 @@ pub async fn sftp_download_directory(
     tracing::info!("Remote directory: {}", remote_dir_path);
     tracing::info!("Local directory: {}", local_dir_path);
 
-    // 验证本地目录父路径
-    let local_path = Path::new(&local_dir_path);
-    if let Some(parent) = local_path.parent() {
-        if !parent.exists() {
-            tokio::fs::create_dir_all(parent).await
-                .map_err(|e| crate::error::SSHError::Io(format!("创建本地父目录失败: {}", e)))?;
-        }
-    }
-
-    // 获取当前用户 ID
-    let user_id = get_current_user_id(&pool);
-
-    // 创建下载记录
-    let now = chrono::Utc::now().timestamp();
-    let download_record = crate::database::repositories::DownloadRecord {
-        id: 0,
-        task_id: task_id.clone(),
-        connection_id: connection_id.clone(),
-        user_id: user_id.clone(),
-        remote_path: remote_dir_path.clone(),
-        local_path: local_dir_path.clone(),
-        total_files: 0,
-        total_dirs: 0,
-        total_size: 0,
-        status: "pending".to_string(),
-        bytes_transferred: 0,
-        files_completed: 0,
-        started_at: now,
-        completed_at: None,
-        elapsed_ms: None,
-        error_message: None,
-        created_at: now,
-        updated_at: now,
-    };
-
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::DownloadRecordsRepository::create(&conn, &download_record);
-    }
-
-    // 更新状态为 downloading
-    if let Ok(conn) = pool.get() {
-        let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
-            &conn,
-            &task_id,
-            crate::database::repositories::DownloadStatus::Downloading,
-            None,
-        );
-    }
-
-    // 获取取消令牌（基于 task_id）
-    let cancellation_token = manager.get_cancellation_token(&task_id).await;
-
-    // 🔥 为任务创建独立的 SFTP Client
-    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
-    let mut client_guard = sftp_client.lock().await;
-
-    // 执行下载操作
-    let result = client_guard.download_directory_recursive(
-        &remote_dir_path,
-        &local_dir_path,
-        &window,
-        &connection_id,
-        &task_id,
-        &cancellation_token,
-        |_transferred, _total| {
-            // 进度回调，暂不使用
-        }
-    ).await;
-
-    // 🔥 清理任务 SFTP Client 和取消令牌
-    manager.cleanup_task_client(&task_id).await;
-    manager.cleanup_cancellation_token(&task_id).await;
-
-    // 处理下载结果
-    match result {
-        Ok(download_result) => {
-            tracing::info!("Download directory completed: {:?}", download_result);
-
-            // 标记下载完成（包含统计信息）
-            let elapsed = chrono::Utc::now().timestamp() - now;
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::DownloadRecordsRepository::mark_completed_with_stats(
-                    &conn,
-                    &task_id,
-                    elapsed * 1000,
-                    download_result.total_size as i64,
-                    download_result.total_files as i64,
-                    download_result.total_files as i64,
-                    download_result.total_dirs as i64,
-                    download_result.total_size as i64,
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: "completed".to_string(),
-                    bytes_transferred: download_result.total_size as i64,
-                    files_completed: download_result.total_files as i64,
-                    total_files: download_result.total_files as i64,
-                    error_message: None,
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Ok(download_result)
-        }
-        Err(e) => {
-            tracing::error!("Download directory failed: {}", e);
-
-            // 判断是取消还是失败
-            let error_msg = e.to_string();
-            let status = if error_msg.contains("已取消") {
-                tracing::info!("Download directory cancelled by user: {}", task_id);
-                crate::database::repositories::DownloadStatus::Cancelled
-            } else {
-                tracing::error!("Download directory failed with error: {}", e);
-                crate::database::repositories::DownloadStatus::Failed
-            };
-
-            // 获取状态字符串（在移动 status 之前）
-            let status_str = status.as_str();
-
-            // 更新数据库状态
-            if let Ok(conn) = pool.get() {
-                let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
-                    &conn,
-                    &task_id,
-                    status,
-                    Some(error_msg.clone()),
-                );
-
-                // 发送状态变更事件
-                let _ = window.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
-                    task_id: task_id.clone(),
-                    connection_id: connection_id.clone(),
-                    status: status_str.to_string(),
-                    bytes_transferred: 0,
-                    files_completed: 0,
-                    total_files: 0,
-                    error_message: Some(error_msg),
-                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
-                });
-            }
-
-            Err(e)
-        }
-    }
+    queue.submit(task_id.clone(), TransferRequest {
+        source: remote_endpoint(connection_id, remote_dir_path),
+        destination: TransferSource::Local { path: local_dir_path },
+        priority: 0,
+    }).await?;
+
+    // 统计信息在任务结束时写入下载记录
+    let conn = pool.get()
+        .map_err(|e| crate::error::SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+    let record = crate::database::repositories::DownloadRecordsRepository::find_by_task_id(&conn, &task_id)
+        .map_err(|e| crate::error::SSHError::Storage(e.to_string()))?
+        .ok_or_else(|| crate::error::SSHError::NotFound(format!("下载记录不存在: {}", task_id)))?;
+    Ok(crate::sftp::DownloadDirectoryResult {
+        total_files: record.total_files as u64,
+        total_dirs: record.total_dirs as u64,
+        total_size: record.total_size as u64,
+        elapsed_time_ms: record.elapsed_ms.unwrap_or(0) as u64,
+    })
 }
 
 /// 取消下载操作
@@ -1285,14 +656,19 @@ pub async fn sftp_download_directory(
 #[tauri::command]
 pub async fn sftp_cancel_download(
     manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
     pool: State<'_, DbPool>,
     task_id: String,
     window: tauri::Window,
 ) -> Result<()> {
     tracing::info!("Cancelling download for task {}", task_id);
     
-    // 取消任务
-    manager.cancel_task(&task_id).await?;
+    // 取消任务（队列中的任务由队列取消，避免被重新调度）
+    if queue.contains(&task_id) {
+        queue.cancel(&task_id).await?;
+    } else {
+        manager.cancel_task(&task_id).await?;
+    }
     
     // 更新数据库状态为 cancelled
     if let Ok(conn) = pool.get() {
@@ -1318,3 +694,89 @@ pub async fn sftp_cancel_download(
     
     Ok(())
 }
+
+/// 续传失败或已取消的单文件下载
+///
+/// 以目标端已有的部分文件为起点继续传输，目录下载任务不支持续传
+///
+/// # 参数
+/// - `task_id`: 下载记录的任务 ID
+/// - `connection_id`: 原连接已断开时使用的新连接 ID（可选）
+/// - `verify`: 是否先校验已有部分的 SHA-256（默认只比较大小）
+///
+/// # 返回
+/// 文件总字节数
+#[tauri::command]
+pub async fn sftp_resume_download(
+    manager: State<'_, SftpManagerState>,
+    queue: State<'_, TransferQueueState>,
+    pool: State<'_, DbPool>,
+    task_id: String,
+    connection_id: Option<String>,
+    verify: Option<bool>,
+    window: tauri::Window,
+) -> Result<u64> {
+    tracing::info!("Resuming download for task {}", task_id);
+
+    let user_id = get_current_user_id(&pool);
+    let mut record = {
+        let conn = pool.get()
+            .map_err(|e| crate::error::SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+        crate::database::repositories::DownloadRecordsRepository::find_by_task_id(&conn, &task_id)
+            .map_err(|e| crate::error::SSHError::Storage(e.to_string()))?
+            .filter(|record| record.user_id == user_id)
+            .ok_or_else(|| crate::error::SSHError::NotFound(format!("下载记录不存在: {}", task_id)))?
+    };
+
+    // 应用异常退出时记录可能停留在进行中，只要任务实际未运行即可续传
+    if crate::database::repositories::DownloadStatus::from(record.status.as_str()) == crate::database::repositories::DownloadStatus::Completed {
+        return Err(crate::error::SSHError::NotSupported("任务已完成".to_string()));
+    }
+    if manager.is_task_active(&task_id).await {
+        return Err(crate::error::SSHError::NotSupported("任务正在进行中".to_string()));
+    }
+    if record.total_files != 1 || record.total_dirs != 0 {
+        return Err(crate::error::SSHError::NotSupported("目录下载任务不支持续传".to_string()));
+    }
+
+    // 通过队列执行的任务重新排队
+    if queue.contains(&task_id) {
+        return queue.retry(&task_id, connection_id, verify.unwrap_or(false)).await;
+    }
+
+    if let Some(connection_id) = connection_id {
+        record.connection_id = connection_id;
+    }
+
+    crate::sftp::transfer::download_file(manager.inner(), pool.inner(), window.app_handle(), &record, Some(verify.unwrap_or(false))).await
+}
+
+/// 获取 SFTP 流水线传输的最大未完成请求数
+#[tauri::command]
+pub async fn sftp_get_pipeline_window(manager: State<'_, SftpManagerState>) -> Result<usize> {
+    Ok(manager.pipeline_window())
+}
+
+/// 设置 SFTP 流水线传输的最大未完成请求数（1 ~ 256），只影响之后开始的任务
+///
+/// 高延迟链路上增大窗口可以提高吞吐量，1 表示逐块等待服务器响应
+#[tauri::command]
+pub async fn sftp_set_pipeline_window(
+    manager: State<'_, SftpManagerState>,
+    pool: State<'_, DbPool>,
+    window: usize,
+) -> Result<usize> {
+    if window == 0 || window > crate::sftp::pipeline::MAX_WINDOW {
+        return Err(crate::error::SSHError::NotSupported(format!(
+            "窗口大小必须在 1 到 {} 之间",
+            crate::sftp::pipeline::MAX_WINDOW
+        )));
+    }
+
+    crate::database::repositories::AppSettingsRepository::new(pool.inner().clone())
+        .set_pipeline_window(window as i64)
+        .map_err(|e| crate::error::SSHError::Storage(e.to_string()))?;
+    manager.set_pipeline_window(window);
+
+    Ok(window)
+}
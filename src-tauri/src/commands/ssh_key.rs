@@ -0,0 +1,141 @@
+//! 密钥管理命令
+//!
+//! 生成、导入和管理保存在应用数据库中的 SSH 密钥，会话通过 `AuthMethod::ManagedKey` 引用
+
+use crate::database::repositories::{SshKeyRepository, UserAuthRepository};
+use crate::database::DbPool;
+use crate::models::ssh_key::SshKeyInfo;
+use crate::services::KeyService;
+use crate::ssh::keys::KeyType;
+use tauri::State;
+
+/// 未登录用户的固定用户ID
+const ANONYMOUS_USER_ID: &str = "anonymous_local";
+
+/// 未登录用户的固定 device_id（用于本地加密）
+const ANONYMOUS_DEVICE_ID: &str = "ssh-terminal-local-device-v1";
+
+/// 列出当前用户的密钥
+#[tauri::command]
+pub async fn db_ssh_key_list(pool: State<'_, DbPool>) -> Result<Vec<SshKeyInfo>, String> {
+    KeyService::new(pool.inner().clone())
+        .list()
+        .map_err(|e| format!("Failed to list keys: {}", e))
+}
+
+/// 生成新密钥
+///
+/// bits 只对 RSA 有效（默认 4096），comment 为空时使用密钥名称
+#[tauri::command]
+pub async fn db_ssh_key_generate(
+    pool: State<'_, DbPool>,
+    name: String,
+    key_type: KeyType,
+    bits: Option<usize>,
+    comment: Option<String>,
+    passphrase: Option<String>,
+) -> Result<SshKeyInfo, String> {
+    let service = KeyService::new(pool.inner().clone());
+    let comment = comment.filter(|c| !c.trim().is_empty()).unwrap_or_else(|| name.clone());
+
+    // RSA 密钥生成较慢，放到阻塞线程中执行
+    tokio::task::spawn_blocking(move || {
+        service.generate(&name, key_type, bits, &comment, passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Key generation task failed: {}", e))?
+    .map_err(|e| e.to_string())
+}
+
+/// 导入已有私钥（OpenSSH、PEM、PKCS#8 或 PuTTY .ppk）
+///
+/// path 与 content 二选一；私钥已加密时需要提供 passphrase
+#[tauri::command]
+pub async fn db_ssh_key_import(
+    pool: State<'_, DbPool>,
+    name: String,
+    path: Option<String>,
+    content: Option<String>,
+    passphrase: Option<String>,
+) -> Result<SshKeyInfo, String> {
+    let content = match (content.filter(|c| !c.trim().is_empty()), path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("无法读取私钥文件 '{}': {}", path, e))?,
+        (None, None) => return Err("请提供私钥文件或内容".to_string()),
+    };
+
+    KeyService::new(pool.inner().clone())
+        .import(&name, &content, passphrase.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 重命名密钥
+#[tauri::command]
+pub async fn db_ssh_key_rename(
+    pool: State<'_, DbPool>,
+    id: String,
+    name: String,
+) -> Result<SshKeyInfo, String> {
+    KeyService::new(pool.inner().clone())
+        .rename(&id, &name)
+        .map_err(|e| e.to_string())
+}
+
+/// 修改私钥密码，new_passphrase 为空时移除密码
+#[tauri::command]
+pub async fn db_ssh_key_change_passphrase(
+    pool: State<'_, DbPool>,
+    id: String,
+    old_passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<SshKeyInfo, String> {
+    KeyService::new(pool.inner().clone())
+        .change_passphrase(&id, old_passphrase.as_deref(), new_passphrase.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 删除密钥（仍被会话使用时失败）
+#[tauri::command]
+pub async fn db_ssh_key_delete(pool: State<'_, DbPool>, id: String) -> Result<(), String> {
+    KeyService::new(pool.inner().clone())
+        .delete(&id)
+        .map_err(|e| e.to_string())
+}
+
+/// 将匿名用户的密钥迁移到当前登录用户
+/// 此命令应该在注册或登录成功后调用（非 auto-login）
+#[tauri::command]
+pub async fn db_ssh_key_migrate_to_user(pool: State<'_, DbPool>) -> Result<usize, String> {
+    let auth_repo = UserAuthRepository::new(pool.inner().clone());
+
+    let current_user = match auth_repo.find_current()
+        .map_err(|e| format!("Failed to get current user: {}", e))?
+    {
+        Some(user) => user,
+        None => return Err("No current user found".to_string()),
+    };
+
+    if current_user.user_id == ANONYMOUS_USER_ID {
+        return Ok(0);
+    }
+
+    let migrated_count = SshKeyRepository::new(pool.inner().clone())
+        .batch_update_user_id(
+            ANONYMOUS_USER_ID,
+            &current_user.user_id,
+            ANONYMOUS_DEVICE_ID,
+            &current_user.device_id,
+        )
+        .map_err(|e| format!("Failed to migrate keys: {}", e))?;
+
+    if migrated_count > 0 {
+        tracing::info!(
+            "Successfully migrated {} keys from anonymous to user {}",
+            migrated_count,
+            current_user.user_id
+        );
+    }
+
+    Ok(migrated_count)
+}
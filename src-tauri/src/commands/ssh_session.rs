@@ -1,8 +1,16 @@
 use crate::database::DbPool;
 use crate::database::repositories::{UserAuthRepository, SshSessionRepository};
 use crate::models::ssh_session::{SshSession, AuthMethod};
-use crate::services::CryptoService;
-use tauri::State;
+use crate::services::{CryptoService, KeyService};
+use crate::commands::session::{session_config_from_db, SSHManagerState};
+use crate::commands::sftp::SftpManagerState;
+use crate::config::bundle::{BundleImportResult, BundleKey, BundleSession, ExportBundle};
+use crate::config::{KeybindingsStorageManager, Storage};
+use crate::ssh::forwarding::PortForwardRule;
+use crate::ssh::import::{self, ImportPlan, ImportSource, ImportedHost, SessionKey};
+use crate::ssh::keys;
+use crate::ssh::session::{AuthMethod as SessionAuthMethod, ConnectionType, PersistentShell, ReconnectPolicy, SessionConfig};
+use tauri::{AppHandle, State};
 
 /// 未登录用户的固定用户ID
 const ANONYMOUS_USER_ID: &str = "anonymous_local";
@@ -59,13 +67,47 @@ fn convert_front_end_auth_method(auth_method: &serde_json::Value) -> Result<Auth
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let certificate_path = public_key_obj
+            .get("certificatePath")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
         return Ok(AuthMethod::PrivateKey {
             private_key_path: private_key_path,
             passphrase,
             key_data: None,
+            certificate_path,
         });
     }
 
+    if let Some(agent_obj) = auth_method.get("Agent") {
+        let identity = agent_obj
+            .get("identity")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        return Ok(AuthMethod::Agent { identity });
+    }
+
+    if let Some(managed_key_obj) = auth_method.get("ManagedKey") {
+        let key_id = managed_key_obj
+            .get("keyId")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| "keyId field not found".to_string())?
+            .to_string();
+
+        let passphrase = managed_key_obj
+            .get("passphrase")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        return Ok(AuthMethod::ManagedKey { key_id, passphrase });
+    }
+
     Err("Invalid auth method format".to_string())
 }
 
@@ -83,25 +125,115 @@ fn auth_method_to_frontend(auth_method: &AuthMethod) -> serde_json::Value {
             private_key_path,
             passphrase,
             key_data: _,
+            certificate_path,
         } => {
+            let mut public_key = serde_json::json!({
+                "privateKeyPath": private_key_path
+            });
             if let Some(pass) = passphrase {
+                public_key["passphrase"] = serde_json::json!(pass);
+            }
+            if let Some(certificate_path) = certificate_path {
+                public_key["certificatePath"] = serde_json::json!(certificate_path);
+            }
+            serde_json::json!({
+                "PublicKey": public_key
+            })
+        }
+        AuthMethod::Agent { identity } => {
+            if let Some(identity) = identity {
                 serde_json::json!({
-                    "PublicKey": {
-                        "privateKeyPath": private_key_path,
-                        "passphrase": pass
+                    "Agent": {
+                        "identity": identity
                     }
                 })
             } else {
                 serde_json::json!({
-                    "PublicKey": {
-                        "privateKeyPath": private_key_path
-                    }
+                    "Agent": {}
                 })
             }
         }
+        AuthMethod::ManagedKey { key_id, passphrase } => {
+            let mut managed_key = serde_json::json!({
+                "keyId": key_id
+            });
+            if let Some(pass) = passphrase {
+                managed_key["passphrase"] = serde_json::json!(pass);
+            }
+            serde_json::json!({
+                "ManagedKey": managed_key
+            })
+        }
+    }
+}
+
+/// 将会话配置的 AuthMethod 转换为数据库格式
+fn convert_session_auth_method(auth_method: &SessionAuthMethod) -> AuthMethod {
+    match auth_method {
+        SessionAuthMethod::Password { password } => AuthMethod::Password {
+            password: password.clone(),
+        },
+        SessionAuthMethod::PublicKey {
+            private_key_path,
+            passphrase,
+            certificate_path,
+        } => AuthMethod::PrivateKey {
+            private_key_path: private_key_path.clone(),
+            passphrase: passphrase.clone(),
+            key_data: None,
+            certificate_path: certificate_path.clone(),
+        },
+        SessionAuthMethod::Agent { identity } => AuthMethod::Agent {
+            identity: identity.clone(),
+        },
+        SessionAuthMethod::ManagedKey { key_id, passphrase } => AuthMethod::ManagedKey {
+            key_id: key_id.clone(),
+            passphrase: passphrase.clone(),
+        },
     }
 }
 
+/// 解析前端传入的跳板机链（会话 ID 数组）
+fn parse_jump_hosts(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 解析前端传入的端口转发规则
+fn parse_port_forwards(value: &serde_json::Value) -> Result<Vec<PortForwardRule>, String> {
+    serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid port forwards: {}", e))
+}
+
+/// 解析前端传入的连接类型，未提供时为 SSH
+fn parse_connection_type(value: &serde_json::Value) -> Result<ConnectionType, String> {
+    if value.is_null() {
+        return Ok(ConnectionType::Ssh);
+    }
+    serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid connection type: {}", e))
+}
+
+/// 解析前端传入的会话保持设置
+fn parse_persistent_shell(value: &serde_json::Value) -> Result<PersistentShell, String> {
+    serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid persistent shell: {}", e))
+}
+
+/// 解析前端传入的重连策略
+fn parse_reconnect_policy(value: &serde_json::Value) -> Result<ReconnectPolicy, String> {
+    serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid reconnect policy: {}", e))
+}
+
 /// 创建 SSH 会话并保存到数据库
 #[tauri::command]
 pub async fn db_ssh_session_create(
@@ -151,6 +283,34 @@ pub async fn db_ssh_session_create(
         .and_then(|v| v.as_u64())
         .map(|r| r as u16);
 
+    let jump_hosts = config.get("jumpHosts")
+        .map(parse_jump_hosts)
+        .unwrap_or_default();
+
+    if jump_hosts.contains(&session_id) {
+        return Err("Session cannot use itself as a jump host".to_string());
+    }
+
+    let port_forwards = match config.get("portForwards") {
+        Some(value) if !value.is_null() => parse_port_forwards(value)?,
+        _ => Vec::new(),
+    };
+
+    let connection_type = match config.get("connectionType") {
+        Some(value) => parse_connection_type(value)?,
+        None => ConnectionType::Ssh,
+    };
+
+    let persistent_shell = match config.get("persistentShell") {
+        Some(value) if !value.is_null() => parse_persistent_shell(value)?,
+        _ => PersistentShell::default(),
+    };
+
+    let reconnect = match config.get("reconnect") {
+        Some(value) if !value.is_null() => parse_reconnect_policy(value)?,
+        _ => ReconnectPolicy::default(),
+    };
+
     // 提取并加密认证信息
     let auth_method_value = config.get("authMethod")
         .ok_or_else(|| "authMethod field is required".to_string())?;
@@ -175,6 +335,11 @@ pub async fn db_ssh_session_create(
         terminal_type,
         columns,
         rows,
+        jump_hosts,
+        port_forwards,
+        connection_type,
+        persistent_shell,
+        reconnect,
         auth_method_encrypted,
         auth_nonce,
         auth_key_salt: None,
@@ -242,6 +407,25 @@ pub async fn db_ssh_session_update(
     if let Some(rows) = updates.get("rows").and_then(|v| v.as_u64()) {
         session.rows = Some(rows as u16);
     }
+    if let Some(jump_hosts) = updates.get("jumpHosts") {
+        let jump_hosts = parse_jump_hosts(jump_hosts);
+        if jump_hosts.contains(&session_id) {
+            return Err("Session cannot use itself as a jump host".to_string());
+        }
+        session.jump_hosts = jump_hosts;
+    }
+    if let Some(port_forwards) = updates.get("portForwards").filter(|v| !v.is_null()) {
+        session.port_forwards = parse_port_forwards(port_forwards)?;
+    }
+    if let Some(connection_type) = updates.get("connectionType") {
+        session.connection_type = parse_connection_type(connection_type)?;
+    }
+    if let Some(persistent_shell) = updates.get("persistentShell").filter(|v| !v.is_null()) {
+        session.persistent_shell = parse_persistent_shell(persistent_shell)?;
+    }
+    if let Some(reconnect) = updates.get("reconnect").filter(|v| !v.is_null()) {
+        session.reconnect = parse_reconnect_policy(reconnect)?;
+    }
 
     // 更新认证信息（如果提供）
     if let Some(auth_method_value) = updates.get("authMethod") {
@@ -270,6 +454,84 @@ pub async fn db_ssh_session_update(
     Ok(())
 }
 
+/// 将公钥部署到已连接的服务器（类似 ssh-copy-id），返回是否新增了 authorized_keys 条目
+///
+/// key_id 为密钥管理器中的密钥；否则使用本地私钥 private_key_path（优先读取同名 .pub 文件）。
+/// 部署后先用该密钥重新登录一次，成功后已保存会话的认证方式才切换为该密钥。
+#[tauri::command]
+pub async fn db_ssh_session_deploy_key(
+    pool: State<'_, DbPool>,
+    manager: State<'_, SSHManagerState>,
+    sftp_manager: State<'_, SftpManagerState>,
+    connection_id: String,
+    key_id: Option<String>,
+    private_key_path: Option<String>,
+    passphrase: Option<String>,
+) -> Result<bool, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let (public_key, auth_method) = match (key_id, private_key_path) {
+        (Some(key_id), _) => {
+            let (info, _) = KeyService::new(pool.inner().clone())
+                .export_private_key(&key_id)
+                .map_err(|e| e.to_string())?;
+            (info.public_key, SessionAuthMethod::ManagedKey { key_id, passphrase })
+        }
+        (None, Some(private_key_path)) => {
+            let public_key = match std::fs::read_to_string(format!("{}.pub", private_key_path)) {
+                Ok(public_key) => public_key,
+                Err(_) => {
+                    let content = std::fs::read_to_string(&private_key_path)
+                        .map_err(|e| format!("无法读取私钥文件 '{}': {}", private_key_path, e))?;
+                    let key = keys::parse(&content, passphrase.as_deref()).map_err(|e| e.to_string())?;
+                    keys::public_key_line(&key).map_err(|e| e.to_string())?
+                }
+            };
+            let auth_method = SessionAuthMethod::PublicKey {
+                private_key_path,
+                passphrase,
+                certificate_path: None,
+            };
+            (public_key, auth_method)
+        }
+        (None, None) => return Err("请选择要部署的密钥".to_string()),
+    };
+
+    let added = sftp_manager.append_authorized_key(&connection_id, &public_key)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 服务器可能不读取 authorized_keys（权限、AuthorizedKeysFile 配置等），确认密钥能登录后再替换原认证方式
+    manager.check_authentication(&connection_id, auth_method.clone())
+        .await
+        .map_err(|e| format!("公钥已部署，但使用该密钥登录失败，会话认证方式未修改: {}", e))?;
+
+    // 快速连接没有保存的会话，只部署公钥
+    let session_id = manager.get_connection(&connection_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .session_id;
+    let current_user = get_current_user_info(&pool);
+    let repo = SshSessionRepository::new(pool.inner().clone());
+    let existing = repo.find_by_id(&session_id)
+        .map_err(|e| format!("Failed to find session: {}", e))?
+        .filter(|session| session.user_id == current_user.user_id && !session.is_deleted);
+
+    if let Some(session) = existing {
+        let mut config = session_config_from_db(session.clone(), &current_user.device_id)?;
+        config.auth_method = auth_method;
+        let session = db_session_from_config(&current_user, &session_id, &config, Some(session))?;
+        repo.update(&session)
+            .map_err(|e| format!("Failed to update session: {}", e))?;
+        manager.create_session_with_id(Some(session_id.clone()), config)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        tracing::info!("Switched session {} to public key authentication", session_id);
+    }
+
+    Ok(added)
+}
+
 /// 删除 SSH 会话（软删除）
 #[tauri::command]
 pub async fn db_ssh_session_delete(
@@ -326,6 +588,11 @@ pub async fn db_ssh_session_list(
                 "terminalType": session.terminal_type,
                 "columns": session.columns,
                 "rows": session.rows,
+                "jumpHosts": session.jump_hosts,
+                "portForwards": session.port_forwards,
+                "connectionType": session.connection_type,
+                "persistentShell": session.persistent_shell,
+                "reconnect": session.reconnect,
                 "createdAt": session.created_at,
                 "updatedAt": session.updated_at,
             })
@@ -380,6 +647,11 @@ pub async fn db_ssh_session_get_by_id(
         "terminalType": session.terminal_type,
         "columns": session.columns,
         "rows": session.rows,
+        "jumpHosts": session.jump_hosts,
+        "portForwards": session.port_forwards,
+        "connectionType": session.connection_type,
+        "persistentShell": session.persistent_shell,
+        "reconnect": session.reconnect,
         "authMethod": auth_method_to_frontend(&auth_method),
         "strictHostKeyChecking": true,
         "keepAliveInterval": 30,
@@ -432,3 +704,314 @@ pub async fn db_ssh_session_migrate_to_user(
 
     Ok(migrated_count)
 }
+
+/// 由会话配置生成数据库记录，认证信息用当前设备加密
+///
+/// existing 为已有记录时保留其同步状态并递增版本号
+fn db_session_from_config(
+    current_user: &CurrentUserInfo,
+    id: &str,
+    config: &SessionConfig,
+    existing: Option<SshSession>,
+) -> Result<SshSession, String> {
+    let (auth_method_encrypted, auth_nonce) = CryptoService::encrypt_password(
+        &serde_json::to_string(&convert_session_auth_method(&config.auth_method)).unwrap(),
+        &current_user.device_id,
+    )
+        .map_err(|e| format!("Failed to encrypt auth method: {}", e))?;
+
+    let now = chrono::Utc::now().timestamp();
+    let (server_ver, client_ver, last_synced_at, created_at) = match &existing {
+        Some(session) => (session.server_ver, session.client_ver + 1, session.last_synced_at, session.created_at),
+        None => (0, 1, None, now),
+    };
+
+    Ok(SshSession {
+        id: id.to_string(),
+        user_id: current_user.user_id.clone(),
+        name: config.name.clone(),
+        host: config.host.clone(),
+        port: config.port,
+        username: config.username.clone(),
+        group_name: config.group.clone(),
+        terminal_type: config.terminal_type.clone(),
+        columns: config.columns,
+        rows: config.rows,
+        jump_hosts: config.jump_hosts.clone(),
+        port_forwards: config.port_forwards.clone(),
+        connection_type: config.connection_type.clone(),
+        persistent_shell: config.persistent_shell.clone(),
+        reconnect: config.reconnect.clone(),
+        auth_method_encrypted,
+        auth_nonce,
+        auth_key_salt: None,
+        server_ver,
+        client_ver,
+        is_dirty: true, // 标记为需要同步
+        last_synced_at,
+        is_deleted: false,
+        deleted_at: None,
+        created_at,
+        updated_at: now,
+    })
+}
+
+/// 加载当前用户的会话配置，session_ids 为空时加载全部
+fn load_user_sessions(
+    pool: &DbPool,
+    current_user: &CurrentUserInfo,
+    session_ids: Option<&[String]>,
+) -> Result<Vec<(String, SessionConfig)>, String> {
+    let repo = SshSessionRepository::new(pool.clone());
+    let sessions = repo.find_by_user_id(&current_user.user_id)
+        .map_err(|e| format!("Failed to list sessions: {}", e))?;
+
+    sessions
+        .into_iter()
+        .rev() // 按创建时间升序导出
+        .filter(|session| session_ids.is_none_or(|ids| ids.contains(&session.id)))
+        .map(|session| {
+            let id = session.id.clone();
+            session_config_from_db(session, &current_user.device_id).map(|config| (id, config))
+        })
+        .collect()
+}
+
+/// 将导入计划中的新会话写入数据库并加载到内存，重复项跳过
+async fn apply_import_plan(
+    pool: &DbPool,
+    manager: &SSHManagerState,
+    plan: &mut ImportPlan,
+) -> Result<(), String> {
+    let current_user = get_current_user_info(pool);
+    let repo = SshSessionRepository::new(pool.clone());
+
+    for candidate in plan.new_sessions() {
+        let session = db_session_from_config(&current_user, &candidate.id, &candidate.config, None)?;
+        repo.create(&session)
+            .map_err(|e| format!("Failed to create session {}: {}", candidate.alias, e))?;
+        manager.create_session_with_id(Some(candidate.id.clone()), candidate.config.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    plan.imported = plan.new_sessions().count();
+    Ok(())
+}
+
+/// 从 OpenSSH 配置文件（默认 ~/.ssh/config）导入会话
+///
+/// dry_run 为 true 时只返回导入计划供预览；否则写入所有不重复的主机。
+/// 主机、端口和用户名都相同的会话视为重复，ProxyJump 引用的主机会一并导入。
+#[tauri::command]
+pub async fn db_ssh_session_import_ssh_config(
+    pool: State<'_, DbPool>,
+    manager: State<'_, SSHManagerState>,
+    path: Option<String>,
+    dry_run: bool,
+) -> Result<ImportPlan, String> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => import::ssh_config::default_path()
+            .ok_or_else(|| "Cannot determine home directory".to_string())?,
+    };
+
+    let (hosts, warnings) = import::ssh_config::load(&path).map_err(|e| e.to_string())?;
+    import_hosts(&pool, &manager, &path, hosts, warnings, dry_run).await
+}
+
+/// 从其他客户端的导出文件导入会话（PuTTY .reg、MobaXterm .mxtsessions、WinSCP.ini、Termius JSON）
+///
+/// 各客户端的文件夹/分组映射为会话分组，无法导入的设置记录在每个候选项的 unsupported 中。
+/// dry_run 与查重规则同 db_ssh_session_import_ssh_config。
+#[tauri::command]
+pub async fn db_ssh_session_import_file(
+    pool: State<'_, DbPool>,
+    manager: State<'_, SSHManagerState>,
+    source: ImportSource,
+    path: String,
+    dry_run: bool,
+) -> Result<ImportPlan, String> {
+    let path = std::path::PathBuf::from(path);
+    let (hosts, warnings) = import::load(source, &path).map_err(|e| e.to_string())?;
+    import_hosts(&pool, &manager, &path, hosts, warnings, dry_run).await
+}
+
+/// 生成导入计划，非 dry_run 时写入不重复的会话
+async fn import_hosts(
+    pool: &DbPool,
+    manager: &SSHManagerState,
+    path: &std::path::Path,
+    hosts: Vec<ImportedHost>,
+    warnings: Vec<String>,
+    dry_run: bool,
+) -> Result<ImportPlan, String> {
+    let current_user = get_current_user_info(pool);
+    let repo = SshSessionRepository::new(pool.clone());
+    let existing: Vec<(String, SessionKey)> = repo.find_by_user_id(&current_user.user_id)
+        .map_err(|e| format!("Failed to list sessions: {}", e))?
+        .into_iter()
+        .map(|session| {
+            let key = SessionKey::new(&session.host, session.port, &session.username);
+            (session.id, key)
+        })
+        .collect();
+
+    let mut plan = ImportPlan::new(hosts, &existing, warnings);
+    if !dry_run {
+        apply_import_plan(pool, manager, &mut plan).await?;
+        tracing::info!("Imported {} sessions from {}", plan.imported, path.display());
+    }
+
+    Ok(plan)
+}
+
+/// 导出会话为 OpenSSH 配置文件（不含密码），返回导出的会话数
+#[tauri::command]
+pub async fn db_ssh_session_export_ssh_config(
+    pool: State<'_, DbPool>,
+    path: String,
+    session_ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let current_user = get_current_user_info(&pool);
+    let sessions = load_user_sessions(&pool, &current_user, session_ids.as_deref())?;
+
+    std::fs::write(&path, import::ssh_config::render(&sessions))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    let exported = sessions.iter().filter(|(_, config)| config.connection_type.is_ssh()).count();
+    tracing::info!("Exported {} sessions to ssh_config {}", exported, path);
+    Ok(exported)
+}
+
+/// 导出会话（含认证信息）、快捷键和终端配置为加密的便携文件，返回导出的会话数
+#[tauri::command]
+pub async fn db_ssh_session_export_bundle(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    path: String,
+    password: String,
+    session_ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let current_user = get_current_user_info(&pool);
+    let sessions: Vec<BundleSession> = load_user_sessions(&pool, &current_user, session_ids.as_deref())?
+        .into_iter()
+        .map(|(id, config)| BundleSession { id, config })
+        .collect();
+
+    // 带上会话引用的托管密钥，导入后会话可直接使用
+    let key_service = KeyService::new(pool.inner().clone());
+    let mut keys: Vec<BundleKey> = Vec::new();
+    for session in &sessions {
+        if let SessionAuthMethod::ManagedKey { key_id, .. } = &session.config.auth_method {
+            if keys.iter().any(|key| &key.info.id == key_id) {
+                continue;
+            }
+            match key_service.export_private_key(key_id) {
+                Ok((info, private_key)) => keys.push(BundleKey { info, private_key }),
+                Err(e) => tracing::warn!("Failed to export key {} for bundle: {}", key_id, e),
+            }
+        }
+    }
+
+    let keybindings = KeybindingsStorageManager::new()
+        .and_then(|manager| manager.load_keybindings())
+        .inspect_err(|e| tracing::warn!("Failed to load keybindings for export: {}", e))
+        .ok();
+    let terminal_config = Storage::load_app_config(Some(&app))
+        .inspect_err(|e| tracing::warn!("Failed to load terminal config for export: {}", e))
+        .ok()
+        .flatten();
+
+    let count = sessions.len();
+    let content = ExportBundle::new(sessions, keys, keybindings, terminal_config)
+        .encrypt(&password)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    tracing::info!("Exported {} sessions to bundle {}", count, path);
+    Ok(count)
+}
+
+/// 导入加密的便携文件
+///
+/// 会话按 ID 合并：已存在的会话被文件中的配置覆盖，其余新建；
+/// 快捷键按动作合并，终端配置整体替换。
+#[tauri::command]
+pub async fn db_ssh_session_import_bundle(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    manager: State<'_, SSHManagerState>,
+    path: String,
+    password: String,
+) -> Result<BundleImportResult, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle = ExportBundle::decrypt(&content, &password).map_err(|e| e.to_string())?;
+
+    let current_user = get_current_user_info(&pool);
+    let repo = SshSessionRepository::new(pool.inner().clone());
+    let mut result = BundleImportResult::default();
+
+    // 先导入密钥，会话按 ID 引用
+    let key_service = KeyService::new(pool.inner().clone());
+    for BundleKey { info, private_key } in &bundle.keys {
+        match key_service.restore(info, private_key) {
+            Ok(true) => result.keys += 1,
+            Ok(false) => {}
+            Err(e) => result.warnings.push(format!("密钥 {}: {}", info.name, e)),
+        }
+    }
+
+    for BundleSession { id, config } in bundle.sessions {
+        let existing = repo.find_by_id(&id)
+            .map_err(|e| format!("Failed to find session: {}", e))?;
+
+        match existing {
+            Some(session) if session.user_id != current_user.user_id || session.is_deleted => {
+                result.warnings.push(format!("{}: 会话属于其他用户或已删除，已跳过", config.name));
+                continue;
+            }
+            Some(session) => {
+                let session = db_session_from_config(&current_user, &id, &config, Some(session))?;
+                repo.update(&session)
+                    .map_err(|e| format!("Failed to update session: {}", e))?;
+                result.updated += 1;
+            }
+            None => {
+                let session = db_session_from_config(&current_user, &id, &config, None)?;
+                repo.create(&session)
+                    .map_err(|e| format!("Failed to create session: {}", e))?;
+                result.created += 1;
+            }
+        }
+
+        manager.create_session_with_id(Some(id), config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(imported) = bundle.keybindings {
+        let storage = KeybindingsStorageManager::new().map_err(|e| e.to_string())?;
+        let mut keybindings = storage.load_keybindings().map_err(|e| e.to_string())?;
+        keybindings.keybindings.extend(imported.keybindings);
+        for preset in imported.presets {
+            keybindings.presets.retain(|existing| existing.id != preset.id);
+            keybindings.presets.push(preset);
+        }
+        storage.save_keybindings(&keybindings).map_err(|e| e.to_string())?;
+        result.keybindings = true;
+    }
+
+    if let Some(terminal_config) = bundle.terminal_config {
+        Storage::save_app_config(&terminal_config, Some(&app)).map_err(|e| e.to_string())?;
+        result.terminal_config = true;
+    }
+
+    tracing::info!(
+        "Imported bundle {}: {} created, {} updated, {} keys",
+        path, result.created, result.updated, result.keys
+    );
+    Ok(result)
+}
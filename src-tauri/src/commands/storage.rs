@@ -170,6 +170,21 @@ pub async fn storage_session_update(
             if let Some(keep_alive_interval) = updates.keep_alive_interval {
                 config.keep_alive_interval = keep_alive_interval;
             }
+            if let Some(jump_hosts) = updates.jump_hosts {
+                config.jump_hosts = jump_hosts;
+            }
+            if let Some(port_forwards) = updates.port_forwards {
+                config.port_forwards = port_forwards;
+            }
+            if let Some(reconnect) = updates.reconnect {
+                config.reconnect = reconnect;
+            }
+            if let Some(connection_type) = updates.connection_type {
+                config.connection_type = connection_type;
+            }
+            if let Some(persistent_shell) = updates.persistent_shell {
+                config.persistent_shell = persistent_shell;
+            }
             updated = true;
             break;
         }
@@ -0,0 +1,82 @@
+//! 传输队列命令
+//!
+//! 通过队列执行的上传/下载受并发限制，按优先级调度，应用重启后继续执行
+
+use crate::error::Result;
+use crate::sftp::queue::{TransferQueue, TransferQueueLimits, TransferRequest};
+use crate::sftp::TransferProgress;
+use std::sync::Arc;
+use tauri::State;
+
+/// 传输队列状态
+pub type TransferQueueState = Arc<TransferQueue>;
+
+/// 添加传输任务，返回新建的队列项
+#[tauri::command]
+pub async fn transfer_queue_enqueue(
+    queue: State<'_, TransferQueueState>,
+    requests: Vec<TransferRequest>,
+) -> Result<Vec<TransferProgress>> {
+    queue.enqueue(requests).await
+}
+
+/// 获取全部队列项
+#[tauri::command]
+pub async fn transfer_queue_list(queue: State<'_, TransferQueueState>) -> Result<Vec<TransferProgress>> {
+    queue.list()
+}
+
+/// 暂停任务
+#[tauri::command]
+pub async fn transfer_queue_pause(queue: State<'_, TransferQueueState>, id: String) -> Result<TransferProgress> {
+    queue.pause(&id).await
+}
+
+/// 恢复已暂停、失败或已取消的任务
+#[tauri::command]
+pub async fn transfer_queue_resume(queue: State<'_, TransferQueueState>, id: String) -> Result<TransferProgress> {
+    queue.resume(&id).await
+}
+
+/// 取消任务
+#[tauri::command]
+pub async fn transfer_queue_cancel(queue: State<'_, TransferQueueState>, id: String) -> Result<TransferProgress> {
+    queue.cancel(&id).await
+}
+
+/// 从队列中移除任务
+#[tauri::command]
+pub async fn transfer_queue_remove(queue: State<'_, TransferQueueState>, id: String) -> Result<()> {
+    queue.remove(&id).await
+}
+
+/// 修改任务优先级
+#[tauri::command]
+pub async fn transfer_queue_set_priority(
+    queue: State<'_, TransferQueueState>,
+    id: String,
+    priority: i32,
+) -> Result<TransferProgress> {
+    queue.set_priority(&id, priority).await
+}
+
+/// 清除已结束的任务
+#[tauri::command]
+pub async fn transfer_queue_clear_finished(queue: State<'_, TransferQueueState>) -> Result<usize> {
+    queue.clear_finished()
+}
+
+/// 获取并发限制
+#[tauri::command]
+pub async fn transfer_queue_get_limits(queue: State<'_, TransferQueueState>) -> Result<TransferQueueLimits> {
+    Ok(queue.limits())
+}
+
+/// 设置并发限制
+#[tauri::command]
+pub async fn transfer_queue_set_limits(
+    queue: State<'_, TransferQueueState>,
+    limits: TransferQueueLimits,
+) -> Result<TransferQueueLimits> {
+    queue.set_limits(limits)
+}
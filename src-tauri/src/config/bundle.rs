@@ -0,0 +1,167 @@
+use crate::config::keybindings::KeybindingsData;
+use crate::config::storage::TerminalConfig;
+use crate::error::{Result, SSHError};
+use crate::models::ssh_key::SshKeyInfo;
+use crate::services::CryptoService;
+use crate::ssh::session::SessionConfig;
+use serde::{Deserialize, Serialize};
+
+/// 导出文件的格式标识
+const BUNDLE_FORMAT: &str = "ssh-terminal-bundle";
+
+/// 当前导出格式版本
+const BUNDLE_VERSION: &str = "1.0";
+
+/// 导出文件：明文外壳 + 用密码加密的 ExportBundle
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleFile {
+    format: String,
+    version: String,
+    /// CryptoService::encrypt_string 的结果
+    data: String,
+}
+
+/// 导出的会话
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleSession {
+    pub id: String,
+    pub config: SessionConfig,
+}
+
+/// 导出的托管密钥
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleKey {
+    pub info: SshKeyInfo,
+    /// OpenSSH 格式私钥（设置了密码时仍是加密的）
+    pub private_key: String,
+}
+
+/// 便携导出包，包含会话（含认证信息）、会话引用的托管密钥、分组、快捷键和终端配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBundle {
+    pub exported_at: i64,
+    pub sessions: Vec<BundleSession>,
+    /// 会话分组（分组只以会话的 group 字段存在，这里列出便于导入时展示）
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub keys: Vec<BundleKey>,
+    #[serde(default)]
+    pub keybindings: Option<KeybindingsData>,
+    #[serde(default)]
+    pub terminal_config: Option<TerminalConfig>,
+}
+
+/// 导入结果
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResult {
+    /// 新建的会话数
+    pub created: usize,
+    /// 按 ID 覆盖的已有会话数
+    pub updated: usize,
+    /// 新导入的托管密钥数
+    pub keys: usize,
+    /// 是否导入了快捷键
+    pub keybindings: bool,
+    /// 是否导入了终端配置
+    pub terminal_config: bool,
+    /// 被跳过的会话等提示
+    pub warnings: Vec<String>,
+}
+
+impl ExportBundle {
+    pub fn new(
+        sessions: Vec<BundleSession>,
+        keys: Vec<BundleKey>,
+        keybindings: Option<KeybindingsData>,
+        terminal_config: Option<TerminalConfig>,
+    ) -> Self {
+        let mut groups: Vec<String> = Vec::new();
+        for session in &sessions {
+            if !groups.contains(&session.config.group) {
+                groups.push(session.config.group.clone());
+            }
+        }
+
+        Self {
+            exported_at: chrono::Utc::now().timestamp(),
+            sessions,
+            groups,
+            keys,
+            keybindings,
+            terminal_config,
+        }
+    }
+
+    /// 用密码加密为导出文件内容
+    pub fn encrypt(&self, password: &str) -> Result<String> {
+        if password.is_empty() {
+            return Err(SSHError::Crypto("导出密码不能为空".to_string()));
+        }
+
+        let json = serde_json::to_string(self)
+            .map_err(|e| SSHError::Storage(format!("Failed to serialize bundle: {}", e)))?;
+        let data = CryptoService::encrypt_string(&json, password)
+            .map_err(|e| SSHError::Crypto(e.to_string()))?;
+
+        let file = BundleFile {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION.to_string(),
+            data,
+        };
+        serde_json::to_string_pretty(&file)
+            .map_err(|e| SSHError::Storage(format!("Failed to serialize bundle: {}", e)))
+    }
+
+    /// 解密导出文件内容
+    pub fn decrypt(content: &str, password: &str) -> Result<Self> {
+        let file: BundleFile = serde_json::from_str(content)
+            .map_err(|e| SSHError::Storage(format!("不是有效的导出文件: {}", e)))?;
+        if file.format != BUNDLE_FORMAT {
+            return Err(SSHError::Storage(format!("不是有效的导出文件: {}", file.format)));
+        }
+        if file.version != BUNDLE_VERSION {
+            return Err(SSHError::Storage(format!("Unsupported version: {}", file.version)));
+        }
+
+        // AES-GCM 校验失败即密码错误
+        let json = CryptoService::decrypt_string(&file.data, password)
+            .map_err(|_| SSHError::Crypto("密码错误或文件已损坏".to_string()))?;
+        serde_json::from_str(&json)
+            .map_err(|e| SSHError::Storage(format!("Failed to parse bundle: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::session::AuthMethod;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let mut config = SessionConfig::ssh(
+            "db".to_string(),
+            "db.internal".to_string(),
+            22,
+            "admin".to_string(),
+            AuthMethod::Password { password: "secret".to_string() },
+        );
+        config.group = "生产".to_string();
+        let bundle = ExportBundle::new(vec![BundleSession { id: "s1".to_string(), config }], Vec::new(), None, None);
+
+        let content = bundle.encrypt("export-password").unwrap();
+        assert!(!content.contains("secret"));
+        assert!(ExportBundle::decrypt(&content, "wrong").is_err());
+
+        let restored = ExportBundle::decrypt(&content, "export-password").unwrap();
+        assert_eq!(restored.groups, vec!["生产".to_string()]);
+        assert_eq!(restored.sessions[0].id, "s1");
+        assert!(matches!(
+            &restored.sessions[0].config.auth_method,
+            AuthMethod::Password { password } if password == "secret"
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod storage;
 pub mod keybindings;
+pub mod bundle;
 
 pub use storage::Storage;
 pub use keybindings::KeybindingsStorageManager;
@@ -1,5 +1,7 @@
 use crate::config::SessionConfig;
 use crate::error::{Result, SSHError};
+use crate::ssh::forwarding::PortForwardRule;
+use crate::ssh::session::{ConnectionType, PersistentShell, ReconnectPolicy};
 use std::fs;
 use std::path::PathBuf;
 use dirs::home_dir;
@@ -167,6 +169,16 @@ pub struct SavedSession {
     pub last_connected: Option<String>,
     #[serde(default = "default_group")]
     pub group: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jump_hosts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForwardRule>,
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    #[serde(default, skip_serializing_if = "ConnectionType::is_ssh")]
+    pub connection_type: ConnectionType,
+    #[serde(default)]
+    pub persistent_shell: PersistentShell,
 }
 
 fn default_group() -> String {
@@ -444,6 +456,11 @@ impl Storage {
             created_at: chrono::Utc::now().to_rfc3339(),
             last_connected: None,
             group: session.group,
+            jump_hosts: session.jump_hosts,
+            port_forwards: session.port_forwards,
+            reconnect: session.reconnect,
+            connection_type: session.connection_type,
+            persistent_shell: session.persistent_shell,
         })
     }
 
@@ -528,6 +545,11 @@ impl Storage {
             strict_host_key_checking: true, // 默认启用严格的主机密钥验证
             group: saved.group,
             keep_alive_interval: 30, // 默认30秒
+            jump_hosts: saved.jump_hosts,
+            port_forwards: saved.port_forwards,
+            reconnect: saved.reconnect,
+            connection_type: saved.connection_type,
+            persistent_shell: saved.persistent_shell,
         };
 
         Ok((saved.id, config))
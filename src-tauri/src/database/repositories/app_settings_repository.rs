@@ -114,6 +114,58 @@ impl AppSettingsRepository {
         Ok(())
     }
 
+    /// 获取传输队列并发限制（全局最大并发数，单个连接最大并发数）
+    pub fn get_transfer_limits(&self) -> Result<(i64, i64)> {
+        let conn = self.get_conn()?;
+
+        let limits = conn.query_row(
+            "SELECT transfer_max_concurrent, transfer_max_per_connection FROM app_settings WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(limits)
+    }
+
+    /// 设置传输队列并发限制
+    pub fn set_transfer_limits(&self, max_concurrent: i64, max_per_connection: i64) -> Result<()> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            "UPDATE app_settings SET transfer_max_concurrent = ?1, transfer_max_per_connection = ?2, updated_at = ?3 WHERE id = 1",
+            (max_concurrent, max_per_connection, now),
+        )?;
+
+        Ok(())
+    }
+
+    /// 获取 SFTP 流水线传输的最大未完成请求数
+    pub fn get_pipeline_window(&self) -> Result<i64> {
+        let conn = self.get_conn()?;
+
+        let window: i64 = conn.query_row(
+            "SELECT transfer_pipeline_window FROM app_settings WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(window)
+    }
+
+    /// 设置 SFTP 流水线传输的最大未完成请求数
+    pub fn set_pipeline_window(&self, window: i64) -> Result<()> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            "UPDATE app_settings SET transfer_pipeline_window = ?1, updated_at = ?2 WHERE id = 1",
+            (window, now),
+        )?;
+
+        Ok(())
+    }
+
     /// 获取语言设置
     pub fn get_language(&self) -> Result<String> {
         let conn = self.get_conn()?;
@@ -4,17 +4,21 @@
 
 pub mod user_auth_repository;
 pub mod ssh_session_repository;
+pub mod ssh_key_repository;
 pub mod app_settings_repository;
 pub mod user_profile_repository;
 pub mod sync_state_repository;
 pub mod upload_records;
 pub mod download_records;
+pub mod transfer_queue_repository;
 
 // 重新导出 Repository 类
 pub use user_auth_repository::UserAuthRepository;
 pub use ssh_session_repository::SshSessionRepository;
+pub use ssh_key_repository::SshKeyRepository;
 pub use app_settings_repository::AppSettingsRepository;
 pub use user_profile_repository::UserProfileRepository;
 pub use sync_state_repository::SyncStateRepository;
 pub use upload_records::{UploadRecordsRepository, PaginatedUploadRecords, UploadRecord, UploadStatus};
-pub use download_records::{DownloadRecordsRepository, PaginatedDownloadRecords, DownloadRecord, DownloadStatus};
\ No newline at end of file
+pub use download_records::{DownloadRecordsRepository, PaginatedDownloadRecords, DownloadRecord, DownloadStatus};
+pub use transfer_queue_repository::TransferQueueRepository;
\ No newline at end of file
@@ -0,0 +1,248 @@
+use anyhow::Result;
+use r2d2::PooledConnection;
+use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
+
+use crate::database::DbPool;
+use crate::models::ssh_key::SshKey;
+
+/// SSH 密钥 Repository
+pub struct SshKeyRepository {
+    pool: DbPool,
+}
+
+impl SshKeyRepository {
+    /// 创建新的 Repository 实例
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取数据库连接
+    fn get_conn(&self) -> Result<PooledConnection<SqliteConnectionManager>> {
+        self.pool
+            .get()
+            .map_err(|e| anyhow::anyhow!("Failed to get database connection: {}", e))
+    }
+
+    /// 创建密钥
+    ///
+    /// 同 ID 的已删除记录会被覆盖（例如从导出包或服务器恢复已删除的密钥）
+    pub fn create(&self, key: &SshKey) -> Result<SshKey> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "INSERT INTO ssh_keys (
+                id, user_id, name, algorithm, fingerprint, public_key,
+                private_key_encrypted, private_key_nonce, has_passphrase,
+                is_dirty, is_deleted, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            ON CONFLICT(id) DO UPDATE SET
+                user_id = excluded.user_id, name = excluded.name, algorithm = excluded.algorithm,
+                fingerprint = excluded.fingerprint, public_key = excluded.public_key,
+                private_key_encrypted = excluded.private_key_encrypted,
+                private_key_nonce = excluded.private_key_nonce, has_passphrase = excluded.has_passphrase,
+                is_dirty = excluded.is_dirty, is_deleted = excluded.is_deleted,
+                created_at = excluded.created_at, updated_at = excluded.updated_at
+            WHERE ssh_keys.is_deleted = 1",
+            (
+                &key.id,
+                &key.user_id,
+                &key.name,
+                &key.algorithm,
+                &key.fingerprint,
+                &key.public_key,
+                &key.private_key_encrypted,
+                &key.private_key_nonce,
+                key.has_passphrase as i32,
+                key.is_dirty as i32,
+                key.is_deleted as i32,
+                key.created_at,
+                key.updated_at,
+            ),
+        )?;
+
+        Ok(key.clone())
+    }
+
+    /// 更新密钥（名称、私钥、密码状态和同步标记）
+    pub fn update(&self, key: &SshKey) -> Result<SshKey> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE ssh_keys SET
+                name = ?1, private_key_encrypted = ?2, private_key_nonce = ?3,
+                has_passphrase = ?4, is_dirty = ?5, updated_at = ?6
+            WHERE id = ?7",
+            (
+                &key.name,
+                &key.private_key_encrypted,
+                &key.private_key_nonce,
+                key.has_passphrase as i32,
+                key.is_dirty as i32,
+                key.updated_at,
+                &key.id,
+            ),
+        )?;
+
+        Ok(key.clone())
+    }
+
+    /// 删除密钥（软删除，等待同步到服务器）
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "UPDATE ssh_keys SET is_deleted = 1, is_dirty = 1, updated_at = ?1 WHERE id = ?2",
+            (now, id),
+        )?;
+        Ok(())
+    }
+
+    /// 彻底删除密钥（其他设备删除后同步下来）
+    pub fn purge(&self, id: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+        conn.execute("DELETE FROM ssh_keys WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// 根据 ID 获取密钥
+    pub fn find_by_id(&self, id: &str) -> Result<Option<SshKey>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, user_id, name, algorithm, fingerprint, public_key,
+                private_key_encrypted, private_key_nonce, has_passphrase,
+                is_dirty, is_deleted, created_at, updated_at
+            FROM ssh_keys
+            WHERE id = ?1 AND is_deleted = 0"
+        )?;
+
+        let mut rows = stmt.query([id])?;
+
+        if let Some(row) = rows.next()? {
+            Ok(Some(Self::map_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 获取用户的所有密钥（按创建时间升序）
+    pub fn find_by_user_id(&self, user_id: &str) -> Result<Vec<SshKey>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, user_id, name, algorithm, fingerprint, public_key,
+                private_key_encrypted, private_key_nonce, has_passphrase,
+                is_dirty, is_deleted, created_at, updated_at
+            FROM ssh_keys
+            WHERE user_id = ?1 AND is_deleted = 0
+            ORDER BY created_at ASC"
+        )?;
+
+        let keys = stmt
+            .query_map([user_id], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(keys)
+    }
+
+    /// 获取需要同步的密钥
+    pub fn get_dirty_keys(&self, user_id: &str) -> Result<Vec<SshKey>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, user_id, name, algorithm, fingerprint, public_key,
+                private_key_encrypted, private_key_nonce, has_passphrase,
+                is_dirty, is_deleted, created_at, updated_at
+            FROM ssh_keys
+            WHERE user_id = ?1 AND is_dirty = 1 AND is_deleted = 0"
+        )?;
+
+        let keys = stmt
+            .query_map([user_id], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(keys)
+    }
+
+    /// 获取等待同步删除的密钥 ID
+    pub fn get_deleted_keys(&self, user_id: &str) -> Result<Vec<String>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id FROM ssh_keys WHERE user_id = ?1 AND is_deleted = 1 AND is_dirty = 1"
+        )?;
+
+        let ids = stmt
+            .query_map([user_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// 清除同步标记
+    pub fn clear_dirty_marker(&self, id: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+        conn.execute("UPDATE ssh_keys SET is_dirty = 0 WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// 批量更新密钥的 user_id（用于从匿名用户迁移到登录用户）
+    ///
+    /// 私钥使用新的 device_id 重新加密，并标记为待同步
+    pub fn batch_update_user_id(
+        &self,
+        old_user_id: &str,
+        new_user_id: &str,
+        old_device_id: &str,
+        new_device_id: &str,
+    ) -> Result<usize> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+
+        let keys = self.find_by_user_id(old_user_id)?;
+        for key in &keys {
+            let private_key = crate::services::CryptoService::decrypt_password(
+                &key.private_key_encrypted,
+                &key.private_key_nonce,
+                old_device_id,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to decrypt key {}: {}", key.id, e))?;
+
+            let (private_key_encrypted, private_key_nonce) =
+                crate::services::CryptoService::encrypt_password(&private_key, new_device_id)
+                    .map_err(|e| anyhow::anyhow!("Failed to encrypt key {}: {}", key.id, e))?;
+
+            conn.execute(
+                "UPDATE ssh_keys SET
+                    user_id = ?1, private_key_encrypted = ?2, private_key_nonce = ?3,
+                    is_dirty = 1, updated_at = ?4
+                WHERE id = ?5",
+                (new_user_id, &private_key_encrypted, &private_key_nonce, now, &key.id),
+            )?;
+        }
+
+        Ok(keys.len())
+    }
+
+    /// 将数据库行转换为 SshKey
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<SshKey> {
+        Ok(SshKey {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            name: row.get(2)?,
+            algorithm: row.get(3)?,
+            fingerprint: row.get(4)?,
+            public_key: row.get(5)?,
+            private_key_encrypted: row.get(6)?,
+            private_key_nonce: row.get(7)?,
+            has_passphrase: row.get::<_, i32>(8)? != 0,
+            is_dirty: row.get::<_, i32>(9)? != 0,
+            is_deleted: row.get::<_, i32>(10)? != 0,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
+        })
+    }
+}
@@ -4,6 +4,7 @@ use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
 
 use crate::database::DbPool;
 use crate::models::ssh_session::*;
+use crate::ssh::session::{ConnectionType, PersistentShell, ReconnectPolicy};
 
 /// SSH 会话 Repository
 pub struct SshSessionRepository {
@@ -30,13 +31,13 @@ impl SshSessionRepository {
         conn.execute(
             "INSERT INTO ssh_sessions (
                 id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
+                terminal_type, columns, rows, jump_hosts, port_forwards, connection_type, persistent_shell, reconnect_policy,
                 auth_method_encrypted, auth_nonce, auth_key_salt,
                 server_ver, client_ver, is_dirty, last_synced_at,
                 is_deleted, deleted_at, created_at, updated_at
             ) VALUES (
                 :id, :user_id, :name, :host, :port, :username, :group_name,
-                :terminal_type, :columns, :rows,
+                :terminal_type, :columns, :rows, :jump_hosts, :port_forwards, :connection_type, :persistent_shell, :reconnect_policy,
                 :auth_method_encrypted, :auth_nonce, :auth_key_salt,
                 :server_ver, :client_ver, :is_dirty, :last_synced_at,
                 :is_deleted, :deleted_at, :created_at, :updated_at
@@ -52,6 +53,11 @@ impl SshSessionRepository {
                 (":terminal_type", &session.terminal_type as &dyn rusqlite::ToSql),
                 (":columns", &session.columns.map(|c| c as i32) as &dyn rusqlite::ToSql),
                 (":rows", &session.rows.map(|r| r as i32) as &dyn rusqlite::ToSql),
+                (":jump_hosts", &encode_json_list(&session.jump_hosts) as &dyn rusqlite::ToSql),
+                (":port_forwards", &encode_json_list(&session.port_forwards) as &dyn rusqlite::ToSql),
+                (":connection_type", &encode_connection_type(&session.connection_type) as &dyn rusqlite::ToSql),
+                (":persistent_shell", &encode_persistent_shell(&session.persistent_shell) as &dyn rusqlite::ToSql),
+                (":reconnect_policy", &encode_reconnect_policy(&session.reconnect) as &dyn rusqlite::ToSql),
                 (":auth_method_encrypted", &session.auth_method_encrypted as &dyn rusqlite::ToSql),
                 (":auth_nonce", &session.auth_nonce as &dyn rusqlite::ToSql),
                 (":auth_key_salt", &session.auth_key_salt as &dyn rusqlite::ToSql),
@@ -76,7 +82,9 @@ impl SshSessionRepository {
         conn.execute(
             "UPDATE ssh_sessions SET
                 name = :name, host = :host, port = :port, username = :username, group_name = :group_name,
-                terminal_type = :terminal_type, columns = :columns, rows = :rows,
+                terminal_type = :terminal_type, columns = :columns, rows = :rows, jump_hosts = :jump_hosts,
+                port_forwards = :port_forwards, connection_type = :connection_type,
+                persistent_shell = :persistent_shell, reconnect_policy = :reconnect_policy,
                 auth_method_encrypted = :auth_method_encrypted, auth_nonce = :auth_nonce, auth_key_salt = :auth_key_salt,
                 server_ver = :server_ver, client_ver = :client_ver, is_dirty = :is_dirty, last_synced_at = :last_synced_at,
                 updated_at = :updated_at
@@ -90,6 +98,11 @@ impl SshSessionRepository {
                 (":terminal_type", &session.terminal_type as &dyn rusqlite::ToSql),
                 (":columns", &session.columns.map(|c| c as i32) as &dyn rusqlite::ToSql),
                 (":rows", &session.rows.map(|r| r as i32) as &dyn rusqlite::ToSql),
+                (":jump_hosts", &encode_json_list(&session.jump_hosts) as &dyn rusqlite::ToSql),
+                (":port_forwards", &encode_json_list(&session.port_forwards) as &dyn rusqlite::ToSql),
+                (":connection_type", &encode_connection_type(&session.connection_type) as &dyn rusqlite::ToSql),
+                (":persistent_shell", &encode_persistent_shell(&session.persistent_shell) as &dyn rusqlite::ToSql),
+                (":reconnect_policy", &encode_reconnect_policy(&session.reconnect) as &dyn rusqlite::ToSql),
                 (":auth_method_encrypted", &session.auth_method_encrypted as &dyn rusqlite::ToSql),
                 (":auth_nonce", &session.auth_nonce as &dyn rusqlite::ToSql),
                 (":auth_key_salt", &session.auth_key_salt as &dyn rusqlite::ToSql),
@@ -125,7 +138,7 @@ impl SshSessionRepository {
         let mut stmt = conn.prepare(
             "SELECT
                 id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
+                terminal_type, columns, rows, jump_hosts, port_forwards, connection_type, persistent_shell, reconnect_policy,
                 auth_method_encrypted, auth_nonce, auth_key_salt,
                 server_ver, client_ver, is_dirty, last_synced_at,
                 is_deleted, deleted_at, created_at, updated_at
@@ -136,7 +149,7 @@ impl SshSessionRepository {
         let mut rows = stmt.query([id])?;
 
         if let Some(row) = rows.next()? {
-            Ok(Some(self.row_to_session(row)?))
+            Ok(Some(Self::map_row(row)?))
         } else {
             Ok(None)
         }
@@ -151,7 +164,7 @@ impl SshSessionRepository {
         let mut stmt = conn.prepare(
             "SELECT
                 id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
+                terminal_type, columns, rows, jump_hosts, port_forwards, connection_type, persistent_shell, reconnect_policy,
                 auth_method_encrypted, auth_nonce, auth_key_salt,
                 server_ver, client_ver, is_dirty, last_synced_at,
                 is_deleted, deleted_at, created_at, updated_at
@@ -160,66 +173,9 @@ impl SshSessionRepository {
             ORDER BY created_at DESC"
         )?;
 
-        let rows = stmt.query_map([user_id], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, i32>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, Option<String>>(7)?,
-                row.get::<_, Option<i32>>(8)?,
-                row.get::<_, Option<i32>>(9)?,
-                row.get::<_, String>(10)?,
-                row.get::<_, String>(11)?,
-                row.get::<_, Option<String>>(12)?,
-                row.get::<_, i32>(13)?,
-                row.get::<_, i32>(14)?,
-                row.get::<_, i32>(15)?,
-                row.get::<_, Option<i64>>(16)?,
-                row.get::<_, i32>(17)?,
-                row.get::<_, Option<i64>>(18)?,
-                row.get::<_, i64>(19)?,
-                row.get::<_, i64>(20)?,
-            ))
-        })?;
-
-        let mut sessions = Vec::new();
-        for row in rows {
-            let (
-                id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
-                auth_method_encrypted, auth_nonce, auth_key_salt,
-                server_ver, client_ver, is_dirty, last_synced_at,
-                is_deleted, deleted_at, created_at, updated_at,
-            ) = row?;
-
-            sessions.push(SshSession {
-                id,
-                user_id,
-                name,
-                host,
-                port: port as u16,
-                username,
-                group_name,
-                terminal_type,
-                columns: columns.map(|c| c as u16),
-                rows: rows.map(|r| r as u16),
-                auth_method_encrypted,
-                auth_nonce,
-                auth_key_salt,
-                server_ver,
-                client_ver,
-                is_dirty: is_dirty != 0,
-                last_synced_at,
-                is_deleted: is_deleted != 0,
-                deleted_at,
-                created_at,
-                updated_at,
-            });
-        }
+        let sessions = stmt
+            .query_map([user_id], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         tracing::info!("[find_by_user] Found {} sessions", sessions.len());
         Ok(sessions)
@@ -232,7 +188,7 @@ impl SshSessionRepository {
         let mut stmt = conn.prepare(
             "SELECT
                 id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
+                terminal_type, columns, rows, jump_hosts, port_forwards, connection_type, persistent_shell, reconnect_policy,
                 auth_method_encrypted, auth_nonce, auth_key_salt,
                 server_ver, client_ver, is_dirty, last_synced_at,
                 is_deleted, deleted_at, created_at, updated_at
@@ -240,66 +196,9 @@ impl SshSessionRepository {
             WHERE user_id = ?1 AND is_dirty = 1 AND is_deleted = 0"
         )?;
 
-        let rows = stmt.query_map([user_id], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, i32>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, Option<String>>(7)?,
-                row.get::<_, Option<i32>>(8)?,
-                row.get::<_, Option<i32>>(9)?,
-                row.get::<_, String>(10)?,
-                row.get::<_, String>(11)?,
-                row.get::<_, Option<String>>(12)?,
-                row.get::<_, i32>(13)?,
-                row.get::<_, i32>(14)?,
-                row.get::<_, i32>(15)?,
-                row.get::<_, Option<i64>>(16)?,
-                row.get::<_, i32>(17)?,
-                row.get::<_, Option<i64>>(18)?,
-                row.get::<_, i64>(19)?,
-                row.get::<_, i64>(20)?,
-            ))
-        })?;
-
-        let mut sessions = Vec::new();
-        for row in rows {
-            let (
-                id, user_id, name, host, port, username, group_name,
-                terminal_type, columns, rows,
-                auth_method_encrypted, auth_nonce, auth_key_salt,
-                server_ver, client_ver, is_dirty, last_synced_at,
-                is_deleted, deleted_at, created_at, updated_at,
-            ) = row?;
-
-            sessions.push(SshSession {
-                id,
-                user_id,
-                name,
-                host,
-                port: port as u16,
-                username,
-                group_name,
-                terminal_type,
-                columns: columns.map(|c| c as u16),
-                rows: rows.map(|r| r as u16),
-                auth_method_encrypted,
-                auth_nonce,
-                auth_key_salt,
-                server_ver,
-                client_ver,
-                is_dirty: is_dirty != 0,
-                last_synced_at,
-                is_deleted: is_deleted != 0,
-                deleted_at,
-                created_at,
-                updated_at,
-            });
-        }
+        let sessions = stmt
+            .query_map([user_id], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(sessions)
     }
@@ -429,7 +328,9 @@ impl SshSessionRepository {
     }
 
     /// 将数据库行转换为 SshSession
-    fn row_to_session(&self, row: &rusqlite::Row) -> Result<SshSession> {
+    ///
+    /// 列顺序与查询语句中的字段列表一致
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<SshSession> {
         Ok(SshSession {
             id: row.get(0)?,
             user_id: row.get(1)?,
@@ -441,17 +342,82 @@ impl SshSessionRepository {
             terminal_type: row.get(7)?,
             columns: row.get::<_, Option<i32>>(8)?.map(|c| c as u16),
             rows: row.get::<_, Option<i32>>(9)?.map(|r| r as u16),
-            auth_method_encrypted: row.get(10)?,
-            auth_nonce: row.get(11)?,
-            auth_key_salt: row.get(12)?,
-            server_ver: row.get(13)?,
-            client_ver: row.get(14)?,
-            is_dirty: row.get::<_, i32>(15)? != 0,
-            last_synced_at: row.get(16)?,
-            is_deleted: row.get::<_, i32>(17)? != 0,
-            deleted_at: row.get(18)?,
-            created_at: row.get(19)?,
-            updated_at: row.get(20)?,
+            jump_hosts: decode_json_list(row.get(10)?),
+            port_forwards: decode_json_list(row.get(11)?),
+            connection_type: decode_connection_type(row.get(12)?),
+            persistent_shell: decode_persistent_shell(row.get(13)?),
+            reconnect: decode_reconnect_policy(row.get(14)?),
+            auth_method_encrypted: row.get(15)?,
+            auth_nonce: row.get(16)?,
+            auth_key_salt: row.get(17)?,
+            server_ver: row.get(18)?,
+            client_ver: row.get(19)?,
+            is_dirty: row.get::<_, i32>(20)? != 0,
+            last_synced_at: row.get(21)?,
+            is_deleted: row.get::<_, i32>(22)? != 0,
+            deleted_at: row.get(23)?,
+            created_at: row.get(24)?,
+            updated_at: row.get(25)?,
         })
     }
 }
+
+/// 将列表编码为 JSON（空列表存储为 NULL）
+fn encode_json_list<T: serde::Serialize>(items: &[T]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        serde_json::to_string(items).ok()
+    }
+}
+
+/// 解析 JSON 列表，无法解析时视为空
+fn decode_json_list<T: serde::de::DeserializeOwned>(json: Option<String>) -> Vec<T> {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// 编码连接类型（SSH 存储为 NULL，兼容旧数据）
+fn encode_connection_type(connection_type: &ConnectionType) -> Option<String> {
+    if connection_type.is_ssh() {
+        None
+    } else {
+        serde_json::to_string(connection_type).ok()
+    }
+}
+
+/// 解析连接类型，为空或无法解析时视为 SSH
+fn decode_connection_type(json: Option<String>) -> ConnectionType {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// 编码会话保持设置（未修改过的默认设置存储为 NULL）
+fn encode_persistent_shell(persistent_shell: &PersistentShell) -> Option<String> {
+    if *persistent_shell == PersistentShell::default() {
+        None
+    } else {
+        serde_json::to_string(persistent_shell).ok()
+    }
+}
+
+/// 解析会话保持设置，为空或无法解析时视为未启用
+fn decode_persistent_shell(json: Option<String>) -> PersistentShell {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// 编码重连策略（默认策略存储为 NULL）
+fn encode_reconnect_policy(reconnect: &ReconnectPolicy) -> Option<String> {
+    if *reconnect == ReconnectPolicy::default() {
+        None
+    } else {
+        serde_json::to_string(reconnect).ok()
+    }
+}
+
+/// 解析重连策略，为空或无法解析时使用默认策略
+fn decode_reconnect_policy(json: Option<String>) -> ReconnectPolicy {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
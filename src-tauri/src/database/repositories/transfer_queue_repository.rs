@@ -0,0 +1,335 @@
+use anyhow::Result;
+use r2d2::PooledConnection;
+use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
+
+use crate::database::DbPool;
+use crate::sftp::{TransferOperation, TransferProgress, TransferSource, TransferStatus};
+
+/// 传输队列 Repository
+pub struct TransferQueueRepository {
+    pool: DbPool,
+}
+
+impl TransferQueueRepository {
+    /// 创建新的 Repository 实例
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取数据库连接
+    fn get_conn(&self) -> Result<PooledConnection<SqliteConnectionManager>> {
+        self.pool
+            .get()
+            .map_err(|e| anyhow::anyhow!("Failed to get database connection: {}", e))
+    }
+
+    /// 添加队列项
+    pub fn create(&self, item: &TransferProgress) -> Result<TransferProgress> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "INSERT INTO transfer_queue (
+                id, operation, source, destination, is_dir, priority, status, error_message,
+                file_size, transferred, speed, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            (
+                &item.id,
+                item.operation.as_str(),
+                serde_json::to_string(&item.source)?,
+                serde_json::to_string(&item.destination)?,
+                item.is_dir as i32,
+                item.priority,
+                item.status.as_str(),
+                Self::error_message(&item.status),
+                item.file_size as i64,
+                item.transferred as i64,
+                item.speed as i64,
+                item.created_at,
+                item.updated_at,
+            ),
+        )?;
+
+        Ok(item.clone())
+    }
+
+    /// 更新队列项（传输两端、优先级、状态和进度）
+    pub fn update(&self, item: &TransferProgress) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE transfer_queue SET
+                source = ?1, destination = ?2, priority = ?3, status = ?4, error_message = ?5,
+                file_size = ?6, transferred = ?7, speed = ?8, updated_at = ?9
+            WHERE id = ?10",
+            (
+                serde_json::to_string(&item.source)?,
+                serde_json::to_string(&item.destination)?,
+                item.priority,
+                item.status.as_str(),
+                Self::error_message(&item.status),
+                item.file_size as i64,
+                item.transferred as i64,
+                item.speed as i64,
+                item.updated_at,
+                &item.id,
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    /// 删除队列项
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+        conn.execute("DELETE FROM transfer_queue WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// 删除所有已结束的队列项，返回删除数量
+    pub fn delete_finished(&self) -> Result<usize> {
+        let conn = self.get_conn()?;
+        let count = conn.execute(
+            "DELETE FROM transfer_queue WHERE status IN ('completed', 'failed', 'cancelled')",
+            [],
+        )?;
+        Ok(count)
+    }
+
+    /// 根据 ID 获取队列项
+    pub fn find_by_id(&self, id: &str) -> Result<Option<TransferProgress>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, operation, source, destination, is_dir, priority, status, error_message,
+                file_size, transferred, speed, created_at, updated_at
+            FROM transfer_queue
+            WHERE id = ?1"
+        )?;
+
+        let mut rows = stmt.query([id])?;
+
+        if let Some(row) = rows.next()? {
+            Ok(Some(Self::map_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 获取全部队列项（按创建时间升序）
+    pub fn find_all(&self) -> Result<Vec<TransferProgress>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, operation, source, destination, is_dir, priority, status, error_message,
+                file_size, transferred, speed, created_at, updated_at
+            FROM transfer_queue
+            ORDER BY created_at ASC, rowid ASC"
+        )?;
+
+        let items = stmt
+            .query_map([], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// 获取等待执行的队列项（优先级高的在前，同优先级按入队顺序）
+    pub fn find_pending(&self) -> Result<Vec<TransferProgress>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                id, operation, source, destination, is_dir, priority, status, error_message,
+                file_size, transferred, speed, created_at, updated_at
+            FROM transfer_queue
+            WHERE status = 'pending'
+            ORDER BY priority DESC, created_at ASC, rowid ASC"
+        )?;
+
+        let items = stmt
+            .query_map([], Self::map_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// 将执行中的队列项重新置为等待（应用启动时调用，上次退出时中断的任务重新排队）
+    pub fn requeue_in_progress(&self) -> Result<usize> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+        let count = conn.execute(
+            "UPDATE transfer_queue SET status = 'pending', updated_at = ?1 WHERE status = 'inProgress'",
+            [now],
+        )?;
+        Ok(count)
+    }
+
+    fn error_message(status: &TransferStatus) -> Option<&str> {
+        match status {
+            TransferStatus::Failed { reason } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// 将数据库行转换为 TransferProgress
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<TransferProgress> {
+        let operation: String = row.get(1)?;
+        let source: String = row.get(2)?;
+        let destination: String = row.get(3)?;
+        let status: String = row.get(6)?;
+
+        Ok(TransferProgress {
+            id: row.get(0)?,
+            operation: TransferOperation::parse(&operation).ok_or_else(|| invalid_value(1, &operation))?,
+            source: parse_source(2, &source)?,
+            destination: parse_source(3, &destination)?,
+            is_dir: row.get::<_, i32>(4)? != 0,
+            priority: row.get(5)?,
+            status: TransferStatus::parse(&status, row.get(7)?).ok_or_else(|| invalid_value(6, &status))?,
+            file_size: row.get::<_, i64>(8)? as u64,
+            transferred: row.get::<_, i64>(9)? as u64,
+            speed: row.get::<_, i64>(10)? as u64,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
+        })
+    }
+}
+
+fn parse_source(column: usize, value: &str) -> rusqlite::Result<TransferSource> {
+    serde_json::from_str(value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn invalid_value(column: usize, value: &str) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        column,
+        rusqlite::types::Type::Text,
+        format!("invalid value: {}", value).into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository() -> TransferQueueRepository {
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::memory())
+            .unwrap();
+        crate::database::schema::init_schema(&pool.get().unwrap()).unwrap();
+        TransferQueueRepository::new(pool)
+    }
+
+    fn item(id: &str, priority: i32, created_at: i64) -> TransferProgress {
+        TransferProgress {
+            id: id.to_string(),
+            operation: TransferOperation::Upload,
+            source: TransferSource::Local { path: format!("/tmp/{}", id) },
+            destination: TransferSource::Remote {
+                connection_id: "conn".to_string(),
+                session_id: Some("session".to_string()),
+                path: format!("/home/{}", id),
+            },
+            is_dir: false,
+            priority,
+            file_size: 10,
+            transferred: 0,
+            speed: 0,
+            status: TransferStatus::Pending,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    fn pending_ids(repo: &TransferQueueRepository) -> Vec<String> {
+        repo.find_pending().unwrap().into_iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn test_find_pending_by_priority() {
+        let repo = repository();
+        repo.create(&item("low", 0, 1)).unwrap();
+        repo.create(&item("high", 5, 3)).unwrap();
+        repo.create(&item("low-later", 0, 2)).unwrap();
+        repo.create(&item("high-same-time", 5, 3)).unwrap();
+
+        let mut paused = item("paused", 9, 0);
+        paused.status = TransferStatus::Paused;
+        repo.create(&paused).unwrap();
+
+        // 优先级高的在前，同优先级按入队顺序，非等待中的不返回
+        assert_eq!(pending_ids(&repo), ["high", "high-same-time", "low", "low-later"]);
+    }
+
+    #[test]
+    fn test_requeue_in_progress() {
+        let repo = repository();
+        let mut running = item("running", 0, 1);
+        running.status = TransferStatus::InProgress;
+        running.transferred = 4;
+        repo.create(&running).unwrap();
+
+        let mut failed = item("failed", 0, 2);
+        failed.status = TransferStatus::Failed { reason: "断开".to_string() };
+        repo.create(&failed).unwrap();
+
+        // 上次退出时中断的任务重新排队，保留已传输的进度
+        assert_eq!(repo.requeue_in_progress().unwrap(), 1);
+        assert_eq!(pending_ids(&repo), ["running"]);
+        assert_eq!(repo.find_by_id("running").unwrap().unwrap().transferred, 4);
+        assert_eq!(
+            repo.find_by_id("failed").unwrap().unwrap().status,
+            TransferStatus::Failed { reason: "断开".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_restore_after_restart() {
+        let dir = std::env::temp_dir().join(format!("transfer-queue-restart-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let open = || {
+            let pool = r2d2::Pool::builder()
+                .max_size(1)
+                .build(SqliteConnectionManager::file(dir.join("queue.db")))
+                .unwrap();
+            crate::database::schema::init_schema(&pool.get().unwrap()).unwrap();
+            TransferQueueRepository::new(pool)
+        };
+
+        {
+            let repo = open();
+            repo.create(&item("waiting", 0, 1)).unwrap();
+
+            let mut running = item("running", 0, 2);
+            running.status = TransferStatus::InProgress;
+            running.transferred = 6;
+            repo.create(&running).unwrap();
+
+            let mut paused = item("paused", 0, 3);
+            paused.status = TransferStatus::Paused;
+            paused.transferred = 3;
+            repo.create(&paused).unwrap();
+        }
+
+        // 重新打开数据库，模拟应用重启
+        let repo = open();
+        assert_eq!(repo.find_all().unwrap().len(), 3);
+        assert_eq!(repo.requeue_in_progress().unwrap(), 1);
+        assert_eq!(pending_ids(&repo), ["waiting", "running"]);
+        assert_eq!(repo.find_by_id("running").unwrap().unwrap().transferred, 6);
+
+        // 暂停的任务保持暂停，恢复后重新排队并保留进度
+        let mut paused = repo.find_by_id("paused").unwrap().unwrap();
+        assert_eq!(paused.status, TransferStatus::Paused);
+        paused.status = TransferStatus::Pending;
+        repo.update(&paused).unwrap();
+        assert_eq!(pending_ids(&repo), ["waiting", "running", "paused"]);
+        assert_eq!(repo.find_by_id("paused").unwrap().unwrap().transferred, 3);
+
+        drop(repo);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
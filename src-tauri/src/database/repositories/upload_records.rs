@@ -64,6 +64,11 @@ pub struct UploadRecord {
     pub error_message: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// 续传起点（字节）
+    pub resume_offset: i64,
+    /// 服务器之间传输时的源连接 ID，此时 local_path 为源服务器上的路径
+    #[serde(default)]
+    pub source_connection_id: Option<String>,
 }
 
 /// 分页结果
@@ -91,8 +96,8 @@ impl UploadRecordsRepository {
                 total_files, total_dirs, total_size, status,
                 bytes_transferred, files_completed, started_at,
                 completed_at, elapsed_ms, error_message,
-                created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+                created_at, updated_at, resume_offset, source_connection_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
             rusqlite::params![
                 &record.task_id,
                 &record.connection_id,
@@ -111,6 +116,8 @@ impl UploadRecordsRepository {
                 record.error_message.as_ref().map(|s| s.as_str()),
                 record.created_at,
                 record.updated_at,
+                record.resume_offset,
+                record.source_connection_id.as_deref(),
             ],
         )?;
 
@@ -137,6 +144,16 @@ impl UploadRecordsRepository {
         Ok(())
     }
 
+    /// 记录续传起点（同时作为已传输字节数）
+    pub fn update_resume_offset(conn: &Connection, task_id: &str, resume_offset: i64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "UPDATE upload_records SET resume_offset = ?1, bytes_transferred = ?1, updated_at = ?2 WHERE task_id = ?3",
+            rusqlite::params![resume_offset, now, task_id],
+        )?;
+        Ok(())
+    }
+
     /// 标记完成
     pub fn mark_completed(conn: &Connection, task_id: &str, elapsed_ms: i64, bytes_transferred: i64, files_completed: i64) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
@@ -178,28 +195,8 @@ impl UploadRecordsRepository {
 
         let records: Result<Vec<UploadRecord>, _> = stmt.query_map(
             rusqlite::params![user_id, page_size as i64, offset as i64],
-        |row| {
-            Ok(UploadRecord {
-                id: row.get(0)?,
-                task_id: row.get(1)?,
-                connection_id: row.get(2)?,
-                user_id: row.get(3)?,
-                local_path: row.get(4)?,
-                remote_path: row.get(5)?,
-                total_files: row.get(6)?,
-                total_dirs: row.get(7)?,
-                total_size: row.get(8)?,
-                status: row.get(9)?,
-                bytes_transferred: row.get(10)?,
-                files_completed: row.get(11)?,
-                started_at: row.get(12)?,
-                completed_at: row.get(13)?,
-                elapsed_ms: row.get(14)?,
-                error_message: row.get(15)?,
-                created_at: row.get(16)?,
-                updated_at: row.get(17)?,
-            })
-        })?.collect();
+            Self::map_row,
+        )?.collect();
 
         Ok(PaginatedUploadRecords {
             records: records?,
@@ -209,6 +206,13 @@ impl UploadRecordsRepository {
         })
     }
 
+    /// 根据 task_id 查询记录
+    pub fn find_by_task_id(conn: &Connection, task_id: &str) -> Result<Option<UploadRecord>> {
+        let mut stmt = conn.prepare("SELECT * FROM upload_records WHERE task_id = ?1")?;
+        let mut rows = stmt.query_map([task_id], Self::map_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
     /// 删除记录
     pub fn delete(conn: &Connection, id: i64) -> Result<()> {
         conn.execute("DELETE FROM upload_records WHERE id = ?1", [id])?;
@@ -267,4 +271,30 @@ impl UploadRecordsRepository {
         tracing::info!("Successfully migrated {} upload records", updated_count);
         Ok(updated_count)
     }
-}
\ No newline at end of file
+
+    /// 将数据库行转换为 UploadRecord（字段顺序与建表语句一致，resume_offset、source_connection_id 为后加字段）
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<UploadRecord> {
+        Ok(UploadRecord {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            connection_id: row.get(2)?,
+            user_id: row.get(3)?,
+            local_path: row.get(4)?,
+            remote_path: row.get(5)?,
+            total_files: row.get(6)?,
+            total_dirs: row.get(7)?,
+            total_size: row.get(8)?,
+            status: row.get(9)?,
+            bytes_transferred: row.get(10)?,
+            files_completed: row.get(11)?,
+            started_at: row.get(12)?,
+            completed_at: row.get(13)?,
+            elapsed_ms: row.get(14)?,
+            error_message: row.get(15)?,
+            created_at: row.get(16)?,
+            updated_at: row.get(17)?,
+            resume_offset: row.get(18)?,
+            source_connection_id: row.get(19)?,
+        })
+    }
+}
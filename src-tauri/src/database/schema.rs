@@ -62,6 +62,11 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
             terminal_type TEXT,
             columns INTEGER,
             rows INTEGER,
+            jump_hosts TEXT, -- 跳板机链（JSON 数组，元素为会话 ID）
+            port_forwards TEXT, -- 端口转发规则（JSON 数组，仅本地保存，不同步）
+            connection_type TEXT, -- 连接类型（JSON 对象，SSH 为 NULL）
+            persistent_shell TEXT, -- tmux/screen 会话保持设置（JSON 对象，仅本地保存，不同步）
+            reconnect_policy TEXT, -- 断线重连策略（JSON 对象，默认策略为 NULL，仅本地保存，不同步）
 
             -- 认证信息（完整同步到服务器，使用端到端加密）
             -- 注意：auth_method_encrypted 在客户端使用用户密钥加密后上传
@@ -87,6 +92,32 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_ssh_sessions_group ON ssh_sessions(group_name);
         CREATE INDEX IF NOT EXISTS idx_ssh_sessions_is_deleted ON ssh_sessions(is_deleted);
 
+        -- ==========================================
+        -- SSH 密钥表（密钥管理器，仅本地保存）
+        -- ==========================================
+        CREATE TABLE IF NOT EXISTS ssh_keys (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            algorithm TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+
+            -- OpenSSH 格式私钥，使用 device_id 派生的密钥加密（同一用户各设备相同，随云同步）
+            private_key_encrypted TEXT NOT NULL,
+            private_key_nonce TEXT NOT NULL,
+            has_passphrase BOOLEAN DEFAULT 0,
+
+            -- 同步控制
+            is_dirty BOOLEAN DEFAULT 1,
+            is_deleted BOOLEAN DEFAULT 0,
+
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_ssh_keys_user_id ON ssh_keys(user_id);
+
         -- ==========================================
         -- 同步状态表（支持多用户）
         -- ==========================================
@@ -113,7 +144,10 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
             sync_interval_minutes INTEGER DEFAULT 5,
             theme TEXT DEFAULT 'system',
             language TEXT DEFAULT 'zh-CN',
-            updated_at INTEGER NOT NULL
+            updated_at INTEGER NOT NULL,
+            transfer_max_concurrent INTEGER NOT NULL DEFAULT 4,
+            transfer_max_per_connection INTEGER NOT NULL DEFAULT 2,
+            transfer_pipeline_window INTEGER NOT NULL DEFAULT 64
         );
 
         -- 初始化默认配置（default_server_url 为 NULL，需要用户首次使用时设置）
@@ -153,7 +187,13 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
             error_message TEXT,
 
             created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL
+            updated_at INTEGER NOT NULL,
+
+            -- 续传起点（字节），失败或取消后按 task_id 续传时使用
+            resume_offset INTEGER NOT NULL DEFAULT 0,
+
+            -- 服务器之间传输时的源连接 ID，此时 local_path 为源服务器上的路径
+            source_connection_id TEXT
         );
 
         CREATE INDEX IF NOT EXISTS idx_upload_records_connection_id ON upload_records(connection_id);
@@ -193,17 +233,89 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
             error_message TEXT,
 
             created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL
+            updated_at INTEGER NOT NULL,
+
+            -- 续传起点（字节），失败或取消后按 task_id 续传时使用
+            resume_offset INTEGER NOT NULL DEFAULT 0
         );
 
         CREATE INDEX IF NOT EXISTS idx_download_records_connection_id ON download_records(connection_id);
         CREATE INDEX IF NOT EXISTS idx_download_records_user_id ON download_records(user_id);
         CREATE INDEX IF NOT EXISTS idx_download_records_status ON download_records(status);
         CREATE INDEX IF NOT EXISTS idx_download_records_created_at ON download_records(created_at DESC);
+
+        -- ==========================================
+        -- 传输队列表
+        -- ==========================================
+        CREATE TABLE IF NOT EXISTS transfer_queue (
+            id TEXT PRIMARY KEY,             -- 同时作为上传/下载记录的 task_id
+            operation TEXT NOT NULL,         -- 'upload', 'download', 'remoteToRemote'
+
+            -- 传输两端（JSON 格式的 TransferSource）
+            source TEXT NOT NULL,
+            destination TEXT NOT NULL,
+            is_dir BOOLEAN NOT NULL DEFAULT 0,
+
+            -- 调度
+            priority INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL,            -- 'pending', 'inProgress', 'paused', 'completed', 'failed', 'cancelled'
+            error_message TEXT,
+
+            -- 进度
+            file_size INTEGER NOT NULL DEFAULT 0,
+            transferred INTEGER NOT NULL DEFAULT 0,
+            speed INTEGER NOT NULL DEFAULT 0,
+
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_transfer_queue_status ON transfer_queue(status);
         ",
     )?;
 
+    migrate_schema(conn)?;
+
     tracing::info!("Database schema initialized successfully");
 
+    Ok(())
+}
+
+/// 为已有数据库补充新增字段
+///
+/// CREATE TABLE IF NOT EXISTS 不会修改已存在的表，新增字段需要同时在这里登记
+fn migrate_schema(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "ssh_sessions", "jump_hosts", "TEXT")?;
+    add_column_if_missing(conn, "ssh_sessions", "port_forwards", "TEXT")?;
+    add_column_if_missing(conn, "ssh_sessions", "connection_type", "TEXT")?;
+    add_column_if_missing(conn, "ssh_sessions", "persistent_shell", "TEXT")?;
+    add_column_if_missing(conn, "ssh_sessions", "reconnect_policy", "TEXT")?;
+    add_column_if_missing(conn, "upload_records", "resume_offset", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "download_records", "resume_offset", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "app_settings", "transfer_max_concurrent", "INTEGER NOT NULL DEFAULT 4")?;
+    add_column_if_missing(conn, "app_settings", "transfer_max_per_connection", "INTEGER NOT NULL DEFAULT 2")?;
+    add_column_if_missing(conn, "app_settings", "transfer_pipeline_window", "INTEGER NOT NULL DEFAULT 64")?;
+    add_column_if_missing(conn, "upload_records", "source_connection_id", "TEXT")?;
+    add_column_if_missing(conn, "ssh_keys", "is_dirty", "BOOLEAN DEFAULT 1")?;
+    add_column_if_missing(conn, "ssh_keys", "is_deleted", "BOOLEAN DEFAULT 0")?;
+
+    Ok(())
+}
+
+/// 字段不存在时添加字段
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let columns = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    if !columns.iter().any(|name| name == column) {
+        tracing::info!("Adding column {}.{}", table, column);
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
     Ok(())
 }
\ No newline at end of file
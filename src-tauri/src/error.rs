@@ -34,14 +34,92 @@ pub enum SSHError {
 
     #[error("存储错误: {0}")]
     Storage(String),
+
+    #[error("主机密钥验证失败: {host}:{port} 的主机密钥已变更（已保存指纹: {expected_fingerprint}，服务器指纹: {actual_fingerprint}），可能存在中间人攻击")]
+    HostKeyMismatch {
+        host: String,
+        port: u16,
+        expected_fingerprint: String,
+        actual_fingerprint: String,
+    },
+
+    #[error("未知主机: {host}:{port} 的主机密钥尚未被信任（指纹: {fingerprint}），确认指纹后才能连接")]
+    HostKeyUnknown {
+        host: String,
+        port: u16,
+        fingerprint: String,
+        /// 服务器出示的公钥（OpenSSH 格式），用户确认指纹后写入 known_hosts
+        public_key: String,
+    },
+
+    #[error("主机密钥已被吊销: {host}:{port} ({fingerprint})")]
+    HostKeyRevoked {
+        host: String,
+        port: u16,
+        fingerprint: String,
+    },
+
+    #[error("不支持主机证书: {host}:{port} 在 known_hosts 中只通过 @cert-authority 信任，当前无法验证主机证书，请将该主机的主机密钥（指纹: {fingerprint}）加入 known_hosts")]
+    HostCertificateUnsupported {
+        host: String,
+        port: u16,
+        fingerprint: String,
+    },
+}
+
+/// 主机密钥错误返回给前端的结构化数据
+///
+/// 前端根据 `kind` 区分未知主机（确认指纹后信任）、密钥变更（中间人攻击警告）和已吊销，
+/// 不需要解析本地化的错误文本
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HostKeyErrorPayload<'a> {
+    kind: &'static str,
+    message: String,
+    host: &'a str,
+    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_fingerprint: Option<&'a str>,
+    actual_fingerprint: &'a str,
 }
 
 impl serde::Serialize for SSHError {
+    /// 主机密钥错误序列化为对象，其他错误序列化为错误信息字符串
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let (kind, host, port, expected_fingerprint, actual_fingerprint) = match self {
+            SSHError::HostKeyUnknown { host, port, fingerprint, .. } => {
+                ("hostKeyUnknown", host, *port, None, fingerprint)
+            }
+            SSHError::HostKeyMismatch {
+                host,
+                port,
+                expected_fingerprint,
+                actual_fingerprint,
+            } => (
+                "hostKeyMismatch",
+                host,
+                *port,
+                Some(expected_fingerprint.as_str()),
+                actual_fingerprint,
+            ),
+            SSHError::HostKeyRevoked { host, port, fingerprint } => {
+                ("hostKeyRevoked", host, *port, None, fingerprint)
+            }
+            _ => return serializer.serialize_str(&self.to_string()),
+        };
+
+        let payload = HostKeyErrorPayload {
+            kind,
+            message: self.to_string(),
+            host,
+            port,
+            expected_fingerprint,
+            actual_fingerprint,
+        };
+        serde::Serialize::serialize(&payload, serializer)
     }
 }
 
@@ -13,14 +13,16 @@ mod types;
 
 use commands::session::SSHManagerState;
 use commands::sftp::SftpManagerState;
+use commands::transfer_queue::TransferQueueState;
 use commands::auth::ApiClientStateWrapper;
 use ssh::manager::SSHManager;
 use sftp::manager::SftpManager;
+use sftp::queue::TransferQueue;
 use std::sync::Arc;
 use tauri::Manager;
 
 use crate::database::repositories::{UserAuthRepository, AppSettingsRepository};
-use crate::services::{ApiClient, CryptoService};
+use crate::services::{ApiClient, CryptoService, KeyService};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -142,13 +144,30 @@ pub fn run() {
                 tracing::info!("No current user found, skipping API client initialization");
             }
 
-            // 初始化SSH管理器，传入AppHandle
-            let ssh_manager = Arc::new(SSHManager::new(app.handle().clone()));
+            // 初始化SSH管理器，传入AppHandle和托管密钥存储
+            let mut ssh_manager = SSHManager::new(app.handle().clone());
+            ssh_manager.set_key_store(Arc::new(KeyService::new(db_pool_for_init.clone())));
+            let ssh_manager = Arc::new(ssh_manager);
             app.manage(ssh_manager.clone() as SSHManagerState);
 
             // 初始化SFTP管理器
-            let sftp_manager = Arc::new(SftpManager::new(ssh_manager));
-            app.manage(sftp_manager as SftpManagerState);
+            let sftp_manager = SftpManager::new(ssh_manager.clone());
+            match AppSettingsRepository::new(db_pool_for_init.clone()).get_pipeline_window() {
+                Ok(window) => sftp_manager.set_pipeline_window(window.max(1) as usize),
+                Err(e) => tracing::warn!("Failed to load SFTP pipeline window, using default: {}", e),
+            }
+            let sftp_manager = Arc::new(sftp_manager);
+            app.manage(sftp_manager.clone() as SftpManagerState);
+
+            // 初始化传输队列，继续执行上次未完成的任务
+            let transfer_queue = TransferQueue::new(
+                db_pool_for_init.clone(),
+                app.handle().clone(),
+                ssh_manager,
+                sftp_manager,
+            );
+            transfer_queue.start();
+            app.manage(transfer_queue as TransferQueueState);
 
             // 初始化音频捕获器状态
             let audio_capturer = commands::audio::AudioCapturerState {
@@ -172,8 +191,21 @@ pub fn run() {
             // Session 会话管理命令
             commands::session_create,
             commands::session_create_temp,
+            commands::session_create_local,
+            commands::session_create_serial,
+            commands::session_serial_ports,
             commands::session_create_with_id,
             commands::session_connect,
+            commands::session_keyboard_interactive_respond,
+            commands::session_trust_host_key,
+            commands::session_port_forward_list,
+            commands::session_port_forward_add,
+            commands::session_port_forward_remove,
+            commands::session_port_forward_toggle,
+            commands::session_duplicate,
+            commands::session_replay_output,
+            commands::session_exec,
+            commands::session_exec_cancel,
             commands::session_disconnect,
             commands::session_list,
             commands::session_get,
@@ -186,6 +218,20 @@ pub fn run() {
             commands::db_ssh_session_list,
             commands::db_ssh_session_get_by_id,
             commands::db_ssh_session_migrate_to_user,
+            commands::db_ssh_session_import_ssh_config,
+            commands::db_ssh_session_import_file,
+            commands::db_ssh_session_export_ssh_config,
+            commands::db_ssh_session_export_bundle,
+            commands::db_ssh_session_import_bundle,
+            // 密钥管理命令
+            commands::db_ssh_key_list,
+            commands::db_ssh_key_generate,
+            commands::db_ssh_key_import,
+            commands::db_ssh_key_rename,
+            commands::db_ssh_key_change_passphrase,
+            commands::db_ssh_key_delete,
+            commands::db_ssh_key_migrate_to_user,
+            commands::db_ssh_session_deploy_key,
             // Terminal 终端命令
             commands::terminal_write,
             commands::terminal_resize,
@@ -215,13 +261,33 @@ pub fn run() {
             commands::sftp_download_file,
             commands::sftp_download_directory,
             commands::sftp_cancel_download,
+            commands::sftp_resume_download,
             commands::sftp_upload_file,
             commands::sftp_upload_directory,
             commands::sftp_cancel_upload,
+            commands::sftp_resume_upload,
+            commands::sftp_copy_remote,
+            commands::sftp_get_pipeline_window,
+            commands::sftp_set_pipeline_window,
             commands::local_list_dir,
             commands::local_home_dir,
             commands::local_available_drives,
             commands::local_drive_root,
+            // 传输队列命令
+            commands::transfer_queue_enqueue,
+            commands::transfer_queue_list,
+            commands::transfer_queue_pause,
+            commands::transfer_queue_resume,
+            commands::transfer_queue_cancel,
+            commands::transfer_queue_remove,
+            commands::transfer_queue_set_priority,
+            commands::transfer_queue_clear_finished,
+            commands::transfer_queue_get_limits,
+            commands::transfer_queue_set_limits,
+            // 目录同步命令
+            commands::sftp_sync_plan,
+            commands::sftp_sync_apply,
+            commands::sftp_sync_cancel,
             // Recording 录制命令
             commands::recording_save,
             commands::recording_load,
@@ -2,7 +2,8 @@
 pub mod user_auth;
 pub mod user_profile;
 pub mod ssh_session;
+pub mod ssh_key;
 pub mod sync;
 
 pub use ssh_session::*;
-// 注意: user_auth、user_profile 和 sync 通过完整路径引用，避免污染命名空间
+// 注意: user_auth、user_profile、ssh_key 和 sync 通过完整路径引用，避免污染命名空间
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// 托管 SSH 密钥（用于本地数据库）
+///
+/// 私钥为 OpenSSH 格式（设置了密码时已用密码加密），再用 device_id 派生的密钥加密存储；
+/// device_id 由服务器按用户分配，同一用户的各设备相同，因此密钥可以随云同步
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKey {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// 算法名称（ssh-ed25519、ecdsa-sha2-nistp256、ssh-rsa 等）
+    pub algorithm: String,
+    /// SHA256 指纹
+    pub fingerprint: String,
+    /// 公钥（authorized_keys 格式）
+    pub public_key: String,
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    /// 私钥是否设置了密码
+    pub has_passphrase: bool,
+    /// 是否有未同步的修改
+    pub is_dirty: bool,
+    /// 是否已删除（等待同步删除）
+    pub is_deleted: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 返回给前端的密钥信息（不含私钥）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub has_passphrase: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<SshKey> for SshKeyInfo {
+    fn from(key: SshKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            algorithm: key.algorithm,
+            fingerprint: key.fingerprint,
+            public_key: key.public_key,
+            has_passphrase: key.has_passphrase,
+            created_at: key.created_at,
+            updated_at: key.updated_at,
+        }
+    }
+}
+
+/// 服务器返回的托管密钥（snake_case 格式）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerSshKey {
+    pub id: String,
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    pub has_passphrase: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub deleted_at: Option<i64>,
+}
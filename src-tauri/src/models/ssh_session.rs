@@ -1,3 +1,5 @@
+use crate::ssh::forwarding::PortForwardRule;
+use crate::ssh::session::{ConnectionType, PersistentShell, ReconnectPolicy};
 use serde::{Deserialize, Serialize};
 
 // ==================== 服务器返回类型（snake_case 格式）====================
@@ -17,6 +19,12 @@ pub struct ServerSshSession {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    /// 跳板机链（旧版本服务器不返回此字段）
+    #[serde(default)]
+    pub jump_hosts: Vec<String>,
+    /// 连接类型，SSH 会话为空（旧版本服务器不返回此字段）
+    #[serde(default)]
+    pub connection_type: Option<ConnectionType>,
 
     // 认证信息（加密存储）
     pub auth_method_encrypted: String,
@@ -45,6 +53,20 @@ pub enum AuthMethod {
         private_key_path: String,
         passphrase: Option<String>,
         key_data: Option<String>,
+        /// OpenSSH 用户证书路径（为空时自动查找 `<私钥路径>-cert.pub`）
+        #[serde(default)]
+        certificate_path: Option<String>,
+    },
+    /// ssh-agent 认证（identity 为指纹或注释过滤条件）
+    Agent {
+        #[serde(default)]
+        identity: Option<String>,
+    },
+    /// 密钥管理器中的密钥（按 ID 引用，密钥随会话一起云同步）
+    ManagedKey {
+        key_id: String,
+        #[serde(default)]
+        passphrase: Option<String>,
     },
 }
 
@@ -64,6 +86,21 @@ pub struct SshSession {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    /// 跳板机链：按顺序经过的会话 ID
+    #[serde(default)]
+    pub jump_hosts: Vec<String>,
+    /// 端口转发规则（仅保存在本地，不参与同步）
+    #[serde(default)]
+    pub port_forwards: Vec<PortForwardRule>,
+    /// 连接类型（SSH / Telnet / 原始 TCP 等）
+    #[serde(default)]
+    pub connection_type: ConnectionType,
+    /// tmux/screen 会话保持设置（仅保存在本地，不参与同步）
+    #[serde(default)]
+    pub persistent_shell: PersistentShell,
+    /// 断线重连策略（仅保存在本地，不参与同步）
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
 
     // 认证信息（加密存储）
     pub auth_method_encrypted: String,
@@ -134,6 +171,11 @@ impl From<ServerSshSession> for SshSession {
             terminal_type: server.terminal_type,
             columns: server.columns,
             rows: server.rows,
+            jump_hosts: server.jump_hosts,
+            port_forwards: Vec::new(),
+            connection_type: server.connection_type.unwrap_or_default(),
+            persistent_shell: PersistentShell::default(),
+            reconnect: ReconnectPolicy::default(),
             auth_method_encrypted: server.auth_method_encrypted,
             auth_nonce: server.auth_nonce,
             auth_key_salt: server.auth_key_salt,
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::ssh::session::ConnectionType;
 use std::collections::HashMap;
 
 // ==================== 服务器请求类型（snake_case 格式）====================
@@ -16,6 +17,10 @@ pub struct SyncRequest {
     pub ssh_sessions: Vec<SshSessionPushItem>,
     /// 删除的会话 ID
     pub deleted_session_ids: Vec<String>,
+    /// 托管密钥更新
+    pub ssh_keys: Vec<SshKeyPushItem>,
+    /// 删除的密钥 ID
+    pub deleted_key_ids: Vec<String>,
 }
 
 /// SSH 会话推送项（snake_case 格式，用于与服务器通信）
@@ -30,6 +35,9 @@ pub struct SshSessionPushItem {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    pub jump_hosts: Vec<String>,
+    /// 连接类型，SSH 会话为空
+    pub connection_type: Option<ConnectionType>,
     pub auth_method_encrypted: String,
     pub auth_nonce: String,
     pub auth_key_salt: Option<String>,
@@ -38,6 +46,38 @@ pub struct SshSessionPushItem {
     pub updated_at: i64,
 }
 
+/// 托管密钥推送项（私钥已用 device_id 加密）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshKeyPushItem {
+    pub id: String,
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    pub has_passphrase: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<crate::models::ssh_key::SshKey> for SshKeyPushItem {
+    fn from(key: crate::models::ssh_key::SshKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            algorithm: key.algorithm,
+            fingerprint: key.fingerprint,
+            public_key: key.public_key,
+            private_key_encrypted: key.private_key_encrypted,
+            private_key_nonce: key.private_key_nonce,
+            has_passphrase: key.has_passphrase,
+            created_at: key.created_at,
+            updated_at: key.updated_at,
+        }
+    }
+}
+
 /// 更新用户资料请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateProfileRequest {
@@ -127,9 +167,14 @@ pub struct ServerSyncResponse {
     pub updated_session_ids: Vec<String>,
     pub deleted_session_ids: Vec<String>,
     pub server_versions: HashMap<String, i32>,
+    /// 成功保存的密钥 ID（旧版服务器不返回，密钥保持待同步状态）
+    #[serde(default)]
+    pub updated_key_ids: Vec<String>,
     /// Pull 结果
     pub user_profile: Option<crate::models::user_profile::ServerUserProfile>,
     pub ssh_sessions: Vec<crate::models::ServerSshSession>,
+    #[serde(default)]
+    pub ssh_keys: Vec<crate::models::ssh_key::ServerSshKey>,
     /// 冲突信息
     pub conflicts: Vec<ServerConflictInfo>,
     /// 消息
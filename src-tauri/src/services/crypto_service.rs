@@ -231,6 +231,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encrypt_decrypt_agent_auth_method() {
+        let auth_method = AuthMethod::Agent {
+            identity: Some("SHA256:fqUZsOMHMdkww0YQc5IXGVQmBOl9VXOw28dc0z5mAvA".to_string()),
+        };
+
+        let (encrypted, nonce, salt) =
+            CryptoService::encrypt_auth_method(&auth_method, "my_user_password")
+                .expect("Encryption failed");
+
+        let decrypted =
+            CryptoService::decrypt_auth_method(&encrypted, &nonce, &salt, "my_user_password")
+                .expect("Decryption failed");
+
+        match decrypted {
+            AuthMethod::Agent { identity } => {
+                assert_eq!(
+                    identity.as_deref(),
+                    Some("SHA256:fqUZsOMHMdkww0YQc5IXGVQmBOl9VXOw28dc0z5mAvA")
+                );
+            }
+            _ => panic!("Wrong auth method type"),
+        }
+    }
+
     #[test]
     fn test_encrypt_decrypt_token() {
         let token = "my_access_token";
@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Result};
+use russh::keys::PrivateKey;
+
+use crate::database::repositories::{SshKeyRepository, SshSessionRepository, UserAuthRepository};
+use crate::database::DbPool;
+use crate::models::ssh_key::{SshKey, SshKeyInfo};
+use crate::models::ssh_session::AuthMethod;
+use crate::services::CryptoService;
+use crate::ssh::keys::{self, KeyType, ManagedKeyStore};
+
+/// 未登录用户的固定用户ID
+const ANONYMOUS_USER_ID: &str = "anonymous_local";
+
+/// 未登录用户的固定 device_id（用于本地加密）
+const ANONYMOUS_DEVICE_ID: &str = "ssh-terminal-local-device-v1";
+
+/// 密钥管理服务
+///
+/// 私钥以 OpenSSH 格式保存，外层再用当前用户的 device_id 加密，与会话认证信息一致
+///
+/// device_id 由服务器按用户分配，登录同一账号的设备相同，密钥随会话一起云同步，
+/// 引用托管密钥的会话（`AuthMethod::ManagedKey`）在其他设备上同样可用
+#[derive(Clone)]
+pub struct KeyService {
+    pool: DbPool,
+}
+
+impl KeyService {
+    /// 创建新的密钥管理服务实例
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 当前用户的 (user_id, device_id)，未登录时使用匿名用户
+    fn current_user(&self) -> (String, String) {
+        match UserAuthRepository::new(self.pool.clone()).find_current() {
+            Ok(Some(user)) => (user.user_id, user.device_id),
+            _ => (ANONYMOUS_USER_ID.to_string(), ANONYMOUS_DEVICE_ID.to_string()),
+        }
+    }
+
+    /// 获取当前用户的密钥
+    fn find_owned(&self, id: &str) -> Result<SshKey> {
+        let (user_id, _) = self.current_user();
+        SshKeyRepository::new(self.pool.clone())
+            .find_by_id(id)?
+            .filter(|key| key.user_id == user_id)
+            .ok_or_else(|| anyhow!("密钥不存在: {}", id))
+    }
+
+    /// 解密得到 OpenSSH 格式的私钥（设置了密码时仍是加密的）
+    fn private_key_pem(&self, key: &SshKey) -> Result<String> {
+        let (_, device_id) = self.current_user();
+        CryptoService::decrypt_password(&key.private_key_encrypted, &key.private_key_nonce, &device_id)
+    }
+
+    /// 列出当前用户的密钥
+    pub fn list(&self) -> Result<Vec<SshKeyInfo>> {
+        let (user_id, _) = self.current_user();
+        let keys = SshKeyRepository::new(self.pool.clone()).find_by_user_id(&user_id)?;
+        Ok(keys.into_iter().map(SshKeyInfo::from).collect())
+    }
+
+    /// 生成新密钥并保存
+    pub fn generate(
+        &self,
+        name: &str,
+        key_type: KeyType,
+        bits: Option<usize>,
+        comment: &str,
+        passphrase: Option<&str>,
+    ) -> Result<SshKeyInfo> {
+        let key = keys::generate(key_type, bits, comment)?;
+        self.save(name, &key, passphrase)
+    }
+
+    /// 导入已有私钥，加密的私钥需要提供密码（导入后继续使用该密码保护）
+    pub fn import(&self, name: &str, content: &str, passphrase: Option<&str>) -> Result<SshKeyInfo> {
+        let key = keys::parse(content, passphrase)?;
+        self.save(name, &key, passphrase)
+    }
+
+    fn save(&self, name: &str, key: &PrivateKey, passphrase: Option<&str>) -> Result<SshKeyInfo> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("密钥名称不能为空"));
+        }
+
+        let (user_id, device_id) = self.current_user();
+        let repo = SshKeyRepository::new(self.pool.clone());
+        let fingerprint = keys::fingerprint(key);
+        if let Some(existing) = repo.find_by_user_id(&user_id)?.into_iter().find(|k| k.fingerprint == fingerprint) {
+            return Err(anyhow!("密钥已存在: {}", existing.name));
+        }
+
+        let pem = keys::encode(key, passphrase)?;
+        let (private_key_encrypted, private_key_nonce) = CryptoService::encrypt_password(&pem, &device_id)?;
+        let now = chrono::Utc::now().timestamp();
+
+        let key = repo.create(&SshKey {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id,
+            name: name.to_string(),
+            algorithm: key.algorithm().as_str().to_string(),
+            fingerprint,
+            public_key: keys::public_key_line(key)?,
+            private_key_encrypted,
+            private_key_nonce,
+            has_passphrase: passphrase.is_some_and(|p| !p.is_empty()),
+            is_dirty: true,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        })?;
+
+        tracing::info!("Saved SSH key {} ({})", key.id, key.fingerprint);
+        Ok(key.into())
+    }
+
+    /// 重命名密钥
+    pub fn rename(&self, id: &str, name: &str) -> Result<SshKeyInfo> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("密钥名称不能为空"));
+        }
+
+        let mut key = self.find_owned(id)?;
+        key.name = name.to_string();
+        key.is_dirty = true;
+        key.updated_at = chrono::Utc::now().timestamp();
+        Ok(SshKeyRepository::new(self.pool.clone()).update(&key)?.into())
+    }
+
+    /// 修改私钥密码，new_passphrase 为空时移除密码
+    ///
+    /// 引用此密钥的会话中保存的密码不会自动更新
+    pub fn change_passphrase(
+        &self,
+        id: &str,
+        old_passphrase: Option<&str>,
+        new_passphrase: Option<&str>,
+    ) -> Result<SshKeyInfo> {
+        let mut key = self.find_owned(id)?;
+        let pem = keys::change_passphrase(&self.private_key_pem(&key)?, old_passphrase, new_passphrase)?;
+
+        let (_, device_id) = self.current_user();
+        let (private_key_encrypted, private_key_nonce) = CryptoService::encrypt_password(&pem, &device_id)?;
+        key.private_key_encrypted = private_key_encrypted;
+        key.private_key_nonce = private_key_nonce;
+        key.has_passphrase = new_passphrase.is_some_and(|p| !p.is_empty());
+        key.is_dirty = true;
+        key.updated_at = chrono::Utc::now().timestamp();
+
+        Ok(SshKeyRepository::new(self.pool.clone()).update(&key)?.into())
+    }
+
+    /// 删除密钥，仍被会话引用时拒绝删除
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let key = self.find_owned(id)?;
+        let (user_id, device_id) = self.current_user();
+
+        let sessions = SshSessionRepository::new(self.pool.clone()).find_by_user_id(&user_id)?;
+        let in_use: Vec<String> = sessions
+            .into_iter()
+            .filter(|session| {
+                CryptoService::decrypt_password(&session.auth_method_encrypted, &session.auth_nonce, &device_id)
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<AuthMethod>(&json).ok())
+                    .is_some_and(|auth| matches!(auth, AuthMethod::ManagedKey { key_id, .. } if key_id == key.id))
+            })
+            .map(|session| session.name)
+            .collect();
+        if !in_use.is_empty() {
+            return Err(anyhow!("密钥仍被以下会话使用: {}", in_use.join(", ")));
+        }
+
+        SshKeyRepository::new(self.pool.clone()).delete(id)
+    }
+
+    /// 导出 OpenSSH 格式的私钥（设置了密码时仍是加密的），用于导出包
+    pub fn export_private_key(&self, id: &str) -> Result<(SshKeyInfo, String)> {
+        let key = self.find_owned(id)?;
+        let pem = self.private_key_pem(&key)?;
+        Ok((key.into(), pem))
+    }
+
+    /// 导入 export_private_key 的结果，返回是否新建
+    ///
+    /// 当前用户已有该 ID 时跳过；ID 被其他用户的密钥占用时返回错误，由调用方作为警告提示
+    pub fn restore(&self, info: &SshKeyInfo, pem: &str) -> Result<bool> {
+        let (user_id, device_id) = self.current_user();
+        let repo = SshKeyRepository::new(self.pool.clone());
+        if let Some(existing) = repo.find_by_id(&info.id)? {
+            if existing.user_id != user_id {
+                tracing::warn!("Key {} already belongs to user {}, skipped", info.id, existing.user_id);
+                return Err(anyhow!("密钥属于其他用户，已跳过"));
+            }
+            return Ok(false);
+        }
+
+        let (private_key_encrypted, private_key_nonce) = CryptoService::encrypt_password(pem, &device_id)?;
+        repo.create(&SshKey {
+            id: info.id.clone(),
+            user_id,
+            name: info.name.clone(),
+            algorithm: info.algorithm.clone(),
+            fingerprint: info.fingerprint.clone(),
+            public_key: info.public_key.clone(),
+            private_key_encrypted,
+            private_key_nonce,
+            has_passphrase: info.has_passphrase,
+            is_dirty: true,
+            is_deleted: false,
+            created_at: info.created_at,
+            updated_at: chrono::Utc::now().timestamp(),
+        })?;
+        Ok(true)
+    }
+}
+
+impl ManagedKeyStore for KeyService {
+    fn load_private_key(&self, key_id: &str, passphrase: Option<&str>) -> crate::error::Result<PrivateKey> {
+        let key = self
+            .find_owned(key_id)
+            .map_err(|e| crate::error::SSHError::AuthenticationFailed(e.to_string()))?;
+        let pem = self
+            .private_key_pem(&key)
+            .map_err(|e| crate::error::SSHError::Crypto(format!("无法解密密钥 '{}': {}", key.name, e)))?;
+        keys::decode(&pem, passphrase)
+    }
+}
@@ -3,10 +3,12 @@ pub mod crypto_service;
 pub mod auth_service;
 pub mod sync_service;
 pub mod user_profile_service;
+pub mod key_service;
 pub mod api_client;
 
 pub use crypto_service::*;
 pub use auth_service::*;
 pub use sync_service::*;
 pub use user_profile_service::*;
+pub use key_service::*;
 pub use api_client::*;
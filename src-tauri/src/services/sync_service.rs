@@ -1,8 +1,9 @@
 use anyhow::Result;
 use anyhow::anyhow;
 
-use crate::database::repositories::{SshSessionRepository, SyncStateRepository, UserAuthRepository};
+use crate::database::repositories::{SshKeyRepository, SshSessionRepository, SyncStateRepository, UserAuthRepository};
 use crate::database::DbPool;
+use crate::models::ssh_key::SshKey;
 use crate::models::sync::*;
 use crate::models::SshSession;
 use crate::services::api_client::ApiClient;
@@ -109,7 +110,7 @@ impl SyncService {
         };
 
         // 4. 构建统一请求
-        let request = self.build_sync_request_with_options(
+        let mut request = self.build_sync_request_with_options(
             &current_user.user_id,
             last_sync_at,
             device_id,
@@ -118,6 +119,11 @@ impl SyncService {
             deleted_session_ids,
         )?;
 
+        // 托管密钥随会话一起推送
+        if matches!(options, SyncOptions::SyncSessions | SyncOptions::SyncAll) {
+            (request.ssh_keys, request.deleted_key_ids) = self.key_changes(&current_user.user_id)?;
+        }
+
         // 5. 调用统一同步 API
         let (sync_response, code, message) = self.get_api_client()?.sync(&request).await?;
 
@@ -224,6 +230,8 @@ impl SyncService {
                 terminal_type: s.terminal_type,
                 columns: s.columns,
                 rows: s.rows,
+                jump_hosts: s.jump_hosts,
+                connection_type: (!s.connection_type.is_ssh()).then_some(s.connection_type),
                 auth_method_encrypted: s.auth_method_encrypted,
                 auth_nonce: s.auth_nonce,
                 auth_key_salt: s.auth_key_salt,
@@ -239,11 +247,21 @@ impl SyncService {
             user_profile,
             ssh_sessions,
             deleted_session_ids,
+            ssh_keys: Vec::new(),
+            deleted_key_ids: Vec::new(),
         })
     }
 
+    /// 获取待推送的托管密钥和已删除的密钥 ID
+    fn key_changes(&self, user_id: &str) -> Result<(Vec<SshKeyPushItem>, Vec<String>)> {
+        let key_repo = SshKeyRepository::new(self.pool.clone());
+        let keys = key_repo.get_dirty_keys(user_id)?.into_iter().map(SshKeyPushItem::from).collect();
+        let deleted = key_repo.get_deleted_keys(user_id)?;
+        Ok((keys, deleted))
+    }
+
     /// 应用 Pull 数据
-    fn apply_pull_data(&self, response: &ServerSyncResponse, _user_id: &str) -> Result<()> {
+    fn apply_pull_data(&self, response: &ServerSyncResponse, user_id: &str) -> Result<()> {
         let session_repo = SshSessionRepository::new(self.pool.clone());
 
         // 1. 应用 SSH 会话数据
@@ -260,8 +278,11 @@ impl SyncService {
             // 应用服务器版本
             let local_session: crate::models::SshSession = server_session.clone().into();
             if let Some(existing) = session_repo.find_by_id(&server_session.id)? {
-                // 更新现有会话（保留本地 is_dirty 和 is_deleted 状态）
+                // 更新现有会话（保留本地 is_dirty 和 is_deleted 状态，以及不同步的端口转发、会话保持和重连设置）
                 let mut updated = local_session;
+                updated.port_forwards = existing.port_forwards;
+                updated.persistent_shell = existing.persistent_shell;
+                updated.reconnect = existing.reconnect;
                 updated.is_dirty = existing.is_dirty;
                 updated.is_deleted = existing.is_deleted;
                 updated.deleted_at = existing.deleted_at;
@@ -272,7 +293,48 @@ impl SyncService {
             }
         }
 
-        // 2. 应用用户资料
+        // 2. 应用托管密钥（私钥已用同一用户的 device_id 加密，直接保存）
+        let key_repo = SshKeyRepository::new(self.pool.clone());
+        for server_key in &response.ssh_keys {
+            let local_key = key_repo.find_by_id(&server_key.id)?;
+            match &local_key {
+                Some(local) if local.user_id != user_id => {
+                    tracing::warn!("Skipping server key {} (owned by another local user)", server_key.id);
+                    continue;
+                }
+                // 本地有未推送的修改，保留本地版本
+                Some(local) if local.is_dirty => continue,
+                _ => {}
+            }
+
+            if server_key.deleted_at.is_some() {
+                key_repo.purge(&server_key.id)?;
+                continue;
+            }
+
+            let key = SshKey {
+                id: server_key.id.clone(),
+                user_id: user_id.to_string(),
+                name: server_key.name.clone(),
+                algorithm: server_key.algorithm.clone(),
+                fingerprint: server_key.fingerprint.clone(),
+                public_key: server_key.public_key.clone(),
+                private_key_encrypted: server_key.private_key_encrypted.clone(),
+                private_key_nonce: server_key.private_key_nonce.clone(),
+                has_passphrase: server_key.has_passphrase,
+                is_dirty: false,
+                is_deleted: false,
+                created_at: server_key.created_at,
+                updated_at: server_key.updated_at,
+            };
+            if local_key.is_some() {
+                key_repo.update(&key)?;
+            } else {
+                key_repo.create(&key)?;
+            }
+        }
+
+        // 3. 应用用户资料
         if let Some(server_profile) = &response.user_profile {
             let profile_repo = crate::database::repositories::UserProfileRepository::new(self.pool.clone());
             // 转换 ServerUserProfile 为 UserProfile 并保存
@@ -296,6 +358,12 @@ impl SyncService {
             }
         }
 
+        // 清理已保存到服务器的密钥的脏标记
+        let key_repo = SshKeyRepository::new(self.pool.clone());
+        for id in &response.updated_key_ids {
+            key_repo.clear_dirty_marker(id)?;
+        }
+
         // 更新同步状态
         let state_repo = SyncStateRepository::new(self.pool.clone());
         state_repo.update_conflict_count(user_id, response.conflicts.len() as i32)?;
@@ -360,7 +428,7 @@ impl SyncService {
             let deleted_session_ids = session_repo.get_deleted_sessions(&current_user.user_id)?;
 
             // 构建统一请求（不指定 last_sync_at，拉取所有数据）
-            let request = self.build_sync_request_with_options(
+            let mut request = self.build_sync_request_with_options(
                 &current_user.user_id,
                 None,
                 device_id,
@@ -368,6 +436,7 @@ impl SyncService {
                 None,
                 deleted_session_ids,
             )?;
+            (request.ssh_keys, request.deleted_key_ids) = self.key_changes(&current_user.user_id)?;
 
             let (sync_response, _, sync_message) = api_client.sync(&request).await?;
 
@@ -4,10 +4,15 @@
 
 use crate::error::{Result, SSHError};
 use crate::sftp::{SftpFileInfo};
+use crate::sftp::pipeline::{PipelineSession, DEFAULT_WINDOW};
+use crate::ssh::backends::russh::SshTransport;
+use crate::ssh::backends::sftp_channel::SftpChannelStream;
 use russh_sftp::client::SftpSession;
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
 use std::path::Path;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tracing::{debug, error, info, warn};
 
 // 需要导入 Tauri 的 Event trait 来使用 emit 方法
@@ -19,6 +24,12 @@ use tauri::Emitter;
 pub struct SftpClient {
     session: SftpSession,
     connection_id: String,
+    /// 底层 SSH 连接，客户端存活期间保持连接不被关闭，也用于打开流水线传输的 channel
+    transport: Option<Arc<SshTransport>>,
+    /// 流水线传输会话（首次传输文件时打开）
+    pipeline: tokio::sync::OnceCell<PipelineSession>,
+    /// 流水线传输的最大未完成请求数
+    pipeline_window: usize,
 }
 
 impl SftpClient {
@@ -30,9 +41,40 @@ impl SftpClient {
         Self {
             session,
             connection_id: "unknown".to_string(),
+            transport: None,
+            pipeline: tokio::sync::OnceCell::new(),
+            pipeline_window: DEFAULT_WINDOW,
         }
     }
 
+    /// 持有底层 SSH 连接的引用
+    pub fn with_transport(mut self, transport: Arc<SshTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 底层 SSH 连接（用于在同一连接上执行命令）
+    pub fn transport(&self) -> Option<&Arc<SshTransport>> {
+        self.transport.as_ref()
+    }
+
+    /// 设置流水线传输的最大未完成请求数
+    pub fn set_pipeline_window(&mut self, window: usize) {
+        self.pipeline_window = window;
+    }
+
+    /// 获取流水线传输会话，首次调用时在同一 SSH 连接上打开新的 SFTP channel
+    async fn pipeline_session(&self) -> Result<&PipelineSession> {
+        self.pipeline
+            .get_or_try_init(|| async {
+                let transport = self.transport.as_ref()
+                    .ok_or_else(|| SSHError::NotSupported("SFTP 客户端未关联 SSH 连接".to_string()))?;
+                let stream = SftpChannelStream::open(&*transport.handle().read().await).await?;
+                PipelineSession::new(stream).await
+            })
+            .await
+    }
+
     /// 创建新的 SFTP 客户端
     ///
     /// # 参数
@@ -52,6 +94,9 @@ impl SftpClient {
         Ok(Self {
             session,
             connection_id,
+            transport: None,
+            pipeline: tokio::sync::OnceCell::new(),
+            pipeline_window: DEFAULT_WINDOW,
         })
     }
 
@@ -270,6 +315,53 @@ impl SftpClient {
         Ok(())
     }
 
+    /// 修改文件的访问和修改时间
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    /// - `mtime`: 修改时间（Unix 时间戳，秒）
+    pub async fn set_modified(&mut self, path: &str, mtime: u64) -> Result<()> {
+        debug!("Setting mtime of {} to {}", path, mtime);
+
+        // 只携带时间属性，避免覆盖权限和属主
+        let time = mtime as u32;
+        let attrs = russh_sftp::protocol::FileAttributes {
+            atime: Some(time),
+            mtime: Some(time),
+            ..russh_sftp::protocol::FileAttributes::empty()
+        };
+
+        self.session.set_metadata(path, attrs).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to set mtime for '{}': {}", path, e)))?;
+        Ok(())
+    }
+
+    /// 检查路径是否存在
+    ///
+    /// # 参数
+    /// - `path`: 文件或目录路径
+    pub async fn exists(&mut self, path: &str) -> Result<bool> {
+        self.session.try_exists(path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to check '{}': {}", path, e)))
+    }
+
+    /// 获取文件或目录信息
+    ///
+    /// # 参数
+    /// - `path`: 文件或目录路径
+    pub async fn stat(&mut self, path: &str) -> Result<SftpFileInfo> {
+        let metadata = self.session.metadata(path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to stat '{}': {}", path, e)))?;
+
+        let mut info = SftpFileInfo::from(metadata);
+        info.name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        info.path = path.to_string();
+        Ok(info)
+    }
+
     /// 读取文件内容
     ///
     /// # 参数
@@ -326,6 +418,36 @@ impl SftpClient {
         Ok(())
     }
 
+    /// 在文件末尾追加内容，文件不存在时以 `mode` 权限创建
+    ///
+    /// 不截断、不替换原文件，写入失败时原有内容保持不变
+    pub async fn append_file(&mut self, path: &str, data: &[u8], mode: u32) -> Result<()> {
+        debug!("Appending {} bytes to {}", data.len(), path);
+
+        let attributes = russh_sftp::protocol::FileAttributes {
+            permissions: Some(mode),
+            ..russh_sftp::protocol::FileAttributes::empty()
+        };
+        let mut file = self.session
+            .open_with_flags_and_attributes(
+                path,
+                russh_sftp::protocol::OpenFlags::WRITE
+                    | russh_sftp::protocol::OpenFlags::APPEND
+                    | russh_sftp::protocol::OpenFlags::CREATE,
+                attributes,
+            )
+            .await
+            .map_err(|e| SSHError::Ssh(format!("Failed to open remote file '{}' for append: {}", path, e)))?;
+
+        file.write_all(data).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to append to remote file '{}': {}", path, e)))?;
+        file.sync_all().await
+            .map_err(|e| SSHError::Ssh(format!("Failed to sync remote file '{}': {}", path, e)))?;
+        file.shutdown().await
+            .map_err(|e| SSHError::Ssh(format!("Failed to close remote file '{}': {}", path, e)))?;
+        Ok(())
+    }
+
     // ============================================================================
     // 未来特性：带进度回调的文件传输
     // 以下方法预留用于将来的带进度回调的文件上传/下载功能
@@ -538,11 +660,129 @@ impl SftpClient {
         Ok(())
     }
 
+    /// 计算上传续传起点
+    ///
+    /// 取远程已有部分文件的大小和上次记录的续传起点 `recorded_offset` 中的较小值：
+    /// 流水线写入失败时窗口中靠后的请求可能已经写入，远程文件大小会超过连续写入的部分。
+    /// 远程文件不存在或比本地文件大时从头上传。
+    /// `verify` 为 true 时比较两端前缀的 SHA-256，不一致同样从头上传
+    pub async fn upload_resume_offset(&mut self, local_path: &str, remote_path: &str, recorded_offset: u64, verify: bool) -> Result<u64> {
+        let local_size = tokio::fs::metadata(local_path).await
+            .map_err(|e| SSHError::Io(format!("无法获取文件 '{}' 的元数据: {}", local_path, e)))?
+            .len();
+        if !self.exists(remote_path).await? {
+            return Ok(0);
+        }
+        let remote_size = self.session.metadata(remote_path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to get metadata for '{}': {}", remote_path, e)))?
+            .size
+            .unwrap_or(0);
+        if remote_size > local_size {
+            return Ok(0);
+        }
+        let remote_size = remote_size.min(recorded_offset);
+        if remote_size == 0 {
+            return Ok(0);
+        }
+
+        if verify {
+            let mut local_file = tokio::fs::File::open(local_path).await
+                .map_err(|e| SSHError::Io(format!("无法打开本地文件 '{}': {}", local_path, e)))?;
+            let mut remote_file = self.session.open(remote_path).await
+                .map_err(|e| SSHError::Ssh(format!("无法打开远程文件 '{}': {}", remote_path, e)))?;
+            let local_hash = hash_prefix(&mut local_file, remote_size).await?;
+            let remote_hash = hash_prefix(&mut remote_file, remote_size).await?;
+            if local_hash != remote_hash {
+                warn!("Partial remote file {} does not match local prefix, restarting upload", remote_path);
+                return Ok(0);
+            }
+        }
+
+        Ok(remote_size)
+    }
+
+    /// 计算下载续传起点
+    ///
+    /// 以本地已有部分文件的大小为准；本地文件不存在或比远程文件大时从头下载。
+    /// `verify` 为 true 时比较两端前缀的 SHA-256，不一致同样从头下载
+    pub async fn download_resume_offset(&self, remote_path: &str, local_path: &str, verify: bool) -> Result<u64> {
+        let local_size = match tokio::fs::metadata(local_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(0),
+        };
+        let remote_size = self.session.metadata(remote_path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to get metadata for '{}': {}", remote_path, e)))?
+            .size
+            .unwrap_or(0);
+        if local_size == 0 || local_size > remote_size {
+            return Ok(0);
+        }
+
+        if verify {
+            let mut local_file = tokio::fs::File::open(local_path).await
+                .map_err(|e| SSHError::Io(format!("无法打开本地文件 '{}': {}", local_path, e)))?;
+            let mut remote_file = self.session.open(remote_path).await
+                .map_err(|e| SSHError::Ssh(format!("无法打开远程文件 '{}': {}", remote_path, e)))?;
+            let local_hash = hash_prefix(&mut local_file, local_size).await?;
+            let remote_hash = hash_prefix(&mut remote_file, local_size).await?;
+            if local_hash != remote_hash {
+                warn!("Partial local file {} does not match remote prefix, restarting download", local_path);
+                return Ok(0);
+            }
+        }
+
+        Ok(local_size)
+    }
+
+    /// 计算服务器之间传输的续传起点
+    ///
+    /// 与上传相同，取目标端已有部分文件的大小和上次记录的续传起点中的较小值；
+    /// 目标文件不存在或比源文件大时从头传输。
+    /// `verify` 为 true 时比较两端前缀的 SHA-256，不一致同样从头传输
+    pub async fn copy_resume_offset(&mut self, source: &SftpClient, source_path: &str, remote_path: &str, recorded_offset: u64, verify: bool) -> Result<u64> {
+        let source_size = source.session.metadata(source_path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to get metadata for '{}': {}", source_path, e)))?
+            .size
+            .unwrap_or(0);
+        if !self.exists(remote_path).await? {
+            return Ok(0);
+        }
+        let remote_size = self.session.metadata(remote_path).await
+            .map_err(|e| SSHError::Ssh(format!("Failed to get metadata for '{}': {}", remote_path, e)))?
+            .size
+            .unwrap_or(0);
+        if remote_size > source_size {
+            return Ok(0);
+        }
+        let remote_size = remote_size.min(recorded_offset);
+        if remote_size == 0 {
+            return Ok(0);
+        }
+
+        if verify {
+            let mut source_file = source.session.open(source_path).await
+                .map_err(|e| SSHError::Ssh(format!("无法打开远程文件 '{}': {}", source_path, e)))?;
+            let mut remote_file = self.session.open(remote_path).await
+                .map_err(|e| SSHError::Ssh(format!("无法打开远程文件 '{}': {}", remote_path, e)))?;
+            let source_hash = hash_prefix(&mut source_file, remote_size).await?;
+            let remote_hash = hash_prefix(&mut remote_file, remote_size).await?;
+            if source_hash != remote_hash {
+                warn!("Partial remote file {} does not match source prefix, restarting copy", remote_path);
+                return Ok(0);
+            }
+        }
+
+        Ok(remote_size)
+    }
+
     /// 流式上传文件（避免一次性读取整个文件到内存）
     ///
+    /// 通过流水线请求分块写入远程文件（见 `sftp::pipeline`）
+    ///
     /// # 参数
     /// - `local_path`: 本地文件路径
     /// - `remote_path`: 远程保存路径
+    /// - `offset`: 续传起点（见 `upload_resume_offset`），0 表示重新上传
     /// - `cancellation_token`: 取消令牌
     /// - `progress_callback`: 进度回调函数 (transferred, total)
     /// - `skip_dir_check`: 是否跳过目录检查（批量上传时使用，提高性能）
@@ -550,6 +790,7 @@ impl SftpClient {
         &mut self,
         local_path: &str,
         remote_path: &str,
+        offset: u64,
         cancellation_token: &tokio_util::sync::CancellationToken,
         progress_callback: F,
         skip_dir_check: bool,
@@ -585,46 +826,70 @@ impl SftpClient {
             }
         }
 
-        // 创建远程文件
-        let mut remote_file = self.session.create(remote_path).await
-            .map_err(|e| SSHError::Ssh(format!("无法创建远程文件 '{}': {}", remote_path, e)))?;
-
-        // 分块读取和写入（64KB buffer）
-        let mut buffer = vec![0u8; 64 * 1024];
-        let mut transferred = 0u64;
+        // 续传时从 offset 处继续读取，远程文件保留已有内容
+        if offset > 0 {
+            local_file.seek(SeekFrom::Start(offset)).await
+                .map_err(|e| SSHError::Io(format!("无法定位本地文件 '{}': {}", local_path, e)))?;
+            info!("Resuming upload at offset {}", offset);
+        }
 
-        loop {
-            // 检查是否被取消
-            if cancellation_token.is_cancelled() {
-                info!("Upload cancelled during file transfer: {}", local_path);
-                return Err(SSHError::Io("上传已取消".to_string()));
-            }
+        // 流水线写入，同时保持多个未完成的 WRITE 请求
+        let pipeline = self.pipeline_session().await?;
+        let transferred = pipeline
+            .upload(&mut local_file, file_size, remote_path, offset, self.pipeline_window, cancellation_token, progress_callback)
+            .await?;
 
-            let n = local_file.read(&mut buffer).await
-                .map_err(|e| SSHError::Io(format!("无法从本地文件 '{}' 读取数据: {}", local_path, e)))?;
+        info!("Stream upload completed: {} bytes", transferred);
+        Ok(transferred)
+    }
 
-            if n == 0 {
-                break; // EOF
-            }
+    /// 从另一个连接复制文件（服务器之间直接传输，不经过本地磁盘）
+    ///
+    /// 源端和目标端各自使用流水线请求（见 `PipelineSession::copy_from`）
+    ///
+    /// # 参数
+    /// - `source`: 源连接的 SFTP 客户端
+    /// - `source_path`: 源文件路径
+    /// - `remote_path`: 目标保存路径
+    /// - `offset`: 续传起点（见 `copy_resume_offset`），0 表示重新传输
+    /// - `cancellation_token`: 取消令牌
+    /// - `progress_callback`: 进度回调函数 (transferred, total)
+    /// - `skip_dir_check`: 是否跳过目录检查（批量传输时使用，提高性能）
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_file_from<F>(
+        &mut self,
+        source: &SftpClient,
+        source_path: &str,
+        remote_path: &str,
+        offset: u64,
+        cancellation_token: &tokio_util::sync::CancellationToken,
+        progress_callback: F,
+        skip_dir_check: bool,
+    ) -> Result<u64>
+    where
+        F: Fn(u64, u64), // (transferred, total)
+    {
+        info!("Copying {}:{} -> {}:{}", source.connection_id, source_path, self.connection_id, remote_path);
 
-            // 再次检查是否被取消（在写入前）
-            if cancellation_token.is_cancelled() {
-                info!("Upload cancelled during file transfer: {}", local_path);
-                return Err(SSHError::Io("上传已取消".to_string()));
+        // 确保父目录存在（除非跳过检查）
+        if !skip_dir_check {
+            if let Some(parent_str) = Path::new(remote_path).parent().and_then(|p| p.to_str()) {
+                if !parent_str.is_empty() && parent_str != "/" {
+                    self.ensure_dir_exists(parent_str).await?;
+                }
             }
-
-            remote_file.write_all(&buffer[..n]).await
-                .map_err(|e| SSHError::Ssh(format!("无法写入远程文件 '{}': {}", remote_path, e)))?;
-
-            transferred += n as u64;
-            progress_callback(transferred, file_size);
+        }
+        if offset > 0 {
+            info!("Resuming copy at offset {}", offset);
         }
 
-        // 确保数据刷新到服务器
-        remote_file.sync_all().await
-            .map_err(|e| SSHError::Ssh(format!("无法刷新远程文件 '{}' 到服务器: {}", remote_path, e)))?;
+        let source_pipeline = source.pipeline_session().await?;
+        let pipeline = self.pipeline_session().await?;
+        let transferred = pipeline
+            .copy_from(source_pipeline, source_path, remote_path, offset, self.pipeline_window, cancellation_token, progress_callback)
+            .await?;
 
-        info!("Stream upload completed: {} bytes", transferred);
+        info!("Copy completed: {} bytes", transferred);
         Ok(transferred)
     }
 
@@ -633,7 +898,7 @@ impl SftpClient {
     /// # 参数
     /// - `local_dir`: 本地目录路径
     /// - `remote_dir`: 远程目录路径
-    /// - `window`: Tauri 窗口实例（用于发送进度事件）
+    /// - `app_handle`: Tauri 应用句柄（用于发送进度事件）
     /// - `connection_id`: 连接 ID
     /// - `task_id`: 上传任务的唯一 ID
     /// - `cancellation_token`: 取消令牌
@@ -644,7 +909,7 @@ impl SftpClient {
         &'a mut self,
         local_dir: &'a str,
         remote_dir: &'a str,
-        window: &'a tauri::Window,
+        app_handle: &'a tauri::AppHandle,
         connection_id: &'a str,
         task_id: &'a str,
         cancellation_token: &'a tokio_util::sync::CancellationToken,
@@ -756,7 +1021,7 @@ impl SftpClient {
 
                 // 流式上传文件（跳过目录检查，已在 Phase 1.5 创建）
                 // 使用节流机制控制事件发送频率（200ms）
-                let window_clone = window.clone();
+                let app_handle_clone = app_handle.clone();
                 let task_id_clone = task_id.to_string();
                 let connection_id_clone = connection_id.to_string();
                 let local_file_path_clone = local_file_path.clone();
@@ -772,6 +1037,7 @@ impl SftpClient {
                 let file_transferred = self.upload_file_stream(
                     &local_file_path,
                     &remote_file_path,
+                    0,
                     cancellation_token,
                     {
                         let last_emit_time = last_emit_time.clone();
@@ -810,7 +1076,7 @@ impl SftpClient {
                                         upload_name: upload_name_clone.to_string(),
                                     };
 
-                                    let _ = window_clone.emit("sftp-upload-progress", &progress_event);
+                                    let _ = app_handle_clone.emit("sftp-upload-progress", &progress_event);
                                 }
                             }
                         }
@@ -849,7 +1115,7 @@ impl SftpClient {
                     upload_name: Arc::clone(&upload_name).to_string(),
                 };
 
-                if let Err(e) = window.emit("sftp-upload-progress", &progress_event) {
+                if let Err(e) = app_handle.emit("sftp-upload-progress", &progress_event) {
                     tracing::warn!("Failed to emit upload progress: {}", e);
                 }
 
@@ -876,6 +1142,147 @@ impl SftpClient {
         })
     }
 
+    /// 递归复制另一个连接上的目录（服务器之间直接传输）
+    ///
+    /// 先扫描源目录并在目标端创建完整的目录结构（包括空目录），再逐个复制文件，
+    /// 进度以上传事件发送
+    ///
+    /// # 参数
+    /// - `source`: 源连接的 SFTP 客户端
+    /// - `source_dir`: 源目录路径
+    /// - `remote_dir`: 目标目录路径
+    /// - `app_handle`: Tauri 应用句柄（用于发送进度事件）
+    /// - `connection_id`: 目标连接 ID
+    /// - `task_id`: 任务的唯一 ID
+    /// - `cancellation_token`: 取消令牌
+    ///
+    /// # 返回
+    /// 传输结果统计
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_directory_from(
+        &mut self,
+        source: &mut SftpClient,
+        source_dir: &str,
+        remote_dir: &str,
+        app_handle: &tauri::AppHandle,
+        connection_id: &str,
+        task_id: &str,
+        cancellation_token: &tokio_util::sync::CancellationToken,
+    ) -> Result<crate::sftp::UploadDirectoryResult> {
+        use crate::sftp::{UploadDirectoryResult, UploadProgressEvent};
+        use std::time::Instant;
+
+        info!("=== Recursive Directory Copy Start ===");
+        info!("Source: {}:{}, Target: {}", source.connection_id, source_dir, remote_dir);
+
+        let upload_name = source_dir.trim_end_matches('/').rsplit('/').next().unwrap_or(source_dir).to_string();
+        let start_time = Instant::now();
+        let start_time_timestamp = chrono::Utc::now().timestamp_millis() as u64;
+
+        // 第一步：扫描源目录结构
+        let mut dirs = vec![remote_dir.to_string()];
+        let mut files: Vec<(String, String)> = Vec::new(); // (source_path, remote_path)
+        let mut total_size: u64 = 0;
+        let mut dir_queue = vec![(source_dir.to_string(), remote_dir.to_string())];
+
+        while let Some((source_path, remote_path)) = dir_queue.pop() {
+            if cancellation_token.is_cancelled() {
+                return Err(SSHError::Io("上传已取消".to_string()));
+            }
+
+            for entry in source.list_dir(&source_path).await? {
+                if entry.name == "." || entry.name == ".." {
+                    continue;
+                }
+                let target_path = format!("{}/{}", remote_path.trim_end_matches('/'), entry.name);
+                if entry.is_dir {
+                    dirs.push(target_path.clone());
+                    dir_queue.push((entry.path, target_path));
+                } else {
+                    total_size += entry.size;
+                    files.push((entry.path, target_path));
+                }
+            }
+        }
+
+        let total_files = files.len() as u64;
+        let total_dirs = dirs.len() as u64 - 1;
+        info!("Scan complete: {} files, {} directories, total size: {} bytes", total_files, total_dirs, total_size);
+
+        // 第二步：创建目录结构（父目录先于子目录加入列表）
+        for dir in &dirs {
+            self.ensure_dir_exists(dir).await?;
+        }
+
+        // 第三步：逐个复制文件
+        let mut files_completed: u64 = 0;
+        let mut total_bytes_transferred: u64 = 0;
+        let progress_event = |current_file: &str, files_completed: u64, bytes_transferred: u64| {
+            let elapsed_ms = start_time.elapsed().as_millis() as u64;
+            UploadProgressEvent {
+                task_id: task_id.to_string(),
+                connection_id: connection_id.to_string(),
+                current_file: current_file.to_string(),
+                current_dir: current_file.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("").to_string(),
+                files_completed,
+                total_files,
+                bytes_transferred,
+                total_bytes: total_size,
+                speed_bytes_per_sec: if elapsed_ms > 0 { bytes_transferred * 1000 / elapsed_ms } else { 0 },
+                start_time: start_time_timestamp,
+                completed_time: chrono::Utc::now().timestamp_millis() as u64,
+                upload_name: upload_name.clone(),
+            }
+        };
+
+        for (source_path, remote_path) in files {
+            if cancellation_token.is_cancelled() {
+                info!("Copy cancelled for connection: {}", connection_id);
+                return Err(SSHError::Io("上传已取消".to_string()));
+            }
+
+            // 节流：每 200ms 最多发送一次进度事件
+            let last_emit_time = std::sync::Mutex::new(Instant::now());
+            let bytes_before = total_bytes_transferred;
+            let file_transferred = self.copy_file_from(
+                source,
+                &source_path,
+                &remote_path,
+                0,
+                cancellation_token,
+                |transferred, _total| {
+                    let mut last = last_emit_time.lock().unwrap();
+                    if last.elapsed() >= std::time::Duration::from_millis(200) {
+                        *last = Instant::now();
+                        let event = progress_event(&source_path, files_completed, bytes_before + transferred);
+                        let _ = app_handle.emit("sftp-upload-progress", &event);
+                    }
+                },
+                true,
+            ).await?;
+
+            files_completed += 1;
+            total_bytes_transferred += file_transferred;
+
+            // 文件完成事件，不受节流限制
+            let event = progress_event(&source_path, files_completed, total_bytes_transferred);
+            if let Err(e) = app_handle.emit("sftp-upload-progress", &event) {
+                tracing::warn!("Failed to emit upload progress: {}", e);
+            }
+        }
+
+        let elapsed_time = start_time.elapsed().as_millis() as u64;
+        info!("=== Directory Copy Complete ===");
+        info!("Files: {}, Directories: {}, Total size: {} bytes, elapsed: {} ms", total_files, total_dirs, total_bytes_transferred, elapsed_time);
+
+        Ok(UploadDirectoryResult {
+            total_files,
+            total_dirs,
+            total_size: total_bytes_transferred,
+            elapsed_time_ms: elapsed_time,
+        })
+    }
+
     /// 递归下载目录
     ///
     /// 分两个阶段执行：
@@ -885,7 +1292,7 @@ impl SftpClient {
     /// # 参数
     /// - `remote_dir_path`: 远程目录路径
     /// - `local_dir_path`: 本地保存路径
-    /// - `window`: Tauri 窗口实例（用于发送进度事件）
+    /// - `app_handle`: Tauri 应用句柄（用于发送进度事件）
     /// - `connection_id`: SSH 连接 ID
     /// - `task_id`: 下载任务的唯一 ID
     /// - `cancellation_token`: 取消令牌
@@ -896,7 +1303,7 @@ impl SftpClient {
         &mut self,
         remote_dir_path: &str,
         local_dir_path: &str,
-        window: &tauri::Window,
+        app_handle: &tauri::AppHandle,
         connection_id: &str,
         task_id: &str,
         cancellation_token: &tokio_util::sync::CancellationToken,
@@ -969,7 +1376,7 @@ impl SftpClient {
 
             // 流式下载文件
             // 使用节流机制控制事件发送频率（200ms）
-            let window_clone = window.clone();
+            let app_handle_clone = app_handle.clone();
             let task_id_clone = task_id.to_string();
             let connection_id_clone = connection_id.to_string();
             let remote_file_path_clone = remote_file_path.clone();
@@ -983,6 +1390,7 @@ impl SftpClient {
             let file_transferred = self.download_file_stream(
                 &remote_file_path,
                 &local_file_path,
+                0,
                 cancellation_token,
                 {
                     let last_emit_time = last_emit_time.clone();
@@ -1020,7 +1428,7 @@ impl SftpClient {
                                     completed_time: chrono::Utc::now().timestamp_millis() as u64,
                                 };
 
-                                let _ = window_clone.emit("sftp-download-progress", &progress_event);
+                                let _ = app_handle_clone.emit("sftp-download-progress", &progress_event);
                             }
                         }
                     }
@@ -1057,7 +1465,7 @@ impl SftpClient {
                 completed_time: chrono::Utc::now().timestamp_millis() as u64,
             };
 
-            if let Err(e) = window.emit("sftp-download-progress", &progress_event) {
+            if let Err(e) = app_handle.emit("sftp-download-progress", &progress_event) {
                 tracing::warn!("Failed to emit download progress: {}", e);
             }
 
@@ -1085,21 +1493,23 @@ impl SftpClient {
 
     /// 流式下载文件
     ///
-    /// 使用固定大小的缓冲区（64KB）从远程文件读取并写入本地文件
+    /// 通过流水线请求分块读取远程文件并按顺序写入本地文件（见 `sftp::pipeline`），
     /// 避免一次性加载大文件到内存
     ///
     /// # 参数
     /// - `remote_path`: 远程文件路径
     /// - `local_path`: 本地保存路径
+    /// - `offset`: 续传起点（见 `download_resume_offset`），0 表示重新下载
     /// - `cancellation_token`: 取消令牌
     /// - `progress_callback`: 进度回调函数
     ///
     /// # 返回
-    /// 传输的字节数
+    /// 传输的字节数（含续传前已有的部分）
     pub async fn download_file_stream<F>(
         &self,
         remote_path: &str,
         local_path: &str,
+        offset: u64,
         cancellation_token: &tokio_util::sync::CancellationToken,
         progress_callback: F,
     ) -> Result<u64>
@@ -1108,47 +1518,29 @@ impl SftpClient {
     {
         info!("Starting file download: {} -> {}", remote_path, local_path);
 
-        // 打开远程文件
-        let mut remote_file = self.session.open(remote_path).await
-            .map_err(|e| SSHError::Ssh(format!("无法打开远程文件: {}", e)))?;
-
-        // 获取文件大小
-        let file_size = remote_file.metadata().await
-            .map_err(|e| SSHError::Ssh(format!("无法获取文件元数据: {}", e)))?
-            .size.unwrap_or(0);
-
-        // 创建本地文件
-        let mut local_file = tokio::fs::File::create(local_path).await
-            .map_err(|e| SSHError::Io(format!("无法创建本地文件: {}", e)))?;
-
-        // 流式传输（64KB 缓冲区）
-        let mut buffer = vec![0u8; 64 * 1024];
-        let mut transferred = 0u64;
-
-        loop {
-            // 检查是否被取消
-            if cancellation_token.is_cancelled() {
-                return Err(SSHError::Io("下载已取消".to_string()));
-            }
-
-            // 从远程文件读取
-            let n = remote_file.read(&mut buffer).await
-                .map_err(|e| SSHError::Ssh(format!("读取远程文件失败: {}", e)))?;
-
-            if n == 0 {
-                break; // EOF
-            }
-
-            // 写入本地文件
-            local_file.write_all(&buffer[..n]).await
-                .map_err(|e| SSHError::Io(format!("写入本地文件失败: {}", e)))?;
+        let pipeline = self.pipeline_session().await?;
+
+        // 创建本地文件（续传时截断到 offset 后继续写入）
+        let mut local_file = if offset > 0 {
+            let mut local_file = tokio::fs::OpenOptions::new().write(true).open(local_path).await
+                .map_err(|e| SSHError::Io(format!("无法打开本地文件: {}", e)))?;
+            local_file.set_len(offset).await
+                .map_err(|e| SSHError::Io(format!("无法截断本地文件: {}", e)))?;
+            local_file.seek(SeekFrom::Start(offset)).await
+                .map_err(|e| SSHError::Io(format!("无法定位本地文件: {}", e)))?;
+            info!("Resuming download at offset {}", offset);
+            local_file
+        } else {
+            tokio::fs::File::create(local_path).await
+                .map_err(|e| SSHError::Io(format!("无法创建本地文件: {}", e)))?
+        };
 
-            transferred += n as u64;
-            progress_callback(transferred, file_size);
-        }
+        // 流水线读取，同时保持多个未完成的 READ 请求，按顺序写入本地文件
+        let transferred = pipeline
+            .download(remote_path, offset, &mut local_file, self.pipeline_window, cancellation_token, progress_callback)
+            .await?;
 
         // 确保数据刷写到磁盘
-        // 注意：russh_sftp 的 File 在 drop 时会自动关闭，无需手动调用 close()
         local_file.sync_all().await
             .map_err(|e| SSHError::Io(format!("同步本地文件失败: {}", e)))?;
 
@@ -1156,3 +1548,23 @@ impl SftpClient {
         Ok(transferred)
     }
 }
+
+/// 计算前 len 字节的 SHA-256，用于续传前校验已有部分
+async fn hash_prefix<R: AsyncRead + Unpin>(reader: &mut R, len: u64) -> Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut remaining = len;
+
+    while remaining > 0 {
+        let want = remaining.min(buffer.len() as u64) as usize;
+        let n = reader.read(&mut buffer[..want]).await
+            .map_err(|e| SSHError::Io(format!("读取文件失败: {}", e)))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        remaining -= n as u64;
+    }
+
+    Ok(hasher.finalize().to_vec())
+}
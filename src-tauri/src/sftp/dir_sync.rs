@@ -0,0 +1,1086 @@
+//! 目录同步
+//!
+//! 类似 rsync 的本地与远程目录同步：扫描两端目录树，按大小和修改时间（可选按 SHA-256）
+//! 比较，生成同步计划供用户确认，执行时只传输有变化的文件，可选删除目标端多余的文件。
+//! 支持 gitignore 语法的 include/exclude 规则，以及读取源端的 .gitignore
+
+use crate::error::{Result, SSHError};
+use crate::sftp::client::SftpClient;
+use crate::sftp::SftpManager;
+use crate::ssh::exec::ExecRequest;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// 同步进度事件名称
+pub const SYNC_PROGRESS_EVENT: &str = "sftp-sync-progress";
+
+/// 单条 sha256sum 命令的最大长度，超过后拆分为多次执行
+const CHECKSUM_COMMAND_LIMIT: usize = 16 * 1024;
+
+/// 进度事件的最小发送间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 同步方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDirection {
+    /// 本地 -> 远程
+    Upload,
+    /// 远程 -> 本地
+    Download,
+}
+
+/// 同步选项
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOptions {
+    pub direction: SyncDirection,
+    pub local_dir: String,
+    pub remote_dir: String,
+    /// 大小相同的文件按 SHA-256 比较（远程端需要 sha256sum 命令），否则按大小和修改时间比较
+    #[serde(default)]
+    pub checksum: bool,
+    /// 删除目标端存在但源端没有的文件和目录
+    #[serde(default)]
+    pub delete: bool,
+    /// 只同步匹配的文件（gitignore 语法），为空表示全部
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// 跳过匹配的文件和目录（gitignore 语法）
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// 遵循源端各目录下的 .gitignore，并跳过 .git 目录
+    #[serde(default)]
+    pub use_gitignore: bool,
+}
+
+/// 同步操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncAction {
+    /// 目标端不存在，新建
+    Create,
+    /// 内容有变化，覆盖
+    Update,
+    /// 源端不存在，从目标端删除（目录只在删除其中的内容后为空时删除）
+    Delete,
+    /// 两端类型不同（文件/目录），未启用删除时跳过
+    Conflict,
+}
+
+/// 同步计划中的一项
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// 相对于同步根目录的路径，以 '/' 分隔
+    pub path: String,
+    pub action: SyncAction,
+    pub is_dir: bool,
+    pub size: u64,
+    /// 源端修改时间（Unix 时间戳，秒），传输后设置到目标文件
+    pub modified: u64,
+}
+
+/// 同步计划
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPlan {
+    pub direction: SyncDirection,
+    pub local_dir: String,
+    pub remote_dir: String,
+    pub entries: Vec<SyncEntry>,
+    pub transfer_files: u64,
+    pub transfer_bytes: u64,
+    pub delete_count: u64,
+    pub conflict_count: u64,
+    pub unchanged_files: u64,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub files_transferred: u64,
+    pub bytes_transferred: u64,
+    pub dirs_created: u64,
+    pub deleted: u64,
+    pub elapsed_time_ms: u64,
+}
+
+/// 同步进度事件
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgressEvent {
+    pub task_id: String,
+    pub connection_id: String,
+    pub current_file: String,
+    pub files_completed: u64,
+    pub total_files: u64,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+    pub speed_bytes_per_sec: u64,
+}
+
+/// 目录树中的一项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub is_dir: bool,
+    pub size: u64,
+    pub mtime: u64,
+}
+
+/// 目录树：相对路径 -> 条目，按路径排序（父目录总在子项之前）
+pub type Tree = BTreeMap<String, TreeEntry>;
+
+/// 同步的过滤规则
+///
+/// 所有路径都是相对于同步根目录、以 '/' 分隔的路径
+pub struct SyncFilter {
+    include: Option<Gitignore>,
+    exclude: Gitignore,
+    /// 各目录的 .gitignore，按目录深度从深到浅排列
+    gitignores: Vec<(String, Gitignore)>,
+    use_gitignore: bool,
+}
+
+impl SyncFilter {
+    pub fn new(include: &[String], exclude: &[String], use_gitignore: bool) -> Result<Self> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_matcher(include)?)
+        };
+
+        Ok(Self {
+            include,
+            exclude: build_matcher(exclude)?,
+            gitignores: Vec::new(),
+            use_gitignore,
+        })
+    }
+
+    /// 添加目录 `dir` 下的 .gitignore，无效的行会被忽略（与 git 一致）
+    pub fn add_gitignore(&mut self, dir: &str, content: &str) {
+        let mut builder = GitignoreBuilder::new("");
+        for line in content.lines() {
+            if let Err(e) = builder.add_line(None, line) {
+                warn!("Skipping invalid .gitignore line in '{}': {}", dir, e);
+            }
+        }
+
+        match builder.build() {
+            Ok(matcher) => {
+                self.gitignores.push((dir.to_string(), matcher));
+                self.gitignores.sort_by_key(|(dir, _)| std::cmp::Reverse(dir.len()));
+            }
+            Err(e) => warn!("Failed to load .gitignore in '{}': {}", dir, e),
+        }
+    }
+
+    /// 路径是否被排除；被排除的目录不再向下扫描
+    pub fn is_excluded(&self, path: &str, is_dir: bool) -> bool {
+        if self.use_gitignore && is_dir && file_name(path) == ".git" {
+            return true;
+        }
+
+        if self.exclude.matched(path, is_dir).is_ignore() {
+            return true;
+        }
+
+        // 由最近的 .gitignore 决定，其中的 "!" 规则可以重新包含上层忽略的文件
+        for (dir, matcher) in &self.gitignores {
+            let Some(relative) = strip_dir(path, dir) else {
+                continue;
+            };
+            let matched = matcher.matched(relative, is_dir);
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                break;
+            }
+        }
+
+        // include 只作用于文件，目录总是向下扫描
+        match &self.include {
+            Some(include) if !is_dir => !include.matched_path_or_any_parents(path, false).is_ignore(),
+            _ => false,
+        }
+    }
+}
+
+/// 将 gitignore 语法的规则列表编译为匹配器
+fn build_matcher(patterns: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns {
+        builder.add_line(None, pattern)
+            .map_err(|e| SSHError::Io(format!("无效的匹配规则 '{}': {}", pattern, e)))?;
+    }
+    builder.build()
+        .map_err(|e| SSHError::Io(format!("无效的匹配规则: {}", e)))
+}
+
+/// 路径最后一段
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// `path` 在目录 `dir` 下时返回相对于 `dir` 的部分（`dir` 为空表示根目录）
+fn strip_dir<'a>(path: &'a str, dir: &str) -> Option<&'a str> {
+    if dir.is_empty() {
+        return Some(path);
+    }
+    path.strip_prefix(dir)?.strip_prefix('/')
+}
+
+/// 拼接相对路径
+fn join_relative(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// 比较源端和目标端目录树，生成同步操作列表
+///
+/// 删除操作排在最前，被删除目录下的内容逐项列出并排在目录之前：
+/// 目标端被排除的文件不在目录树中，逐项删除后仍保留在目录里，目录因此不会被删除。
+/// 两端同时存在的文件由 `same_content` 判断是否需要更新
+pub fn diff_trees<F>(source: &Tree, target: &Tree, delete: bool, same_content: F) -> Vec<SyncEntry>
+where
+    F: Fn(&str, &TreeEntry, &TreeEntry) -> bool,
+{
+    let under_any = |path: &str, dirs: &[String]| dirs.iter().any(|dir| strip_dir(path, dir).is_some());
+
+    let mut deletes = Vec::new();
+    if delete {
+        // 逆序遍历，子项总在所在目录之前
+        for (path, target_entry) in target.iter().rev() {
+            // 类型冲突（文件/目录）时先删除，再按新建处理
+            if matches!(source.get(path), Some(source_entry) if source_entry.is_dir == target_entry.is_dir) {
+                continue;
+            }
+            deletes.push(SyncEntry {
+                path: path.clone(),
+                action: SyncAction::Delete,
+                is_dir: target_entry.is_dir,
+                size: target_entry.size,
+                modified: target_entry.mtime,
+            });
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut conflict_dirs: Vec<String> = Vec::new();
+    for (path, source_entry) in source {
+        if under_any(path, &conflict_dirs) {
+            continue;
+        }
+        let action = match target.get(path) {
+            None => SyncAction::Create,
+            Some(target_entry) if target_entry.is_dir != source_entry.is_dir => {
+                if delete {
+                    SyncAction::Create
+                } else {
+                    if source_entry.is_dir {
+                        conflict_dirs.push(path.clone());
+                    }
+                    SyncAction::Conflict
+                }
+            }
+            Some(_) if source_entry.is_dir => continue,
+            Some(target_entry) => {
+                if same_content(path, source_entry, target_entry) {
+                    continue;
+                }
+                SyncAction::Update
+            }
+        };
+        changes.push(SyncEntry {
+            path: path.clone(),
+            action,
+            is_dir: source_entry.is_dir,
+            size: source_entry.size,
+            modified: source_entry.mtime,
+        });
+    }
+
+    deletes.extend(changes);
+    deletes
+}
+
+/// 相对路径对应的本地路径
+fn local_path(root: &Path, relative: &str) -> PathBuf {
+    relative.split('/').filter(|part| !part.is_empty()).fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+/// 相对路径对应的远程路径
+fn remote_path(root: &str, relative: &str) -> String {
+    if relative.is_empty() {
+        root.to_string()
+    } else {
+        format!("{}/{}", root.trim_end_matches('/'), relative)
+    }
+}
+
+/// 同步计划来自前端，执行前确认路径不会跳出同步根目录
+fn validate_relative_path(path: &str) -> Result<()> {
+    let valid = !path.starts_with('/')
+        && path.split('/').all(|part| {
+            !part.is_empty() && part != "." && part != ".." && !(cfg!(windows) && part.contains('\\'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(SSHError::Io(format!("无效的同步路径: {}", path)))
+    }
+}
+
+fn cancelled() -> SSHError {
+    SSHError::Io("同步已取消".to_string())
+}
+
+fn unix_secs(time: Option<SystemTime>) -> u64 {
+    time.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// 扫描本地目录树，根目录不存在时返回空树
+///
+/// 符号链接和特殊文件不参与同步。`load_gitignore` 为 true 时（本地为源端）
+/// 读取各目录下的 .gitignore 加入过滤规则
+fn scan_local(root: &Path, filter: &mut SyncFilter, load_gitignore: bool, token: &CancellationToken) -> Result<Tree> {
+    let mut tree = Tree::new();
+    match std::fs::metadata(root) {
+        Ok(metadata) if metadata.is_dir() => {}
+        Ok(_) => return Err(SSHError::Io(format!("'{}' 不是目录", root.display()))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(tree),
+        Err(e) => return Err(SSHError::Io(format!("无法访问本地目录 '{}': {}", root.display(), e))),
+    }
+
+    let mut dirs = vec![String::new()];
+    while let Some(dir) = dirs.pop() {
+        if token.is_cancelled() {
+            return Err(cancelled());
+        }
+
+        let dir_path = local_path(root, &dir);
+        if load_gitignore {
+            let gitignore = dir_path.join(".gitignore");
+            if gitignore.is_file() {
+                let content = std::fs::read(&gitignore)
+                    .map_err(|e| SSHError::Io(format!("读取 '{}' 失败: {}", gitignore.display(), e)))?;
+                filter.add_gitignore(&dir, &String::from_utf8_lossy(&content));
+            }
+        }
+
+        let read_dir = std::fs::read_dir(&dir_path)
+            .map_err(|e| SSHError::Io(format!("无法读取本地目录 '{}': {}", dir_path.display(), e)))?;
+        for entry in read_dir {
+            let entry = entry
+                .map_err(|e| SSHError::Io(format!("无法读取本地目录 '{}': {}", dir_path.display(), e)))?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                warn!("Skipping non UTF-8 file name in {}", dir_path.display());
+                continue;
+            };
+            let file_type = entry.file_type()
+                .map_err(|e| SSHError::Io(format!("无法读取 '{}' 的类型: {}", name, e)))?;
+            if !file_type.is_dir() && !file_type.is_file() {
+                continue;
+            }
+
+            let path = join_relative(&dir, &name);
+            let is_dir = file_type.is_dir();
+            if filter.is_excluded(&path, is_dir) {
+                continue;
+            }
+
+            let metadata = entry.metadata()
+                .map_err(|e| SSHError::Io(format!("无法获取 '{}' 的元数据: {}", path, e)))?;
+            tree.insert(path.clone(), TreeEntry {
+                is_dir,
+                size: if is_dir { 0 } else { metadata.len() },
+                mtime: unix_secs(metadata.modified().ok()),
+            });
+            if is_dir {
+                dirs.push(path);
+            }
+        }
+    }
+
+    Ok(tree)
+}
+
+/// 在阻塞线程池中扫描本地目录树，返回目录树和（可能加载了 .gitignore 的）过滤规则
+async fn scan_local_blocking(
+    root: PathBuf,
+    mut filter: SyncFilter,
+    load_gitignore: bool,
+    token: CancellationToken,
+) -> Result<(Tree, SyncFilter)> {
+    tokio::task::spawn_blocking(move || {
+        let tree = scan_local(&root, &mut filter, load_gitignore, &token)?;
+        Ok((tree, filter))
+    })
+    .await
+    .map_err(|e| SSHError::Io(format!("扫描本地目录失败: {}", e)))?
+}
+
+/// 远程条目的文件类型位
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+
+/// 扫描远程目录树，根目录不存在时返回空树
+///
+/// 符号链接和特殊文件不参与同步（服务器未返回类型位时按普通文件处理）
+async fn scan_remote(
+    client: &mut SftpClient,
+    root: &str,
+    filter: &mut SyncFilter,
+    load_gitignore: bool,
+    token: &CancellationToken,
+) -> Result<Tree> {
+    let mut tree = Tree::new();
+    if !client.exists(root).await? {
+        return Ok(tree);
+    }
+    if !client.stat(root).await?.is_dir {
+        return Err(SSHError::Io(format!("'{}' 不是目录", root)));
+    }
+
+    let mut dirs = vec![String::new()];
+    while let Some(dir) = dirs.pop() {
+        if token.is_cancelled() {
+            return Err(cancelled());
+        }
+
+        let entries = client.list_dir(&remote_path(root, &dir)).await?;
+        if load_gitignore {
+            if let Some(gitignore) = entries.iter().find(|entry| entry.name == ".gitignore" && !entry.is_dir) {
+                let content = client.read_file(&gitignore.path).await?;
+                filter.add_gitignore(&dir, &String::from_utf8_lossy(&content));
+            }
+        }
+
+        for entry in entries {
+            if !entry.is_dir && !matches!(entry.mode & S_IFMT, 0 | S_IFREG) {
+                continue;
+            }
+
+            let path = join_relative(&dir, &entry.name);
+            if filter.is_excluded(&path, entry.is_dir) {
+                continue;
+            }
+
+            tree.insert(path.clone(), TreeEntry {
+                is_dir: entry.is_dir,
+                size: if entry.is_dir { 0 } else { entry.size },
+                mtime: entry.modified,
+            });
+            if entry.is_dir {
+                dirs.push(path);
+            }
+        }
+    }
+
+    Ok(tree)
+}
+
+/// 按 POSIX shell 单引号规则转义参数
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 解析 sha256sum 的输出，返回 文件名 -> 哈希
+///
+/// 文件名含换行或反斜杠时，该行以 '\' 开头且文件名被转义
+fn parse_sha256sum(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (escaped, line) = match line.strip_prefix('\\') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (hash, name) = line.split_once(' ')?;
+            // 文本模式为两个空格，二进制模式为 " *"
+            let name = name.strip_prefix(' ').or_else(|| name.strip_prefix('*'))?;
+            let name = if escaped { unescape_checksum_name(name) } else { name.to_string() };
+            Some((name, hash.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+fn unescape_checksum_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 计算远程文件的 SHA-256（在同一连接上执行 sha256sum），读取失败的文件不出现在结果中
+async fn hash_remote_files(
+    client: &SftpClient,
+    root: &str,
+    paths: &[String],
+    token: &CancellationToken,
+) -> Result<HashMap<String, String>> {
+    let transport = client.transport()
+        .ok_or_else(|| SSHError::NotSupported("当前连接无法执行命令，不能按校验和比较".to_string()))?;
+
+    // 按命令长度分批，避免超过远程 shell 的参数长度限制
+    let prefix = format!("cd -- {} && sha256sum --", shell_quote(root));
+    let mut commands = Vec::new();
+    let mut command = prefix.clone();
+    for path in paths {
+        let argument = shell_quote(path);
+        if command.len() > prefix.len() && command.len() + argument.len() + 1 > CHECKSUM_COMMAND_LIMIT {
+            commands.push(std::mem::replace(&mut command, prefix.clone()));
+        }
+        command.push(' ');
+        command.push_str(&argument);
+    }
+    if command.len() > prefix.len() {
+        commands.push(command);
+    }
+
+    let mut hashes = HashMap::new();
+    for command in commands {
+        let request = ExecRequest { command, timeout_ms: None };
+        let result = transport.exec(&request, token.clone(), |_, _| {}).await?;
+        if result.cancelled {
+            return Err(cancelled());
+        }
+        if result.exit_code == Some(127) {
+            return Err(SSHError::NotSupported("远程主机没有 sha256sum 命令，不能按校验和比较".to_string()));
+        }
+        // 个别文件读取失败时 sha256sum 退出码为 1，缺少哈希的文件按有变化处理
+        hashes.extend(parse_sha256sum(&result.stdout));
+    }
+
+    Ok(hashes)
+}
+
+/// 计算本地文件的 SHA-256（十六进制）
+fn hash_local_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 两端大小相同的文件按 SHA-256 比较，返回内容相同的路径
+async fn identical_by_checksum(
+    client: &SftpClient,
+    options: &SyncOptions,
+    source: &Tree,
+    target: &Tree,
+    token: &CancellationToken,
+) -> Result<std::collections::HashSet<String>> {
+    let candidates: Vec<String> = source
+        .iter()
+        .filter(|(path, entry)| {
+            !entry.is_dir && matches!(target.get(*path), Some(other) if !other.is_dir && other.size == entry.size)
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    if candidates.is_empty() {
+        return Ok(Default::default());
+    }
+    info!("Comparing {} files by checksum", candidates.len());
+
+    let remote_hashes = hash_remote_files(client, &options.remote_dir, &candidates, token).await?;
+
+    let local_root = PathBuf::from(&options.local_dir);
+    let local_candidates = candidates.clone();
+    let local_token = token.clone();
+    let local_hashes = tokio::task::spawn_blocking(move || {
+        let mut hashes = HashMap::new();
+        for path in local_candidates {
+            if local_token.is_cancelled() {
+                return Err(cancelled());
+            }
+            match hash_local_file(&local_path(&local_root, &path)) {
+                Ok(hash) => {
+                    hashes.insert(path, hash);
+                }
+                Err(e) => warn!("Failed to hash local file '{}': {}", path, e),
+            }
+        }
+        Ok(hashes)
+    })
+    .await
+    .map_err(|e| SSHError::Io(format!("计算本地文件校验和失败: {}", e)))??;
+
+    Ok(candidates
+        .into_iter()
+        .filter(|path| matches!((local_hashes.get(path), remote_hashes.get(path)), (Some(a), Some(b)) if a == b))
+        .collect())
+}
+
+/// 扫描两端目录树并生成同步计划
+///
+/// 先扫描源端（同时加载 .gitignore），目标端使用同样的规则，因此被排除的文件不会被删除
+pub async fn build_plan(client: &mut SftpClient, options: &SyncOptions, token: &CancellationToken) -> Result<SyncPlan> {
+    info!(
+        "Building sync plan ({:?}): {} <-> {}",
+        options.direction, options.local_dir, options.remote_dir
+    );
+    let filter = SyncFilter::new(&options.include, &options.exclude, options.use_gitignore)?;
+    let local_root = PathBuf::from(&options.local_dir);
+
+    let (source, target) = match options.direction {
+        SyncDirection::Upload => {
+            let (source, mut filter) =
+                scan_local_blocking(local_root, filter, options.use_gitignore, token.clone()).await?;
+            let target = scan_remote(client, &options.remote_dir, &mut filter, false, token).await?;
+            (source, target)
+        }
+        SyncDirection::Download => {
+            let mut filter = filter;
+            let source = scan_remote(client, &options.remote_dir, &mut filter, options.use_gitignore, token).await?;
+            let (target, _) = scan_local_blocking(local_root, filter, false, token.clone()).await?;
+            (source, target)
+        }
+    };
+
+    let identical = if options.checksum {
+        Some(identical_by_checksum(client, options, &source, &target, token).await?)
+    } else {
+        None
+    };
+
+    let entries = diff_trees(&source, &target, options.delete, |path, source_entry, target_entry| {
+        match &identical {
+            Some(identical) => identical.contains(path),
+            None => source_entry.size == target_entry.size && source_entry.mtime == target_entry.mtime,
+        }
+    });
+
+    let mut plan = SyncPlan {
+        direction: options.direction,
+        local_dir: options.local_dir.clone(),
+        remote_dir: options.remote_dir.clone(),
+        entries: Vec::new(),
+        transfer_files: 0,
+        transfer_bytes: 0,
+        delete_count: 0,
+        conflict_count: 0,
+        unchanged_files: source.values().filter(|entry| !entry.is_dir).count() as u64,
+    };
+    for entry in &entries {
+        match entry.action {
+            SyncAction::Create | SyncAction::Update if !entry.is_dir => {
+                plan.transfer_files += 1;
+                plan.transfer_bytes += entry.size;
+            }
+            SyncAction::Delete => plan.delete_count += 1,
+            SyncAction::Conflict => plan.conflict_count += 1,
+            _ => {}
+        }
+        if entry.action != SyncAction::Delete && !entry.is_dir {
+            plan.unchanged_files -= 1;
+        }
+    }
+    plan.entries = entries;
+
+    info!(
+        "Sync plan: {} files ({} bytes) to transfer, {} to delete, {} conflicts, {} unchanged",
+        plan.transfer_files, plan.transfer_bytes, plan.delete_count, plan.conflict_count, plan.unchanged_files
+    );
+    Ok(plan)
+}
+
+/// 本地目录是否为空
+async fn is_empty_local_dir(path: &Path) -> std::io::Result<bool> {
+    Ok(tokio::fs::read_dir(path).await?.next_entry().await?.is_none())
+}
+
+/// 同步进度事件发送器
+struct SyncProgress<'a> {
+    app_handle: &'a AppHandle,
+    task_id: &'a str,
+    connection_id: &'a str,
+    total_files: u64,
+    total_bytes: u64,
+    start: Instant,
+}
+
+impl SyncProgress<'_> {
+    fn emit(&self, current_file: &str, files_completed: u64, bytes_transferred: u64) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let speed = if elapsed > 0.0 { (bytes_transferred as f64 / elapsed) as u64 } else { 0 };
+        let _ = self.app_handle.emit(SYNC_PROGRESS_EVENT, SyncProgressEvent {
+            task_id: self.task_id.to_string(),
+            connection_id: self.connection_id.to_string(),
+            current_file: current_file.to_string(),
+            files_completed,
+            total_files: self.total_files,
+            bytes_transferred,
+            total_bytes: self.total_bytes,
+            speed_bytes_per_sec: speed,
+        });
+    }
+}
+
+/// 执行同步计划
+///
+/// 依次删除、创建目录、传输文件；传输后把源端修改时间设置到目标文件，
+/// 下次按大小和修改时间比较时视为未变化。类型冲突项被跳过
+pub async fn apply_plan(
+    client: &mut SftpClient,
+    plan: &SyncPlan,
+    app_handle: &AppHandle,
+    connection_id: &str,
+    task_id: &str,
+    token: &CancellationToken,
+) -> Result<SyncResult> {
+    for entry in &plan.entries {
+        validate_relative_path(&entry.path)?;
+    }
+
+    let start = Instant::now();
+    let local_root = PathBuf::from(&plan.local_dir);
+    let mut result = SyncResult {
+        files_transferred: 0,
+        bytes_transferred: 0,
+        dirs_created: 0,
+        deleted: 0,
+        elapsed_time_ms: 0,
+    };
+
+    // 阶段 1: 逐项删除目标端多余的文件和目录（子项在目录之前），
+    // 目录中还有被排除的文件时保留目录
+    for entry in plan.entries.iter().filter(|entry| entry.action == SyncAction::Delete) {
+        if token.is_cancelled() {
+            return Err(cancelled());
+        }
+        let removed = match plan.direction {
+            SyncDirection::Upload => {
+                let path = remote_path(&plan.remote_dir, &entry.path);
+                if !entry.is_dir {
+                    client.remove_file(&path).await?;
+                    true
+                } else if client.list_dir(&path).await?.is_empty() {
+                    client.remove_dir(&path, false).await?;
+                    true
+                } else {
+                    false
+                }
+            }
+            SyncDirection::Download => {
+                let path = local_path(&local_root, &entry.path);
+                let io_error = |e: std::io::Error| SSHError::Io(format!("删除 '{}' 失败: {}", path.display(), e));
+                if !entry.is_dir {
+                    tokio::fs::remove_file(&path).await.map_err(io_error)?;
+                    true
+                } else if is_empty_local_dir(&path).await.map_err(io_error)? {
+                    tokio::fs::remove_dir(&path).await.map_err(io_error)?;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+
+        if removed {
+            result.deleted += 1;
+            continue;
+        }
+        // 源端同一路径是文件时无法替换
+        let replaced = plan.entries.iter().any(|other| other.action == SyncAction::Create && other.path == entry.path);
+        if replaced {
+            return Err(SSHError::Io(format!("目录 '{}' 中有被排除的文件，无法替换为文件", entry.path)));
+        }
+        info!("Keeping directory {} with excluded entries", entry.path);
+    }
+
+    // 阶段 2: 创建目录（父目录总在子目录之前）
+    match plan.direction {
+        SyncDirection::Upload => client.create_dir(&plan.remote_dir, true).await?,
+        SyncDirection::Download => tokio::fs::create_dir_all(&local_root).await
+            .map_err(|e| SSHError::Io(format!("创建本地目录失败: {}", e)))?,
+    }
+    for entry in plan.entries.iter().filter(|entry| entry.action == SyncAction::Create && entry.is_dir) {
+        match plan.direction {
+            SyncDirection::Upload => client.create_dir(&remote_path(&plan.remote_dir, &entry.path), false).await?,
+            SyncDirection::Download => tokio::fs::create_dir_all(local_path(&local_root, &entry.path)).await
+                .map_err(|e| SSHError::Io(format!("创建本地目录失败: {}", e)))?,
+        }
+        result.dirs_created += 1;
+    }
+
+    // 阶段 3: 传输新建和有变化的文件
+    let files: Vec<&SyncEntry> = plan.entries
+        .iter()
+        .filter(|entry| matches!(entry.action, SyncAction::Create | SyncAction::Update) && !entry.is_dir)
+        .collect();
+    let progress = SyncProgress {
+        app_handle,
+        task_id,
+        connection_id,
+        total_files: files.len() as u64,
+        total_bytes: files.iter().map(|entry| entry.size).sum(),
+        start,
+    };
+
+    for entry in files {
+        if token.is_cancelled() {
+            info!("Sync cancelled for task: {}", task_id);
+            return Err(cancelled());
+        }
+
+        let remote = remote_path(&plan.remote_dir, &entry.path);
+        let local = local_path(&local_root, &entry.path);
+        let local_str = local.to_string_lossy();
+
+        // 使用节流机制控制事件发送频率
+        let files_completed = result.files_transferred;
+        let bytes_before = result.bytes_transferred;
+        let last_emit = std::sync::Mutex::new(Instant::now());
+        let on_progress = |transferred: u64, _total: u64| {
+            let mut last_emit = last_emit.lock().unwrap();
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                *last_emit = Instant::now();
+                progress.emit(&entry.path, files_completed, bytes_before + transferred);
+            }
+        };
+
+        let transferred = match plan.direction {
+            SyncDirection::Upload => {
+                let transferred = client.upload_file_stream(&local_str, &remote, 0, token, on_progress, true).await?;
+                if let Err(e) = client.set_modified(&remote, entry.modified).await {
+                    warn!("Failed to preserve mtime of {}: {}", remote, e);
+                }
+                transferred
+            }
+            SyncDirection::Download => {
+                let transferred = client.download_file_stream(&remote, &local_str, 0, token, on_progress).await?;
+                let modified = UNIX_EPOCH + Duration::from_secs(entry.modified);
+                let preserved = std::fs::File::options()
+                    .write(true)
+                    .open(&local)
+                    .and_then(|file| file.set_modified(modified));
+                if let Err(e) = preserved {
+                    warn!("Failed to preserve mtime of {}: {}", local.display(), e);
+                }
+                transferred
+            }
+        };
+
+        result.files_transferred += 1;
+        result.bytes_transferred += transferred;
+        progress.emit(&entry.path, result.files_transferred, result.bytes_transferred);
+    }
+
+    result.elapsed_time_ms = start.elapsed().as_millis() as u64;
+    info!(
+        "Sync completed: {} files ({} bytes) transferred, {} dirs created, {} deleted in {} ms",
+        result.files_transferred, result.bytes_transferred, result.dirs_created, result.deleted, result.elapsed_time_ms
+    );
+    Ok(result)
+}
+
+/// 生成同步计划
+///
+/// 扫描使用独立的任务 SFTP Client，不阻塞文件浏览，扫描期间可按 task_id 取消
+pub(crate) async fn plan_task(
+    manager: &SftpManager,
+    connection_id: &str,
+    task_id: &str,
+    options: &SyncOptions,
+) -> Result<SyncPlan> {
+    let cancellation_token = manager.get_cancellation_token(task_id).await;
+    let sftp_client = manager.create_task_client(connection_id, task_id).await?;
+
+    let result = {
+        let mut client_guard = sftp_client.lock().await;
+        build_plan(&mut client_guard, options, &cancellation_token).await
+    };
+
+    manager.cleanup_task_client(task_id).await;
+    manager.cleanup_cancellation_token(task_id).await;
+    result
+}
+
+/// 执行同步计划，进度通过 `sftp-sync-progress` 事件发送
+pub(crate) async fn apply_task(
+    manager: &SftpManager,
+    app_handle: &AppHandle,
+    connection_id: &str,
+    task_id: &str,
+    plan: &SyncPlan,
+) -> Result<SyncResult> {
+    let cancellation_token = manager.get_cancellation_token(task_id).await;
+    let sftp_client = manager.create_task_client(connection_id, task_id).await?;
+
+    let result = {
+        let mut client_guard = sftp_client.lock().await;
+        apply_plan(&mut client_guard, plan, app_handle, connection_id, task_id, &cancellation_token).await
+    };
+
+    manager.cleanup_task_client(task_id).await;
+    manager.cleanup_cancellation_token(task_id).await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(size: u64, mtime: u64) -> TreeEntry {
+        TreeEntry { is_dir: false, size, mtime }
+    }
+
+    fn dir() -> TreeEntry {
+        TreeEntry { is_dir: true, size: 0, mtime: 0 }
+    }
+
+    fn tree(entries: &[(&str, TreeEntry)]) -> Tree {
+        entries.iter().map(|(path, entry)| (path.to_string(), *entry)).collect()
+    }
+
+    fn actions(entries: &[SyncEntry]) -> Vec<(&str, SyncAction)> {
+        entries.iter().map(|entry| (entry.path.as_str(), entry.action)).collect()
+    }
+
+    fn same_size_and_mtime(_: &str, a: &TreeEntry, b: &TreeEntry) -> bool {
+        a.size == b.size && a.mtime == b.mtime
+    }
+
+    #[test]
+    fn test_filter_rules() {
+        let include = vec!["*.rs".to_string(), "docs/".to_string()];
+        let exclude = vec!["target/".to_string(), "*.log".to_string()];
+        let filter = SyncFilter::new(&include, &exclude, true).unwrap();
+
+        assert!(filter.is_excluded("target", true));
+        assert!(filter.is_excluded("src/debug.log", false));
+        assert!(filter.is_excluded(".git", true));
+        assert!(filter.is_excluded("README.md", false));
+        assert!(!filter.is_excluded("src", true));
+        assert!(!filter.is_excluded("src/main.rs", false));
+        assert!(!filter.is_excluded("docs/guide/intro.md", false));
+
+        assert!(SyncFilter::new(&[], &["{a,b".to_string()], false).is_err());
+    }
+
+    #[test]
+    fn test_nested_gitignore() {
+        let mut filter = SyncFilter::new(&[], &[], true).unwrap();
+        filter.add_gitignore("", "*.tmp\n/build\nnode_modules/\n");
+        filter.add_gitignore("web", "!keep.tmp\ndist\n");
+
+        assert!(filter.is_excluded("a.tmp", false));
+        assert!(filter.is_excluded("web/other.tmp", false));
+        assert!(!filter.is_excluded("web/keep.tmp", false));
+        assert!(filter.is_excluded("build", true));
+        assert!(!filter.is_excluded("web/build", true));
+        assert!(filter.is_excluded("web/node_modules", true));
+        assert!(filter.is_excluded("web/dist", true));
+        assert!(!filter.is_excluded("dist", true));
+        assert!(filter.is_excluded("webapp/keep.tmp", false));
+    }
+
+    #[test]
+    fn test_diff_trees() {
+        let source = tree(&[
+            ("a.txt", file(10, 100)),
+            ("b.txt", file(20, 200)),
+            ("c.txt", file(30, 300)),
+            ("src", dir()),
+            ("src/main.rs", file(5, 50)),
+        ]);
+        let target = tree(&[
+            ("a.txt", file(10, 100)),
+            ("b.txt", file(20, 201)),
+            ("old", dir()),
+            ("old/x.txt", file(1, 1)),
+            ("stale.txt", file(2, 2)),
+        ]);
+
+        let entries = diff_trees(&source, &target, false, same_size_and_mtime);
+        assert_eq!(actions(&entries), vec![
+            ("b.txt", SyncAction::Update),
+            ("c.txt", SyncAction::Create),
+            ("src", SyncAction::Create),
+            ("src/main.rs", SyncAction::Create),
+        ]);
+
+        // 删除排在最前，被删除目录下的内容逐项列出并排在目录之前
+        let entries = diff_trees(&source, &target, true, same_size_and_mtime);
+        assert_eq!(actions(&entries), vec![
+            ("stale.txt", SyncAction::Delete),
+            ("old/x.txt", SyncAction::Delete),
+            ("old", SyncAction::Delete),
+            ("b.txt", SyncAction::Update),
+            ("c.txt", SyncAction::Create),
+            ("src", SyncAction::Create),
+            ("src/main.rs", SyncAction::Create),
+        ]);
+    }
+
+    #[test]
+    fn test_diff_trees_type_conflict() {
+        let source = tree(&[("x", dir()), ("x/a.txt", file(1, 1)), ("y", file(2, 2))]);
+        let target = tree(&[("x", file(3, 3)), ("y", dir()), ("y/b.txt", file(4, 4))]);
+
+        let entries = diff_trees(&source, &target, false, same_size_and_mtime);
+        assert_eq!(actions(&entries), vec![("x", SyncAction::Conflict), ("y", SyncAction::Conflict)]);
+
+        let entries = diff_trees(&source, &target, true, same_size_and_mtime);
+        assert_eq!(actions(&entries), vec![
+            ("y/b.txt", SyncAction::Delete),
+            ("y", SyncAction::Delete),
+            ("x", SyncAction::Delete),
+            ("x", SyncAction::Create),
+            ("x/a.txt", SyncAction::Create),
+            ("y", SyncAction::Create),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_sha256sum() {
+        let output = "abc  a.txt\nDEF *dir/b c.txt\n\\123  new\\nline\\\\x\nsha256sum: missing: No such file\n";
+        let hashes = parse_sha256sum(output);
+
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes["a.txt"], "abc");
+        assert_eq!(hashes["dir/b c.txt"], "def");
+        assert_eq!(hashes["new\nline\\x"], "123");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_validate_relative_path() {
+        assert!(validate_relative_path("a/b.txt").is_ok());
+        assert!(validate_relative_path("..").is_err());
+        assert!(validate_relative_path("a/../../etc").is_err());
+        assert!(validate_relative_path("/etc/passwd").is_err());
+        assert!(validate_relative_path("a//b").is_err());
+        assert!(validate_relative_path("").is_err());
+    }
+}
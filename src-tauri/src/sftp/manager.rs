@@ -4,8 +4,10 @@
 
 use crate::error::{Result, SSHError};
 use crate::sftp::client::SftpClient;
+use crate::ssh::keys;
 use crate::ssh::manager::SSHManager;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{debug, info};
@@ -21,11 +23,15 @@ pub struct SftpManager {
     // 浏览专用客户端映射: connection_id -> Arc<Mutex<SftpClient>>
     // 用于所有快速浏览操作（list_dir, remove_file, rename 等）
     browse_clients: Arc<Mutex<HashMap<String, Arc<Mutex<SftpClient>>>>>,
-    // 任务客户端映射: task_id -> Arc<Mutex<SftpClient>>
+    // 任务客户端映射: task_id -> (connection_id, Arc<Mutex<SftpClient>>)
     // 每个上传/下载任务使用独立的 SFTP Client，实现完全并发
-    task_clients: Arc<Mutex<HashMap<String, Arc<Mutex<SftpClient>>>>>,
+    task_clients: Arc<Mutex<HashMap<String, (String, Arc<Mutex<SftpClient>>)>>>,
+    // 服务器之间传输的源端连接: task_id -> source connection_id
+    task_sources: Arc<Mutex<HashMap<String, String>>>,
     // 取消令牌映射: task_id -> CancellationToken
     cancellation_tokens: Arc<Mutex<HashMap<String, tokio_util::sync::CancellationToken>>>,
+    // 任务客户端流水线传输的最大未完成请求数
+    pipeline_window: AtomicUsize,
 }
 
 impl SftpManager {
@@ -36,10 +42,22 @@ impl SftpManager {
             ssh_manager,
             browse_clients: Arc::new(Mutex::new(HashMap::new())),
             task_clients: Arc::new(Mutex::new(HashMap::new())),
+            task_sources: Arc::new(Mutex::new(HashMap::new())),
             cancellation_tokens: Arc::new(Mutex::new(HashMap::new())),
+            pipeline_window: AtomicUsize::new(super::pipeline::DEFAULT_WINDOW),
         }
     }
 
+    /// 流水线传输的最大未完成请求数
+    pub fn pipeline_window(&self) -> usize {
+        self.pipeline_window.load(Ordering::Relaxed)
+    }
+
+    /// 设置流水线传输的最大未完成请求数，只影响之后开始的任务
+    pub fn set_pipeline_window(&self, window: usize) {
+        self.pipeline_window.store(window.clamp(1, super::pipeline::MAX_WINDOW), Ordering::Relaxed);
+    }
+
     /// 列出目录（使用浏览客户端）
     pub async fn list_dir(&self, connection_id: &str, path: &str) -> Result<Vec<super::SftpFileInfo>> {
         info!("Listing directory: {}", path);
@@ -96,6 +114,13 @@ impl SftpManager {
         client_guard.read_file(path).await
     }
 
+    /// 获取文件或目录信息（使用浏览客户端）
+    pub async fn stat(&self, connection_id: &str, path: &str) -> Result<super::SftpFileInfo> {
+        let client = self.get_or_create_browse_client(connection_id).await?;
+        let mut client_guard = client.lock().await;
+        client_guard.stat(path).await
+    }
+
     /// 写入文件（使用浏览客户端）
     pub async fn write_file(&self, connection_id: &str, path: &str, content: Vec<u8>) -> Result<()> {
         tracing::info!("=== Write File Start ===");
@@ -112,6 +137,41 @@ impl SftpManager {
         client_guard.write_file(path, &content).await
     }
 
+    /// 将公钥追加到远程 ~/.ssh/authorized_keys（使用浏览客户端）
+    ///
+    /// 相对路径以登录目录为基准；.ssh 不存在时以 700 创建，authorized_keys 不存在时以 600 创建。
+    /// 只在文件末尾追加新密钥，不改写已有内容。
+    /// 已存在相同密钥时不修改文件并返回 false
+    pub async fn append_authorized_key(&self, connection_id: &str, public_key: &str) -> Result<bool> {
+        const SSH_DIR: &str = ".ssh";
+        const AUTHORIZED_KEYS: &str = ".ssh/authorized_keys";
+
+        let client = self.get_or_create_browse_client(connection_id).await?;
+        let mut client_guard = client.lock().await;
+
+        if !client_guard.exists(SSH_DIR).await? {
+            client_guard.create_dir(SSH_DIR, false).await?;
+            client_guard.chmod(SSH_DIR, 0o700).await?;
+        }
+
+        let existing = if client_guard.exists(AUTHORIZED_KEYS).await? {
+            String::from_utf8_lossy(&client_guard.read_file(AUTHORIZED_KEYS).await?).into_owned()
+        } else {
+            String::new()
+        };
+
+        let Some(addition) = keys::add_authorized_key(&existing, public_key)? else {
+            info!("Public key already present in authorized_keys on {}", connection_id);
+            return Ok(false);
+        };
+
+        // 只追加新的一行，不改写原文件：写入失败时已有的密钥不受影响
+        client_guard.append_file(AUTHORIZED_KEYS, addition.as_bytes(), 0o600).await?;
+
+        info!("Public key appended to authorized_keys on {}", connection_id);
+        Ok(true)
+    }
+
     /// 获取或创建浏览专用 SFTP Client
     ///
     /// 用于快速浏览操作如 list_dir, get_file_info, remove_file 等
@@ -147,25 +207,60 @@ impl SftpManager {
         info!("Creating task SFTP client for task: {} on connection: {}", task_id, connection_id);
 
         // 创建新的 SFTP Client（基于同一个 SSH 连接）
-        let client = self.create_sftp_client(connection_id).await?;
+        let mut client = self.create_sftp_client(connection_id).await?;
+        client.set_pipeline_window(self.pipeline_window());
         let client_arc = Arc::new(Mutex::new(client));
 
         // 注册到任务映射
         let mut task_clients = self.task_clients.lock().await;
-        task_clients.insert(task_id.to_string(), client_arc.clone());
+        task_clients.insert(task_id.to_string(), (connection_id.to_string(), client_arc.clone()));
 
         Ok(client_arc)
     }
 
+    /// 创建服务器之间传输的源端 SFTP Client
+    ///
+    /// 客户端由任务持有并在结束时释放，只登记源端连接用于并发计数；取消和状态查询以目标端任务客户端为准
+    pub async fn create_source_client(&self, connection_id: &str, task_id: &str) -> Result<SftpClient> {
+        info!("Creating source SFTP client for task: {} on connection: {}", task_id, connection_id);
+
+        let mut client = self.create_sftp_client(connection_id).await?;
+        client.set_pipeline_window(self.pipeline_window());
+        self.task_sources.lock().await.insert(task_id.to_string(), connection_id.to_string());
+        Ok(client)
+    }
+
     /// 清理任务 SFTP Client
     ///
     /// 在任务完成或失败后调用，释放资源
     pub async fn cleanup_task_client(&self, task_id: &str) {
-        let mut task_clients = self.task_clients.lock().await;
-        task_clients.remove(task_id);
+        self.task_clients.lock().await.remove(task_id);
+        self.task_sources.lock().await.remove(task_id);
         info!("Task SFTP client cleaned up for task: {}", task_id);
     }
 
+    /// 任务是否正在运行（持有任务 SFTP Client）
+    pub async fn is_task_active(&self, task_id: &str) -> bool {
+        self.task_clients.lock().await.contains_key(task_id)
+    }
+
+    /// 正在运行的任务及其占用的连接：task_id -> [connection_id, source connection_id]
+    ///
+    /// 服务器之间传输同时占用源端连接；包括不经过传输队列的任务，传输队列据此计算并发名额
+    pub async fn task_connections(&self) -> HashMap<String, Vec<String>> {
+        let task_sources = self.task_sources.lock().await;
+        self.task_clients
+            .lock()
+            .await
+            .iter()
+            .map(|(task_id, (connection_id, _))| {
+                let mut connections = vec![connection_id.clone()];
+                connections.extend(task_sources.get(task_id).cloned());
+                (task_id.clone(), connections)
+            })
+            .collect()
+    }
+
     /// 创建 SFTP 客户端
     ///
     /// 通过打开 SSH 连接上的 SFTP 子系统来创建
@@ -3,7 +3,11 @@
 //! 提供基于 SFTP 协议的远程文件操作功能
 
 pub mod client;
+pub mod dir_sync;
 pub mod manager;
+pub mod pipeline;
+pub mod queue;
+pub mod transfer;
 
 pub use manager::SftpManager;
 
@@ -39,14 +43,8 @@ impl From<russh_sftp::protocol::FileAttributes> for SftpFileInfo {
     }
 }
 
-// ============================================================================
-// 未来特性：文件传输进度追踪
-// 以下类型和方法预留用于将来的文件传输进度追踪功能
-// ============================================================================
-
 /// 文件传输操作类型
-#[allow(dead_code)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransferOperation {
     Upload,
@@ -54,30 +52,84 @@ pub enum TransferOperation {
     RemoteToRemote,
 }
 
+impl TransferOperation {
+    /// 数据库中保存的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferOperation::Upload => "upload",
+            TransferOperation::Download => "download",
+            TransferOperation::RemoteToRemote => "remoteToRemote",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upload" => Some(TransferOperation::Upload),
+            "download" => Some(TransferOperation::Download),
+            "remoteToRemote" => Some(TransferOperation::RemoteToRemote),
+            _ => None,
+        }
+    }
+}
+
 /// 传输源
-#[allow(dead_code)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type")]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
 pub enum TransferSource {
     Local { path: String },
-    Remote { connection_id: String, path: String },
+    Remote {
+        connection_id: String,
+        /// 连接所属的会话配置ID，原连接断开（如应用重启）后用于改用同一会话的其他连接
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_id: Option<String>,
+        path: String,
+    },
 }
 
 /// 传输状态
-#[allow(dead_code)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "state")]
 #[serde(rename_all = "camelCase")]
 pub enum TransferStatus {
     Pending,
     InProgress,
+    /// 用户暂停，恢复前不会被调度
+    Paused,
     Completed,
     Failed { reason: String },
     Cancelled,
 }
 
-/// 文件传输进度
-#[allow(dead_code)]
+impl TransferStatus {
+    /// 数据库中保存的名称（失败原因单独保存）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Pending => "pending",
+            TransferStatus::InProgress => "inProgress",
+            TransferStatus::Paused => "paused",
+            TransferStatus::Completed => "completed",
+            TransferStatus::Failed { .. } => "failed",
+            TransferStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str, error_message: Option<String>) -> Option<Self> {
+        match value {
+            "pending" => Some(TransferStatus::Pending),
+            "inProgress" => Some(TransferStatus::InProgress),
+            "paused" => Some(TransferStatus::Paused),
+            "completed" => Some(TransferStatus::Completed),
+            "failed" => Some(TransferStatus::Failed { reason: error_message.unwrap_or_default() }),
+            "cancelled" => Some(TransferStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// 文件传输进度（传输队列中的一项）
+///
+/// id 同时作为上传/下载记录的 task_id，进度事件按 task_id 对应
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransferProgress {
@@ -85,10 +137,15 @@ pub struct TransferProgress {
     pub operation: TransferOperation,
     pub source: TransferSource,
     pub destination: TransferSource,
+    pub is_dir: bool,
+    /// 优先级，数值越大越先执行
+    pub priority: i32,
     pub file_size: u64,
     pub transferred: u64,
     pub speed: u64,         // bytes/s
     pub status: TransferStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
 /// 目录上传结果
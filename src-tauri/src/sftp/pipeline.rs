@@ -0,0 +1,685 @@
+//! 流水线 SFTP 传输
+//!
+//! 通过 `File` 逐块读写时每个请求都要等待一次往返，高延迟链路上吞吐量被限制在 块大小 / RTT。
+//! 这里在单独的 SFTP channel 上直接发送 READ/WRITE 请求，同时保持最多 window 个未完成的请求
+//! （OpenSSH sftp 默认 64 个）。响应按请求顺序处理：下载时按顺序写入本地文件，
+//! 上传时已确认的字节始终是文件的连续前缀，可以直接作为续传起点。
+
+use crate::error::{Result, SSHError};
+use futures::stream::{FuturesOrdered, StreamExt};
+use russh_sftp::client::error::Error as SftpError;
+use russh_sftp::client::RawSftpSession;
+use russh_sftp::protocol::{FileAttributes, OpenFlags, StatusCode};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+/// 默认最大未完成请求数
+pub const DEFAULT_WINDOW: usize = 64;
+
+/// 最大未完成请求数上限
+pub const MAX_WINDOW: usize = 256;
+
+/// 单个请求的数据长度，SFTP 服务器都支持 32KB 的读写
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// 请求超时（秒）
+///
+/// 请求在窗口中排队等待的时间也计入超时，低带宽链路上需要比默认的 10 秒更长
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// 服务器之间复制时内存管道的缓冲区大小
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+const FSYNC_EXTENSION: &str = "fsync@openssh.com";
+
+/// 流水线传输使用的 SFTP 会话
+pub struct PipelineSession {
+    session: RawSftpSession,
+    /// 服务器是否支持 fsync@openssh.com
+    fsync: bool,
+}
+
+impl PipelineSession {
+    /// 在已打开的 SFTP subsystem channel 上初始化会话
+    pub async fn new<S>(stream: S) -> Result<Self>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let session = RawSftpSession::new(stream);
+        session.set_timeout(REQUEST_TIMEOUT_SECS).await;
+
+        let version = session.init().await
+            .map_err(|e| SSHError::Ssh(format!("Failed to create SFTP session: {}", e)))?;
+        let fsync = version.extensions.get(FSYNC_EXTENSION).is_some_and(|v| v == "1");
+
+        Ok(Self { session, fsync })
+    }
+
+    /// 下载远程文件，从 offset 处开始按顺序写入 writer（writer 需已定位到 offset）
+    ///
+    /// # 参数
+    /// - `remote_path`: 远程文件路径
+    /// - `offset`: 起始位置
+    /// - `writer`: 本地写入端
+    /// - `window`: 最大未完成请求数
+    /// - `cancellation_token`: 取消令牌
+    /// - `progress_callback`: 进度回调函数 (transferred, total)
+    ///
+    /// # 返回
+    /// 传输结束时的文件位置
+    pub async fn download<W, F>(
+        &self,
+        remote_path: &str,
+        offset: u64,
+        writer: &mut W,
+        window: usize,
+        cancellation_token: &CancellationToken,
+        progress_callback: F,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: Fn(u64, u64),
+    {
+        let handle = self.session
+            .open(remote_path, OpenFlags::READ, FileAttributes::empty())
+            .await
+            .map_err(|e| SSHError::Ssh(format!("无法打开远程文件: {}", e)))?
+            .handle;
+
+        let result = self
+            .read_pipelined(&handle, offset, writer, window, cancellation_token, progress_callback)
+            .await;
+        let _ = self.session.close(handle).await;
+        result
+    }
+
+    /// 上传到远程文件，从 offset 处开始写入（reader 需已定位到 offset）
+    ///
+    /// offset 为 0 时截断远程文件，否则保留已有内容
+    ///
+    /// # 参数
+    /// - `reader`: 本地读取端
+    /// - `file_size`: 本地文件大小（用于进度回调）
+    /// - `remote_path`: 远程文件路径
+    /// - `offset`: 起始位置
+    /// - `window`: 最大未完成请求数
+    /// - `cancellation_token`: 取消令牌
+    /// - `progress_callback`: 进度回调函数 (transferred, total)
+    ///
+    /// # 返回
+    /// 传输结束时的文件位置
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload<R, F>(
+        &self,
+        reader: &mut R,
+        file_size: u64,
+        remote_path: &str,
+        offset: u64,
+        window: usize,
+        cancellation_token: &CancellationToken,
+        progress_callback: F,
+    ) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        F: Fn(u64, u64),
+    {
+        let flags = if offset > 0 {
+            OpenFlags::WRITE | OpenFlags::CREATE
+        } else {
+            OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE
+        };
+        let handle = self.session
+            .open(remote_path, flags, FileAttributes::empty())
+            .await
+            .map_err(|e| SSHError::Ssh(format!("无法创建远程文件 '{}': {}", remote_path, e)))?
+            .handle;
+
+        let mut result = self
+            .write_pipelined(&handle, reader, file_size, offset, window, cancellation_token, progress_callback)
+            .await;
+        if result.is_ok() && self.fsync {
+            if let Err(e) = self.session.fsync(handle.as_str()).await {
+                result = Err(SSHError::Ssh(format!("无法刷新远程文件 '{}' 到服务器: {}", remote_path, e)));
+            }
+        }
+
+        // 服务器可能在关闭时才报告写入错误
+        let closed = self.session.close(handle).await;
+        let transferred = result?;
+        closed.map_err(|e| SSHError::Ssh(format!("无法关闭远程文件 '{}': {}", remote_path, e)))?;
+        Ok(transferred)
+    }
+
+    /// 从另一个会话的文件复制到本会话，数据经内存管道转发，不写入本地磁盘
+    ///
+    /// 源端流水线读取，目标端流水线写入，两端同时进行；从 offset 处继续时保留目标文件已有内容
+    ///
+    /// # 参数
+    /// - `source`: 源会话
+    /// - `source_path`: 源文件路径
+    /// - `remote_path`: 目标文件路径
+    /// - `offset`: 起始位置
+    /// - `window`: 每端最大未完成请求数
+    /// - `cancellation_token`: 取消令牌
+    /// - `progress_callback`: 进度回调函数 (transferred, total)，按目标端已确认的字节数计算
+    ///
+    /// # 返回
+    /// 传输结束时的文件位置
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_from<F>(
+        &self,
+        source: &PipelineSession,
+        source_path: &str,
+        remote_path: &str,
+        offset: u64,
+        window: usize,
+        cancellation_token: &CancellationToken,
+        progress_callback: F,
+    ) -> Result<u64>
+    where
+        F: Fn(u64, u64),
+    {
+        let file_size = source.session.stat(source_path).await
+            .map_err(|e| SSHError::Ssh(format!("无法获取文件元数据: {}", e)))?
+            .attrs
+            .size
+            .unwrap_or(0);
+
+        let (mut writer, mut reader) = tokio::io::duplex(COPY_BUFFER_SIZE);
+        let read = async move {
+            // 读取结束后关闭写入端，目标端读到 EOF
+            source.download(source_path, offset, &mut writer, window, cancellation_token, |_, _| {}).await
+        };
+        let write = self.upload(&mut reader, file_size, remote_path, offset, window, cancellation_token, progress_callback);
+
+        // 任一端出错时立即结束，另一端的请求随之放弃
+        let (_, transferred) = tokio::try_join!(read, write)?;
+        Ok(transferred)
+    }
+
+    async fn read_pipelined<W, F>(
+        &self,
+        handle: &str,
+        offset: u64,
+        writer: &mut W,
+        window: usize,
+        cancellation_token: &CancellationToken,
+        progress_callback: F,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: Fn(u64, u64),
+    {
+        let file_size = self.session.fstat(handle).await
+            .map_err(|e| SSHError::Ssh(format!("无法获取文件元数据: {}", e)))?
+            .attrs
+            .size
+            .unwrap_or(0);
+
+        let window = window.clamp(1, MAX_WINDOW);
+        let mut requests = FuturesOrdered::new();
+        let mut next = offset;
+        let mut position = offset;
+
+        loop {
+            if cancellation_token.is_cancelled() {
+                return Err(SSHError::Io("下载已取消".to_string()));
+            }
+
+            // 超出打开时的文件大小后每次只发一个请求，直到服务器返回 EOF（文件可能在传输期间变大）
+            while requests.len() < window && (next < file_size || requests.is_empty()) {
+                requests.push_back(self.read_chunk(handle, next, CHUNK_SIZE));
+                next += CHUNK_SIZE as u64;
+            }
+
+            let chunk_offset = position;
+            let mut data = match requests.next().await {
+                Some(result) => result?,
+                None => break,
+            };
+
+            // 服务器可以返回比请求更短的数据，缺少的部分先补读，保证按顺序写入
+            let mut filled = 0;
+            let mut eof = false;
+            loop {
+                if data.is_empty() {
+                    eof = true;
+                    break;
+                }
+
+                writer.write_all(&data).await
+                    .map_err(|e| SSHError::Io(format!("写入本地文件失败: {}", e)))?;
+                filled += data.len();
+                position += data.len() as u64;
+                progress_callback(position, file_size.max(position));
+
+                if filled >= CHUNK_SIZE {
+                    break;
+                }
+                data = self.read_chunk(handle, chunk_offset + filled as u64, CHUNK_SIZE - filled).await?;
+            }
+
+            // 之后的请求都在文件末尾之后
+            if eof {
+                break;
+            }
+        }
+
+        writer.flush().await
+            .map_err(|e| SSHError::Io(format!("写入本地文件失败: {}", e)))?;
+        Ok(position)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn write_pipelined<R, F>(
+        &self,
+        handle: &str,
+        reader: &mut R,
+        file_size: u64,
+        offset: u64,
+        window: usize,
+        cancellation_token: &CancellationToken,
+        progress_callback: F,
+    ) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        F: Fn(u64, u64),
+    {
+        let window = window.clamp(1, MAX_WINDOW);
+        let mut requests = FuturesOrdered::new();
+        let mut next = offset;
+        let mut acknowledged = offset;
+        let mut eof = false;
+
+        loop {
+            if cancellation_token.is_cancelled() {
+                return Err(SSHError::Io("上传已取消".to_string()));
+            }
+
+            while !eof && requests.len() < window {
+                let data = read_full(reader, CHUNK_SIZE).await?;
+                eof = data.len() < CHUNK_SIZE;
+                if data.is_empty() {
+                    break;
+                }
+
+                let len = data.len() as u64;
+                requests.push_back(self.write_chunk(handle, next, data));
+                next += len;
+            }
+
+            match requests.next().await {
+                Some(result) => acknowledged += result?,
+                None => break,
+            }
+            progress_callback(acknowledged, file_size);
+        }
+
+        Ok(acknowledged)
+    }
+
+    /// 读取一块数据，文件末尾返回空数据
+    async fn read_chunk(&self, handle: &str, offset: u64, len: usize) -> Result<Vec<u8>> {
+        match self.session.read(handle, offset, len as u32).await {
+            Ok(data) => Ok(data.data),
+            Err(SftpError::Status(status)) if status.status_code == StatusCode::Eof => Ok(Vec::new()),
+            Err(e) => Err(SSHError::Ssh(format!("读取远程文件失败: {}", e))),
+        }
+    }
+
+    /// 写入一块数据，返回写入的字节数
+    async fn write_chunk(&self, handle: &str, offset: u64, data: Vec<u8>) -> Result<u64> {
+        let len = data.len() as u64;
+        self.session.write(handle, offset, data).await
+            .map_err(|e| SSHError::Ssh(format!("写入远程文件失败: {}", e)))?;
+        Ok(len)
+    }
+}
+
+/// 读满 len 字节，只有到达文件末尾时才返回更短的数据
+async fn read_full<R: AsyncRead + Unpin>(reader: &mut R, len: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; len];
+    let mut filled = 0;
+
+    while filled < len {
+        let n = reader.read(&mut buffer[filled..]).await
+            .map_err(|e| SSHError::Io(format!("读取本地文件失败: {}", e)))?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+
+    buffer.truncate(filled);
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use russh_sftp::protocol::{Attrs, Data, Handle, Status};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio::time::Instant;
+
+    type Files = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// 内存中的 SFTP 服务器，只实现传输用到的请求
+    struct MemoryServer {
+        files: Files,
+        handles: HashMap<String, String>,
+        next_handle: u64,
+        /// 单次 READ 最多返回的字节数，用于模拟服务器返回短数据
+        max_read: usize,
+        /// 写入该位置的请求失败一次，用于模拟流水线中途写入失败
+        fail_write_at: Option<u64>,
+    }
+
+    fn ok_status(id: u32) -> Status {
+        Status {
+            id,
+            status_code: StatusCode::Ok,
+            error_message: "Ok".to_string(),
+            language_tag: "en-US".to_string(),
+        }
+    }
+
+    impl russh_sftp::server::Handler for MemoryServer {
+        type Error = StatusCode;
+
+        fn unimplemented(&self) -> Self::Error {
+            StatusCode::OpUnsupported
+        }
+
+        async fn open(
+            &mut self,
+            id: u32,
+            filename: String,
+            pflags: OpenFlags,
+            _attrs: FileAttributes,
+        ) -> std::result::Result<Handle, Self::Error> {
+            let mut files = self.files.lock().unwrap();
+            if pflags.contains(OpenFlags::CREATE) {
+                let file = files.entry(filename.clone()).or_default();
+                if pflags.contains(OpenFlags::TRUNCATE) {
+                    file.clear();
+                }
+            } else if !files.contains_key(&filename) {
+                return Err(StatusCode::NoSuchFile);
+            }
+
+            self.next_handle += 1;
+            let handle = self.next_handle.to_string();
+            self.handles.insert(handle.clone(), filename);
+            Ok(Handle { id, handle })
+        }
+
+        async fn close(&mut self, id: u32, handle: String) -> std::result::Result<Status, Self::Error> {
+            self.handles.remove(&handle);
+            Ok(ok_status(id))
+        }
+
+        async fn read(
+            &mut self,
+            id: u32,
+            handle: String,
+            offset: u64,
+            len: u32,
+        ) -> std::result::Result<Data, Self::Error> {
+            let files = self.files.lock().unwrap();
+            let file = &files[&self.handles[&handle]];
+            let offset = offset as usize;
+            if offset >= file.len() {
+                return Err(StatusCode::Eof);
+            }
+            let end = (offset + (len as usize).min(self.max_read)).min(file.len());
+            Ok(Data { id, data: file[offset..end].to_vec() })
+        }
+
+        async fn write(
+            &mut self,
+            id: u32,
+            handle: String,
+            offset: u64,
+            data: Vec<u8>,
+        ) -> std::result::Result<Status, Self::Error> {
+            if self.fail_write_at == Some(offset) {
+                self.fail_write_at = None;
+                return Err(StatusCode::Failure);
+            }
+            let mut files = self.files.lock().unwrap();
+            let file = files.get_mut(&self.handles[&handle]).unwrap();
+            let (start, end) = (offset as usize, offset as usize + data.len());
+            if file.len() < end {
+                file.resize(end, 0);
+            }
+            file[start..end].copy_from_slice(&data);
+            Ok(ok_status(id))
+        }
+
+        async fn fstat(&mut self, id: u32, handle: String) -> std::result::Result<Attrs, Self::Error> {
+            let files = self.files.lock().unwrap();
+            let attrs = FileAttributes {
+                size: Some(files[&self.handles[&handle]].len() as u64),
+                ..Default::default()
+            };
+            Ok(Attrs { id, attrs })
+        }
+
+        async fn stat(&mut self, id: u32, path: String) -> std::result::Result<Attrs, Self::Error> {
+            let files = self.files.lock().unwrap();
+            let file = files.get(&path).ok_or(StatusCode::NoSuchFile)?;
+            let attrs = FileAttributes {
+                size: Some(file.len() as u64),
+                ..Default::default()
+            };
+            Ok(Attrs { id, attrs })
+        }
+    }
+
+    /// 单向转发数据，每段数据延迟 delay 后送达（不限制带宽）
+    async fn forward<R, W>(mut reader: R, mut writer: W, delay: Duration)
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+        tokio::spawn(async move {
+            while let Some((deadline, data)) = rx.recv().await {
+                tokio::time::sleep_until(deadline).await;
+                if writer.write_all(&data).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        while let Ok(n) = reader.read(&mut buffer).await {
+            if n == 0 || tx.send((Instant::now() + delay, buffer[..n].to_vec())).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// 启动内存服务器，客户端与服务器之间的往返延迟为 rtt
+    async fn start_server(rtt: Duration, max_read: usize) -> (PipelineSession, Files) {
+        start_server_failing_at(rtt, max_read, None).await
+    }
+
+    /// 启动内存服务器，写入 fail_write_at 位置的请求失败一次
+    async fn start_server_failing_at(
+        rtt: Duration,
+        max_read: usize,
+        fail_write_at: Option<u64>,
+    ) -> (PipelineSession, Files) {
+        let (client, client_link) = tokio::io::duplex(1 << 20);
+        let (server, server_link) = tokio::io::duplex(1 << 20);
+        let (client_read, client_write) = tokio::io::split(client_link);
+        let (server_read, server_write) = tokio::io::split(server_link);
+        tokio::spawn(forward(client_read, server_write, rtt / 2));
+        tokio::spawn(forward(server_read, client_write, rtt / 2));
+
+        let files = Files::default();
+        russh_sftp::server::run(
+            server,
+            MemoryServer {
+                files: files.clone(),
+                handles: HashMap::new(),
+                next_handle: 0,
+                max_read,
+                fail_write_at,
+            },
+        )
+        .await;
+
+        (PipelineSession::new(client).await.unwrap(), files)
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_upload_and_download() {
+        // 服务器每次最多返回 10000 字节，下载时需要补读
+        let (session, files) = start_server(Duration::from_millis(2), 10_000).await;
+        let data: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        let token = CancellationToken::new();
+
+        let uploaded = session
+            .upload(&mut &data[..], data.len() as u64, "/file", 0, 8, &token, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(uploaded, data.len() as u64);
+        assert_eq!(files.lock().unwrap()["/file"], data);
+
+        // 续传时保留远程已有的部分
+        files.lock().unwrap().get_mut("/file").unwrap().truncate(100_000);
+        let uploaded = session
+            .upload(&mut &data[100_000..], data.len() as u64, "/file", 100_000, 8, &token, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(uploaded, data.len() as u64);
+        assert_eq!(files.lock().unwrap()["/file"], data);
+
+        let progress = Mutex::new(Vec::new());
+        let mut downloaded = Vec::new();
+        let position = session
+            .download("/file", 0, &mut downloaded, 8, &token, |transferred, total| {
+                progress.lock().unwrap().push((transferred, total));
+            })
+            .await
+            .unwrap();
+        assert_eq!(position, data.len() as u64);
+        assert_eq!(downloaded, data);
+        let progress = progress.into_inner().unwrap();
+        assert!(progress.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(progress.last(), Some(&(data.len() as u64, data.len() as u64)));
+
+        let mut tail = Vec::new();
+        session.download("/file", 250_000, &mut tail, 8, &token, |_, _| {}).await.unwrap();
+        assert_eq!(tail, data[250_000..]);
+
+        // 空文件和不存在的文件
+        files.lock().unwrap().insert("/empty".to_string(), Vec::new());
+        let mut empty = Vec::new();
+        assert_eq!(session.download("/empty", 0, &mut empty, 8, &token, |_, _| {}).await.unwrap(), 0);
+        assert!(session.download("/missing", 0, &mut empty, 8, &token, |_, _| {}).await.is_err());
+
+        token.cancel();
+        let error = session.download("/file", 0, &mut Vec::new(), 8, &token, |_, _| {}).await.unwrap_err();
+        assert!(error.to_string().contains("已取消"));
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_copy_between_sessions() {
+        let (source, source_files) = start_server(Duration::from_millis(2), 10_000).await;
+        let (target, target_files) = start_server(Duration::from_millis(2), usize::MAX).await;
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 241) as u8).collect();
+        source_files.lock().unwrap().insert("/src".to_string(), data.clone());
+        let token = CancellationToken::new();
+
+        let copied = target.copy_from(&source, "/src", "/dst", 0, 8, &token, |_, _| {}).await.unwrap();
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(target_files.lock().unwrap()["/dst"], data);
+
+        // 从目标端已有的部分继续
+        target_files.lock().unwrap().get_mut("/dst").unwrap().truncate(70_000);
+        let progress = Mutex::new(Vec::new());
+        let copied = target
+            .copy_from(&source, "/src", "/dst", 70_000, 8, &token, |transferred, total| {
+                progress.lock().unwrap().push((transferred, total));
+            })
+            .await
+            .unwrap();
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(target_files.lock().unwrap()["/dst"], data);
+        assert_eq!(progress.into_inner().unwrap().last(), Some(&(data.len() as u64, data.len() as u64)));
+
+        assert!(target.copy_from(&source, "/missing", "/dst", 0, 8, &token, |_, _| {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_failed_write_in_window() {
+        // 窗口中第 10 块写入失败，之后已发出的写入仍然成功，远程文件比已确认的部分更长
+        let failed_at = 10 * CHUNK_SIZE as u64;
+        let (session, files) = start_server_failing_at(Duration::from_millis(2), usize::MAX, Some(failed_at)).await;
+        let data: Vec<u8> = (0..(DEFAULT_WINDOW * 2 * CHUNK_SIZE) as u32).map(|i| (i % 239) as u8).collect();
+        let token = CancellationToken::new();
+
+        let acknowledged = Mutex::new(0);
+        let result = session
+            .upload(&mut &data[..], data.len() as u64, "/file", 0, DEFAULT_WINDOW, &token, |transferred, _| {
+                *acknowledged.lock().unwrap() = transferred;
+            })
+            .await;
+        assert!(result.is_err());
+        let acknowledged = acknowledged.into_inner().unwrap();
+        let remote_size = files.lock().unwrap()["/file"].len() as u64;
+        assert_eq!(acknowledged, failed_at);
+        assert!(remote_size > acknowledged);
+
+        // 从远程大小和已确认位置中较小的一个继续，失败的块会重新写入
+        let offset = remote_size.min(acknowledged);
+        let uploaded = session
+            .upload(&mut &data[offset as usize..], data.len() as u64, "/file", offset, DEFAULT_WINDOW, &token, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(uploaded, data.len() as u64);
+        assert_eq!(files.lock().unwrap()["/file"], data);
+    }
+
+    /// 注入延迟后比较不同窗口的吞吐量，window = 1 相当于逐块等待响应
+    ///
+    /// cargo test --release benchmark_pipelined_transfer -- --ignored --nocapture
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn benchmark_pipelined_transfer() {
+        const SIZE: usize = 4 * 1024 * 1024;
+        let data = vec![0x5a; SIZE];
+        let token = CancellationToken::new();
+        let mb_per_sec = |elapsed: Duration| SIZE as f64 / 1024.0 / 1024.0 / elapsed.as_secs_f64();
+
+        for rtt in [10, 50] {
+            for window in [1, 16, DEFAULT_WINDOW] {
+                let (session, _files) = start_server(Duration::from_millis(rtt), usize::MAX).await;
+
+                let start = Instant::now();
+                session.upload(&mut &data[..], SIZE as u64, "/bench", 0, window, &token, |_, _| {}).await.unwrap();
+                let upload = start.elapsed();
+
+                let mut downloaded = Vec::with_capacity(SIZE);
+                let start = Instant::now();
+                session.download("/bench", 0, &mut downloaded, window, &token, |_, _| {}).await.unwrap();
+                let download = start.elapsed();
+                assert_eq!(downloaded.len(), SIZE);
+
+                println!(
+                    "rtt {:>3}ms window {:>3}: upload {:>8.2} MB/s, download {:>8.2} MB/s",
+                    rtt,
+                    window,
+                    mb_per_sec(upload),
+                    mb_per_sec(download)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,857 @@
+//! SFTP 传输队列
+//!
+//! 上传/下载/服务器之间传输任务先进入队列，由后台调度按优先级执行，并限制全局和单个连接的并发传输数，
+//! 避免同时为每个任务打开独立的 SFTP Client。队列保存在 SQLite 中，应用重启后未完成的任务继续排队：
+//! 原连接已不存在时改用同一会话下已连接的连接，没有可用连接的任务保持等待。
+//!
+//! 上传/下载命令同样通过队列执行（`submit`），命令等待任务结束后返回。
+
+use crate::database::repositories::{
+    AppSettingsRepository, DownloadRecord, DownloadRecordsRepository, TransferQueueRepository, UploadRecord,
+    UploadRecordsRepository, UserAuthRepository,
+};
+use crate::database::DbPool;
+use crate::error::{Result, SSHError};
+use crate::sftp::{transfer, SftpManager, TransferOperation, TransferProgress, TransferSource, TransferStatus};
+use crate::ssh::manager::SSHManager;
+use crate::ssh::session::SessionStatus;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex, Notify};
+
+/// 默认全局最大并发传输数
+pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+
+/// 默认单个连接最大并发传输数
+pub const DEFAULT_MAX_PER_CONNECTION: usize = 2;
+
+/// 队列变化事件，负载为完整的队列
+pub const QUEUE_CHANGED_EVENT: &str = "sftp-transfer-queue-changed";
+
+/// 没有状态变化时的调度间隔（等待断开的连接恢复）
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 匿名用户的固定用户ID
+const ANONYMOUS_USER_ID: &str = "anonymous_local";
+
+/// 并发限制
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferQueueLimits {
+    /// 全局最大并发传输数
+    pub max_concurrent: usize,
+    /// 单个连接最大并发传输数
+    pub max_per_connection: usize,
+}
+
+impl Default for TransferQueueLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            max_per_connection: DEFAULT_MAX_PER_CONNECTION,
+        }
+    }
+}
+
+/// 入队请求
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRequest {
+    pub source: TransferSource,
+    pub destination: TransferSource,
+    /// 优先级，数值越大越先执行
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// 传输队列
+pub struct TransferQueue {
+    pool: DbPool,
+    app_handle: AppHandle,
+    ssh_manager: Arc<SSHManager>,
+    sftp_manager: Arc<SftpManager>,
+    limits: StdMutex<TransferQueueLimits>,
+    /// 执行中的任务：队列项 ID -> 占用的连接 ID
+    ///
+    /// 执行中任务的状态变更都在持有此锁时进行，避免暂停/取消与任务结束互相覆盖
+    running: Mutex<HashMap<String, Vec<String>>>,
+    /// 等待任务结束的传输命令：队列项 ID -> 结果发送端
+    waiters: StdMutex<HashMap<String, oneshot::Sender<Result<u64>>>>,
+    /// 续传前需要校验已传输部分的队列项
+    verify: StdMutex<HashSet<String>>,
+    notify: Notify,
+}
+
+impl TransferQueue {
+    /// 创建传输队列，并发限制从应用设置中读取
+    pub fn new(
+        pool: DbPool,
+        app_handle: AppHandle,
+        ssh_manager: Arc<SSHManager>,
+        sftp_manager: Arc<SftpManager>,
+    ) -> Arc<Self> {
+        let limits = match AppSettingsRepository::new(pool.clone()).get_transfer_limits() {
+            Ok((max_concurrent, max_per_connection)) => TransferQueueLimits {
+                max_concurrent: max_concurrent.max(1) as usize,
+                max_per_connection: max_per_connection.max(1) as usize,
+            },
+            Err(e) => {
+                tracing::warn!("Failed to load transfer limits, using defaults: {}", e);
+                TransferQueueLimits::default()
+            }
+        };
+
+        Arc::new(Self {
+            pool,
+            app_handle,
+            ssh_manager,
+            sftp_manager,
+            limits: StdMutex::new(limits),
+            running: Mutex::new(HashMap::new()),
+            waiters: StdMutex::new(HashMap::new()),
+            verify: StdMutex::new(HashSet::new()),
+            notify: Notify::new(),
+        })
+    }
+
+    /// 启动后台调度，上次退出时中断的任务重新排队
+    pub fn start(self: &Arc<Self>) {
+        match self.repo().requeue_in_progress() {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Requeued {} interrupted transfers", count),
+            Err(e) => tracing::error!("Failed to requeue interrupted transfers: {}", e),
+        }
+
+        let queue = self.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                queue.schedule().await;
+                tokio::select! {
+                    _ = queue.notify.notified() => {}
+                    _ = tokio::time::sleep(SCHEDULE_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    /// 添加传输任务
+    pub async fn enqueue(&self, requests: Vec<TransferRequest>) -> Result<Vec<TransferProgress>> {
+        let mut items = Vec::with_capacity(requests.len());
+        for request in requests {
+            items.push(self.prepare(request).await?);
+        }
+
+        let repo = self.repo();
+        for item in &items {
+            repo.create(item).map_err(storage_error)?;
+        }
+        tracing::info!("Enqueued {} transfers", items.len());
+
+        self.changed();
+        Ok(items)
+    }
+
+    /// 添加单个传输任务并等待其结束，供上传/下载命令使用
+    ///
+    /// `id` 同时作为上传/下载记录的 task_id；等待期间任务可在队列中暂停和恢复
+    pub async fn submit(&self, id: String, request: TransferRequest) -> Result<u64> {
+        let mut item = self.prepare(request).await?;
+        item.id = id;
+
+        let receiver = self.wait(&item.id);
+        if let Err(e) = self.repo().create(&item) {
+            self.waiters.lock().unwrap().remove(&item.id);
+            return Err(storage_error(e));
+        }
+        tracing::info!("Enqueued transfer {}", item.id);
+
+        self.changed();
+        wait_result(receiver).await
+    }
+
+    /// 重新排队未完成的任务并等待其结束，供续传命令使用
+    ///
+    /// `connection_id` 替换记录所在一端（上传为目标端，下载为源端）的连接，
+    /// `verify` 为 true 时续传前先校验已传输部分的哈希
+    pub async fn retry(&self, id: &str, connection_id: Option<String>, verify: bool) -> Result<u64> {
+        let receiver = {
+            let running = self.running.lock().await;
+            let mut item = self.find(id)?;
+            if running.contains_key(id) {
+                return Err(SSHError::NotSupported("任务正在进行中".to_string()));
+            }
+            if item.status == TransferStatus::Completed {
+                return Err(SSHError::NotSupported("任务已完成".to_string()));
+            }
+
+            if let Some(connection_id) = connection_id {
+                let endpoint = match item.operation {
+                    TransferOperation::Download => &mut item.source,
+                    _ => &mut item.destination,
+                };
+                if let TransferSource::Remote { connection_id: current, .. } = endpoint {
+                    *current = connection_id;
+                }
+                self.bind_session(endpoint).await?;
+            }
+            if verify {
+                self.verify.lock().unwrap().insert(id.to_string());
+            }
+
+            item.status = TransferStatus::Pending;
+            item.speed = 0;
+            self.save(&mut item)?;
+            self.wait(id)
+        };
+
+        self.changed();
+        wait_result(receiver).await
+    }
+
+    /// 获取全部队列项
+    pub fn list(&self) -> Result<Vec<TransferProgress>> {
+        self.repo().find_all().map_err(storage_error)
+    }
+
+    /// 是否为队列中的任务
+    pub fn contains(&self, id: &str) -> bool {
+        matches!(self.repo().find_by_id(id), Ok(Some(_)))
+    }
+
+    /// 暂停任务
+    ///
+    /// 执行中的任务会被中断，恢复后单文件任务从已传输的部分继续，目录任务重新开始
+    pub async fn pause(&self, id: &str) -> Result<TransferProgress> {
+        let _running = self.running.lock().await;
+        let mut item = self.find(id)?;
+
+        let interrupt = pause_interrupts(&item.status)?;
+        item.status = TransferStatus::Paused;
+        self.save(&mut item)?;
+        if interrupt {
+            let _ = self.sftp_manager.cancel_task(id).await;
+        }
+
+        self.changed();
+        Ok(item)
+    }
+
+    /// 恢复已暂停的任务，失败或已取消的任务同样可以重新排队
+    pub async fn resume(&self, id: &str) -> Result<TransferProgress> {
+        let _running = self.running.lock().await;
+        let mut item = self.find(id)?;
+
+        check_resumable(&item.status)?;
+        item.status = TransferStatus::Pending;
+        item.speed = 0;
+        self.save(&mut item)?;
+
+        self.changed();
+        Ok(item)
+    }
+
+    /// 取消任务
+    pub async fn cancel(&self, id: &str) -> Result<TransferProgress> {
+        let _running = self.running.lock().await;
+        let mut item = self.find(id)?;
+
+        let interrupt = match item.status {
+            TransferStatus::Pending | TransferStatus::Paused => false,
+            TransferStatus::InProgress => true,
+            _ => return Err(SSHError::NotSupported("任务已结束".to_string())),
+        };
+
+        item.status = TransferStatus::Cancelled;
+        self.save(&mut item)?;
+        if interrupt {
+            let _ = self.sftp_manager.cancel_task(id).await;
+        } else {
+            self.notify_waiter(id, Err(SSHError::Io("传输已取消".to_string())));
+        }
+
+        self.changed();
+        Ok(item)
+    }
+
+    /// 从队列中移除任务，执行中的任务会先被取消
+    pub async fn remove(&self, id: &str) -> Result<()> {
+        let running = self.running.lock().await;
+        if running.contains_key(id) {
+            let _ = self.sftp_manager.cancel_task(id).await;
+        }
+        self.repo().delete(id).map_err(storage_error)?;
+        // 等待的命令收到"已移除"错误
+        self.waiters.lock().unwrap().remove(id);
+        drop(running);
+
+        self.changed();
+        Ok(())
+    }
+
+    /// 修改任务优先级（只影响尚未开始的任务）
+    pub async fn set_priority(&self, id: &str, priority: i32) -> Result<TransferProgress> {
+        let _running = self.running.lock().await;
+        let mut item = self.find(id)?;
+
+        item.priority = priority;
+        self.save(&mut item)?;
+
+        self.changed();
+        Ok(item)
+    }
+
+    /// 清除已结束的任务，返回清除数量
+    pub fn clear_finished(&self) -> Result<usize> {
+        let count = self.repo().delete_finished().map_err(storage_error)?;
+        self.changed();
+        Ok(count)
+    }
+
+    /// 当前并发限制
+    pub fn limits(&self) -> TransferQueueLimits {
+        *self.limits.lock().unwrap()
+    }
+
+    /// 修改并发限制，只影响之后开始的任务
+    pub fn set_limits(&self, limits: TransferQueueLimits) -> Result<TransferQueueLimits> {
+        if limits.max_concurrent == 0 || limits.max_per_connection == 0 {
+            return Err(SSHError::NotSupported("并发数必须大于 0".to_string()));
+        }
+
+        AppSettingsRepository::new(self.pool.clone())
+            .set_transfer_limits(limits.max_concurrent as i64, limits.max_per_connection as i64)
+            .map_err(storage_error)?;
+        *self.limits.lock().unwrap() = limits;
+
+        self.notify.notify_one();
+        Ok(limits)
+    }
+
+    fn repo(&self) -> TransferQueueRepository {
+        TransferQueueRepository::new(self.pool.clone())
+    }
+
+    fn find(&self, id: &str) -> Result<TransferProgress> {
+        self.repo()
+            .find_by_id(id)
+            .map_err(storage_error)?
+            .ok_or_else(|| SSHError::NotFound(format!("传输任务不存在: {}", id)))
+    }
+
+    fn save(&self, item: &mut TransferProgress) -> Result<()> {
+        item.updated_at = chrono::Utc::now().timestamp();
+        self.repo().update(item).map_err(storage_error)
+    }
+
+    /// 登记等待任务结束的命令
+    fn wait(&self, id: &str) -> oneshot::Receiver<Result<u64>> {
+        let (sender, receiver) = oneshot::channel();
+        self.waiters.lock().unwrap().insert(id.to_string(), sender);
+        receiver
+    }
+
+    /// 将任务结果发送给等待的命令
+    fn notify_waiter(&self, id: &str, result: Result<u64>) {
+        if let Some(sender) = self.waiters.lock().unwrap().remove(id) {
+            let _ = sender.send(result);
+        }
+    }
+
+    /// 通知前端队列变化
+    fn emit_changed(&self) {
+        match self.list() {
+            Ok(items) => {
+                let _ = self.app_handle.emit(QUEUE_CHANGED_EVENT, &items);
+            }
+            Err(e) => tracing::warn!("Failed to load transfer queue: {}", e),
+        }
+    }
+
+    /// 通知前端并触发一次调度
+    fn changed(&self) {
+        self.emit_changed();
+        self.notify.notify_one();
+    }
+
+    /// 检查传输两端并生成队列项
+    async fn prepare(&self, request: TransferRequest) -> Result<TransferProgress> {
+        let TransferRequest { mut source, mut destination, priority } = request;
+        self.bind_session(&mut source).await?;
+        self.bind_session(&mut destination).await?;
+
+        let (operation, is_dir, file_size) = match (&source, &destination) {
+            (TransferSource::Local { path }, TransferSource::Remote { .. }) => {
+                let metadata = std::fs::metadata(path)
+                    .map_err(|_| SSHError::NotFound(format!("本地文件不存在: {}", path)))?;
+                let size = if metadata.is_dir() { 0 } else { metadata.len() };
+                (TransferOperation::Upload, metadata.is_dir(), size)
+            }
+            (TransferSource::Remote { connection_id, path, .. }, TransferSource::Local { .. }) => {
+                let info = self.sftp_manager.stat(connection_id, path).await?;
+                let size = if info.is_dir { 0 } else { info.size };
+                (TransferOperation::Download, info.is_dir, size)
+            }
+            (TransferSource::Remote { connection_id, path, .. }, TransferSource::Remote { .. }) => {
+                let info = self.sftp_manager.stat(connection_id, path).await?;
+                let size = if info.is_dir { 0 } else { info.size };
+                (TransferOperation::RemoteToRemote, info.is_dir, size)
+            }
+            (TransferSource::Local { .. }, TransferSource::Local { .. }) => {
+                return Err(SSHError::NotSupported("传输两端不能都是本地路径".to_string()));
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        Ok(TransferProgress {
+            id: format!("transfer-{}", uuid::Uuid::new_v4()),
+            operation,
+            source,
+            destination,
+            is_dir,
+            priority,
+            file_size,
+            transferred: 0,
+            speed: 0,
+            status: TransferStatus::Pending,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// 记录远程端连接所属的会话，用于原连接断开后重新绑定
+    async fn bind_session(&self, endpoint: &mut TransferSource) -> Result<()> {
+        if let TransferSource::Remote { connection_id, session_id, .. } = endpoint {
+            let connection = self.ssh_manager.get_connection(connection_id).await?;
+            *session_id = Some(connection.session_id);
+        }
+        Ok(())
+    }
+
+    async fn is_connected(&self, connection_id: &str) -> bool {
+        match self.ssh_manager.get_connection(connection_id).await {
+            Ok(connection) => matches!(connection.status().await, SessionStatus::Connected),
+            Err(_) => false,
+        }
+    }
+
+    /// 确认远程端的连接可用，原连接不可用时改用同一会话下已连接的连接
+    ///
+    /// 返回任务占用的连接 ID，没有可用连接时返回 None（任务继续等待）
+    async fn bind_connections(&self, item: &mut TransferProgress) -> Option<Vec<String>> {
+        let mut connections = Vec::new();
+        for endpoint in [&mut item.source, &mut item.destination] {
+            if let TransferSource::Remote { connection_id, session_id, .. } = endpoint {
+                if !self.is_connected(connection_id).await {
+                    let session_id = session_id.as_deref()?;
+                    let rebound = self.ssh_manager.find_connected_connection(session_id).await?;
+                    tracing::info!("Transfer {} rebound from connection {} to {}", item.id, connection_id, rebound);
+                    *connection_id = rebound;
+                }
+                connections.push(connection_id.clone());
+            }
+        }
+        Some(connections)
+    }
+
+    /// 按优先级启动等待中的任务，直到达到并发限制
+    ///
+    /// 不在队列中的传输（如续传队列之前创建的记录）同样占用并发名额，结束后由定时调度补上
+    async fn schedule(self: &Arc<Self>) {
+        let mut running = self.running.lock().await;
+        let limits = self.limits();
+
+        let mut slots = Slots::default();
+        for connections in running.values() {
+            slots.add(connections);
+        }
+        for (task_id, connections) in self.sftp_manager.task_connections().await {
+            if !running.contains_key(&task_id) {
+                slots.add(&connections);
+            }
+        }
+        if slots.is_full(&limits) {
+            return;
+        }
+
+        let pending = match self.repo().find_pending() {
+            Ok(pending) => pending,
+            Err(e) => {
+                tracing::error!("Failed to load pending transfers: {}", e);
+                return;
+            }
+        };
+
+        let mut started = false;
+        for mut item in pending {
+            if slots.is_full(&limits) {
+                break;
+            }
+            // 暂停后立即恢复的任务可能还没有停止，等这次执行结束后再开始
+            if running.contains_key(&item.id) {
+                continue;
+            }
+
+            let connections = match self.bind_connections(&mut item).await {
+                Some(connections) => connections,
+                None => continue,
+            };
+            if !slots.try_acquire(&connections, &limits) {
+                continue;
+            }
+
+            item.status = TransferStatus::InProgress;
+            item.speed = 0;
+            if let Err(e) = self.save(&mut item) {
+                tracing::error!("Failed to start transfer {}: {}", item.id, e);
+                continue;
+            }
+
+            // 开始前创建取消令牌，任务刚启动时的暂停/取消同样能中断传输
+            self.sftp_manager.get_cancellation_token(&item.id).await;
+            running.insert(item.id.clone(), connections);
+            started = true;
+
+            let queue = self.clone();
+            tauri::async_runtime::spawn(async move {
+                queue.execute(item).await;
+            });
+        }
+
+        drop(running);
+        if started {
+            self.emit_changed();
+        }
+    }
+
+    /// 执行单个队列项，上传/下载记录的 task_id 即队列项 ID（服务器之间传输使用上传记录）
+    async fn execute(self: Arc<Self>, item: TransferProgress) {
+        tracing::info!("Starting queued transfer {} ({})", item.id, item.operation.as_str());
+        let start = Instant::now();
+
+        let result = match (&item.source, &item.destination) {
+            (TransferSource::Local { path: local_path }, TransferSource::Remote { connection_id, path: remote_path, .. }) => {
+                self.run_upload(&item, None, connection_id, local_path, remote_path).await
+            }
+            (
+                TransferSource::Remote { connection_id: source_connection_id, path: source_path, .. },
+                TransferSource::Remote { connection_id, path: remote_path, .. },
+            ) => {
+                self.run_upload(&item, Some(source_connection_id), connection_id, source_path, remote_path).await
+            }
+            (TransferSource::Remote { connection_id, path: remote_path, .. }, TransferSource::Local { path: local_path }) => {
+                self.run_download(&item, connection_id, remote_path, local_path).await
+            }
+            _ => Err(SSHError::NotSupported(format!("不支持的传输类型: {}", item.operation.as_str()))),
+        };
+
+        self.finish(&item, result, start.elapsed()).await;
+    }
+
+    /// 执行上传，`source_connection_id` 不为空时 `local_path` 为源连接上的路径（服务器之间传输）
+    async fn run_upload(
+        &self,
+        item: &TransferProgress,
+        source_connection_id: Option<&str>,
+        connection_id: &str,
+        local_path: &str,
+        remote_path: &str,
+    ) -> Result<u64> {
+        let conn = self.pool.get()
+            .map_err(|e| SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+
+        // 暂停后恢复时沿用已有记录，单文件任务从远程已有的部分继续
+        let existing = UploadRecordsRepository::find_by_task_id(&conn, &item.id).map_err(storage_error)?;
+        let verify = self.verify.lock().unwrap().remove(&item.id);
+        let resume = (existing.is_some() && !item.is_dir).then_some(verify);
+        let record = match existing {
+            Some(mut record) => {
+                record.connection_id = connection_id.to_string();
+                record.source_connection_id = source_connection_id.map(str::to_string);
+                record
+            }
+            None => {
+                let now = chrono::Utc::now().timestamp();
+                let record = UploadRecord {
+                    id: 0, // 数据库会自动生成
+                    task_id: item.id.clone(),
+                    connection_id: connection_id.to_string(),
+                    user_id: self.current_user_id(),
+                    local_path: local_path.to_string(),
+                    remote_path: remote_path.to_string(),
+                    total_files: if item.is_dir { 0 } else { 1 },
+                    total_dirs: 0,
+                    total_size: item.file_size as i64,
+                    status: "pending".to_string(),
+                    bytes_transferred: 0,
+                    files_completed: 0,
+                    started_at: now,
+                    completed_at: None,
+                    elapsed_ms: None,
+                    error_message: None,
+                    created_at: now,
+                    updated_at: now,
+                    resume_offset: 0,
+                    source_connection_id: source_connection_id.map(str::to_string),
+                };
+                UploadRecordsRepository::create(&conn, &record).map_err(storage_error)?;
+                record
+            }
+        };
+        drop(conn);
+
+        if item.is_dir {
+            transfer::upload_directory(&self.sftp_manager, &self.pool, &self.app_handle, &record)
+                .await
+                .map(|result| result.total_size)
+        } else {
+            transfer::upload_file(&self.sftp_manager, &self.pool, &self.app_handle, &record, resume).await
+        }
+    }
+
+    async fn run_download(
+        &self,
+        item: &TransferProgress,
+        connection_id: &str,
+        remote_path: &str,
+        local_path: &str,
+    ) -> Result<u64> {
+        let conn = self.pool.get()
+            .map_err(|e| SSHError::Storage(format!("Failed to get database connection: {}", e)))?;
+
+        // 暂停后恢复时沿用已有记录，单文件任务从本地已有的部分继续
+        let existing = DownloadRecordsRepository::find_by_task_id(&conn, &item.id).map_err(storage_error)?;
+        let verify = self.verify.lock().unwrap().remove(&item.id);
+        let resume = (existing.is_some() && !item.is_dir).then_some(verify);
+        let record = match existing {
+            Some(mut record) => {
+                record.connection_id = connection_id.to_string();
+                record
+            }
+            None => {
+                let now = chrono::Utc::now().timestamp();
+                let record = DownloadRecord {
+                    id: 0,
+                    task_id: item.id.clone(),
+                    connection_id: connection_id.to_string(),
+                    user_id: self.current_user_id(),
+                    remote_path: remote_path.to_string(),
+                    local_path: local_path.to_string(),
+                    total_files: if item.is_dir { 0 } else { 1 },
+                    total_dirs: 0,
+                    total_size: item.file_size as i64,
+                    status: "pending".to_string(),
+                    bytes_transferred: 0,
+                    files_completed: 0,
+                    started_at: now,
+                    completed_at: None,
+                    elapsed_ms: None,
+                    error_message: None,
+                    created_at: now,
+                    updated_at: now,
+                    resume_offset: 0,
+                };
+                DownloadRecordsRepository::create(&conn, &record).map_err(storage_error)?;
+                record
+            }
+        };
+        drop(conn);
+
+        if item.is_dir {
+            if let Some(parent) = Path::new(local_path).parent() {
+                tokio::fs::create_dir_all(parent).await
+                    .map_err(|e| SSHError::Io(format!("创建本地父目录失败: {}", e)))?;
+            }
+            transfer::download_directory(&self.sftp_manager, &self.pool, &self.app_handle, &record)
+                .await
+                .map(|result| result.total_size)
+        } else {
+            transfer::download_file(&self.sftp_manager, &self.pool, &self.app_handle, &record, resume).await
+        }
+    }
+
+    /// 记录任务结果，释放并发名额
+    async fn finish(&self, item: &TransferProgress, result: Result<u64>, elapsed: Duration) {
+        let mut running = self.running.lock().await;
+        running.remove(&item.id);
+        // 传输开始前失败时取消令牌还没有被清理
+        self.sftp_manager.cleanup_cancellation_token(&item.id).await;
+
+        let mut current = match self.repo().find_by_id(&item.id) {
+            Ok(Some(current)) => current,
+            // 已从队列中移除
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to load transfer {}: {}", item.id, e);
+                return;
+            }
+        };
+
+        match &result {
+            Ok(bytes) => {
+                let bytes = *bytes;
+                tracing::info!("Queued transfer {} completed: {} bytes", item.id, bytes);
+                current.status = TransferStatus::Completed;
+                current.transferred = bytes;
+                current.file_size = current.file_size.max(bytes);
+                current.speed = bytes * 1000 / (elapsed.as_millis() as u64).max(1);
+            }
+            Err(e) => {
+                tracing::warn!("Queued transfer {} stopped: {}", item.id, e);
+                current.transferred = self.record_progress(item).unwrap_or(current.transferred);
+                current.speed = 0;
+
+                // 暂停或取消时状态已由对应操作写入
+                if current.status == TransferStatus::InProgress {
+                    let error_msg = e.to_string();
+                    current.status = if error_msg.contains("已取消") {
+                        TransferStatus::Cancelled
+                    } else {
+                        TransferStatus::Failed { reason: error_msg }
+                    };
+                }
+            }
+        }
+
+        if let Err(e) = self.save(&mut current) {
+            tracing::error!("Failed to update transfer {}: {}", item.id, e);
+        }
+        // 暂停的任务恢复后继续执行，等待的命令继续等待
+        if current.status != TransferStatus::Paused {
+            self.notify_waiter(&item.id, result);
+        }
+        drop(running);
+
+        self.changed();
+    }
+
+    /// 从上传/下载记录中读取已传输的字节数
+    fn record_progress(&self, item: &TransferProgress) -> Option<u64> {
+        let conn = self.pool.get().ok()?;
+        let bytes = match item.operation {
+            TransferOperation::Upload | TransferOperation::RemoteToRemote => {
+                UploadRecordsRepository::find_by_task_id(&conn, &item.id).ok()??.bytes_transferred
+            }
+            TransferOperation::Download => DownloadRecordsRepository::find_by_task_id(&conn, &item.id)
+                .ok()??
+                .bytes_transferred,
+        };
+        Some(bytes as u64)
+    }
+
+    /// 获取当前用户的 user_id，未登录时返回匿名用户ID
+    fn current_user_id(&self) -> String {
+        match UserAuthRepository::new(self.pool.clone()).find_current() {
+            Ok(Some(user)) => user.user_id,
+            _ => ANONYMOUS_USER_ID.to_string(),
+        }
+    }
+}
+
+fn storage_error(e: anyhow::Error) -> SSHError {
+    SSHError::Storage(e.to_string())
+}
+
+/// 等待任务结果，任务被移出队列时返回错误
+async fn wait_result(receiver: oneshot::Receiver<Result<u64>>) -> Result<u64> {
+    receiver
+        .await
+        .unwrap_or_else(|_| Err(SSHError::NotFound("传输任务已从队列中移除".to_string())))
+}
+
+/// 检查任务能否暂停，返回是否需要中断执行中的传输
+fn pause_interrupts(status: &TransferStatus) -> Result<bool> {
+    match status {
+        TransferStatus::Pending => Ok(false),
+        TransferStatus::InProgress => Ok(true),
+        _ => Err(SSHError::NotSupported("只能暂停等待中或进行中的任务".to_string())),
+    }
+}
+
+/// 检查任务能否恢复：已暂停、失败或已取消的任务可以重新排队
+fn check_resumable(status: &TransferStatus) -> Result<()> {
+    match status {
+        TransferStatus::Paused | TransferStatus::Failed { .. } | TransferStatus::Cancelled => Ok(()),
+        _ => Err(SSHError::NotSupported("任务未暂停".to_string())),
+    }
+}
+
+/// 已占用的并发名额
+#[derive(Debug, Default)]
+struct Slots {
+    total: usize,
+    /// 连接 ID -> 该连接上执行中的任务数
+    per_connection: HashMap<String, usize>,
+}
+
+impl Slots {
+    /// 记录一个执行中的任务，任务占用其远程端的所有连接
+    fn add(&mut self, connections: &[String]) {
+        self.total += 1;
+        for connection_id in connections {
+            *self.per_connection.entry(connection_id.clone()).or_default() += 1;
+        }
+    }
+
+    /// 全局名额是否已满
+    fn is_full(&self, limits: &TransferQueueLimits) -> bool {
+        self.total >= limits.max_concurrent
+    }
+
+    /// 在并发限制内占用名额，任一连接已满时返回 false
+    fn try_acquire(&mut self, connections: &[String], limits: &TransferQueueLimits) -> bool {
+        let busy = connections.iter().any(|connection_id| {
+            self.per_connection.get(connection_id).copied().unwrap_or(0) >= limits.max_per_connection
+        });
+        if self.is_full(limits) || busy {
+            return false;
+        }
+        self.add(connections);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connections(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_slots_per_connection_limit() {
+        let limits = TransferQueueLimits { max_concurrent: 3, max_per_connection: 2 };
+        let mut slots = Slots::default();
+
+        // 不在队列中的传输同样计入
+        slots.add(&connections(&["c1"]));
+        assert!(slots.try_acquire(&connections(&["c1"]), &limits));
+        assert!(!slots.try_acquire(&connections(&["c1"]), &limits));
+
+        // 服务器之间传输同时占用两端的连接
+        assert!(!slots.try_acquire(&connections(&["c2", "c1"]), &limits));
+        assert!(slots.try_acquire(&connections(&["c2"]), &limits));
+        assert!(slots.is_full(&limits));
+        assert!(!slots.try_acquire(&connections(&["c3"]), &limits));
+    }
+
+    #[test]
+    fn test_pause_and_resume_transitions() {
+        // 等待中的任务直接暂停，执行中的任务需要中断传输
+        assert!(!pause_interrupts(&TransferStatus::Pending).unwrap());
+        assert!(pause_interrupts(&TransferStatus::InProgress).unwrap());
+        assert!(pause_interrupts(&TransferStatus::Paused).is_err());
+        assert!(pause_interrupts(&TransferStatus::Completed).is_err());
+
+        // 暂停、失败和取消的任务可以恢复，其余状态不能
+        assert!(check_resumable(&TransferStatus::Paused).is_ok());
+        assert!(check_resumable(&TransferStatus::Failed { reason: "断开".to_string() }).is_ok());
+        assert!(check_resumable(&TransferStatus::Cancelled).is_ok());
+        assert!(check_resumable(&TransferStatus::Pending).is_err());
+        assert!(check_resumable(&TransferStatus::InProgress).is_err());
+        assert!(check_resumable(&TransferStatus::Completed).is_err());
+    }
+}
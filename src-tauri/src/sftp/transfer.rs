@@ -0,0 +1,865 @@
+//! SFTP 传输任务执行
+//!
+//! 单个上传/下载任务的完整流程：更新传输记录、创建任务 SFTP Client、发送进度和状态事件。
+//! 传输命令和传输队列共用，调用前传输记录必须已经创建
+
+use crate::database::repositories::{DownloadRecord, UploadRecord};
+use crate::database::DbPool;
+use crate::error::Result;
+use crate::sftp::{DownloadDirectoryResult, SftpManager, UploadDirectoryResult};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// 执行单文件上传任务
+///
+/// `resume` 为 None 时从头上传；否则从远程已有的部分继续，Some(true) 时先校验已有前缀的哈希。
+/// 记录带有 `source_connection_id` 时从源连接直接传输到目标连接（服务器之间传输）
+pub(crate) async fn upload_file(
+    manager: &SftpManager,
+    pool: &DbPool,
+    app_handle: &AppHandle,
+    record: &UploadRecord,
+    resume: Option<bool>,
+) -> Result<u64> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let now = chrono::Utc::now().timestamp();
+
+    // 更新状态为 uploading
+    if let Ok(conn) = pool.get() {
+        let _ = crate::database::repositories::UploadRecordsRepository::update_status(
+            &conn,
+            &task_id,
+            crate::database::repositories::UploadStatus::Uploading,
+            None,
+        );
+    }
+
+    // 获取取消令牌
+    let cancellation_token = manager.get_cancellation_token(&task_id).await;
+
+    // 记录已传输字节数（失败时作为续传起点），开始传输前保留上次记录的位置
+    let transferred_bytes = std::sync::Arc::new(std::sync::Mutex::new(record.resume_offset.max(0) as u64));
+
+    // 准备阶段（连接源端、创建任务 SFTP Client、计算续传起点）失败时同样清理并更新记录状态
+    let result = run_upload_file(manager, app_handle, record, resume, &cancellation_token, transferred_bytes.clone()).await;
+
+    // 🔥 清理任务 SFTP Client 和取消令牌（无论成功或失败）
+    // 注意：先清理 client，再清理取消令牌，确保传输函数已经返回
+    manager.cleanup_task_client(&task_id).await;
+    manager.cleanup_cancellation_token(&task_id).await;
+
+    // 返回上传结果
+    match result {
+        Ok(transferred) => {
+            tracing::info!("Upload completed: {} bytes", transferred);
+
+            // 标记上传完成
+            let elapsed = chrono::Utc::now().timestamp() - now;
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::UploadRecordsRepository::mark_completed(
+                    &conn,
+                    &task_id,
+                    elapsed * 1000, // 转换为毫秒
+                    transferred as i64,
+                    1, // 单文件上传，files_completed = 1
+                );
+                
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: "completed".to_string(),
+                    bytes_transferred: transferred as i64,
+                    files_completed: 1,
+                    total_files: 1,
+                    error_message: None,
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Ok(transferred)
+        }
+        Err(e) => {
+            tracing::error!("Upload failed: {}", e);
+
+            // 判断是取消还是失败
+            let error_msg = e.to_string();
+            let status = if error_msg.contains("已取消") {
+                tracing::info!("Upload cancelled by user: {}", task_id);
+                crate::database::repositories::UploadStatus::Cancelled
+            } else {
+                tracing::error!("Upload failed with error: {}", e);
+                crate::database::repositories::UploadStatus::Failed
+            };
+
+            // 获取状态字符串（在移动 status 之前）
+            let status_str = status.as_str();
+
+            // 记录续传起点，之后可按 task_id 续传
+            let resume_offset = transferred_bytes.lock().map(|bytes| *bytes).unwrap_or(0);
+
+            // 更新数据库状态
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::UploadRecordsRepository::update_status(
+                    &conn,
+                    &task_id,
+                    status,
+                    Some(error_msg.clone()),
+                );
+                let _ = crate::database::repositories::UploadRecordsRepository::update_resume_offset(
+                    &conn,
+                    &task_id,
+                    resume_offset as i64,
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: status_str.to_string(),
+                    bytes_transferred: resume_offset as i64,
+                    files_completed: 0,
+                    total_files: 1,
+                    error_message: Some(error_msg),
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Err(e)
+        }
+    }
+}
+
+
+/// 准备并执行单文件上传，返回上传后的文件大小
+///
+/// 任务 SFTP Client 在返回前释放，由调用方统一清理注册信息和更新记录状态
+async fn run_upload_file(
+    manager: &SftpManager,
+    app_handle: &AppHandle,
+    record: &UploadRecord,
+    resume: Option<bool>,
+    cancellation_token: &tokio_util::sync::CancellationToken,
+    transferred_bytes: std::sync::Arc<std::sync::Mutex<u64>>,
+) -> Result<u64> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let local_path = record.local_path.clone();
+    let remote_path = record.remote_path.clone();
+    let local_path_obj = Path::new(&local_path);
+
+    // 服务器之间传输时另外连接源端
+    let mut source_client = match &record.source_connection_id {
+        Some(source_connection_id) => Some(manager.create_source_client(source_connection_id, &task_id).await?),
+        None => None,
+    };
+
+    // 🔥 为任务创建独立的 SFTP Client
+    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
+    let mut client_guard = sftp_client.lock().await;
+
+    // 续传时从目标端已有的部分继续，不超过上次记录的续传起点
+    let recorded_offset = record.resume_offset.max(0) as u64;
+    let start_offset = match (resume, &source_client) {
+        (Some(verify), Some(source)) => {
+            client_guard.copy_resume_offset(source, &local_path, &remote_path, recorded_offset, verify).await?
+        }
+        (Some(verify), None) => {
+            client_guard.upload_resume_offset(&local_path, &remote_path, recorded_offset, verify).await?
+        }
+        (None, _) => 0,
+    };
+    if let Ok(mut bytes) = transferred_bytes.lock() {
+        *bytes = start_offset;
+    }
+
+    // 获取文件大小
+    let file_size = match source_client.as_mut() {
+        Some(source) => source.stat(&local_path).await?.size,
+        None => local_path_obj.metadata()
+            .map_err(|e| crate::error::SSHError::Io(format!("无法获取文件元数据: {}", e)))?
+            .len(),
+    };
+
+    // 记录任务开始时间
+    let start_time = chrono::Utc::now().timestamp_millis() as u64;
+
+    // 发送开始进度事件
+    let start_event = crate::sftp::UploadProgressEvent {
+        task_id: task_id.clone(),
+        connection_id: connection_id.clone(),
+        current_file: local_path.clone(),
+        current_dir: local_path_obj.parent()
+            .and_then(|p| p.to_str())
+            .unwrap_or("")
+            .to_string(),
+        files_completed: 0,
+        total_files: 1,
+        bytes_transferred: start_offset,
+        total_bytes: file_size,
+        speed_bytes_per_sec: 0,
+        start_time,
+        completed_time: start_time,
+        upload_name: local_path_obj.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_else(|| local_path.as_str())
+            .to_string(),
+    };
+    let _ = app_handle.emit("sftp-upload-progress", &start_event);
+
+    // 流式上传文件
+    let task_id_for_callback = task_id.clone();
+    let connection_id_for_callback = connection_id.clone();
+    let local_path_for_callback = local_path.clone();
+    let local_dir = local_path_obj.parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or("")
+        .to_string();
+    let start_time_for_callback = start_time;
+
+    let transferred_bytes_for_callback = transferred_bytes;
+
+    // 添加节流机制（参考目录传输）
+    let last_emit_time = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
+    let last_emit_time_for_callback = last_emit_time.clone();
+
+    let app_handle_for_callback = app_handle.clone();
+    let progress_callback = move |transferred: u64, total: u64| {
+        // 记录已传输字节数（失败时作为续传起点）
+        if let Ok(mut bytes) = transferred_bytes_for_callback.lock() {
+            *bytes = transferred;
+        }
+
+        // 使用节流机制：每 200ms 最多发送一次事件
+        let now = std::time::Instant::now();
+        let should_emit = {
+            let mut last = last_emit_time_for_callback.lock().unwrap();
+            if now.duration_since(*last) >= std::time::Duration::from_millis(200) {
+                *last = now;
+                true
+            } else {
+                false
+            }
+        };
+
+        if should_emit {
+            // 计算传输速度
+            let current_time = chrono::Utc::now().timestamp_millis() as u64;
+            let elapsed_ms = if current_time > start_time_for_callback {
+                current_time - start_time_for_callback
+            } else {
+                1
+            };
+            let speed_bytes_per_sec = if elapsed_ms > 0 {
+                (transferred.saturating_sub(start_offset) * 1000) / elapsed_ms
+            } else {
+                0
+            };
+
+            // 发送进度事件（前端显示用）
+            let progress_event = crate::sftp::UploadProgressEvent {
+                task_id: task_id_for_callback.clone(),
+                connection_id: connection_id_for_callback.clone(),
+                current_file: local_path_for_callback.clone(),
+                current_dir: local_dir.clone(),
+                files_completed: if transferred >= total { 1 } else { 0 },
+                total_files: 1,
+                bytes_transferred: transferred,
+                total_bytes: total,
+                speed_bytes_per_sec,
+                start_time: start_time_for_callback,
+                completed_time: current_time,
+                upload_name: local_path_for_callback.rsplit('/')
+                    .next()
+                    .or_else(|| local_path_for_callback.rsplit('\\').next())
+                    .unwrap_or(local_path_for_callback.as_str())
+                    .to_string(),
+            };
+            let _ = app_handle_for_callback.emit("sftp-upload-progress", &progress_event);
+        }
+    };
+
+    match &source_client {
+        Some(source) => client_guard.copy_file_from(
+            source,
+            &local_path,
+            &remote_path,
+            start_offset,
+            cancellation_token,
+            progress_callback,
+            false,
+        ).await,
+        None => client_guard.upload_file_stream(
+            &local_path,
+            &remote_path,
+            start_offset,
+            cancellation_token,
+            progress_callback,
+            false,
+        ).await,
+    }
+}
+
+/// 执行单文件下载任务
+///
+/// `resume` 为 None 时从头下载；否则从本地已有的部分继续，Some(true) 时先校验已有前缀的哈希
+pub(crate) async fn download_file(
+    manager: &SftpManager,
+    pool: &DbPool,
+    app_handle: &AppHandle,
+    record: &DownloadRecord,
+    resume: Option<bool>,
+) -> Result<u64> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let now = chrono::Utc::now().timestamp();
+
+    // 更新状态为 downloading
+    if let Ok(conn) = pool.get() {
+        let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
+            &conn,
+            &task_id,
+            crate::database::repositories::DownloadStatus::Downloading,
+            None,
+        );
+    }
+
+    // 获取取消令牌
+    let cancellation_token = manager.get_cancellation_token(&task_id).await;
+
+    // 记录已传输字节数（失败时作为续传起点），开始传输前保留上次记录的位置
+    let transferred_bytes = std::sync::Arc::new(std::sync::Mutex::new(record.resume_offset.max(0) as u64));
+
+    // 准备阶段（创建任务 SFTP Client、计算续传起点）失败时同样清理并更新记录状态
+    let result = run_download_file(manager, app_handle, record, resume, &cancellation_token, transferred_bytes.clone()).await;
+
+    // 🔥 清理任务 SFTP Client 和取消令牌（无论成功或失败）
+    // 注意：先清理 client，再清理取消令牌，确保传输函数已经返回
+    manager.cleanup_task_client(&task_id).await;
+    manager.cleanup_cancellation_token(&task_id).await;
+
+    // 返回下载结果
+    match result {
+        Ok(transferred) => {
+            tracing::info!("Download completed: {} bytes", transferred);
+
+            // 标记下载完成
+            let elapsed = chrono::Utc::now().timestamp() - now;
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::DownloadRecordsRepository::mark_completed(
+                    &conn,
+                    &task_id,
+                    elapsed * 1000, // 转换为毫秒
+                    transferred as i64,
+                    1, // 单文件下载，files_completed = 1
+                );
+                
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: "completed".to_string(),
+                    bytes_transferred: transferred as i64,
+                    files_completed: 1,
+                    total_files: 1,
+                    error_message: None,
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Ok(transferred)
+        }
+        Err(e) => {
+            tracing::error!("Download failed: {}", e);
+
+            // 判断是取消还是失败
+            let error_msg = e.to_string();
+            let status = if error_msg.contains("已取消") {
+                tracing::info!("Download cancelled by user: {}", task_id);
+                crate::database::repositories::DownloadStatus::Cancelled
+            } else {
+                tracing::error!("Download failed with error: {}", e);
+                crate::database::repositories::DownloadStatus::Failed
+            };
+
+            // 获取状态字符串（在移动 status 之前）
+            let status_str = status.as_str();
+
+            // 记录续传起点，之后可按 task_id 续传
+            let resume_offset = transferred_bytes.lock().map(|bytes| *bytes).unwrap_or(0);
+
+            // 更新数据库状态
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
+                    &conn,
+                    &task_id,
+                    status,
+                    Some(error_msg.clone()),
+                );
+                let _ = crate::database::repositories::DownloadRecordsRepository::update_resume_offset(
+                    &conn,
+                    &task_id,
+                    resume_offset as i64,
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: status_str.to_string(),
+                    bytes_transferred: resume_offset as i64,
+                    files_completed: 0,
+                    total_files: 1,
+                    error_message: Some(error_msg),
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Err(e)
+        }
+    }
+}
+
+
+/// 准备并执行单文件下载，返回下载后的文件大小
+///
+/// 任务 SFTP Client 在返回前释放，由调用方统一清理注册信息和更新记录状态
+async fn run_download_file(
+    manager: &SftpManager,
+    app_handle: &AppHandle,
+    record: &DownloadRecord,
+    resume: Option<bool>,
+    cancellation_token: &tokio_util::sync::CancellationToken,
+    transferred_bytes: std::sync::Arc<std::sync::Mutex<u64>>,
+) -> Result<u64> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let remote_path = record.remote_path.clone();
+    let local_path = record.local_path.clone();
+
+    // 🔥 为任务创建独立的 SFTP Client
+    let sftp_client = manager.create_task_client(&connection_id, &task_id).await?;
+    let client_guard = sftp_client.lock().await;
+
+    // 续传时从目标端已有的部分继续
+    let start_offset = match resume {
+        Some(verify) => client_guard.download_resume_offset(&remote_path, &local_path, verify).await?,
+        None => 0,
+    };
+    if let Ok(mut bytes) = transferred_bytes.lock() {
+        *bytes = start_offset;
+    }
+
+    // 提取文件名和目录信息
+    let file_name = remote_path.rsplit('/').next().unwrap_or(&remote_path).to_string();
+    let current_dir = remote_path.rsplit('/')
+        .skip(1)
+        .next()
+        .unwrap_or("")
+        .to_string();
+
+    // 记录任务开始时间
+    let start_time = chrono::Utc::now().timestamp_millis() as u64;
+
+    // 发送开始进度事件
+    let start_event = crate::sftp::DownloadProgressEvent {
+        task_id: task_id.clone(),
+        connection_id: connection_id.clone(),
+        current_file: file_name.clone(),
+        current_dir: current_dir.clone(),
+        files_completed: 0,
+        total_files: 1,
+        bytes_transferred: start_offset,
+        total_bytes: 0, // 初始为0，会在第一次进度回调时更新
+        speed_bytes_per_sec: 0,
+        start_time,
+        completed_time: start_time,
+    };
+    let _ = app_handle.emit("sftp-download-progress", &start_event);
+
+    // 流式下载文件
+    let task_id_for_callback = task_id.clone();
+    let connection_id_for_callback = connection_id.clone();
+    let file_name_for_callback = file_name.clone();
+    let current_dir_for_callback = current_dir.clone();
+    let start_time_for_callback = start_time;
+
+    let transferred_bytes_for_callback = transferred_bytes;
+
+    // 添加节流机制（参考目录传输）
+    let last_emit_time = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
+    let last_emit_time_for_callback = last_emit_time.clone();
+
+    let app_handle_for_callback = app_handle.clone();
+    client_guard.download_file_stream(
+        &remote_path,
+        &local_path,
+        start_offset,
+        cancellation_token,
+        move |transferred, total| {
+            // 记录已传输字节数（失败时作为续传起点）
+            if let Ok(mut bytes) = transferred_bytes_for_callback.lock() {
+                *bytes = transferred;
+            }
+
+            // 使用节流机制：每 200ms 最多发送一次事件
+            let now = std::time::Instant::now();
+            let should_emit = {
+                let mut last = last_emit_time_for_callback.lock().unwrap();
+                if now.duration_since(*last) >= std::time::Duration::from_millis(200) {
+                    *last = now;
+                    true
+                } else {
+                    false
+                }
+            };
+
+            if should_emit {
+                // 计算传输速度
+                let current_time = chrono::Utc::now().timestamp_millis() as u64;
+                let elapsed_ms = if current_time > start_time_for_callback {
+                    current_time - start_time_for_callback
+                } else {
+                    1
+                };
+                let speed_bytes_per_sec = if elapsed_ms > 0 {
+                    (transferred.saturating_sub(start_offset) * 1000) / elapsed_ms
+                } else {
+                    0
+                };
+
+                // 发送进度事件（前端显示用）
+                let progress_event = crate::sftp::DownloadProgressEvent {
+                    task_id: task_id_for_callback.clone(),
+                    connection_id: connection_id_for_callback.clone(),
+                    current_file: file_name_for_callback.clone(),
+                    current_dir: current_dir_for_callback.clone(),
+                    files_completed: if transferred >= total { 1 } else { 0 },
+                    total_files: 1,
+                    bytes_transferred: transferred,
+                    total_bytes: total,
+                    speed_bytes_per_sec,
+                    start_time: start_time_for_callback,
+                    completed_time: current_time,
+                };
+                let _ = app_handle_for_callback.emit("sftp-download-progress", &progress_event);
+            }
+        }
+    ).await
+}
+
+/// 执行目录上传任务
+///
+/// 记录带有 `source_connection_id` 时从源连接直接复制目录（服务器之间传输）
+pub(crate) async fn upload_directory(
+    manager: &SftpManager,
+    pool: &DbPool,
+    app_handle: &AppHandle,
+    record: &UploadRecord,
+) -> Result<UploadDirectoryResult> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let local_dir_path = record.local_path.clone();
+    let remote_dir_path = record.remote_path.clone();
+    let now = chrono::Utc::now().timestamp();
+
+    // 更新状态为 uploading
+    if let Ok(conn) = pool.get() {
+        let _ = crate::database::repositories::UploadRecordsRepository::update_status(
+            &conn,
+            &task_id,
+            crate::database::repositories::UploadStatus::Uploading,
+            None,
+        );
+    }
+
+    // 获取取消令牌（基于 task_id）
+    let cancellation_token = manager.get_cancellation_token(&task_id).await;
+
+    // 准备阶段（连接源端、创建任务 SFTP Client）失败时同样清理并更新记录状态
+    let result = run_upload_directory(manager, app_handle, record, &cancellation_token).await;
+
+    // 🔥 清理任务 SFTP Client 和取消令牌
+    manager.cleanup_task_client(&task_id).await;
+    manager.cleanup_cancellation_token(&task_id).await;
+
+    // 处理上传结果
+    match result {
+        Ok(upload_result) => {
+            tracing::info!("Upload directory completed: {:?}", upload_result);
+
+            // 发送最终完成事件
+            let completed_event = crate::sftp::UploadProgressEvent {
+                task_id: task_id.clone(),
+                connection_id: connection_id.clone(),
+                current_file: "".to_string(),
+                current_dir: remote_dir_path.clone(),
+                files_completed: upload_result.total_files,
+                total_files: upload_result.total_files,
+                bytes_transferred: upload_result.total_size,
+                total_bytes: upload_result.total_size,
+                speed_bytes_per_sec: 0,
+                start_time: chrono::Utc::now().timestamp_millis() as u64,
+                completed_time: chrono::Utc::now().timestamp_millis() as u64,
+                upload_name: Path::new(&local_dir_path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_else(|| {
+                        local_dir_path.rsplit('/')
+                            .next()
+                            .or_else(|| local_dir_path.rsplit('\\').next())
+                            .unwrap_or(&local_dir_path)
+                    })
+                    .to_string(),
+            };
+            let _ = app_handle.emit("sftp-upload-progress", &completed_event);
+
+            // 标记上传完成（包含统计信息）
+            let elapsed = chrono::Utc::now().timestamp() - now;
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::UploadRecordsRepository::mark_completed_with_stats(
+                    &conn,
+                    &task_id,
+                    elapsed * 1000,
+                    upload_result.total_size as i64,
+                    upload_result.total_files as i64,
+                    upload_result.total_files as i64,
+                    upload_result.total_dirs as i64,
+                    upload_result.total_size as i64,
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: "completed".to_string(),
+                    bytes_transferred: upload_result.total_size as i64,
+                    files_completed: upload_result.total_files as i64,
+                    total_files: upload_result.total_files as i64,
+                    error_message: None,
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Ok(upload_result)
+        }
+        Err(e) => {
+            tracing::error!("Upload directory failed: {}", e);
+
+            // 判断是取消还是失败
+            let error_msg = e.to_string();
+            let status = if error_msg.contains("已取消") {
+                tracing::info!("Upload directory cancelled by user: {}", task_id);
+                crate::database::repositories::UploadStatus::Cancelled
+            } else {
+                tracing::error!("Upload directory failed with error: {}", e);
+                crate::database::repositories::UploadStatus::Failed
+            };
+
+            // 获取状态字符串（在移动 status 之前）
+            let status_str = status.as_str();
+
+            // 更新数据库状态
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::UploadRecordsRepository::update_status(
+                    &conn,
+                    &task_id,
+                    status,
+                    Some(error_msg.clone()),
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-upload-status-change", crate::sftp::UploadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: status_str.to_string(),
+                    bytes_transferred: 0,
+                    files_completed: 0,
+                    total_files: 0,
+                    error_message: Some(error_msg),
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Err(e)
+        }
+    }
+}
+
+/// 准备并执行目录上传
+///
+/// 任务 SFTP Client 在返回前释放，由调用方统一清理注册信息和更新记录状态
+async fn run_upload_directory(
+    manager: &SftpManager,
+    app_handle: &AppHandle,
+    record: &UploadRecord,
+    cancellation_token: &tokio_util::sync::CancellationToken,
+) -> Result<UploadDirectoryResult> {
+    let task_id = &record.task_id;
+    let connection_id = &record.connection_id;
+
+    // 服务器之间传输时另外连接源端
+    let mut source_client = match &record.source_connection_id {
+        Some(source_connection_id) => Some(manager.create_source_client(source_connection_id, task_id).await?),
+        None => None,
+    };
+
+    // 🔥 为任务创建独立的 SFTP Client
+    let sftp_client = manager.create_task_client(connection_id, task_id).await?;
+    let mut client_guard = sftp_client.lock().await;
+
+    match source_client.as_mut() {
+        Some(source) => client_guard.copy_directory_from(
+            source,
+            &record.local_path,
+            &record.remote_path,
+            app_handle,
+            connection_id,
+            task_id,
+            cancellation_token
+        ).await,
+        None => client_guard.upload_directory_recursive(
+            &record.local_path,
+            &record.remote_path,
+            app_handle,
+            connection_id,
+            task_id,
+            cancellation_token
+        ).await,
+    }
+}
+
+/// 执行目录下载任务
+pub(crate) async fn download_directory(
+    manager: &SftpManager,
+    pool: &DbPool,
+    app_handle: &AppHandle,
+    record: &DownloadRecord,
+) -> Result<DownloadDirectoryResult> {
+    let task_id = record.task_id.clone();
+    let connection_id = record.connection_id.clone();
+    let now = chrono::Utc::now().timestamp();
+
+    // 更新状态为 downloading
+    if let Ok(conn) = pool.get() {
+        let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
+            &conn,
+            &task_id,
+            crate::database::repositories::DownloadStatus::Downloading,
+            None,
+        );
+    }
+
+    // 获取取消令牌（基于 task_id）
+    let cancellation_token = manager.get_cancellation_token(&task_id).await;
+
+    // 创建任务 SFTP Client 失败时同样清理并更新记录状态
+    let result = run_download_directory(manager, app_handle, record, &cancellation_token).await;
+
+    // 🔥 清理任务 SFTP Client 和取消令牌
+    manager.cleanup_task_client(&task_id).await;
+    manager.cleanup_cancellation_token(&task_id).await;
+
+    // 处理下载结果
+    match result {
+        Ok(download_result) => {
+            tracing::info!("Download directory completed: {:?}", download_result);
+
+            // 标记下载完成（包含统计信息）
+            let elapsed = chrono::Utc::now().timestamp() - now;
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::DownloadRecordsRepository::mark_completed_with_stats(
+                    &conn,
+                    &task_id,
+                    elapsed * 1000,
+                    download_result.total_size as i64,
+                    download_result.total_files as i64,
+                    download_result.total_files as i64,
+                    download_result.total_dirs as i64,
+                    download_result.total_size as i64,
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: "completed".to_string(),
+                    bytes_transferred: download_result.total_size as i64,
+                    files_completed: download_result.total_files as i64,
+                    total_files: download_result.total_files as i64,
+                    error_message: None,
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Ok(download_result)
+        }
+        Err(e) => {
+            tracing::error!("Download directory failed: {}", e);
+
+            // 判断是取消还是失败
+            let error_msg = e.to_string();
+            let status = if error_msg.contains("已取消") {
+                tracing::info!("Download directory cancelled by user: {}", task_id);
+                crate::database::repositories::DownloadStatus::Cancelled
+            } else {
+                tracing::error!("Download directory failed with error: {}", e);
+                crate::database::repositories::DownloadStatus::Failed
+            };
+
+            // 获取状态字符串（在移动 status 之前）
+            let status_str = status.as_str();
+
+            // 更新数据库状态
+            if let Ok(conn) = pool.get() {
+                let _ = crate::database::repositories::DownloadRecordsRepository::update_status(
+                    &conn,
+                    &task_id,
+                    status,
+                    Some(error_msg.clone()),
+                );
+
+                // 发送状态变更事件
+                let _ = app_handle.emit("sftp-download-status-change", crate::sftp::DownloadStatusChangeEvent {
+                    task_id: task_id.clone(),
+                    connection_id: connection_id.clone(),
+                    status: status_str.to_string(),
+                    bytes_transferred: 0,
+                    files_completed: 0,
+                    total_files: 0,
+                    error_message: Some(error_msg),
+                    completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            }
+
+            Err(e)
+        }
+    }
+}
+
+/// 准备并执行目录下载
+///
+/// 任务 SFTP Client 在返回前释放，由调用方统一清理注册信息和更新记录状态
+async fn run_download_directory(
+    manager: &SftpManager,
+    app_handle: &AppHandle,
+    record: &DownloadRecord,
+    cancellation_token: &tokio_util::sync::CancellationToken,
+) -> Result<DownloadDirectoryResult> {
+    // 🔥 为任务创建独立的 SFTP Client
+    let sftp_client = manager.create_task_client(&record.connection_id, &record.task_id).await?;
+    let mut client_guard = sftp_client.lock().await;
+
+    client_guard.download_directory_recursive(
+        &record.remote_path,
+        &record.local_path,
+        app_handle,
+        &record.connection_id,
+        &record.task_id,
+        cancellation_token,
+        |_transferred, _total| {
+            // 进度回调，暂不使用
+        }
+    ).await
+}
@@ -1,4 +1,6 @@
-use crate::error::Result;
+use crate::error::{Result, SSHError};
+use crate::ssh::forwarding::{PortForwardRule, PortForwardStatus};
+use crate::ssh::session::SessionEnd;
 use async_trait::async_trait;
 use tokio::io::{AsyncRead, AsyncWrite};
 
@@ -32,6 +34,35 @@ pub trait SSHBackend: Send {
     /// 断开 SSH 连接
     async fn disconnect(&mut self) -> Result<()>;
 
+    /// 会话结束信息（结束原因、远程退出码）
+    ///
+    /// 读取器遇到 EOF 后调用，会话仍在运行时返回 None
+    fn session_end(&self) -> Option<SessionEnd> {
+        None
+    }
+
+    /// 添加端口转发规则，规则启用时立即启动
+    ///
+    /// 默认实现返回不支持，由支持端口转发的后端覆盖
+    async fn add_port_forward(&mut self, _rule: PortForwardRule) -> Result<()> {
+        Err(SSHError::NotSupported("端口转发".to_string()))
+    }
+
+    /// 停止并移除端口转发规则
+    async fn remove_port_forward(&mut self, _rule_id: &str) -> Result<()> {
+        Err(SSHError::NotSupported("端口转发".to_string()))
+    }
+
+    /// 启用或停用端口转发规则
+    async fn set_port_forward_enabled(&mut self, _rule_id: &str, _enabled: bool) -> Result<()> {
+        Err(SSHError::NotSupported("端口转发".to_string()))
+    }
+
+    /// 列出端口转发规则及其运行状态
+    async fn port_forwards(&self) -> Vec<PortForwardStatus> {
+        Vec::new()
+    }
+
     /// 获取读取器（用于读取 SSH 输出）
     ///
     /// 返回一个异步读取器，可以读取 SSH 服务器的输出
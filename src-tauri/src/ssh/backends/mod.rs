@@ -6,5 +6,16 @@ pub mod russh;
 // SFTP channel 包装器
 pub mod sftp_channel;
 
+// 端口转发
+pub mod port_forward;
+
+// Telnet 协议处理与 TCP 后端
+pub mod telnet;
+pub mod tcp;
+
+// 串口（仅桌面平台）
+#[cfg(not(target_os = "android"))]
+pub mod serial;
+
 // 所有平台默认使用 russh（纯 Rust 实现）
 pub use russh::RusshBackend as DefaultBackend;
@@ -0,0 +1,432 @@
+// 基于 russh 的端口转发实现
+//
+// - Local / Dynamic：本地监听端口，每个连接打开一个 direct-tcpip channel
+// - Remote：通过 tcpip-forward 请求服务器监听，服务器发起的 forwarded-tcpip
+//   channel 由 RusshHandler 根据注册表转发到本机目标
+
+use crate::error::{Result, SSHError};
+use crate::ssh::backends::russh::SharedHandle;
+use crate::ssh::forwarding::{
+    self, PortForwardEvent, PortForwardKind, PortForwardRule, PortForwardStatus, TunnelStats,
+    SOCKS_REPLY_GENERAL_FAILURE, SOCKS_REPLY_SUCCEEDED,
+};
+use russh::client::Msg;
+use russh::Channel;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 远程转发的本机目标
+#[derive(Clone)]
+pub struct RemoteForwardTarget {
+    pub rule_id: String,
+    pub host: String,
+    pub port: u16,
+    pub stats: Arc<TunnelStats>,
+    pub events: Option<mpsc::UnboundedSender<PortForwardEvent>>,
+}
+
+/// 远程转发注册表：(服务器监听地址, 端口) -> 本机目标
+///
+/// 由 PortForwarder 维护，RusshHandler 在收到 forwarded-tcpip channel 时查询
+pub type RemoteForwardRegistry = Arc<StdMutex<HashMap<(String, u32), RemoteForwardTarget>>>;
+
+/// 处理服务器发起的 forwarded-tcpip channel
+pub async fn serve_remote_connection(channel: Channel<Msg>, target: RemoteForwardTarget) {
+    let local = match TcpStream::connect((target.host.as_str(), target.port)).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            let message = format!("无法连接本地目标 {}:{}: {}", target.host, target.port, e);
+            warn!("Remote forward {}: {}", target.rule_id, message);
+            report_connection_error(&target.rule_id, &target.stats, target.events.as_ref(), message);
+            let _ = channel.close().await;
+            return;
+        }
+    };
+
+    if let Err(e) = forwarding::pipe(local, channel.into_stream(), target.stats.clone()).await {
+        debug!("Remote forward {} connection closed: {}", target.rule_id, e);
+    }
+}
+
+/// 记录单个转发连接的错误，并以事件通知前端
+///
+/// 规则本身仍在运行，事件中 active 为 true
+fn report_connection_error(
+    rule_id: &str,
+    stats: &TunnelStats,
+    events: Option<&mpsc::UnboundedSender<PortForwardEvent>>,
+    error: String,
+) {
+    stats.record_error(error.clone());
+    if let Some(events) = events {
+        let _ = events.send(PortForwardEvent {
+            rule_id: rule_id.to_string(),
+            active: true,
+            error: Some(error),
+        });
+    }
+}
+
+/// 单条转发规则的运行时状态
+struct Tunnel {
+    rule: PortForwardRule,
+    stats: Arc<TunnelStats>,
+    /// 本地监听任务（Local/Dynamic）
+    listener: Option<JoinHandle<()>>,
+    /// 实际监听端口
+    bound_port: Option<u16>,
+}
+
+impl Tunnel {
+    fn is_active(&self) -> bool {
+        self.bound_port.is_some()
+    }
+}
+
+/// 单个 SSH 连接上的端口转发管理器
+pub struct PortForwarder {
+    handle: SharedHandle,
+    remote_forwards: RemoteForwardRegistry,
+    events: Option<mpsc::UnboundedSender<PortForwardEvent>>,
+    tunnels: Vec<Tunnel>,
+}
+
+impl Drop for PortForwarder {
+    fn drop(&mut self) {
+        // 监听任务持有 Handle 的引用，需随转发管理器一起停止
+        for tunnel in &mut self.tunnels {
+            if let Some(listener) = tunnel.listener.take() {
+                listener.abort();
+            }
+        }
+    }
+}
+
+impl PortForwarder {
+    pub fn new(
+        handle: SharedHandle,
+        remote_forwards: RemoteForwardRegistry,
+        events: Option<mpsc::UnboundedSender<PortForwardEvent>>,
+    ) -> Self {
+        Self {
+            handle,
+            remote_forwards,
+            events,
+            tunnels: Vec::new(),
+        }
+    }
+
+    /// 添加规则，已启用的规则会立即启动
+    ///
+    /// 启动失败不会移除规则，错误记录在状态中并通过事件通知
+    pub async fn add(&mut self, rule: PortForwardRule) -> Result<()> {
+        if self.tunnels.iter().any(|t| t.rule.id == rule.id) {
+            self.remove(&rule.id).await?;
+        }
+
+        let enabled = rule.enabled;
+        self.tunnels.push(Tunnel {
+            rule,
+            stats: Arc::new(TunnelStats::default()),
+            listener: None,
+            bound_port: None,
+        });
+
+        if enabled {
+            let index = self.tunnels.len() - 1;
+            self.start_at(index).await?;
+        }
+        Ok(())
+    }
+
+    /// 停止并移除规则
+    pub async fn remove(&mut self, rule_id: &str) -> Result<()> {
+        let index = self.index_of(rule_id)?;
+        self.stop_at(index).await;
+        self.tunnels.remove(index);
+        Ok(())
+    }
+
+    /// 启用或停用规则
+    pub async fn set_enabled(&mut self, rule_id: &str, enabled: bool) -> Result<()> {
+        let index = self.index_of(rule_id)?;
+        self.tunnels[index].rule.enabled = enabled;
+
+        if enabled && !self.tunnels[index].is_active() {
+            self.start_at(index).await
+        } else if !enabled && self.tunnels[index].is_active() {
+            self.stop_at(index).await;
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 列出所有规则及运行状态
+    pub fn list(&self) -> Vec<PortForwardStatus> {
+        self.tunnels
+            .iter()
+            .map(|tunnel| PortForwardStatus {
+                rule: tunnel.rule.clone(),
+                active: tunnel.is_active(),
+                bound_port: tunnel.bound_port,
+                bytes_sent: tunnel.stats.bytes_sent.load(Ordering::Relaxed),
+                bytes_received: tunnel.stats.bytes_received.load(Ordering::Relaxed),
+                active_connections: tunnel.stats.active_connections.load(Ordering::Relaxed),
+                last_error: tunnel.stats.last_error(),
+            })
+            .collect()
+    }
+
+    fn index_of(&self, rule_id: &str) -> Result<usize> {
+        self.tunnels
+            .iter()
+            .position(|t| t.rule.id == rule_id)
+            .ok_or_else(|| SSHError::NotFound(format!("端口转发规则 {}", rule_id)))
+    }
+
+    fn emit(&self, rule_id: &str, active: bool, error: Option<String>) {
+        if let Some(events) = &self.events {
+            let _ = events.send(PortForwardEvent {
+                rule_id: rule_id.to_string(),
+                active,
+                error,
+            });
+        }
+    }
+
+    async fn start_at(&mut self, index: usize) -> Result<()> {
+        let rule = self.tunnels[index].rule.clone();
+        let stats = self.tunnels[index].stats.clone();
+
+        let result = match rule.kind {
+            PortForwardKind::Local | PortForwardKind::Dynamic => {
+                self.start_listener(&rule, stats.clone()).await
+            }
+            PortForwardKind::Remote => self.start_remote(&rule, stats.clone()).await,
+        };
+
+        match result {
+            Ok((bound_port, listener)) => {
+                info!(
+                    "Port forward {} ({:?}) started on {}:{}",
+                    rule.id, rule.kind, rule.bind_host, bound_port
+                );
+                let tunnel = &mut self.tunnels[index];
+                tunnel.bound_port = Some(bound_port);
+                tunnel.listener = listener;
+                self.emit(&rule.id, true, None);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to start port forward {}: {}", rule.id, e);
+                stats.record_error(e.to_string());
+                self.emit(&rule.id, false, Some(e.to_string()));
+                Err(e)
+            }
+        }
+    }
+
+    async fn stop_at(&mut self, index: usize) {
+        let tunnel = &mut self.tunnels[index];
+        let Some(bound_port) = tunnel.bound_port.take() else {
+            return;
+        };
+
+        if let Some(listener) = tunnel.listener.take() {
+            listener.abort();
+        }
+
+        if tunnel.rule.kind == PortForwardKind::Remote {
+            let bind_host = tunnel.rule.bind_host.clone();
+            self.remote_forwards
+                .lock()
+                .unwrap()
+                .remove(&(bind_host.clone(), bound_port as u32));
+            if let Err(e) = self
+                .handle
+                .read()
+                .await
+                .cancel_tcpip_forward(bind_host, bound_port as u32)
+                .await
+            {
+                warn!("Failed to cancel remote forward: {}", e);
+            }
+        }
+
+        let rule_id = self.tunnels[index].rule.id.clone();
+        info!("Port forward {} stopped", rule_id);
+        self.emit(&rule_id, false, None);
+    }
+
+    /// 启动本地监听（Local / Dynamic）
+    async fn start_listener(
+        &self,
+        rule: &PortForwardRule,
+        stats: Arc<TunnelStats>,
+    ) -> Result<(u16, Option<JoinHandle<()>>)> {
+        let listener = TcpListener::bind((rule.bind_host.as_str(), rule.bind_port))
+            .await
+            .map_err(|e| {
+                SSHError::Io(format!(
+                    "无法监听 {}:{}: {}",
+                    rule.bind_host, rule.bind_port, e
+                ))
+            })?;
+        let bound_port = listener.local_addr()?.port();
+
+        let handle = self.handle.clone();
+        let events = self.events.clone();
+        let rule = rule.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("Port forward {} accept failed: {}", rule.id, e);
+                        report_connection_error(&rule.id, &stats, events.as_ref(), e.to_string());
+                        continue;
+                    }
+                };
+
+                let handle = handle.clone();
+                let events = events.clone();
+                let rule = rule.clone();
+                let stats = stats.clone();
+                tokio::spawn(async move {
+                    let result = match rule.kind {
+                        PortForwardKind::Dynamic => serve_socks_connection(handle, stream, peer, stats.clone()).await,
+                        _ => {
+                            serve_local_connection(
+                                handle,
+                                stream,
+                                peer,
+                                &rule.target_host,
+                                rule.target_port,
+                                stats.clone(),
+                            )
+                            .await
+                        }
+                    };
+                    if let Err(e) = result {
+                        debug!("Port forward {} connection from {} failed: {}", rule.id, peer, e);
+                        report_connection_error(&rule.id, &stats, events.as_ref(), e.to_string());
+                    }
+                });
+            }
+        });
+
+        Ok((bound_port, Some(task)))
+    }
+
+    /// 请求服务器监听端口（Remote）
+    async fn start_remote(
+        &self,
+        rule: &PortForwardRule,
+        stats: Arc<TunnelStats>,
+    ) -> Result<(u16, Option<JoinHandle<()>>)> {
+        let allocated = self
+            .handle
+            .write()
+            .await
+            .tcpip_forward(rule.bind_host.clone(), rule.bind_port as u32)
+            .await
+            .map_err(|e| {
+                SSHError::Ssh(format!(
+                    "服务器拒绝远程转发 {}:{}: {}",
+                    rule.bind_host, rule.bind_port, e
+                ))
+            })?;
+
+        // 指定端口时服务器返回 0
+        let bound_port = if rule.bind_port == 0 {
+            allocated as u16
+        } else {
+            rule.bind_port
+        };
+
+        self.remote_forwards.lock().unwrap().insert(
+            (rule.bind_host.clone(), bound_port as u32),
+            RemoteForwardTarget {
+                rule_id: rule.id.clone(),
+                host: rule.target_host.clone(),
+                port: rule.target_port,
+                stats,
+                events: self.events.clone(),
+            },
+        );
+
+        Ok((bound_port, None))
+    }
+}
+
+/// 处理本地转发的单个连接
+async fn serve_local_connection(
+    handle: SharedHandle,
+    stream: TcpStream,
+    peer: std::net::SocketAddr,
+    target_host: &str,
+    target_port: u16,
+    stats: Arc<TunnelStats>,
+) -> Result<()> {
+    // channel_open_direct_tcpip 要等服务器连上目标才返回，只持有读锁，不阻塞其他 channel 的打开
+    let channel = handle
+        .read()
+        .await
+        .channel_open_direct_tcpip(
+            target_host,
+            target_port as u32,
+            peer.ip().to_string(),
+            peer.port() as u32,
+        )
+        .await
+        .map_err(|e| {
+            SSHError::Ssh(format!("无法打开到 {}:{} 的通道: {}", target_host, target_port, e))
+        })?;
+
+    forwarding::pipe(stream, channel.into_stream(), stats).await?;
+    Ok(())
+}
+
+/// 处理动态转发（SOCKS5）的单个连接
+async fn serve_socks_connection(
+    handle: SharedHandle,
+    mut stream: TcpStream,
+    peer: std::net::SocketAddr,
+    stats: Arc<TunnelStats>,
+) -> Result<()> {
+    let (target_host, target_port) = forwarding::socks5_handshake(&mut stream).await?;
+    debug!("SOCKS request from {} to {}:{}", peer, target_host, target_port);
+
+    // channel_open_direct_tcpip 要等服务器连上目标才返回，只持有读锁，不阻塞其他 channel 的打开
+    let channel = handle
+        .read()
+        .await
+        .channel_open_direct_tcpip(
+            target_host.as_str(),
+            target_port as u32,
+            peer.ip().to_string(),
+            peer.port() as u32,
+        )
+        .await;
+
+    let channel = match channel {
+        Ok(channel) => channel,
+        Err(e) => {
+            let _ = forwarding::socks5_reply(&mut stream, SOCKS_REPLY_GENERAL_FAILURE).await;
+            return Err(SSHError::Ssh(format!(
+                "无法打开到 {}:{} 的通道: {}",
+                target_host, target_port, e
+            )));
+        }
+    };
+
+    forwarding::socks5_reply(&mut stream, SOCKS_REPLY_SUCCEEDED).await?;
+    forwarding::pipe(stream, channel.into_stream(), stats).await?;
+    Ok(())
+}
@@ -2,24 +2,41 @@
 
 use crate::error::{Result, SSHError};
 use crate::ssh::backend::{BackendReader, SSHBackend};
-use crate::ssh::session::{AuthMethod, SessionConfig};
+use crate::ssh::exec::{ExecCapture, ExecRequest, ExecResult, ExecStream};
+use crate::ssh::forwarding::{PortForwardEvent, PortForwardRule, PortForwardStatus};
+use crate::ssh::interactive::{InteractivePromptItem, InteractiveResponder};
+use crate::ssh::certificate::{self, CertificateInfo};
+use crate::ssh::keys::ManagedKeyStore;
+use crate::ssh::known_hosts::HostKeyVerifier;
+use crate::ssh::session::{AuthMethod, DisconnectReason, LifecycleEvent, SessionConfig, SessionEnd};
 use async_trait::async_trait;
 use russh::client;
-use russh::client::{Config, Handle, Msg};
+use russh::client::{AuthResult, Config, Handle, KeyboardInteractiveAuthResponse, Msg};
 use russh::*;
 use russh::{ChannelReadHalf, ChannelWriteHalf};
-use russh::keys::{load_secret_key, PrivateKeyWithHashAlg};
+use russh::keys::agent::client::{AgentClient, AgentStream};
+use russh::keys::{load_secret_key, Certificate, HashAlg, PrivateKey, PrivateKeyWithHashAlg};
 use std::io;
 use std::io::Cursor;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
-use tokio::io::{AsyncRead, ReadBuf};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, trace, warn};
 
 // 导入 SFTP channel 包装器
 use super::sftp_channel::SftpChannelStream;
+use crate::ssh::output::{ChannelReader, COMMAND_CHANNEL_CAPACITY, IO_TRACE_TARGET, OUTPUT_CHANNEL_CAPACITY};
+use super::port_forward::{self, PortForwarder, RemoteForwardRegistry};
+
+/// 可在多个任务间共享的 SSH Handle
+///
+/// tcpip_forward 等全局请求需要 `&mut Handle`，因此用异步读写锁包装；
+/// 打开 channel 等只需 `&Handle` 的操作使用读锁，可以并发进行
+pub type SharedHandle = Arc<tokio::sync::RwLock<Handle<RusshHandler>>>;
+
+/// 键盘交互认证的最大轮数（防止服务器无限要求部分成功）
+const MAX_KEYBOARD_INTERACTIVE_ROUNDS: usize = 5;
 
 /// SSH Channel 命令
 ///
@@ -34,121 +51,362 @@ enum ChannelCommand {
 ///
 /// 纯 Rust SSH 实现，基于 russh 库
 pub struct RusshBackend {
-    handle: Option<Handle<RusshHandler>>,
-    command_sender: Option<mpsc::UnboundedSender<ChannelCommand>>,
-    receiver: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+    /// 底层 SSH 连接，可与其他 channel 共享
+    transport: Option<Arc<SshTransport>>,
+    command_sender: Option<mpsc::Sender<ChannelCommand>>,
+    receiver: Option<mpsc::Receiver<Vec<u8>>>,
     connected: bool,
+    /// 键盘交互认证应答器（未设置时无法完成 keyboard-interactive 认证）
+    interactive_responder: Option<Arc<dyn InteractiveResponder>>,
+    /// 跳板机配置（按连接顺序）
+    jump_hosts: Vec<SessionConfig>,
+    /// 托管密钥存储（未设置时无法使用 ManagedKey 认证）
+    key_store: Option<Arc<dyn ManagedKeyStore>>,
+    /// 端口转发状态事件发送端
+    port_forward_events: Option<mpsc::UnboundedSender<PortForwardEvent>>,
+    /// 生命周期事件发送端
+    lifecycle_events: Option<mpsc::UnboundedSender<LifecycleEvent>>,
+    /// 会话结束信息，由会话循环在退出前写入
+    session_end: Arc<StdMutex<Option<SessionEnd>>>,
 }
 
-/// russh 客户端 Handler
+/// 已认证的 SSH 连接（传输层）
 ///
-/// 实现 client::Handler trait 来处理 SSH 协议事件
-pub struct RusshHandler;
+/// 终端、SFTP、exec 等 channel 通过 `Arc<SshTransport>` 共享同一个连接，
+/// 最后一个引用释放时关闭连接（包括跳板机）
+pub struct SshTransport {
+    handle: SharedHandle,
+    /// 跳板机连接，需在目标连接存活期间保持
+    jump_handles: Vec<Handle<RusshHandler>>,
+    /// 端口转发属于连接而不是单个 channel
+    port_forwarder: tokio::sync::Mutex<PortForwarder>,
+    /// 登录目标服务器时出示的用户证书
+    user_certificate: Option<CertificateInfo>,
+}
 
-impl client::Handler for RusshHandler {
-    type Error = russh::Error;
+impl SshTransport {
+    /// 获取共享的 SSH Handle
+    pub fn handle(&self) -> SharedHandle {
+        self.handle.clone()
+    }
 
-    /// 验证服务器主机密钥
-    ///
-    /// 在生产环境中，应该实现 known_hosts 验证
-    async fn check_server_key(
-        &mut self,
-        _server_public_key: &russh::keys::PublicKey,
-    ) -> std::result::Result<bool, Self::Error> {
-        // TODO: 实现完整的主机密钥验证
-        // 目前暂时接受所有主机密钥（仅用于开发）
-        // 生产环境应该：
-        // 1. 读取 known_hosts 文件
-        // 2. 比较服务器公钥
-        // 3. 提示用户确认新密钥
-        debug!("Accepting server host key (verification not implemented)");
-        Ok(true)
+    /// 登录时出示的用户证书，过期状态按当前时间计算
+    pub fn user_certificate(&self) -> Option<CertificateInfo> {
+        self.user_certificate
+            .clone()
+            .map(|info| info.refreshed(certificate::unix_now()))
     }
-}
 
-/// russh 的异步读取器
-///
-/// 从 mpsc channel 接收 SSH 输出数据
-pub struct RusshReader {
-    receiver: mpsc::UnboundedReceiver<Vec<u8>>,
-    buffer: Option<Vec<u8>>,
-    position: usize,
-}
+    /// 连接是否已关闭（网络中断或服务器断开）
+    pub async fn is_closed(&self) -> bool {
+        self.handle.read().await.is_closed()
+    }
 
-impl AsyncRead for RusshReader {
-    fn poll_read(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &mut ReadBuf<'_>,
-    ) -> Poll<io::Result<()>> {
-        // 如果当前 buffer 还有数据，先消费
-        if self.buffer.is_some() {
-            let buffer_len = {
-                let buffer = self.buffer.as_ref().unwrap();
-                buffer.len()
-            };
+    /// 在连接上打开新的 session channel
+    pub async fn open_session_channel(&self) -> Result<Channel<Msg>> {
+        self.handle
+            .read()
+            .await
+            .channel_open_session()
+            .await
+            .map_err(|e| SSHError::ConnectionFailed(format!("Failed to open channel: {}", e)))
+    }
 
-            if self.position < buffer_len {
-                let mut temp_buffer = None;
-                std::mem::swap(&mut self.buffer, &mut temp_buffer);
+    /// 在独立的 exec channel 上执行命令
+    ///
+    /// 输出通过 on_output 实时回调；超时或取消时关闭 channel，返回已采集的输出
+    pub async fn exec<F>(
+        &self,
+        request: &ExecRequest,
+        cancel: CancellationToken,
+        mut on_output: F,
+    ) -> Result<ExecResult>
+    where
+        F: FnMut(ExecStream, &[u8]) + Send,
+    {
+        let mut channel = self.open_session_channel().await?;
+        debug!("Executing command on exec channel");
+        channel
+            .exec(true, request.command.as_bytes())
+            .await
+            .map_err(|e| SSHError::ConnectionFailed(format!("Failed to exec command: {}", e)))?;
 
-                if let Some(buffer) = temp_buffer {
-                    let remaining = &buffer[self.position..];
-                    let to_copy = std::cmp::min(remaining.len(), buf.capacity());
-                    buf.put_slice(&remaining[..to_copy]);
-                    self.position += to_copy;
+        let deadline = request
+            .timeout_ms
+            .map(|ms| tokio::time::Instant::now() + Duration::from_millis(ms));
+        let mut capture = ExecCapture::default();
+        let (mut exit_code, mut exit_signal) = (None, None);
+        let (mut timed_out, mut cancelled) = (false, false);
 
-                    if self.position >= buffer.len() {
-                        self.buffer = None;
-                        self.position = 0;
-                    } else {
-                        self.buffer = Some(buffer);
+        loop {
+            let timeout = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                msg = channel.wait() => match msg {
+                    Some(ChannelMsg::Data { data }) => {
+                        trace!(target: IO_TRACE_TARGET, "exec stdout: {} bytes", data.len());
+                        on_output(ExecStream::Stdout, &data);
+                        capture.push(ExecStream::Stdout, &data);
+                    }
+                    // ext = 1 为 SSH_EXTENDED_DATA_STDERR
+                    Some(ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                        trace!(target: IO_TRACE_TARGET, "exec stderr: {} bytes", data.len());
+                        on_output(ExecStream::Stderr, &data);
+                        capture.push(ExecStream::Stderr, &data);
                     }
+                    Some(ChannelMsg::ExitStatus { exit_status }) => exit_code = Some(exit_status),
+                    Some(ChannelMsg::ExitSignal { signal_name, .. }) => {
+                        exit_signal = Some(format!("{:?}", signal_name));
+                    }
+                    // 退出状态之后可能还有数据，等到 channel 关闭
+                    Some(ChannelMsg::Close) | None => break,
+                    Some(_) => {}
+                },
+                _ = timeout => {
+                    warn!("Exec command timed out");
+                    timed_out = true;
+                    break;
+                }
+                _ = cancel.cancelled() => {
+                    info!("Exec command cancelled");
+                    cancelled = true;
+                    break;
                 }
-                return Poll::Ready(Ok(()));
             }
         }
 
-        // 尝试从 channel 接收新数据
-        match self.receiver.poll_recv(cx) {
-            Poll::Ready(Some(data)) => {
-                let to_copy = std::cmp::min(data.len(), buf.capacity());
-                buf.put_slice(&data[..to_copy]);
-                if data.len() > to_copy {
-                    self.buffer = Some(data[to_copy..].to_vec());
-                    self.position = 0;
+        if timed_out || cancelled {
+            let _ = channel.close().await;
+        }
+
+        let mut result = capture.finish(exit_code, exit_signal);
+        result.timed_out = timed_out;
+        result.cancelled = cancelled;
+        Ok(result)
+    }
+
+    /// 获取端口转发管理器
+    pub async fn port_forwarder(&self) -> tokio::sync::MutexGuard<'_, PortForwarder> {
+        self.port_forwarder.lock().await
+    }
+}
+
+impl Drop for SshTransport {
+    fn drop(&mut self) {
+        let handle = self.handle.clone();
+        let jump_handles = std::mem::take(&mut self.jump_handles);
+
+        // 不在 tokio 运行时中时，Handle 释放后连接也会随之关闭
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        runtime.spawn(async move {
+            info!("Last channel released, closing SSH connection");
+            if let Err(e) = handle
+                .read()
+                .await
+                .disconnect(Disconnect::ByApplication, "", "English")
+                .await
+            {
+                warn!("Failed to disconnect: {}", e);
+            }
+
+            // 从最后一跳开始关闭跳板机连接
+            for jump_handle in jump_handles.into_iter().rev() {
+                if let Err(e) = jump_handle
+                    .disconnect(Disconnect::ByApplication, "", "English")
+                    .await
+                {
+                    warn!("Failed to disconnect jump host: {}", e);
                 }
-                Poll::Ready(Ok(()))
             }
-            Poll::Ready(None) => {
-                // Channel 关闭
-                debug!("RusshReader channel closed");
-                Poll::Ready(Ok(()))
+        });
+    }
+}
+
+/// russh 客户端 Handler
+///
+/// 实现 client::Handler trait 来处理 SSH 协议事件
+pub struct RusshHandler {
+    host_key_verifier: HostKeyVerifier,
+    /// 主机密钥验证失败的具体原因
+    ///
+    /// check_server_key 只能返回 bool，russh 会将 false 转换为通用错误，
+    /// 这里保存详细信息，供 connect 转换为对应的 SSHError
+    host_key_error: Arc<StdMutex<Option<SSHError>>>,
+    /// 远程端口转发目标，用于接收服务器发起的 forwarded-tcpip channel
+    remote_forwards: RemoteForwardRegistry,
+}
+
+impl RusshHandler {
+    pub fn new(config: &SessionConfig, remote_forwards: RemoteForwardRegistry) -> Self {
+        Self::with_verifier(
+            HostKeyVerifier::new(&config.host, config.port, config.strict_host_key_checking),
+            remote_forwards,
+        )
+    }
+
+    fn with_verifier(host_key_verifier: HostKeyVerifier, remote_forwards: RemoteForwardRegistry) -> Self {
+        Self {
+            host_key_verifier,
+            host_key_error: Arc::new(StdMutex::new(None)),
+            remote_forwards,
+        }
+    }
+
+    /// 获取主机密钥验证错误的共享槽位
+    pub fn host_key_error(&self) -> Arc<StdMutex<Option<SSHError>>> {
+        self.host_key_error.clone()
+    }
+
+    fn reject(&self, error: SSHError) -> bool {
+        error!("{}", error);
+        *self.host_key_error.lock().unwrap() = Some(error);
+        false
+    }
+}
+
+/// 握手失败时优先返回主机密钥验证的具体原因
+fn handshake_error(host_key_error: &StdMutex<Option<SSHError>>, e: russh::Error) -> SSHError {
+    host_key_error
+        .lock()
+        .unwrap()
+        .take()
+        .unwrap_or_else(|| SSHError::ConnectionFailed(format!("Failed to connect: {}", e)))
+}
+
+impl client::Handler for RusshHandler {
+    type Error = russh::Error;
+
+    /// 验证服务器主机密钥，规则见 [`HostKeyVerifier::check`]
+    ///
+    /// russh 目前只协商普通主机密钥算法（不含 `*-cert-v01@openssh.com`），这里收到的都是普通公钥，
+    /// 因此不支持按 `@cert-authority` 验证主机证书；只通过 `@cert-authority` 信任的主机由 HostKeyVerifier 明确报告为不支持
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &russh::keys::PublicKey,
+    ) -> std::result::Result<bool, Self::Error> {
+        match self.host_key_verifier.check(server_public_key) {
+            Ok(()) => Ok(true),
+            Err(e) => Ok(self.reject(e)),
+        }
+    }
+
+    /// 服务器为远程端口转发打开的 channel
+    ///
+    /// 未注册的地址直接关闭 channel
+    async fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<Msg>,
+        connected_address: &str,
+        connected_port: u32,
+        originator_address: &str,
+        originator_port: u32,
+        _session: &mut client::Session,
+    ) -> std::result::Result<(), Self::Error> {
+        let target = self
+            .remote_forwards
+            .lock()
+            .unwrap()
+            .get(&(connected_address.to_string(), connected_port))
+            .cloned();
+
+        match target {
+            Some(target) => {
+                debug!(
+                    "Forwarded connection from {}:{} to {}:{} -> {}:{}",
+                    originator_address,
+                    originator_port,
+                    connected_address,
+                    connected_port,
+                    target.host,
+                    target.port
+                );
+                tokio::spawn(port_forward::serve_remote_connection(channel, target));
+            }
+            None => {
+                warn!(
+                    "Rejecting forwarded connection to unregistered {}:{}",
+                    connected_address, connected_port
+                );
+                let _ = channel.close().await;
             }
-            Poll::Pending => Poll::Pending,
         }
+        Ok(())
     }
 }
 
 impl RusshBackend {
     /// 创建新的 russh 后端实例
     pub fn new() -> Self {
-        let (_command_sender, _) = mpsc::unbounded_channel::<ChannelCommand>();
-        let (_output_sender, output_receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+        let (_output_sender, output_receiver) = mpsc::channel::<Vec<u8>>(1);
         Self {
-            handle: None,
+            transport: None,
             command_sender: None,
             receiver: Some(output_receiver),
             connected: false,
+            interactive_responder: None,
+            jump_hosts: Vec::new(),
+            key_store: None,
+            port_forward_events: None,
+            lifecycle_events: None,
+            session_end: Arc::new(StdMutex::new(None)),
         }
     }
 
+    /// 设置生命周期事件的发送端
+    pub fn set_lifecycle_events(&mut self, sender: mpsc::UnboundedSender<LifecycleEvent>) {
+        self.lifecycle_events = Some(sender);
+    }
+
+    fn emit_lifecycle(&self, event: LifecycleEvent) {
+        if let Some(events) = &self.lifecycle_events {
+            let _ = events.send(event);
+        }
+    }
+
+    /// 设置端口转发状态事件的发送端
+    pub fn set_port_forward_events(&mut self, sender: mpsc::UnboundedSender<PortForwardEvent>) {
+        self.port_forward_events = Some(sender);
+    }
+
+    /// 设置跳板机链（按连接顺序，最后一个跳板机直接连接目标服务器）
+    pub fn set_jump_hosts(&mut self, jump_hosts: Vec<SessionConfig>) {
+        self.jump_hosts = jump_hosts;
+    }
+
+    /// 设置托管密钥存储
+    pub fn set_key_store(&mut self, key_store: Arc<dyn ManagedKeyStore>) {
+        self.key_store = Some(key_store);
+    }
+
+    /// 设置键盘交互认证应答器
+    pub fn set_interactive_responder(&mut self, responder: Arc<dyn InteractiveResponder>) {
+        self.interactive_responder = Some(responder);
+    }
+
+    /// 复用已认证的连接，connect 时只打开新的 channel
+    pub fn set_transport(&mut self, transport: Arc<SshTransport>) {
+        self.transport = Some(transport);
+    }
+
+    /// 获取底层连接
+    pub fn transport(&self) -> Option<Arc<SshTransport>> {
+        self.transport.clone()
+    }
+
     /// 获取 SSH Handle 的引用
     ///
     /// 用于创建额外的 channel（如 SFTP）
     #[allow(dead_code)]
-    pub fn get_handle(&self) -> Option<&Handle<RusshHandler>> {
-        self.handle.as_ref()
+    pub fn get_handle(&self) -> Option<SharedHandle> {
+        self.transport.as_ref().map(|transport| transport.handle())
     }
 
     /// 创建 russh 客户端配置
@@ -183,18 +441,453 @@ impl RusshBackend {
         russh_config
     }
 
+    /// 连接本机 ssh-agent
+    ///
+    /// Unix 平台通过 SSH_AUTH_SOCK 连接，Windows 平台使用 OpenSSH 的命名管道
+    async fn connect_agent() -> Result<AgentClient<Box<dyn AgentStream + Send + Unpin>>> {
+        #[cfg(unix)]
+        let agent = AgentClient::connect_env().await.map(|agent| agent.dynamic());
+        #[cfg(windows)]
+        let agent = AgentClient::connect_named_pipe(r"\\.\pipe\openssh-ssh-agent")
+            .await
+            .map(|agent| agent.dynamic());
+
+        agent.map_err(|e| {
+            error!("Failed to connect to ssh-agent: {}", e);
+            SSHError::AuthenticationFailed(format!(
+                "无法连接 ssh-agent: {}（请确认 agent 已启动且 SSH_AUTH_SOCK 已设置）",
+                e
+            ))
+        })
+    }
+
+    /// 通过 ssh-agent 进行公钥认证
+    ///
+    /// 依次尝试 agent 中的密钥，identity 不为空时只尝试指纹或注释匹配的密钥。
+    /// 认证成功或部分成功（服务器要求继续其他认证方式）时返回对应结果
+    async fn authenticate_with_agent(
+        handle: &mut Handle<RusshHandler>,
+        username: &str,
+        identity: Option<&str>,
+    ) -> Result<AuthResult> {
+        let mut agent = Self::connect_agent().await?;
+
+        let keys = agent.request_identities().await.map_err(|e| {
+            error!("Failed to list ssh-agent identities: {}", e);
+            SSHError::AuthenticationFailed(format!("无法获取 ssh-agent 中的密钥: {}", e))
+        })?;
+
+        let candidates: Vec<_> = keys
+            .into_iter()
+            .filter(|key| {
+                identity.is_none_or(|identity| {
+                    key.fingerprint(HashAlg::Sha256).to_string() == identity
+                        || key.comment() == identity
+                })
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(SSHError::AuthenticationFailed(match identity {
+                Some(identity) => format!("ssh-agent 中没有匹配 '{}' 的密钥", identity),
+                None => "ssh-agent 中没有可用的密钥".to_string(),
+            }));
+        }
+
+        for key in candidates {
+            let fingerprint = key.fingerprint(HashAlg::Sha256);
+            let hash_alg = if key.algorithm().is_rsa() {
+                handle
+                    .best_supported_rsa_hash()
+                    .await
+                    .map_err(|e| {
+                        SSHError::AuthenticationFailed(format!(
+                            "无法获取服务器支持的 RSA 哈希算法: {}",
+                            e
+                        ))
+                    })?
+                    .flatten()
+            } else {
+                None
+            };
+
+            info!("Trying ssh-agent key {} ({})", fingerprint, key.comment());
+            let auth_result = handle
+                .authenticate_publickey_with(username, key, hash_alg, &mut agent)
+                .await
+                .map_err(|e| {
+                    error!("ssh-agent authentication error for user {}: {}", username, e);
+                    SSHError::AuthenticationFailed(format!("ssh-agent 认证错误: {}", e))
+                })?;
+
+            match auth_result {
+                AuthResult::Success => {
+                    info!("ssh-agent authentication successful with key {}", fingerprint);
+                    return Ok(auth_result);
+                }
+                AuthResult::Failure { partial_success: true, .. } => {
+                    info!("ssh-agent key {} accepted, server requires further authentication", fingerprint);
+                    return Ok(auth_result);
+                }
+                AuthResult::Failure { .. } => {
+                    debug!("Server rejected ssh-agent key {}", fingerprint);
+                }
+            }
+        }
+
+        error!("ssh-agent authentication failed for user: {}", username);
+        Err(SSHError::AuthenticationFailed(format!(
+            "ssh-agent 认证失败 (user: {})：服务器未接受 agent 中的任何密钥",
+            username
+        )))
+    }
+
+    /// 判断认证失败后是否可以继续进行键盘交互认证
+    ///
+    /// - 部分成功（如公钥通过后要求 OTP）：服务器允许时继续
+    /// - 密码认证被拒绝：服务器可能只开放了 keyboard-interactive（如 PAM），允许回退
+    fn can_continue_with_keyboard_interactive(auth_result: &AuthResult, allow_fallback: bool) -> bool {
+        match auth_result {
+            AuthResult::Success => false,
+            AuthResult::Failure {
+                remaining_methods,
+                partial_success,
+            } => {
+                (*partial_success || allow_fallback)
+                    && remaining_methods.contains(&MethodKind::KeyboardInteractive)
+            }
+        }
+    }
+
+    /// 键盘交互认证
+    ///
+    /// 服务器的每轮提示交给 responder 处理（通常转发给前端），
+    /// 支持服务器在部分成功后再次要求键盘交互认证（多因素链式认证）
+    async fn authenticate_keyboard_interactive(
+        handle: &mut Handle<RusshHandler>,
+        username: &str,
+        responder: Option<&Arc<dyn InteractiveResponder>>,
+    ) -> Result<()> {
+        let responder = responder.ok_or_else(|| {
+            SSHError::AuthenticationFailed(
+                "服务器要求键盘交互认证，但当前连接无法向用户请求输入".to_string(),
+            )
+        })?;
+
+        let map_err = |e: russh::Error| {
+            error!("Keyboard-interactive authentication error for user {}: {}", username, e);
+            SSHError::AuthenticationFailed(format!("键盘交互认证错误: {}", e))
+        };
+
+        info!("Authenticating with keyboard-interactive for user: {}", username);
+        let mut rounds = 1;
+        let mut response = handle
+            .authenticate_keyboard_interactive_start(username, None)
+            .await
+            .map_err(map_err)?;
+
+        loop {
+            match response {
+                KeyboardInteractiveAuthResponse::Success => {
+                    info!("Keyboard-interactive authentication successful for user: {}", username);
+                    return Ok(());
+                }
+                KeyboardInteractiveAuthResponse::Failure {
+                    remaining_methods,
+                    partial_success,
+                } => {
+                    if partial_success
+                        && remaining_methods.contains(&MethodKind::KeyboardInteractive)
+                        && rounds < MAX_KEYBOARD_INTERACTIVE_ROUNDS
+                    {
+                        debug!("Partial success, server requires another keyboard-interactive round");
+                        rounds += 1;
+                        response = handle
+                            .authenticate_keyboard_interactive_start(username, None)
+                            .await
+                            .map_err(map_err)?;
+                        continue;
+                    }
+
+                    error!("Keyboard-interactive authentication failed for user: {}", username);
+                    return Err(SSHError::AuthenticationFailed(format!(
+                        "键盘交互认证失败 (user: {})",
+                        username
+                    )));
+                }
+                KeyboardInteractiveAuthResponse::InfoRequest {
+                    name,
+                    instructions,
+                    prompts,
+                } => {
+                    // 没有提示项的 InfoRequest 直接回复空答案
+                    let answers = if prompts.is_empty() {
+                        Vec::new()
+                    } else {
+                        debug!("Server sent {} keyboard-interactive prompt(s)", prompts.len());
+                        let items = prompts
+                            .into_iter()
+                            .map(|p| InteractivePromptItem {
+                                prompt: p.prompt,
+                                echo: p.echo,
+                            })
+                            .collect();
+                        responder.respond(name, instructions, items).await?
+                    };
+
+                    response = handle
+                        .authenticate_keyboard_interactive_respond(answers)
+                        .await
+                        .map_err(map_err)?;
+                }
+            }
+        }
+    }
+
+    /// 与单个 SSH 服务器完成握手和认证
+    ///
+    /// tunnel 为空时直接通过 TCP 连接，否则在已打开的 direct-tcpip 通道上握手
+    async fn establish(
+        &self,
+        config: &SessionConfig,
+        tunnel: Option<ChannelStream<Msg>>,
+        remote_forwards: RemoteForwardRegistry,
+    ) -> Result<(Handle<RusshHandler>, Option<CertificateInfo>)> {
+        // 创建 russh 配置
+        let russh_config = Arc::new(Self::create_config(config));
+
+        // 创建 handler
+        let handler = RusshHandler::new(config, remote_forwards);
+        let host_key_error = handler.host_key_error();
+
+        // 建立连接：直连或在上一跳的 direct-tcpip 通道上握手
+        let connect_result = match tunnel {
+            Some(stream) => client::connect_stream(russh_config, stream, handler).await,
+            None => {
+                client::connect(russh_config, (config.host.as_str(), config.port), handler).await
+            }
+        };
+        let mut handle = connect_result.map_err(|e| handshake_error(&host_key_error, e))?;
+
+        // 根据认证方式进行认证
+        let mut user_certificate = None;
+        let auth_result = match &config.auth_method {
+            AuthMethod::Password { password } => {
+                info!("Authenticating with password for user: {}", config.username);
+                let auth_result = handle
+                    .authenticate_password(&config.username, password)
+                    .await
+                    .map_err(|e| {
+                        error!("Password authentication error for user {}: {}", config.username, e);
+                        SSHError::AuthenticationFailed(format!("密码认证错误: {}", e))
+                    })?;
+
+                if Self::can_continue_with_keyboard_interactive(&auth_result, true) {
+                    info!("Password rejected, falling back to keyboard-interactive authentication");
+                } else if !auth_result.success() {
+                    error!("Password authentication failed for user: {}", config.username);
+                    return Err(SSHError::AuthenticationFailed(
+                        format!("密码认证失败: 用户名或密码错误 (user: {})", config.username),
+                    ));
+                } else {
+                    info!("Password authentication successful for user: {}", config.username);
+                }
+                auth_result
+            }
+            AuthMethod::PublicKey {
+                private_key_path,
+                passphrase,
+                certificate_path,
+            } => {
+                info!("Authenticating with public key for user: {}, key path: {}", config.username, private_key_path);
+                let key_pair = load_secret_key(private_key_path, passphrase.as_deref()).map_err(
+                    |e| {
+                        error!("Failed to load private key from {}: {}", private_key_path, e);
+                        SSHError::AuthenticationFailed(format!("无法加载私钥文件 '{}': {}", private_key_path, e))
+                    },
+                )?;
+
+                // 私钥有对应的证书时出示证书（短期证书由 CA 签发，服务器无需配置 authorized_keys）
+                // 自动查找到的 `<key>-cert.pub` 不可用时（过期、与私钥不匹配等）回退到普通公钥认证
+                let explicit_cert = certificate::is_explicit_certificate_path(certificate_path.as_deref());
+                let cert = match certificate::user_certificate_path(private_key_path, certificate_path.as_deref()) {
+                    Some(path) => match certificate::load_user_certificate(&path, &key_pair, certificate::unix_now()) {
+                        Ok(cert) => Some(cert),
+                        Err(e) if !explicit_cert => {
+                            warn!("Ignoring certificate {}: {}", path.display(), e);
+                            None
+                        }
+                        Err(e) => return Err(e),
+                    },
+                    None => None,
+                };
+
+                let (auth_result, certificate) =
+                    Self::authenticate_with_key(&mut handle, &config.username, key_pair, cert, explicit_cert).await?;
+                user_certificate = certificate;
+                auth_result
+            }
+            AuthMethod::ManagedKey { key_id, passphrase } => {
+                info!("Authenticating with managed key {} for user: {}", key_id, config.username);
+                let key_store = self.key_store.as_ref().ok_or_else(|| {
+                    SSHError::AuthenticationFailed("密钥管理器不可用".to_string())
+                })?;
+                let key_pair = key_store.load_private_key(key_id, passphrase.as_deref())?;
+
+                Self::authenticate_with_key(&mut handle, &config.username, key_pair, None, false).await?.0
+            }
+            AuthMethod::Agent { identity } => {
+                info!("Authenticating with ssh-agent for user: {}", config.username);
+                Self::authenticate_with_agent(&mut handle, &config.username, identity.as_deref())
+                    .await?
+            }
+        };
+
+        // 部分成功或密码被拒绝时继续键盘交互认证（如 TOTP/OTP）
+        if !auth_result.success() {
+            Self::authenticate_keyboard_interactive(
+                &mut handle,
+                &config.username,
+                self.interactive_responder.as_ref(),
+            )
+            .await?;
+        }
+
+        Ok((handle, user_certificate))
+    }
+
+    /// 使用私钥认证，有证书时出示证书
+    ///
+    /// 服务器拒绝自动查找到的证书时回退到普通公钥认证，显式指定的证书被拒绝时直接失败。
+    /// 返回认证结果和被接受的证书信息；部分成功时由调用方继续键盘交互认证
+    async fn authenticate_with_key(
+        handle: &mut Handle<RusshHandler>,
+        username: &str,
+        key_pair: PrivateKey,
+        cert: Option<Certificate>,
+        explicit_cert: bool,
+    ) -> Result<(AuthResult, Option<CertificateInfo>)> {
+        let key_pair = Arc::new(key_pair);
+
+        if let Some(cert) = cert {
+            info!("Attempting certificate authentication, key id: {}", cert.key_id());
+            let cert_info = CertificateInfo::new(&cert, certificate::unix_now());
+            let auth_result = handle
+                .authenticate_openssh_cert(username, key_pair.clone(), cert)
+                .await
+                .map_err(|e| {
+                    error!("Certificate authentication error for user {}: {}", username, e);
+                    SSHError::AuthenticationFailed(format!("证书认证错误: {}", e))
+                })?;
+
+            if Self::can_continue_with_keyboard_interactive(&auth_result, false) {
+                info!("Certificate accepted, server requires further authentication");
+                return Ok((auth_result, Some(cert_info)));
+            } else if auth_result.success() {
+                info!("Certificate authentication successful for user: {}", username);
+                return Ok((auth_result, Some(cert_info)));
+            } else if explicit_cert {
+                error!("Certificate authentication failed for user: {}", username);
+                return Err(SSHError::AuthenticationFailed(
+                    format!("证书认证失败 (user: {})\n可能原因:\n1. 服务器未信任签发证书的 CA（TrustedUserCAKeys）\n2. 证书的 principals 不包含该用户名", username),
+                ));
+            }
+            warn!("Server rejected certificate for user {}, falling back to public key authentication", username);
+        }
+
+        // 统一使用 PrivateKeyWithHashAlg 包装
+        // 参考 russh-info.md 中的最佳实践
+        let key_with_hash = if key_pair.algorithm().is_rsa() {
+            // 获取服务器支持的 RSA 哈希算法
+            let best_hash = handle
+                .best_supported_rsa_hash()
+                .await
+                .map_err(|e| {
+                    error!("Failed to get supported RSA hash: {}", e);
+                    SSHError::AuthenticationFailed(format!(
+                        "无法获取服务器支持的 RSA 哈希算法: {}",
+                        e
+                    ))
+                })?
+                .flatten();
+
+            if let Some(hash) = best_hash {
+                info!("Using RSA key with {:?} hash", hash);
+            } else {
+                info!("Using RSA key");
+            }
+            PrivateKeyWithHashAlg::new(key_pair, best_hash)
+        } else {
+            info!("Using {:?} key", key_pair.algorithm());
+            PrivateKeyWithHashAlg::new(key_pair, None)
+        };
+
+        info!("Attempting public key authentication...");
+        let auth_result = handle
+            .authenticate_publickey(username, key_with_hash)
+            .await
+            .map_err(|e| {
+                error!("Public key authentication error for user {}: {}", username, e);
+                SSHError::AuthenticationFailed(format!(
+                    "公钥认证错误: {}",
+                    e
+                ))
+            })?;
+
+        if Self::can_continue_with_keyboard_interactive(&auth_result, false) {
+            info!("Public key accepted, server requires further authentication");
+        } else if !auth_result.success() {
+            error!("Public key authentication failed for user: {}", username);
+            error!("Possible reasons: 1) Public key not authorized on server, 2) Private key doesn't match public key, 3) Wrong user");
+            return Err(SSHError::AuthenticationFailed(
+                format!("公钥认证失败 (user: {})\n可能原因:\n1. 服务器上未授权此公钥（检查 ~/.ssh/authorized_keys）\n2. 私钥与公钥不匹配\n3. 用户名错误", username),
+            ));
+        } else {
+            info!("Public key authentication successful for user: {}", username);
+        }
+
+        Ok((auth_result, None))
+    }
+
+    /// 通过已连接的跳板机打开到下一跳的 direct-tcpip 通道
+    async fn open_tunnel(
+        jump_handle: &Handle<RusshHandler>,
+        jump: &SessionConfig,
+        target: &SessionConfig,
+    ) -> Result<ChannelStream<Msg>> {
+        debug!(
+            "Opening direct-tcpip channel via {}:{} to {}:{}",
+            jump.host, jump.port, target.host, target.port
+        );
+        let channel = jump_handle
+            .channel_open_direct_tcpip(target.host.as_str(), target.port as u32, "127.0.0.1", 0)
+            .await
+            .map_err(|e| {
+                SSHError::ConnectionFailed(format!(
+                    "无法通过跳板机 {}:{} 连接到 {}:{}: {}",
+                    jump.host, jump.port, target.host, target.port, e
+                ))
+            })?;
+
+        Ok(channel.into_stream())
+    }
+
     /// 启动 SSH 会话管理任务
     ///
     /// 从 SSH channel 读取数据并处理命令
     fn start_session_loop(
         mut read_half: ChannelReadHalf,
         write_half: ChannelWriteHalf<Msg>,
-        output_sender: mpsc::UnboundedSender<Vec<u8>>,
-        mut command_receiver: mpsc::UnboundedReceiver<ChannelCommand>,
+        output_sender: mpsc::Sender<Vec<u8>>,
+        mut command_receiver: mpsc::Receiver<ChannelCommand>,
+        lifecycle_events: Option<mpsc::UnboundedSender<LifecycleEvent>>,
+        session_end: Arc<StdMutex<Option<SessionEnd>>>,
     ) {
         tokio::spawn(async move {
             debug!("Starting SSH session loop");
-            loop {
+            let mut exit_code: Option<u32> = None;
+            let mut exit_message: Option<String> = None;
+            let end = loop {
                 tokio::select! {
                     // 处理来自 SSH 服务器的数据
                     msg = read_half.wait() => {
@@ -203,20 +896,33 @@ impl RusshBackend {
                                 // 直接发送 CryptoVec 的数据
                                 // data: &CryptoVec
                                 let data_vec = data.to_vec();
-                                println!("[russh] SSH→Channel: {} bytes", data_vec.len());
-                                println!("[russh] Raw bytes: {:?}", &data_vec[..data_vec.len().min(50)]);
-                                let text = String::from_utf8_lossy(&data_vec);
-                                println!("[russh] Text: {}", text);
+                                trace!(target: IO_TRACE_TARGET, "SSH -> channel: {} bytes", data_vec.len());
 
-                                // 发送数据到输出 channel
-                                if let Err(e) = output_sender.send(data_vec) {
+                                // 发送数据到输出 channel，channel 写满时在此等待（背压）
+                                if let Err(e) = output_sender.send(data_vec).await {
                                     error!("Failed to send data to output: {}", e);
-                                    break;
+                                    break SessionEnd {
+                                        reason: DisconnectReason::Error,
+                                        exit_code,
+                                        message: Some(e.to_string()),
+                                    };
                                 }
                             }
                             Some(ChannelMsg::ExitStatus { exit_status }) => {
                                 info!("Remote command exited with status: {}", exit_status);
-                                break;
+                                exit_code = Some(exit_status);
+                                if let Some(events) = &lifecycle_events {
+                                    let _ = events.send(LifecycleEvent::ExitStatus { exit_code: exit_status });
+                                }
+                                // 继续等待 Close，避免丢失退出前的输出
+                            }
+                            Some(ChannelMsg::ExitSignal { signal_name, error_message, .. }) => {
+                                info!("Remote command killed by signal: {:?}", signal_name);
+                                exit_message = Some(if error_message.is_empty() {
+                                    format!("signal {:?}", signal_name)
+                                } else {
+                                    error_message
+                                });
                             }
                             Some(ChannelMsg::Eof) => {
                                 debug!("Received EOF from server");
@@ -224,7 +930,12 @@ impl RusshBackend {
                             }
                             Some(ChannelMsg::Close) => {
                                 debug!("Channel closed by server");
-                                break;
+                                let reason = if exit_code.is_some() || exit_message.is_some() {
+                                    DisconnectReason::RemoteExit
+                                } else {
+                                    DisconnectReason::ClosedByServer
+                                };
+                                break SessionEnd { reason, exit_code, message: exit_message };
                             }
                             Some(ChannelMsg::WindowAdjusted { .. }) => {
                                 debug!("Window adjusted");
@@ -235,7 +946,13 @@ impl RusshBackend {
                             }
                             None => {
                                 debug!("Channel wait returned None, connection closed");
-                                break;
+                                // 收到退出码后连接关闭视为正常退出，否则为连接中断
+                                let reason = if exit_code.is_some() {
+                                    DisconnectReason::RemoteExit
+                                } else {
+                                    DisconnectReason::ConnectionLost
+                                };
+                                break SessionEnd { reason, exit_code, message: exit_message };
                             }
                         }
                     }
@@ -244,12 +961,16 @@ impl RusshBackend {
                         match cmd {
                             Some(ChannelCommand::Write(data)) => {
                                 // 写入数据到 SSH channel
-                                debug!("Writing {} bytes to SSH channel", data.len());
+                                trace!(target: IO_TRACE_TARGET, "channel -> SSH: {} bytes", data.len());
                                 // 使用 Cursor 将 Vec<u8> 转换为 AsyncRead
                                 let mut cursor = Cursor::new(data);
                                 if let Err(e) = write_half.data(&mut cursor).await {
                                     error!("Failed to write data to SSH channel: {}", e);
-                                    break;
+                                    break SessionEnd {
+                                        reason: DisconnectReason::ConnectionLost,
+                                        exit_code,
+                                        message: Some(e.to_string()),
+                                    };
                                 }
                             }
                             Some(ChannelCommand::Resize(rows, cols)) => {
@@ -262,20 +983,98 @@ impl RusshBackend {
                             }
                             Some(ChannelCommand::Disconnect) => {
                                 debug!("Disconnect command received");
-                                break;
+                                break SessionEnd::new(DisconnectReason::UserRequested);
                             }
                             None => {
                                 debug!("Command channel closed");
-                                break;
+                                break SessionEnd::new(DisconnectReason::UserRequested);
                             }
                         }
                     }
                 }
+            };
+            debug!("Session loop ended: {:?}", end);
+
+            // 连接可能仍被其他 channel 使用，主动关闭终端 channel
+            if end.reason == DisconnectReason::UserRequested {
+                let _ = write_half.close().await;
             }
-            debug!("Session loop ended");
+
+            // 先写入结束信息，再释放 output_sender 让读取器收到 EOF
+            *session_end.lock().unwrap() = Some(end);
+            drop(output_sender);
         });
     }
 
+    /// 依次连接跳板机和目标服务器，建立新的 SSH 连接
+    ///
+    /// 连接建立后启动会话配置中已启用的端口转发
+    async fn connect_transport(&self, config: &SessionConfig) -> Result<SshTransport> {
+        // 依次连接跳板机，最后一跳为目标服务器
+        // 远程端口转发只在目标服务器上注册，跳板机使用空注册表
+        let remote_forwards = RemoteForwardRegistry::default();
+        let mut jump_handles = Vec::with_capacity(self.jump_hosts.len());
+        let mut previous: Option<(Handle<RusshHandler>, &SessionConfig)> = None;
+        let mut user_certificate = None;
+        for (index, hop) in self.jump_hosts.iter().chain(std::iter::once(config)).enumerate() {
+            let tunnel = match previous.take() {
+                Some((jump_handle, jump)) => {
+                    let stream = Self::open_tunnel(&jump_handle, jump, hop).await?;
+                    jump_handles.push(jump_handle);
+                    Some(stream)
+                }
+                None => None,
+            };
+
+            if index < self.jump_hosts.len() {
+                info!("Connecting to jump host {}:{} as {}", hop.host, hop.port, hop.username);
+            }
+            let registry = if index < self.jump_hosts.len() {
+                RemoteForwardRegistry::default()
+            } else {
+                remote_forwards.clone()
+            };
+            let (handle, certificate) = self.establish(hop, tunnel, registry).await?;
+            previous = Some((handle, hop));
+            user_certificate = certificate;
+            self.emit_lifecycle(LifecycleEvent::Authenticated {
+                host: hop.host.clone(),
+                port: hop.port,
+            });
+        }
+        let (handle, _) = previous.expect("connection chain always contains the target");
+
+        let handle: SharedHandle = Arc::new(tokio::sync::RwLock::new(handle));
+        info!("SSH connection established successfully");
+
+        // 启动会话配置中的端口转发，单条规则失败不影响连接
+        let mut forwarder =
+            PortForwarder::new(handle.clone(), remote_forwards, self.port_forward_events.clone());
+        for rule in config.port_forwards.iter().cloned() {
+            let rule_id = rule.id.clone();
+            if let Err(e) = forwarder.add(rule).await {
+                warn!("Port forward {} not started: {}", rule_id, e);
+            }
+        }
+
+        Ok(SshTransport {
+            handle,
+            jump_handles,
+            port_forwarder: tokio::sync::Mutex::new(forwarder),
+            user_certificate,
+        })
+    }
+
+    /// 建立一次独立的连接完成认证后立即断开，用于确认认证方式可用
+    ///
+    /// 不打开 shell，也不启动端口转发
+    pub async fn check_authentication(&self, config: &SessionConfig) -> Result<()> {
+        let mut config = config.clone();
+        config.port_forwards.clear();
+        // 最后一个引用释放时连接随之关闭
+        self.connect_transport(&config).await.map(drop)
+    }
+
     /// 直接创建 SFTP 客户端
     ///
     /// 这是一个特定于 RusshBackend 的方法，用于直接创建 SFTP 客户端
@@ -285,13 +1084,13 @@ impl RusshBackend {
             return Err(SSHError::NotConnected);
         }
 
-        let handle = self.handle.as_ref()
+        let transport = self.transport.clone()
             .ok_or(SSHError::NotConnected)?;
 
         debug!("Creating SFTP client directly from russh handle");
 
         // 使用 SftpChannelStream 打开 channel
-        let stream = SftpChannelStream::open(handle).await?;
+        let stream = SftpChannelStream::open(&*transport.handle.read().await).await?;
 
         // 直接使用 SftpChannelStream 创建 SFTP session
         let session = russh_sftp::client::SftpSession::new(stream)
@@ -300,7 +1099,7 @@ impl RusshBackend {
 
         debug!("SFTP client created successfully");
 
-        Ok(crate::sftp::client::SftpClient::from_session(session))
+        Ok(crate::sftp::client::SftpClient::from_session(session).with_transport(transport))
     }
 }
 
@@ -323,122 +1122,29 @@ impl SSHBackend for RusshBackend {
         );
 
         // 创建输出 channel
-        let (output_sender, output_receiver) = mpsc::unbounded_channel();
+        let (output_sender, output_receiver) = mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
         self.receiver = Some(output_receiver);
 
         // 创建命令 channel
-        let (command_sender, command_receiver) = mpsc::unbounded_channel();
+        let (command_sender, command_receiver) = mpsc::channel(COMMAND_CHANNEL_CAPACITY);
         self.command_sender = Some(command_sender);
 
-        // 创建 russh 配置
-        let russh_config = std::sync::Arc::new(Self::create_config(config));
-
-        // 创建 handler
-        let handler = RusshHandler;
-
-        // 建立连接
-        let mut handle = client::connect(
-            russh_config,
-            (config.host.as_str(), config.port),
-            handler,
-        )
-        .await
-        .map_err(|e| SSHError::ConnectionFailed(format!("Failed to connect: {}", e)))?;
-
-        // 根据认证方式进行认证
-        match &config.auth_method {
-            AuthMethod::Password { password } => {
-                info!("Authenticating with password for user: {}", config.username);
-                let auth_result = handle
-                    .authenticate_password(&config.username, password)
-                    .await
-                    .map_err(|e| {
-                        error!("Password authentication error for user {}: {}", config.username, e);
-                        SSHError::AuthenticationFailed(format!("密码认证错误: {}", e))
-                    })?;
-
-                if !auth_result.success() {
-                    error!("Password authentication failed for user: {}", config.username);
-                    return Err(SSHError::AuthenticationFailed(
-                        format!("密码认证失败: 用户名或密码错误 (user: {})", config.username),
-                    ));
-                }
-                info!("Password authentication successful for user: {}", config.username);
+        // 复用已有连接，或建立新连接
+        let transport = match self.transport.clone() {
+            Some(transport) => {
+                info!("Reusing existing SSH connection to {}:{}", config.host, config.port);
+                transport
             }
-            AuthMethod::PublicKey {
-                private_key_path,
-                passphrase,
-            } => {
-                info!("Authenticating with public key for user: {}, key path: {}", config.username, private_key_path);
-                let key_pair = load_secret_key(private_key_path, passphrase.as_deref()).map_err(
-                    |e| {
-                        error!("Failed to load private key from {}: {}", private_key_path, e);
-                        SSHError::AuthenticationFailed(format!("无法加载私钥文件 '{}': {}", private_key_path, e))
-                    },
-                )?;
-
-                // 统一使用 PrivateKeyWithHashAlg 包装
-                // 参考 russh-info.md 中的最佳实践
-                let key_with_hash = if key_pair.algorithm().is_rsa() {
-                    // 获取服务器支持的 RSA 哈希算法
-                    let best_hash = handle
-                        .best_supported_rsa_hash()
-                        .await
-                        .map_err(|e| {
-                            error!("Failed to get supported RSA hash: {}", e);
-                            SSHError::AuthenticationFailed(format!(
-                                "无法获取服务器支持的 RSA 哈希算法: {}",
-                                e
-                            ))
-                        })?
-                        .flatten();
-
-                    if let Some(hash) = best_hash {
-                        info!("Using RSA key with {:?} hash", hash);
-                    } else {
-                        info!("Using RSA key");
-                    }
-                    PrivateKeyWithHashAlg::new(
-                        std::sync::Arc::new(key_pair),
-                        best_hash,
-                    )
-                } else {
-                    info!("Using {:?} key", key_pair.algorithm());
-                    PrivateKeyWithHashAlg::new(
-                        std::sync::Arc::new(key_pair),
-                        None,
-                    )
-                };
-
-                info!("Attempting public key authentication...");
-                let auth_result = handle
-                    .authenticate_publickey(&config.username, key_with_hash)
-                    .await
-                    .map_err(|e| {
-                        error!("Public key authentication error for user {}: {}", config.username, e);
-                        SSHError::AuthenticationFailed(format!(
-                            "公钥认证错误: {}",
-                            e
-                        ))
-                    })?;
-
-                if !auth_result.success() {
-                    error!("Public key authentication failed for user: {}", config.username);
-                    error!("Possible reasons: 1) Public key not authorized on server, 2) Private key doesn't match public key, 3) Wrong user");
-                    return Err(SSHError::AuthenticationFailed(
-                        format!("公钥认证失败 (user: {})\n可能原因:\n1. 服务器上未授权此公钥（检查 ~/.ssh/authorized_keys）\n2. 私钥与公钥不匹配\n3. 用户名错误", config.username),
-                    ));
-                }
-                info!("Public key authentication successful for user: {}", config.username);
+            None => {
+                let transport = Arc::new(self.connect_transport(config).await?);
+                self.transport = Some(transport.clone());
+                transport
             }
-        }
+        };
 
         // 打开 session channel
         debug!("Opening session channel");
-        let channel = handle
-            .channel_open_session()
-            .await
-            .map_err(|e| SSHError::ConnectionFailed(format!("Failed to open channel: {}", e)))?;
+        let channel = transport.open_session_channel().await?;
 
         // 请求 PTY
         let rows = config.rows.unwrap_or(24);
@@ -465,22 +1171,40 @@ impl SSHBackend for RusshBackend {
             .await
             .map_err(|e| SSHError::ConnectionFailed(format!("Failed to request PTY: {}", e)))?;
 
-        // 启动 shell
-        debug!("Requesting shell");
-        channel
-            .request_shell(true)
-            .await
-            .map_err(|e| SSHError::ConnectionFailed(format!("Failed to request shell: {}", e)))?;
+        // 启动 shell，启用会话保持时改为附加到 tmux/screen 会话
+        if config.persistent_shell.enabled {
+            let name = config.persistent_shell.session_name("ssh-terminal");
+            debug!("Attaching to persistent shell: {}", name);
+            channel
+                .exec(true, config.persistent_shell.command(&name).as_bytes())
+                .await
+                .map_err(|e| SSHError::ConnectionFailed(format!("Failed to start persistent shell: {}", e)))?;
+        } else {
+            debug!("Requesting shell");
+            channel
+                .request_shell(true)
+                .await
+                .map_err(|e| SSHError::ConnectionFailed(format!("Failed to request shell: {}", e)))?;
+        }
+
+        self.emit_lifecycle(LifecycleEvent::ShellReady);
 
         // 分割 channel 为读写两半
         let (read_half, write_half) = channel.split();
 
         // 启动会话管理循环
-        Self::start_session_loop(read_half, write_half, output_sender, command_receiver);
+        *self.session_end.lock().unwrap() = None;
+        Self::start_session_loop(
+            read_half,
+            write_half,
+            output_sender,
+            command_receiver,
+            self.lifecycle_events.clone(),
+            self.session_end.clone(),
+        );
 
-        self.handle = Some(handle);
         self.connected = true;
-        info!("SSH connection established successfully");
+        info!("SSH session channel opened successfully");
         Ok(())
     }
 
@@ -492,6 +1216,7 @@ impl SSHBackend for RusshBackend {
         if let Some(ref sender) = self.command_sender {
             sender
                 .send(ChannelCommand::Write(data.to_vec()))
+                .await
                 .map_err(|e| SSHError::IoError(io::Error::new(io::ErrorKind::Other, e)))?;
             Ok(())
         } else {
@@ -507,6 +1232,7 @@ impl SSHBackend for RusshBackend {
         if let Some(ref sender) = self.command_sender {
             sender
                 .send(ChannelCommand::Resize(rows, cols))
+                .await
                 .map_err(|e| SSHError::IoError(io::Error::new(io::ErrorKind::Other, e)))?;
             Ok(())
         } else {
@@ -516,35 +1242,143 @@ impl SSHBackend for RusshBackend {
 
     async fn disconnect(&mut self) -> Result<()> {
         if let Some(ref sender) = self.command_sender {
-            let _ = sender.send(ChannelCommand::Disconnect);
+            // 会话循环可能正阻塞在输出背压上，这里不等待
+            let _ = sender.try_send(ChannelCommand::Disconnect);
         }
 
-        if let Some(handle) = self.handle.take() {
-            info!("Disconnecting SSH session");
-            handle
-                .disconnect(Disconnect::ByApplication, "", "English")
-                .await
-                .map_err(|e| {
-                    error!("Failed to disconnect: {}", e);
-                    SSHError::IoError(io::Error::new(io::ErrorKind::Other, e.to_string()))
-                })?;
-        }
+        // 释放连接引用，没有其他 channel 使用时连接随之关闭
+        self.transport = None;
 
         self.connected = false;
         info!("SSH session disconnected");
         Ok(())
     }
 
+    async fn add_port_forward(&mut self, rule: PortForwardRule) -> Result<()> {
+        let transport = self.transport.as_ref().ok_or(SSHError::NotConnected)?;
+        transport.port_forwarder().await.add(rule).await
+    }
+
+    async fn remove_port_forward(&mut self, rule_id: &str) -> Result<()> {
+        let transport = self.transport.as_ref().ok_or(SSHError::NotConnected)?;
+        transport.port_forwarder().await.remove(rule_id).await
+    }
+
+    async fn set_port_forward_enabled(&mut self, rule_id: &str, enabled: bool) -> Result<()> {
+        let transport = self.transport.as_ref().ok_or(SSHError::NotConnected)?;
+        transport.port_forwarder().await.set_enabled(rule_id, enabled).await
+    }
+
+    fn session_end(&self) -> Option<SessionEnd> {
+        self.session_end.lock().unwrap().clone()
+    }
+
+    async fn port_forwards(&self) -> Vec<PortForwardStatus> {
+        match self.transport.as_ref() {
+            Some(transport) => transport.port_forwarder().await.list(),
+            None => Vec::new(),
+        }
+    }
+
     fn reader(&mut self) -> Result<Box<dyn BackendReader + Send>> {
         if let Some(receiver) = self.receiver.take() {
-            let reader = RusshReader {
-                receiver,
-                buffer: None,
-                position: 0,
-            };
-            Ok(Box::new(reader))
+            Ok(Box::new(ChannelReader::new(receiver)))
         } else {
             Err(SSHError::NotConnected)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::known_hosts::{self, KnownHosts};
+    use rand::rngs::OsRng;
+    use russh::keys::Algorithm;
+    use russh::server;
+    use std::path::{Path, PathBuf};
+    use tokio::net::TcpListener;
+
+    /// 只完成密钥交换的测试服务器
+    struct TestServer;
+
+    impl server::Handler for TestServer {
+        type Error = russh::Error;
+    }
+
+    /// 在本地端口启动测试服务器，每个新连接使用当前的主机密钥
+    async fn start_server(host_key: Arc<StdMutex<PrivateKey>>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let config = Arc::new(server::Config {
+                    keys: vec![host_key.lock().unwrap().clone()],
+                    ..Default::default()
+                });
+                tokio::spawn(async move {
+                    if let Ok(session) = server::run_stream(config, socket, TestServer).await {
+                        let _ = session.await;
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    /// 以严格模式连接，只使用测试目录中的 known_hosts
+    async fn connect(port: u16, store_path: &Path) -> Result<Handle<RusshHandler>> {
+        let verifier = HostKeyVerifier::new("127.0.0.1", port, true)
+            .with_files(vec![store_path.to_path_buf()], store_path.to_path_buf());
+        let handler = RusshHandler::with_verifier(verifier, RemoteForwardRegistry::default());
+        let host_key_error = handler.host_key_error();
+        client::connect(Arc::new(Config::default()), ("127.0.0.1", port), handler)
+            .await
+            .map_err(|e| handshake_error(&host_key_error, e))
+    }
+
+    #[tokio::test]
+    async fn test_rotated_host_key_is_reported_as_mismatch() {
+        let dir = std::env::temp_dir().join(format!("host-key-rotate-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store_path: PathBuf = dir.join("known_hosts");
+
+        let old_key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519).unwrap();
+        let host_key = Arc::new(StdMutex::new(old_key.clone()));
+        let port = start_server(host_key.clone()).await;
+
+        // 首次连接：未知主机，返回指纹等待用户确认
+        let error = connect(port, &store_path).await.err().unwrap();
+        let (fingerprint, public_key) = match &error {
+            SSHError::HostKeyUnknown { fingerprint, public_key, .. } => (fingerprint.clone(), public_key.clone()),
+            other => panic!("unexpected error: {}", other),
+        };
+        assert_eq!(fingerprint, known_hosts::fingerprint(old_key.public_key()));
+        let payload = serde_json::to_value(&error).unwrap();
+        assert_eq!(payload["kind"], "hostKeyUnknown");
+        assert_eq!(payload["actualFingerprint"], fingerprint.as_str());
+
+        // 用户确认指纹后可以连接
+        KnownHosts::load(std::slice::from_ref(&store_path), store_path.clone())
+            .trust("127.0.0.1", port, &public_key, &fingerprint)
+            .unwrap();
+        connect(port, &store_path).await.unwrap();
+
+        // 服务器更换主机密钥后拒绝连接，并带上新旧指纹
+        let new_key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519).unwrap();
+        *host_key.lock().unwrap() = new_key.clone();
+        let error = connect(port, &store_path).await.err().unwrap();
+        assert!(matches!(error, SSHError::HostKeyMismatch { .. }), "unexpected error: {}", error);
+        let payload = serde_json::to_value(&error).unwrap();
+        assert_eq!(payload["kind"], "hostKeyMismatch");
+        assert_eq!(payload["host"], "127.0.0.1");
+        assert_eq!(payload["port"], port);
+        assert_eq!(payload["expectedFingerprint"], fingerprint.as_str());
+        assert_eq!(
+            payload["actualFingerprint"],
+            known_hosts::fingerprint(new_key.public_key()).as_str()
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,256 @@
+// 串口后端 - 基于 tokio-serial（仅桌面平台）
+
+use crate::error::{Result, SSHError};
+use crate::ssh::backend::{BackendReader, SSHBackend};
+use crate::ssh::output::{ChannelReader, IO_TRACE_TARGET, OUTPUT_CHANNEL_CAPACITY};
+use crate::ssh::session::{
+    ConnectionType, DisconnectReason, SerialConfig, SerialFlowControl, SerialParity,
+    SerialPortEntry, SerialStopBits, SessionConfig, SessionEnd,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::sync::mpsc;
+use tokio_serial::{DataBits, FlowControl, Parity, SerialPortType, SerialStream, StopBits};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, trace, warn};
+
+/// 列出系统中的串口设备
+pub fn available_ports() -> Result<Vec<SerialPortEntry>> {
+    let ports = tokio_serial::available_ports()
+        .map_err(|e| SSHError::Io(format!("无法枚举串口: {}", e)))?;
+
+    Ok(ports
+        .into_iter()
+        .map(|port| {
+            let description = match port.port_type {
+                SerialPortType::UsbPort(usb) => {
+                    let parts: Vec<String> = [usb.manufacturer, usb.product]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    (!parts.is_empty()).then(|| parts.join(" "))
+                }
+                _ => None,
+            };
+            SerialPortEntry {
+                path: port.port_name,
+                description,
+            }
+        })
+        .collect())
+}
+
+/// 串口后端
+///
+/// 串口没有窗口大小的概念，resize 直接忽略
+pub struct SerialBackend {
+    writer: Option<WriteHalf<SerialStream>>,
+    receiver: Option<mpsc::Receiver<Vec<u8>>>,
+    /// 停止读取任务，读取任务持有读半部分，停止后串口才会关闭
+    cancel: Option<CancellationToken>,
+    session_end: Arc<StdMutex<Option<SessionEnd>>>,
+}
+
+impl SerialBackend {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            receiver: None,
+            cancel: None,
+            session_end: Arc::new(StdMutex::new(None)),
+        }
+    }
+
+    fn open(config: &SerialConfig) -> Result<SerialStream> {
+        let data_bits = match config.data_bits {
+            5 => DataBits::Five,
+            6 => DataBits::Six,
+            7 => DataBits::Seven,
+            8 => DataBits::Eight,
+            other => {
+                return Err(SSHError::ConnectionFailed(format!("无效的数据位: {}", other)));
+            }
+        };
+        let parity = match config.parity {
+            SerialParity::None => Parity::None,
+            SerialParity::Odd => Parity::Odd,
+            SerialParity::Even => Parity::Even,
+        };
+        let stop_bits = match config.stop_bits {
+            SerialStopBits::One => StopBits::One,
+            SerialStopBits::Two => StopBits::Two,
+        };
+        let flow_control = match config.flow_control {
+            SerialFlowControl::None => FlowControl::None,
+            SerialFlowControl::Software => FlowControl::Software,
+            SerialFlowControl::Hardware => FlowControl::Hardware,
+        };
+
+        let builder = tokio_serial::new(&config.path, config.baud_rate)
+            .data_bits(data_bits)
+            .parity(parity)
+            .stop_bits(stop_bits)
+            .flow_control(flow_control);
+
+        SerialStream::open(&builder).map_err(|e| {
+            SSHError::ConnectionFailed(format!("Failed to open serial port {}: {}", config.path, e))
+        })
+    }
+
+    /// 启动读取任务，设备断开（如 USB 拔出）时记录为连接中断以便重连
+    fn spawn_reader(
+        mut reader: ReadHalf<SerialStream>,
+        sender: mpsc::Sender<Vec<u8>>,
+        cancel: CancellationToken,
+        session_end: Arc<StdMutex<Option<SessionEnd>>>,
+    ) {
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 4096];
+            let end = loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break SessionEnd::new(DisconnectReason::UserRequested),
+                    result = reader.read(&mut buffer) => match result {
+                        Ok(0) => break SessionEnd::new(DisconnectReason::ConnectionLost),
+                        Ok(n) => {
+                            trace!(target: IO_TRACE_TARGET, "serial -> channel: {} bytes", n);
+                            if sender.send(buffer[..n].to_vec()).await.is_err() {
+                                debug!("Serial output receiver dropped");
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Serial read failed: {}", e);
+                            break SessionEnd {
+                                reason: DisconnectReason::ConnectionLost,
+                                exit_code: None,
+                                message: Some(e.to_string()),
+                            };
+                        }
+                    },
+                }
+            };
+            debug!("Serial session ended: {:?}", end);
+
+            // 先写入结束信息，再释放 sender 让读取器收到 EOF
+            session_end.lock().unwrap().get_or_insert(end);
+            drop(sender);
+        });
+    }
+}
+
+impl Default for SerialBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SSHBackend for SerialBackend {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn connect(&mut self, config: &SessionConfig) -> Result<()> {
+        let ConnectionType::Serial(serial) = &config.connection_type else {
+            return Err(SSHError::NotSupported("串口后端只支持串口会话".to_string()));
+        };
+
+        info!("Opening serial port {} at {} baud", serial.path, serial.baud_rate);
+        let stream = Self::open(serial)?;
+        let (reader, writer) = tokio::io::split(stream);
+
+        let (sender, receiver) = mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
+        let cancel = CancellationToken::new();
+        *self.session_end.lock().unwrap() = None;
+        Self::spawn_reader(reader, sender, cancel.clone(), self.session_end.clone());
+
+        self.writer = Some(writer);
+        self.receiver = Some(receiver);
+        self.cancel = Some(cancel);
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        let writer = self.writer.as_mut().ok_or(SSHError::NotConnected)?;
+        trace!(target: IO_TRACE_TARGET, "channel -> serial: {} bytes", data.len());
+        writer.write_all(data).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    async fn resize(&mut self, _rows: u16, _cols: u16) -> Result<()> {
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.cancel();
+        }
+        self.writer = None;
+        info!("Serial port closed");
+        Ok(())
+    }
+
+    fn session_end(&self) -> Option<SessionEnd> {
+        self.session_end.lock().unwrap().clone()
+    }
+
+    fn reader(&mut self) -> Result<Box<dyn BackendReader + Send>> {
+        self.receiver
+            .take()
+            .map(|receiver| Box::new(ChannelReader::new(receiver)) as Box<dyn BackendReader + Send>)
+            .ok_or(SSHError::NotConnected)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use portable_pty::{native_pty_system, PtySize};
+    use std::io::{Read, Write};
+
+    /// 使用伪终端对模拟串口设备：backend 打开 slave 端，测试读写 master 端
+    #[tokio::test]
+    async fn test_serial_backend_with_pty_pair() {
+        let pair = native_pty_system().openpty(PtySize::default()).unwrap();
+        let path = pair.master.tty_name().unwrap().to_string_lossy().into_owned();
+
+        let config = SessionConfig::serial("console".to_string(), SerialConfig {
+            path,
+            baud_rate: 9600,
+            data_bits: 8,
+            parity: SerialParity::None,
+            stop_bits: SerialStopBits::One,
+            flow_control: SerialFlowControl::None,
+        });
+
+        let mut backend = SerialBackend::new();
+        backend.connect(&config).await.unwrap();
+        let mut reader = backend.reader().unwrap();
+
+        // 设备 -> 终端
+        let mut master_writer = pair.master.take_writer().unwrap();
+        master_writer.write_all(b"login: ").unwrap();
+        let mut buffer = [0u8; 64];
+        let n = reader.read(&mut buffer).await.unwrap();
+        assert!(String::from_utf8_lossy(&buffer[..n]).contains("login"));
+
+        // 终端 -> 设备
+        backend.write(b"admin").await.unwrap();
+        let mut master_reader = pair.master.try_clone_reader().unwrap();
+        let received = tokio::task::spawn_blocking(move || {
+            let mut buffer = [0u8; 64];
+            let n = master_reader.read(&mut buffer).unwrap();
+            buffer[..n].to_vec()
+        })
+        .await
+        .unwrap();
+        assert!(String::from_utf8_lossy(&received).contains("admin"));
+
+        backend.disconnect().await.unwrap();
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(backend.session_end().unwrap().reason, DisconnectReason::UserRequested);
+    }
+}
@@ -0,0 +1,245 @@
+// TCP 后端 - Telnet 和原始套接字连接
+
+use crate::error::{Result, SSHError};
+use crate::ssh::backend::{BackendReader, SSHBackend};
+use crate::ssh::backends::telnet::TelnetSession;
+use crate::ssh::output::{ChannelReader, IO_TRACE_TARGET, OUTPUT_CHANNEL_CAPACITY};
+use crate::ssh::session::{ConnectionType, DisconnectReason, SessionConfig, SessionEnd};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tracing::{debug, info, trace, warn};
+
+/// 建立 TCP 连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum TcpCommand {
+    Write(Vec<u8>),
+    Resize(u16, u16),
+    Disconnect,
+}
+
+/// Telnet / 原始 TCP 后端
+///
+/// 连接由后台任务持有，写入和调整窗口大小通过命令 channel 发送给它，
+/// Telnet 协商的回复也由该任务直接写回服务器
+pub struct TcpBackend {
+    commands: Option<mpsc::Sender<TcpCommand>>,
+    receiver: Option<mpsc::Receiver<Vec<u8>>>,
+    session_end: Arc<StdMutex<Option<SessionEnd>>>,
+}
+
+impl TcpBackend {
+    pub fn new() -> Self {
+        Self {
+            commands: None,
+            receiver: None,
+            session_end: Arc::new(StdMutex::new(None)),
+        }
+    }
+
+    /// 会话循环：转发服务器输出、处理用户命令，原始 TCP 模式下 telnet 为空
+    async fn run(
+        mut stream: TcpStream,
+        mut telnet: Option<TelnetSession>,
+        mut commands: mpsc::Receiver<TcpCommand>,
+        sender: mpsc::Sender<Vec<u8>>,
+        session_end: Arc<StdMutex<Option<SessionEnd>>>,
+    ) {
+        let mut buffer = vec![0u8; 8192];
+
+        let result: std::io::Result<SessionEnd> = async {
+            if let Some(telnet) = telnet.as_mut() {
+                stream.write_all(&telnet.start()).await?;
+            }
+
+            loop {
+                tokio::select! {
+                    read = stream.read(&mut buffer) => {
+                        let n = read?;
+                        if n == 0 {
+                            return Ok(SessionEnd::new(DisconnectReason::ClosedByServer));
+                        }
+                        trace!(target: IO_TRACE_TARGET, "socket -> channel: {} bytes", n);
+
+                        let data = match telnet.as_mut() {
+                            Some(telnet) => {
+                                let output = telnet.receive(&buffer[..n]);
+                                if !output.reply.is_empty() {
+                                    stream.write_all(&output.reply).await?;
+                                }
+                                output.data
+                            }
+                            None => buffer[..n].to_vec(),
+                        };
+                        if !data.is_empty() && sender.send(data).await.is_err() {
+                            debug!("TCP output receiver dropped");
+                            return Ok(SessionEnd::new(DisconnectReason::UserRequested));
+                        }
+                    }
+                    command = commands.recv() => match command {
+                        Some(TcpCommand::Write(data)) => {
+                            trace!(target: IO_TRACE_TARGET, "channel -> socket: {} bytes", data.len());
+                            let data = match telnet.as_ref() {
+                                Some(telnet) => telnet.encode_input(&data),
+                                None => data,
+                            };
+                            stream.write_all(&data).await?;
+                        }
+                        Some(TcpCommand::Resize(rows, cols)) => {
+                            if let Some(bytes) = telnet.as_mut().and_then(|telnet| telnet.resize(cols, rows)) {
+                                stream.write_all(&bytes).await?;
+                            }
+                        }
+                        Some(TcpCommand::Disconnect) | None => {
+                            let _ = stream.shutdown().await;
+                            return Ok(SessionEnd::new(DisconnectReason::UserRequested));
+                        }
+                    },
+                }
+            }
+        }
+        .await;
+
+        let end = result.unwrap_or_else(|e| {
+            warn!("TCP connection failed: {}", e);
+            SessionEnd {
+                reason: DisconnectReason::ConnectionLost,
+                exit_code: None,
+                message: Some(e.to_string()),
+            }
+        });
+        debug!("TCP session ended: {:?}", end);
+
+        // 先写入结束信息，再释放 sender 让读取器收到 EOF
+        session_end.lock().unwrap().get_or_insert(end);
+        drop(sender);
+    }
+}
+
+impl Default for TcpBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SSHBackend for TcpBackend {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn connect(&mut self, config: &SessionConfig) -> Result<()> {
+        let telnet = match &config.connection_type {
+            ConnectionType::Telnet => Some(TelnetSession::new(
+                config.terminal_type.clone().unwrap_or_else(|| "xterm-256color".to_string()),
+                config.columns.unwrap_or(80),
+                config.rows.unwrap_or(24),
+            )),
+            ConnectionType::RawTcp => None,
+            _ => return Err(SSHError::NotSupported("TCP 后端只支持 Telnet 和原始 TCP 会话".to_string())),
+        };
+
+        info!("Connecting to {}:{} ({})", config.host, config.port, if telnet.is_some() { "telnet" } else { "raw" });
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((config.host.as_str(), config.port)))
+            .await
+            .map_err(|_| SSHError::ConnectionFailed(format!("Connection to {}:{} timed out", config.host, config.port)))?
+            .map_err(|e| SSHError::ConnectionFailed(format!("Failed to connect: {}", e)))?;
+        if let Err(e) = stream.set_nodelay(true) {
+            debug!("Failed to set TCP_NODELAY: {}", e);
+        }
+
+        let (sender, receiver) = mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
+        let (command_sender, command_receiver) = mpsc::channel(64);
+        *self.session_end.lock().unwrap() = None;
+        tokio::spawn(Self::run(stream, telnet, command_receiver, sender, self.session_end.clone()));
+
+        self.commands = Some(command_sender);
+        self.receiver = Some(receiver);
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        let commands = self.commands.as_ref().ok_or(SSHError::NotConnected)?;
+        commands
+            .send(TcpCommand::Write(data.to_vec()))
+            .await
+            .map_err(|_| SSHError::NotConnected)
+    }
+
+    async fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
+        let commands = self.commands.as_ref().ok_or(SSHError::NotConnected)?;
+        commands
+            .send(TcpCommand::Resize(rows, cols))
+            .await
+            .map_err(|_| SSHError::NotConnected)
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if let Some(commands) = self.commands.take() {
+            // 会话已经结束时发送失败，忽略即可
+            let _ = commands.try_send(TcpCommand::Disconnect);
+        }
+        info!("TCP connection closed");
+        Ok(())
+    }
+
+    fn session_end(&self) -> Option<SessionEnd> {
+        self.session_end.lock().unwrap().clone()
+    }
+
+    fn reader(&mut self) -> Result<Box<dyn BackendReader + Send>> {
+        self.receiver
+            .take()
+            .map(|receiver| Box::new(ChannelReader::new(receiver)) as Box<dyn BackendReader + Send>)
+            .ok_or(SSHError::NotConnected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_raw_tcp_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"hello\r\n").await.unwrap();
+            let mut buffer = [0u8; 16];
+            let n = socket.read(&mut buffer).await.unwrap();
+            buffer[..n].to_vec()
+        });
+
+        let config: SessionConfig = serde_json::from_value(serde_json::json!({
+            "name": "raw",
+            "host": "127.0.0.1",
+            "port": port,
+            "username": "",
+            "authMethod": { "Password": { "password": "" } },
+            "connectionType": { "type": "rawTcp" },
+        }))
+        .unwrap();
+
+        let mut backend = TcpBackend::new();
+        backend.connect(&config).await.unwrap();
+        let mut reader = backend.reader().unwrap();
+
+        let mut buffer = [0u8; 16];
+        let n = reader.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"hello\r\n");
+
+        // 原始模式下 \r 不做转换
+        backend.write(b"quit\r").await.unwrap();
+        assert_eq!(server.await.unwrap(), b"quit\r");
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(backend.session_end().unwrap().reason, DisconnectReason::ClosedByServer);
+    }
+}
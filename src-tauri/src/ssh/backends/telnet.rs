@@ -0,0 +1,304 @@
+// Telnet 协议处理（RFC 854 / 1091 / 1073）
+//
+// 只负责字节流的解析与选项协商，不涉及网络 IO，由 TcpBackend 驱动。
+// 支持的选项：本端 NAWS、TTYPE、SGA，对端 ECHO、SGA，其余一律拒绝。
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+const OPT_ECHO: u8 = 1;
+const OPT_SGA: u8 = 3;
+const OPT_TTYPE: u8 = 24;
+const OPT_NAWS: u8 = 31;
+
+const TTYPE_IS: u8 = 0;
+const TTYPE_SEND: u8 = 1;
+
+/// 选项状态（RFC 1143 的简化版本，避免协商死循环）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum OptionState {
+    #[default]
+    No,
+    /// 已发出请求，等待对方确认
+    WantYes,
+    Yes,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParseState {
+    Data,
+    /// 上一个字节是 CR，后面的 NUL 需要丢弃
+    Cr,
+    Iac,
+    Negotiate(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// 一次解析的结果
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TelnetOutput {
+    /// 交给终端显示的数据
+    pub data: Vec<u8>,
+    /// 需要回复给服务器的协商字节
+    pub reply: Vec<u8>,
+}
+
+/// Telnet 会话状态
+pub struct TelnetSession {
+    terminal_type: String,
+    cols: u16,
+    rows: u16,
+    state: ParseState,
+    subnegotiation: Vec<u8>,
+    /// 本端选项（我们 WILL/WONT）
+    local: [OptionState; 256],
+    /// 对端选项（服务器 WILL/WONT）
+    remote: [OptionState; 256],
+}
+
+impl TelnetSession {
+    pub fn new(terminal_type: String, cols: u16, rows: u16) -> Self {
+        Self {
+            terminal_type,
+            cols,
+            rows,
+            state: ParseState::Data,
+            subnegotiation: Vec::new(),
+            local: [OptionState::No; 256],
+            remote: [OptionState::No; 256],
+        }
+    }
+
+    /// 连接建立后主动发出的协商请求
+    pub fn start(&mut self) -> Vec<u8> {
+        let mut reply = Vec::new();
+        for option in [OPT_NAWS, OPT_TTYPE] {
+            self.local[option as usize] = OptionState::WantYes;
+            reply.extend_from_slice(&[IAC, WILL, option]);
+        }
+        self.remote[OPT_SGA as usize] = OptionState::WantYes;
+        reply.extend_from_slice(&[IAC, DO, OPT_SGA]);
+        reply
+    }
+
+    /// 解析服务器发来的数据，IAC 序列可以跨多次调用
+    pub fn receive(&mut self, input: &[u8]) -> TelnetOutput {
+        let mut output = TelnetOutput::default();
+
+        for &byte in input {
+            self.state = match self.state {
+                ParseState::Data | ParseState::Cr => {
+                    let after_cr = self.state == ParseState::Cr;
+                    match byte {
+                        IAC => ParseState::Iac,
+                        // CR NUL 表示单独的回车
+                        0 if after_cr => ParseState::Data,
+                        b'\r' => {
+                            output.data.push(byte);
+                            ParseState::Cr
+                        }
+                        _ => {
+                            output.data.push(byte);
+                            ParseState::Data
+                        }
+                    }
+                }
+                ParseState::Iac => match byte {
+                    IAC => {
+                        output.data.push(IAC);
+                        ParseState::Data
+                    }
+                    DO | DONT | WILL | WONT => ParseState::Negotiate(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+                        ParseState::Subnegotiation
+                    }
+                    // NOP、GA 等命令直接忽略
+                    _ => ParseState::Data,
+                },
+                ParseState::Negotiate(command) => {
+                    self.negotiate(command, byte, &mut output.reply);
+                    ParseState::Data
+                }
+                ParseState::Subnegotiation => match byte {
+                    IAC => ParseState::SubnegotiationIac,
+                    _ => {
+                        self.subnegotiation.push(byte);
+                        ParseState::Subnegotiation
+                    }
+                },
+                ParseState::SubnegotiationIac => match byte {
+                    SE => {
+                        self.handle_subnegotiation(&mut output.reply);
+                        ParseState::Data
+                    }
+                    IAC => {
+                        self.subnegotiation.push(IAC);
+                        ParseState::Subnegotiation
+                    }
+                    // 格式错误，丢弃这次子协商
+                    _ => ParseState::Data,
+                },
+            };
+        }
+
+        output
+    }
+
+    /// 编码用户输入：转义 0xFF，单独的 CR 补 NUL
+    pub fn encode_input(&self, data: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(data.len() + 1);
+        for (i, &byte) in data.iter().enumerate() {
+            match byte {
+                IAC => encoded.extend_from_slice(&[IAC, IAC]),
+                b'\r' if data.get(i + 1) != Some(&b'\n') => encoded.extend_from_slice(b"\r\0"),
+                _ => encoded.push(byte),
+            }
+        }
+        encoded
+    }
+
+    /// 记录新的窗口大小，NAWS 已启用时返回需要发送的子协商
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Option<Vec<u8>> {
+        self.cols = cols;
+        self.rows = rows;
+        (self.local[OPT_NAWS as usize] == OptionState::Yes).then(|| self.window_size())
+    }
+
+    fn negotiate(&mut self, command: u8, option: u8, reply: &mut Vec<u8>) {
+        let index = option as usize;
+        match command {
+            DO => match self.local[index] {
+                OptionState::Yes => {}
+                OptionState::WantYes => {
+                    self.local[index] = OptionState::Yes;
+                    self.local_enabled(option, reply);
+                }
+                OptionState::No if matches!(option, OPT_NAWS | OPT_TTYPE | OPT_SGA) => {
+                    self.local[index] = OptionState::Yes;
+                    reply.extend_from_slice(&[IAC, WILL, option]);
+                    self.local_enabled(option, reply);
+                }
+                OptionState::No => reply.extend_from_slice(&[IAC, WONT, option]),
+            },
+            DONT => {
+                if self.local[index] == OptionState::Yes {
+                    reply.extend_from_slice(&[IAC, WONT, option]);
+                }
+                self.local[index] = OptionState::No;
+            }
+            WILL => match self.remote[index] {
+                OptionState::Yes => {}
+                OptionState::WantYes => self.remote[index] = OptionState::Yes,
+                OptionState::No if matches!(option, OPT_ECHO | OPT_SGA) => {
+                    self.remote[index] = OptionState::Yes;
+                    reply.extend_from_slice(&[IAC, DO, option]);
+                }
+                OptionState::No => reply.extend_from_slice(&[IAC, DONT, option]),
+            },
+            WONT => {
+                if self.remote[index] == OptionState::Yes {
+                    reply.extend_from_slice(&[IAC, DONT, option]);
+                }
+                self.remote[index] = OptionState::No;
+            }
+            _ => {}
+        }
+    }
+
+    /// 本端选项启用后的动作：NAWS 需要立即上报窗口大小
+    fn local_enabled(&self, option: u8, reply: &mut Vec<u8>) {
+        if option == OPT_NAWS {
+            reply.extend_from_slice(&self.window_size());
+        }
+    }
+
+    fn handle_subnegotiation(&self, reply: &mut Vec<u8>) {
+        if self.subnegotiation.as_slice() == [OPT_TTYPE, TTYPE_SEND]
+            && self.local[OPT_TTYPE as usize] == OptionState::Yes
+        {
+            reply.extend_from_slice(&[IAC, SB, OPT_TTYPE, TTYPE_IS]);
+            reply.extend(self.terminal_type.to_ascii_uppercase().bytes().filter(|&b| b != IAC));
+            reply.extend_from_slice(&[IAC, SE]);
+        }
+    }
+
+    fn window_size(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(4);
+        payload.extend_from_slice(&self.cols.to_be_bytes());
+        payload.extend_from_slice(&self.rows.to_be_bytes());
+
+        let mut bytes = vec![IAC, SB, OPT_NAWS];
+        for byte in payload {
+            // 数据中的 0xFF 需要转义
+            if byte == IAC {
+                bytes.push(IAC);
+            }
+            bytes.push(byte);
+        }
+        bytes.extend_from_slice(&[IAC, SE]);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiation_replies_only_on_change() {
+        let mut session = TelnetSession::new("xterm".to_string(), 80, 24);
+        assert_eq!(session.start(), vec![IAC, WILL, OPT_NAWS, IAC, WILL, OPT_TTYPE, IAC, DO, OPT_SGA]);
+
+        // 确认我们发起的请求时不再回复，NAWS 启用后立即上报窗口大小
+        let output = session.receive(&[IAC, DO, OPT_NAWS, IAC, DO, OPT_TTYPE, IAC, WILL, OPT_SGA]);
+        assert!(output.data.is_empty());
+        assert_eq!(output.reply, vec![IAC, SB, OPT_NAWS, 0, 80, 0, 24, IAC, SE]);
+
+        // 接受服务器回显，拒绝不支持的选项
+        let output = session.receive(&[IAC, WILL, OPT_ECHO, IAC, DO, 39, IAC, WILL, 42]);
+        assert_eq!(output.reply, vec![IAC, DO, OPT_ECHO, IAC, WONT, 39, IAC, DONT, 42]);
+        assert_eq!(session.remote[OPT_ECHO as usize], OptionState::Yes);
+
+        // 重复的请求不回复
+        assert!(session.receive(&[IAC, WILL, OPT_ECHO]).reply.is_empty());
+    }
+
+    #[test]
+    fn test_terminal_type_and_window_size() {
+        let mut session = TelnetSession::new("xterm-256color".to_string(), 80, 24);
+        assert_eq!(session.resize(100, 30), None);
+
+        session.receive(&[IAC, DO, OPT_TTYPE, IAC, DO, OPT_NAWS]);
+        let output = session.receive(&[IAC, SB, OPT_TTYPE, TTYPE_SEND, IAC, SE]);
+        let mut expected = vec![IAC, SB, OPT_TTYPE, TTYPE_IS];
+        expected.extend_from_slice(b"XTERM-256COLOR");
+        expected.extend_from_slice(&[IAC, SE]);
+        assert_eq!(output.reply, expected);
+
+        // 255 列需要转义
+        assert_eq!(
+            session.resize(255, 40),
+            Some(vec![IAC, SB, OPT_NAWS, 0, IAC, IAC, 0, 40, IAC, SE])
+        );
+    }
+
+    #[test]
+    fn test_data_escaping_across_chunks() {
+        let mut session = TelnetSession::new("xterm".to_string(), 80, 24);
+
+        let mut data = session.receive(b"login:\r").data;
+        data.extend(session.receive(&[0, IAC]).data);
+        data.extend(session.receive(&[IAC, b'\r', b'\n']).data);
+        assert_eq!(data, b"login:\r\xff\r\n");
+
+        assert_eq!(session.encode_input(b"ls\r"), b"ls\r\0");
+        assert_eq!(session.encode_input(b"a\r\nb\xff"), b"a\r\nb\xff\xff");
+    }
+}
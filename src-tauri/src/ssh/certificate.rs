@@ -0,0 +1,168 @@
+//! OpenSSH 证书
+//!
+//! 用户证书：私钥旁的 `<key>-cert.pub`（或显式指定的路径），认证时与私钥一起出示。
+//! 主机证书不做验证：russh 握手时不协商 `*-cert-v01@openssh.com` 主机密钥算法，
+//! 服务器只会出示普通主机密钥。
+//!
+//! 证书有效期以 Unix 秒表示，`valid_before` 为 u64::MAX 表示永不过期。
+
+use crate::error::{Result, SSHError};
+use crate::ssh::session::AuthMethod;
+use russh::keys::ssh_key::certificate::CertType;
+use russh::keys::{Certificate, HashAlg, PrivateKey};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 证书信息，显示在会话信息中
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub key_id: String,
+    pub serial: u64,
+    /// 允许使用的用户名（主机证书为主机名），为空表示不限制
+    pub principals: Vec<String>,
+    /// 签发 CA 的 SHA256 指纹
+    pub ca_fingerprint: String,
+    /// 生效时间（Unix 秒）
+    pub valid_after: u64,
+    /// 过期时间（Unix 秒），为空表示永不过期
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_before: Option<u64>,
+    pub expired: bool,
+}
+
+impl CertificateInfo {
+    pub fn new(cert: &Certificate, now: u64) -> Self {
+        let valid_before = (cert.valid_before() != u64::MAX).then(|| cert.valid_before());
+        Self {
+            key_id: cert.key_id().to_string(),
+            serial: cert.serial(),
+            principals: cert.valid_principals().to_vec(),
+            ca_fingerprint: cert.signature_key().fingerprint(HashAlg::Sha256).to_string(),
+            valid_after: cert.valid_after(),
+            valid_before,
+            expired: valid_before.is_some_and(|before| now >= before),
+        }
+    }
+
+    /// 按当前时间刷新过期状态（短期证书可能在会话期间过期）
+    pub fn refreshed(mut self, now: u64) -> Self {
+        self.expired = self.valid_before.is_some_and(|before| now >= before);
+        self
+    }
+}
+
+/// 当前 Unix 时间（秒）
+pub fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+fn format_time(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp.min(i64::MAX as u64) as i64, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// 检查证书有效期，返回不可用的原因
+fn check_validity(cert: &Certificate, now: u64) -> std::result::Result<(), String> {
+    if now < cert.valid_after() {
+        return Err(format!("证书尚未生效（生效时间 {}）", format_time(cert.valid_after())));
+    }
+    if now >= cert.valid_before() {
+        return Err(format!("证书已过期（过期时间 {}）", format_time(cert.valid_before())));
+    }
+    Ok(())
+}
+
+/// 会话配置中是否显式指定了证书路径
+pub fn is_explicit_certificate_path(explicit: Option<&str>) -> bool {
+    explicit.is_some_and(|path| !path.trim().is_empty())
+}
+
+/// 用户证书路径：优先使用显式指定的路径，否则查找私钥旁的 `<key>-cert.pub`
+pub fn user_certificate_path(private_key_path: &str, explicit: Option<&str>) -> Option<PathBuf> {
+    if is_explicit_certificate_path(explicit) {
+        return explicit.map(PathBuf::from);
+    }
+    let candidate = PathBuf::from(format!("{}-cert.pub", private_key_path));
+    candidate.is_file().then_some(candidate)
+}
+
+/// 加载用户证书并检查：必须是用户证书、与私钥匹配且在有效期内
+pub fn load_user_certificate(path: &Path, key: &PrivateKey, now: u64) -> Result<Certificate> {
+    let cert = Certificate::read_file(path).map_err(|e| {
+        SSHError::AuthenticationFailed(format!("无法加载证书文件 '{}': {}", path.display(), e))
+    })?;
+
+    if cert.cert_type() != CertType::User {
+        return Err(SSHError::AuthenticationFailed(format!(
+            "证书 '{}' 不是用户证书",
+            path.display()
+        )));
+    }
+    if cert.public_key() != key.public_key().key_data() {
+        return Err(SSHError::AuthenticationFailed(format!(
+            "证书 '{}' 与私钥不匹配",
+            path.display()
+        )));
+    }
+    check_validity(&cert, now).map_err(|reason| {
+        SSHError::AuthenticationFailed(format!("{}: {}", path.display(), reason))
+    })?;
+
+    Ok(cert)
+}
+
+/// 读取会话配置中的用户证书信息（不校验私钥），用于在连接前显示有效期
+pub fn inspect_user_certificate(auth_method: &AuthMethod) -> Option<CertificateInfo> {
+    let AuthMethod::PublicKey { private_key_path, certificate_path, .. } = auth_method else {
+        return None;
+    };
+    let path = user_certificate_path(private_key_path, certificate_path.as_deref())?;
+    let cert = Certificate::read_file(&path).ok()?;
+    Some(CertificateInfo::new(&cert, unix_now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use russh::keys::ssh_key::certificate::Builder;
+    use russh::keys::ssh_key::private::Ed25519Keypair;
+
+    fn key(seed: u8) -> PrivateKey {
+        PrivateKey::from(Ed25519Keypair::from_seed(&[seed; 32]))
+    }
+
+    fn certificate(subject: &PrivateKey, ca: &PrivateKey, cert_type: CertType, principal: &str) -> Certificate {
+        let mut builder = Builder::new([0u8; 16], subject.public_key().key_data().clone(), 1000, 2000).unwrap();
+        builder.cert_type(cert_type).unwrap();
+        builder.key_id("test").unwrap();
+        builder.valid_principal(principal).unwrap();
+        builder.sign(ca).unwrap()
+    }
+
+    #[test]
+    fn test_user_certificate_checks() {
+        let (user, ca) = (key(1), key(2));
+        let cert = certificate(&user, &ca, CertType::User, "deploy");
+        let dir = std::env::temp_dir().join(format!("cert-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("id_ed25519-cert.pub");
+        cert.write_file(&path).unwrap();
+
+        assert!(load_user_certificate(&path, &user, 1500).is_ok());
+        assert!(load_user_certificate(&path, &user, 2000).unwrap_err().to_string().contains("已过期"));
+        assert!(load_user_certificate(&path, &key(3), 1500).unwrap_err().to_string().contains("不匹配"));
+
+        let info = CertificateInfo::new(&cert, 1500);
+        assert_eq!(info.principals, vec!["deploy".to_string()]);
+        assert_eq!(info.valid_before, Some(2000));
+        assert!(!info.expired);
+        assert!(info.refreshed(2500).expired);
+
+        let key_path = dir.join("id_ed25519");
+        assert_eq!(user_certificate_path(key_path.to_str().unwrap(), None), Some(path));
+        assert_eq!(user_certificate_path("/nonexistent/id_rsa", None), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,10 +1,15 @@
 use crate::ssh::backend::{SSHBackend, BackendReader};
 use crate::ssh::session::{SessionConfig, SessionStatus, SessionInfo};
-use std::sync::Arc;
+use crate::ssh::screen::ScreenState;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex;
 use chrono::{DateTime, Utc};
 
 /// 实际的SSH连接实例
+///
+/// 每个实例对应一个终端 channel，底层 SSH 连接（`SshTransport`）
+/// 可由同一会话的多个实例以及 SFTP、exec channel 共享
 #[derive(Clone)]
 pub struct ConnectionInstance {
     pub id: String,
@@ -12,6 +17,12 @@ pub struct ConnectionInstance {
     pub config: SessionConfig,  // 保存配置副本
     pub status: Arc<Mutex<SessionStatus>>,
     pub connected_at: Arc<Mutex<Option<DateTime<Utc>>>>,
+    /// 最近一次设置的终端大小 (rows, cols)，重连时恢复
+    pub terminal_size: Arc<Mutex<Option<(u16, u16)>>>,
+    /// 用户主动断开标记，置位后不再自动重连
+    pub closing: Arc<AtomicBool>,
+    /// 本地屏幕状态，前端错过输出（重新挂载、断线重连）时回放
+    pub screen: Arc<StdMutex<ScreenState>>,
 
     // 后端连接
     pub backend: Arc<Mutex<Option<Box<dyn SSHBackend>>>>,
@@ -26,6 +37,9 @@ impl ConnectionInstance {
             config,
             status: Arc::new(Mutex::new(SessionStatus::Disconnected)),
             connected_at: Arc::new(Mutex::new(None)),
+            terminal_size: Arc::new(Mutex::new(None)),
+            closing: Arc::new(AtomicBool::new(false)),
+            screen: Arc::new(StdMutex::new(ScreenState::default())),
             backend: Arc::new(Mutex::new(None)),
             backend_reader: Arc::new(Mutex::new(None)),
         }
@@ -39,8 +53,45 @@ impl ConnectionInstance {
         *self.status.lock().await = status;
     }
 
+    /// 是否已由用户主动断开
+    pub fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::SeqCst)
+    }
+
+    pub fn set_closing(&self, closing: bool) {
+        self.closing.store(closing, Ordering::SeqCst);
+    }
+
+    /// 用于建立连接的配置，终端大小使用最近一次 resize 的值
+    ///
+    /// 启用会话保持但未指定会话名时按连接 ID 生成，保证重连后附加到同一个远程会话
+    pub async fn connect_config(&self) -> SessionConfig {
+        let mut config = self.config.clone();
+        if let Some((rows, cols)) = *self.terminal_size.lock().await {
+            config.rows = Some(rows);
+            config.columns = Some(cols);
+        }
+        if config.persistent_shell.enabled && config.persistent_shell.name.is_none() {
+            config.persistent_shell.name = Some(self.persistent_shell_name());
+        }
+        config
+    }
+
+    /// 远程 tmux/screen 会话名
+    pub fn persistent_shell_name(&self) -> String {
+        let fallback = format!("ssh-terminal-{}", self.id.chars().take(8).collect::<String>());
+        self.config.persistent_shell.session_name(&fallback)
+    }
+
     /// 返回SessionInfo（用于兼容旧API）
+    ///
+    /// 已连接时返回登录时出示的证书，否则读取配置中的证书文件
     pub async fn session_info(&self) -> SessionInfo {
+        let certificate = match self.transport().await {
+            Ok(transport) => transport.user_certificate(),
+            Err(_) => crate::ssh::certificate::inspect_user_certificate(&self.config.auth_method),
+        };
+
         SessionInfo {
             id: self.id.clone(),
             name: self.config.name.clone(),
@@ -52,9 +103,25 @@ impl ConnectionInstance {
             group: self.config.group.clone(),
             connection_session_id: Some(self.session_id.clone()),
             connection_id: Some(self.id.clone()),
+            certificate,
         }
     }
 
+    /// 获取终端 channel 所在的 SSH 连接
+    pub async fn transport(&self) -> crate::error::Result<Arc<crate::ssh::backends::russh::SshTransport>> {
+        use crate::ssh::backends::russh::RusshBackend;
+
+        let backend_guard = self.backend.lock().await;
+        let backend = backend_guard.as_ref()
+            .ok_or(crate::error::SSHError::NotConnected)?;
+
+        backend.as_any()
+            .downcast_ref::<RusshBackend>()
+            .ok_or(crate::error::SSHError::NotSupported("该连接类型不支持 SSH channel".to_string()))?
+            .transport()
+            .ok_or(crate::error::SSHError::NotConnected)
+    }
+
     /// 创建 SFTP 客户端
     ///
     /// 此方法使用 Any trait 安全地 downcast backend
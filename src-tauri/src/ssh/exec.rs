@@ -0,0 +1,121 @@
+// 非交互式命令执行
+//
+// 在独立的 exec channel 上运行命令，不经过用户的终端 shell。
+// stdout/stderr 分别通过 `ssh-exec-<execId>` 事件实时推送，
+// 结束后返回退出码和采集到的输出。
+
+use serde::{Deserialize, Serialize};
+
+/// 单次执行最多保留的输出字节数（stdout/stderr 分别计算），超出部分只通过事件推送
+pub const MAX_CAPTURE_BYTES: usize = 4 * 1024 * 1024;
+
+/// 命令执行请求
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecRequest {
+    pub command: String,
+    /// 超时时间（毫秒），为空表示不限制
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// 输出流
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecStream {
+    Stdout,
+    Stderr,
+}
+
+/// 输出事件（`ssh-exec-<execId>`）
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOutputEvent {
+    pub exec_id: String,
+    pub stream: ExecStream,
+    pub data: Vec<u8>,
+}
+
+/// 命令执行结果
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecResult {
+    /// 退出码，被信号终止或超时/取消时为空
+    pub exit_code: Option<u32>,
+    /// 终止命令的信号名（如 "KILL"）
+    pub exit_signal: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+    /// 输出超过 MAX_CAPTURE_BYTES 被截断
+    pub truncated: bool,
+    pub timed_out: bool,
+    pub cancelled: bool,
+}
+
+/// 输出采集器，分别保存 stdout/stderr 并限制大小
+#[derive(Default)]
+pub struct ExecCapture {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    truncated: bool,
+}
+
+impl ExecCapture {
+    pub fn push(&mut self, stream: ExecStream, data: &[u8]) {
+        let buffer = match stream {
+            ExecStream::Stdout => &mut self.stdout,
+            ExecStream::Stderr => &mut self.stderr,
+        };
+
+        let room = MAX_CAPTURE_BYTES.saturating_sub(buffer.len());
+        if data.len() > room {
+            self.truncated = true;
+        }
+        buffer.extend_from_slice(&data[..data.len().min(room)]);
+    }
+
+    /// 生成执行结果，输出按 UTF-8 解码（非法字节替换为 U+FFFD）
+    pub fn finish(self, exit_code: Option<u32>, exit_signal: Option<String>) -> ExecResult {
+        ExecResult {
+            exit_code,
+            exit_signal,
+            stdout: String::from_utf8_lossy(&self.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&self.stderr).into_owned(),
+            truncated: self.truncated,
+            timed_out: false,
+            cancelled: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_separates_streams() {
+        let mut capture = ExecCapture::default();
+        capture.push(ExecStream::Stdout, b"out ");
+        capture.push(ExecStream::Stderr, b"err");
+        capture.push(ExecStream::Stdout, b"more");
+
+        let result = capture.finish(Some(0), None);
+        assert_eq!(result.stdout, "out more");
+        assert_eq!(result.stderr, "err");
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn test_capture_truncates_large_output() {
+        let mut capture = ExecCapture::default();
+        capture.push(ExecStream::Stdout, &vec![b'a'; MAX_CAPTURE_BYTES - 1]);
+        capture.push(ExecStream::Stdout, b"bc");
+        capture.push(ExecStream::Stderr, b"still captured");
+
+        let result = capture.finish(Some(1), None);
+        assert_eq!(result.stdout.len(), MAX_CAPTURE_BYTES);
+        assert!(result.stdout.ends_with("ab"));
+        assert_eq!(result.stderr, "still captured");
+        assert!(result.truncated);
+    }
+}
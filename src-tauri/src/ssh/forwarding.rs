@@ -0,0 +1,328 @@
+// 端口转发（-L / -R / -D）
+//
+// 规则随会话配置保存，连接建立后自动启动已启用的规则，
+// 运行期间可以通过 Tauri 命令查看、添加、删除和启停
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/// 端口转发类型
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PortForwardKind {
+    /// 本地转发（ssh -L）：本地监听，经服务器连接目标
+    Local,
+    /// 远程转发（ssh -R）：服务器监听，经本机连接目标
+    Remote,
+    /// 动态转发（ssh -D）：本地 SOCKS5 代理
+    Dynamic,
+}
+
+/// 端口转发规则
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardRule {
+    pub id: String,
+    pub kind: PortForwardKind,
+    /// 监听地址（Local/Dynamic 为本机地址，Remote 为服务器地址）
+    #[serde(default = "default_bind_host")]
+    pub bind_host: String,
+    /// 监听端口（Remote 为 0 时由服务器分配）
+    pub bind_port: u16,
+    /// 目标地址（Dynamic 不使用）
+    #[serde(default)]
+    pub target_host: String,
+    #[serde(default)]
+    pub target_port: u16,
+    /// 连接时是否自动启动
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_bind_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 端口转发运行状态
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardStatus {
+    #[serde(flatten)]
+    pub rule: PortForwardRule,
+    /// 是否正在运行
+    pub active: bool,
+    /// 实际监听端口（Remote 规则的端口可能由服务器分配）
+    pub bound_port: Option<u16>,
+    /// 本地 → 远端字节数
+    pub bytes_sent: u64,
+    /// 远端 → 本地字节数
+    pub bytes_received: u64,
+    /// 当前活动的连接数
+    pub active_connections: u64,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+}
+
+/// 端口转发事件（错误或状态变更）
+///
+/// 以 `ssh-port-forward-<connectionId>` 事件发送到前端
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardEvent {
+    pub rule_id: String,
+    pub active: bool,
+    /// 规则启动失败，或运行中某个转发连接出错（此时 active 仍为 true）
+    pub error: Option<String>,
+}
+
+/// 单条转发的流量统计
+#[derive(Default, Debug)]
+pub struct TunnelStats {
+    pub bytes_sent: AtomicU64,
+    pub bytes_received: AtomicU64,
+    pub active_connections: AtomicU64,
+    pub last_error: StdMutex<Option<String>>,
+}
+
+impl TunnelStats {
+    pub fn record_error(&self, error: String) {
+        *self.last_error.lock().unwrap() = Some(error);
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
+
+/// 统计读写字节数的流包装器
+///
+/// 从内部流读取的数据计为发送，写入内部流的数据计为接收
+pub struct CountingStream<S> {
+    inner: S,
+    stats: Arc<TunnelStats>,
+}
+
+impl<S> CountingStream<S> {
+    pub fn new(inner: S, stats: Arc<TunnelStats>) -> Self {
+        Self { inner, stats }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            let n = (buf.filled().len() - before) as u64;
+            self.stats.bytes_sent.fetch_add(n, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = result {
+            self.stats.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// 在两个流之间双向转发数据，并统计流量
+///
+/// local 为本机一侧的流，remote 为 SSH channel 一侧的流
+pub async fn pipe<L, R>(local: L, mut remote: R, stats: Arc<TunnelStats>) -> io::Result<()>
+where
+    L: AsyncRead + AsyncWrite + Unpin,
+    R: AsyncRead + AsyncWrite + Unpin,
+{
+    stats.active_connections.fetch_add(1, Ordering::Relaxed);
+    let mut local = CountingStream::new(local, stats.clone());
+    let result = tokio::io::copy_bidirectional(&mut local, &mut remote).await;
+    stats.active_connections.fetch_sub(1, Ordering::Relaxed);
+    result.map(|_| ())
+}
+
+// ============================================================================
+// SOCKS5（RFC 1928），仅支持无认证的 CONNECT 命令
+// ============================================================================
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+
+/// SOCKS5 应答码
+pub const SOCKS_REPLY_SUCCEEDED: u8 = 0x00;
+pub const SOCKS_REPLY_GENERAL_FAILURE: u8 = 0x01;
+const SOCKS_REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const SOCKS_REPLY_ADDRESS_NOT_SUPPORTED: u8 = 0x08;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// 完成 SOCKS5 握手，返回客户端请求连接的目标地址
+///
+/// 握手成功后调用方需要通过 [`socks5_reply`] 告知客户端连接结果
+pub async fn socks5_handshake<S>(stream: &mut S) -> io::Result<(String, u16)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // 协商认证方式：VER NMETHODS METHODS...
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(invalid_data("unsupported SOCKS version"));
+    }
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+
+    if !methods.contains(&SOCKS_NO_AUTH) {
+        stream.write_all(&[SOCKS_VERSION, SOCKS_NO_ACCEPTABLE_METHOD]).await?;
+        return Err(invalid_data("SOCKS client requires authentication"));
+    }
+    stream.write_all(&[SOCKS_VERSION, SOCKS_NO_AUTH]).await?;
+
+    // 请求：VER CMD RSV ATYP DST.ADDR DST.PORT
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    if request[0] != SOCKS_VERSION {
+        return Err(invalid_data("unsupported SOCKS version"));
+    }
+
+    let address_len = match request[3] {
+        SOCKS_ATYP_IPV4 => 4,
+        SOCKS_ATYP_IPV6 => 16,
+        SOCKS_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        _ => {
+            socks5_reply(stream, SOCKS_REPLY_ADDRESS_NOT_SUPPORTED).await?;
+            return Err(invalid_data("unsupported SOCKS address type"));
+        }
+    };
+    let mut address = vec![0u8; address_len + 2];
+    stream.read_exact(&mut address).await?;
+
+    if request[1] != SOCKS_CMD_CONNECT {
+        socks5_reply(stream, SOCKS_REPLY_COMMAND_NOT_SUPPORTED).await?;
+        return Err(invalid_data("only SOCKS CONNECT is supported"));
+    }
+
+    parse_socks5_address(request[3], &address)
+        .ok_or_else(|| invalid_data("invalid SOCKS destination address"))
+}
+
+/// 解析 DST.ADDR + DST.PORT（域名类型不包含长度字节）
+fn parse_socks5_address(address_type: u8, data: &[u8]) -> Option<(String, u16)> {
+    if data.len() < 2 {
+        return None;
+    }
+    let (address, port) = data.split_at(data.len() - 2);
+    let port = u16::from_be_bytes([port[0], port[1]]);
+
+    let host = match address_type {
+        SOCKS_ATYP_IPV4 => {
+            let octets: [u8; 4] = address.try_into().ok()?;
+            std::net::Ipv4Addr::from(octets).to_string()
+        }
+        SOCKS_ATYP_IPV6 => {
+            let octets: [u8; 16] = address.try_into().ok()?;
+            std::net::Ipv6Addr::from(octets).to_string()
+        }
+        SOCKS_ATYP_DOMAIN => String::from_utf8(address.to_vec()).ok()?,
+        _ => return None,
+    };
+
+    Some((host, port))
+}
+
+/// 发送 SOCKS5 应答（绑定地址固定为 0.0.0.0:0）
+pub async fn socks5_reply<S>(stream: &mut S, reply: u8) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream
+        .write_all(&[SOCKS_VERSION, reply, 0x00, SOCKS_ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socks5_address() {
+        assert_eq!(
+            parse_socks5_address(SOCKS_ATYP_IPV4, &[10, 0, 0, 1, 0x00, 0x50]),
+            Some(("10.0.0.1".to_string(), 80))
+        );
+        assert_eq!(
+            parse_socks5_address(SOCKS_ATYP_DOMAIN, b"example.com\x01\xbb"),
+            Some(("example.com".to_string(), 443))
+        );
+
+        let mut ipv6 = [0u8; 18];
+        ipv6[15] = 1;
+        ipv6[17] = 22;
+        assert_eq!(
+            parse_socks5_address(SOCKS_ATYP_IPV6, &ipv6),
+            Some(("::1".to_string(), 22))
+        );
+
+        assert_eq!(parse_socks5_address(SOCKS_ATYP_IPV4, &[10, 0, 0]), None);
+    }
+
+    #[tokio::test]
+    async fn test_socks5_handshake() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        let handshake = tokio::spawn(async move { socks5_handshake(&mut server).await });
+
+        client.write_all(&[0x05, 0x01, 0x00]).await.unwrap();
+        let mut method = [0u8; 2];
+        client.read_exact(&mut method).await.unwrap();
+        assert_eq!(method, [0x05, 0x00]);
+
+        client
+            .write_all(&[0x05, 0x01, 0x00, 0x03, 4, b'h', b'o', b's', b't', 0x1f, 0x90])
+            .await
+            .unwrap();
+
+        let target = handshake.await.unwrap().unwrap();
+        assert_eq!(target, ("host".to_string(), 8080));
+    }
+}
@@ -0,0 +1,156 @@
+// MobaXterm 会话导入
+//
+// 读取「Export all sessions」导出的 .mxtsessions 文件。每个 [Bookmarks_N] 节是
+// 一个文件夹，SubRep 为文件夹路径；其余每行是一个会话：
+// `名称=#图标#类型%主机%端口%用户%...#终端设置#...`
+
+use super::{parse_ini, ImportedHost};
+use crate::ssh::session::{whoami_fallback, AuthMethod, ConnectionType, SessionConfig};
+
+/// 会话类型字段
+const TYPE_SSH: &str = "0";
+const TYPE_TELNET: &str = "1";
+
+/// 解析 .mxtsessions 文件，返回可导入的主机和被跳过的会话
+pub fn parse(content: &str) -> (Vec<ImportedHost>, Vec<String>) {
+    let mut hosts = Vec::new();
+    let mut warnings = Vec::new();
+
+    for section in parse_ini(content) {
+        if !section.name.starts_with("Bookmarks") {
+            continue;
+        }
+        // 文件夹路径用 `\` 分隔，根目录为空
+        let group = section
+            .get("SubRep")
+            .filter(|group| !group.is_empty())
+            .map(|group| group.replace('\\', "/"));
+
+        for (name, value) in &section.entries {
+            if name.eq_ignore_ascii_case("SubRep") || name.eq_ignore_ascii_case("ImgNum") {
+                continue;
+            }
+            match session(name, value) {
+                Ok(mut host) => {
+                    if let Some(group) = &group {
+                        host.config.group = group.clone();
+                    }
+                    hosts.push(host);
+                }
+                Err(reason) => warnings.push(format!("{}: {}，已跳过", name, reason)),
+            }
+        }
+    }
+
+    (hosts, warnings)
+}
+
+fn session(name: &str, value: &str) -> Result<ImportedHost, String> {
+    // 第一段为空，第二段是图标编号，第三段是连接参数
+    let connection = value.split('#').nth(2).ok_or("无法识别的会话格式")?;
+    let fields: Vec<String> = connection.split('%').map(unescape).collect();
+    let field = |index: usize| fields.get(index).map(String::as_str).filter(|value| !value.is_empty());
+
+    let connection_type = match fields[0].as_str() {
+        TYPE_SSH => ConnectionType::Ssh,
+        TYPE_TELNET => ConnectionType::Telnet,
+        other => return Err(format!("不支持的会话类型 {}", other)),
+    };
+
+    let host = field(1).ok_or("缺少主机名")?.to_string();
+    let port = field(2).and_then(|port| port.parse().ok()).unwrap_or(22);
+    let username = field(3).map(str::to_string).unwrap_or_else(whoami_fallback);
+    let mut unsupported = Vec::new();
+    let mut proxy_jump = Vec::new();
+
+    let auth_method = if connection_type.is_ssh() {
+        if let Some(command) = field(7) {
+            unsupported.push(format!("远程命令 {}", command));
+        }
+        // SSH 网关
+        if let Some(gateway) = field(8) {
+            let port = field(9).unwrap_or("22");
+            proxy_jump.push(match field(10) {
+                Some(username) => format!("{}@{}:{}", username, gateway, port),
+                None => format!("{}:{}", gateway, port),
+            });
+        }
+        match field(14) {
+            Some(path) => AuthMethod::PublicKey {
+                private_key_path: expand_path(path),
+                passphrase: None,
+                certificate_path: None,
+            },
+            None => AuthMethod::Agent { identity: None },
+        }
+    } else {
+        AuthMethod::Agent { identity: None }
+    };
+
+    let mut config = SessionConfig::ssh(name.to_string(), host, port, username, auth_method);
+    config.connection_type = connection_type;
+
+    let mut host = ImportedHost::new(name.to_string(), config);
+    host.proxy_jump = proxy_jump;
+    host.unsupported = unsupported;
+    Ok(host)
+}
+
+/// MobaXterm 用占位符转义会话行中的分隔符
+fn unescape(value: &str) -> String {
+    value
+        .replace("__DIEZE__", "#")
+        .replace("__PERCENT__", "%")
+        .replace("__PTVIRG__", ";")
+        .replace("__DBLQUO__", "\"")
+        .replace("__PIPE__", "|")
+}
+
+/// 展开 MobaXterm 的路径变量
+fn expand_path(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("_ProfileDir_") {
+        if let Some(home) = dirs::home_dir() {
+            return format!("{}{}", home.display(), rest);
+        }
+    }
+    if let Some(rest) = path.strip_prefix("_CurrentDrive_") {
+        return format!("C:{}", rest);
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bookmarks() {
+        let content = r"[Bookmarks]
+SubRep=
+ImgNum=42
+router=#98#1%10.0.0.1%23%%%2%%%%%0%0%%1080%#MobaFont%10%0%0%-1%15#0# #-1
+
+[Bookmarks_1]
+SubRep=Servers\Prod
+ImgNum=41
+web01=#109#0%web01.example.com%2222%root%%-1%-1%uptime%bastion%22%ops%0%0%0%C:\keys\web.ppk%%-1%0%0%0%%1080%%0%0%1#MobaFont%10%0%0%-1%15#0# #-1
+desktop=#91#4%10.0.0.5%5900%%-1%%%%%0%0%0%%%#MobaFont%10%0%0%-1%15#0# #-1
+";
+        let (hosts, warnings) = parse(content);
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(warnings, vec!["desktop: 不支持的会话类型 4，已跳过".to_string()]);
+
+        assert_eq!(hosts[0].config.connection_type, ConnectionType::Telnet);
+        assert_eq!((hosts[0].config.host.as_str(), hosts[0].config.port), ("10.0.0.1", 23));
+
+        let web = &hosts[1];
+        assert_eq!(web.config.group, "Servers/Prod");
+        assert_eq!((web.config.port, web.config.username.as_str()), (2222, "root"));
+        assert_eq!(web.proxy_jump, vec!["ops@bastion:22".to_string()]);
+        assert_eq!(web.unsupported, vec!["远程命令 uptime".to_string()]);
+        assert!(matches!(
+            &web.config.auth_method,
+            AuthMethod::PublicKey { private_key_path, .. } if private_key_path == "C:\\keys\\web.ppk"
+        ));
+    }
+}
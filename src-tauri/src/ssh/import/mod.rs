@@ -0,0 +1,346 @@
+// 从其他工具导入会话
+//
+// 各导入源只负责把自己的格式解析为 ImportedHost，
+// 跳板机引用解析、与已有会话的查重统一由 ImportPlan 处理。
+
+pub mod mobaxterm;
+pub mod putty;
+pub mod ssh_config;
+pub mod termius;
+pub mod winscp;
+
+use crate::error::{Result, SSHError};
+use crate::ssh::session::{AuthMethod, SessionConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 导入源格式
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSource {
+    /// OpenSSH 客户端配置（~/.ssh/config）
+    SshConfig,
+    /// PuTTY 注册表导出（.reg）
+    Putty,
+    /// MobaXterm 会话导出（.mxtsessions）
+    MobaXterm,
+    /// WinSCP.ini
+    WinScp,
+    /// Termius JSON 导出
+    Termius,
+}
+
+/// 按格式解析导入文件，返回可导入的主机和解析过程中的提示
+pub fn load(source: ImportSource, path: &Path) -> Result<(Vec<ImportedHost>, Vec<String>)> {
+    match source {
+        ImportSource::SshConfig => ssh_config::load(path),
+        ImportSource::Putty => Ok(putty::parse(&read_text(path)?)),
+        ImportSource::MobaXterm => Ok(mobaxterm::parse(&read_text(path)?)),
+        ImportSource::WinScp => Ok(winscp::parse(&read_text(path)?)),
+        ImportSource::Termius => termius::parse(&read_text(path)?),
+    }
+}
+
+/// 导入源中解析出的一台主机
+#[derive(Clone, Debug)]
+pub struct ImportedHost {
+    /// 导入源中的名称（如 ssh_config 的 Host 别名）
+    pub alias: String,
+    /// 会话配置，jump_hosts 为空，由 proxy_jump 解析后填入
+    pub config: SessionConfig,
+    /// 跳板机引用：导入源中的别名或 `[user@]host[:port]`
+    pub proxy_jump: Vec<String>,
+    /// 无法导入的设置，显示在预览中
+    pub unsupported: Vec<String>,
+}
+
+impl ImportedHost {
+    pub fn new(alias: String, config: SessionConfig) -> Self {
+        Self {
+            alias,
+            config,
+            proxy_jump: Vec::new(),
+            unsupported: Vec::new(),
+        }
+    }
+}
+
+/// 查重依据：主机（不区分大小写）、端口和用户名
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+}
+
+impl SessionKey {
+    pub fn new(host: &str, port: u16, username: &str) -> Self {
+        Self {
+            host: host.to_lowercase(),
+            port,
+            username: username.to_string(),
+        }
+    }
+
+    pub fn of(config: &SessionConfig) -> Self {
+        Self::new(&config.host, config.port, &config.username)
+    }
+}
+
+/// 导入计划中的一个会话
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidate {
+    /// 导入后使用的会话 ID，重复项为已有会话的 ID
+    pub id: String,
+    pub alias: String,
+    pub config: SessionConfig,
+    /// 与已有会话（或本次导入中靠前的主机）重复，导入时跳过
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    /// 无法导入的设置
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unsupported: Vec<String>,
+}
+
+/// 导入计划，dry-run 时直接返回给前端预览
+#[derive(Clone, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    pub candidates: Vec<ImportCandidate>,
+    /// 被忽略的配置项等提示
+    pub warnings: Vec<String>,
+    /// 实际写入的会话数，预览时为 0
+    pub imported: usize,
+}
+
+impl ImportPlan {
+    /// 生成导入计划
+    ///
+    /// existing 为已保存的会话（ID 与查重依据）。跳板机引用依次按导入的别名、
+    /// 已有会话、本次导入的主机解析，都找不到时为它补一个会话。
+    pub fn new(hosts: Vec<ImportedHost>, existing: &[(String, SessionKey)], warnings: Vec<String>) -> Self {
+        let mut plan = Self {
+            candidates: Vec::new(),
+            warnings,
+            imported: 0,
+        };
+        let mut known: HashMap<SessionKey, String> = existing
+            .iter()
+            .map(|(id, key)| (key.clone(), id.clone()))
+            .collect();
+        let mut aliases: HashMap<String, String> = HashMap::new();
+        let mut jumps = Vec::with_capacity(hosts.len());
+
+        for host in hosts {
+            let id = plan.add(host.alias.clone(), host.config, host.unsupported, &mut known);
+            aliases.entry(host.alias).or_insert(id);
+            jumps.push(host.proxy_jump);
+        }
+
+        for (index, references) in jumps.into_iter().enumerate() {
+            let mut jump_hosts = Vec::with_capacity(references.len());
+            for reference in references {
+                let id = match aliases.get(&reference) {
+                    Some(id) => id.clone(),
+                    None => {
+                        let (username, host, port) = parse_jump_reference(&reference);
+                        let username = username.unwrap_or_else(|| plan.candidates[index].config.username.clone());
+                        let config = SessionConfig::ssh(reference.clone(), host, port, username, AuthMethod::Agent { identity: None });
+                        let id = plan.add(reference.clone(), config, Vec::new(), &mut known);
+                        aliases.insert(reference, id.clone());
+                        id
+                    }
+                };
+                if id == plan.candidates[index].id {
+                    plan.warnings.push(format!("{}: 跳板机不能是自身，已忽略", plan.candidates[index].alias));
+                    continue;
+                }
+                jump_hosts.push(id);
+            }
+            plan.candidates[index].config.jump_hosts = jump_hosts;
+        }
+
+        plan
+    }
+
+    /// 需要写入的会话（不含重复项）
+    pub fn new_sessions(&self) -> impl Iterator<Item = &ImportCandidate> {
+        self.candidates.iter().filter(|candidate| candidate.duplicate_of.is_none())
+    }
+
+    fn add(
+        &mut self,
+        alias: String,
+        config: SessionConfig,
+        unsupported: Vec<String>,
+        known: &mut HashMap<SessionKey, String>,
+    ) -> String {
+        let key = SessionKey::of(&config);
+        let (id, duplicate_of) = match known.get(&key) {
+            Some(id) => (id.clone(), Some(id.clone())),
+            None => {
+                let id = uuid::Uuid::new_v4().to_string();
+                known.insert(key, id.clone());
+                (id, None)
+            }
+        };
+        self.candidates.push(ImportCandidate {
+            id: id.clone(),
+            alias,
+            config,
+            duplicate_of,
+            unsupported,
+        });
+        id
+    }
+}
+
+/// 解析 `[user@]host[:port]`，IPv6 地址需要写成 `[addr]:port`
+pub fn parse_jump_reference(reference: &str) -> (Option<String>, String, u16) {
+    let (username, address) = match reference.rsplit_once('@') {
+        Some((username, address)) => (Some(username.to_string()), address),
+        None => (None, reference),
+    };
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        match rest.split_once(']') {
+            Some((host, port)) => (host, port.strip_prefix(':')),
+            None => (rest, None),
+        }
+    } else {
+        match address.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') => (host, Some(port)),
+            _ => (address, None),
+        }
+    };
+
+    let port = port.and_then(|port| port.parse().ok()).unwrap_or(22);
+    (username, host.to_string(), port)
+}
+
+/// 读取导入文件，支持 UTF-16（Windows 注册表导出）和带 BOM 的 UTF-8
+fn read_text(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| SSHError::Io(format!("无法读取 {}: {}", path.display(), e)))?;
+
+    if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&bytes);
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// INI 文件的一节
+struct IniSection {
+    name: String,
+    entries: Vec<(String, String)>,
+}
+
+impl IniSection {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 解析 INI 格式（.reg 也是这种结构），忽略注释和节之外的内容
+fn parse_ini(content: &str) -> Vec<IniSection> {
+    let mut sections: Vec<IniSection> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            sections.push(IniSection {
+                name: name.to_string(),
+                entries: Vec::new(),
+            });
+        } else if let (Some(section), Some((key, value))) = (sections.last_mut(), line.split_once('=')) {
+            section.entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+/// 解码 `%XX` 转义（PuTTY 会话名、WinSCP 配置值）
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let hex = |byte: u8| (byte as char).to_digit(16);
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(alias: &str, hostname: &str, username: &str, proxy_jump: &[&str]) -> ImportedHost {
+        let config = SessionConfig::ssh(
+            alias.to_string(),
+            hostname.to_string(),
+            22,
+            username.to_string(),
+            AuthMethod::Agent { identity: None },
+        );
+        let mut host = ImportedHost::new(alias.to_string(), config);
+        host.proxy_jump = proxy_jump.iter().map(|s| s.to_string()).collect();
+        host
+    }
+
+    #[test]
+    fn test_plan_resolves_jumps_and_duplicates() {
+        let existing = vec![("existing".to_string(), SessionKey::new("DB.internal", 22, "admin"))];
+        let plan = ImportPlan::new(
+            vec![
+                host("db", "db.internal", "admin", &["bastion"]),
+                host("bastion", "bastion.example.com", "ops", &[]),
+                host("web", "web.internal", "admin", &["deploy@gw.example.com:2222"]),
+                host("bastion-copy", "bastion.example.com", "ops", &[]),
+            ],
+            &existing,
+            Vec::new(),
+        );
+
+        let by_alias = |alias: &str| plan.candidates.iter().find(|c| c.alias == alias).unwrap();
+        assert_eq!(by_alias("db").duplicate_of.as_deref(), Some("existing"));
+        assert_eq!(by_alias("db").config.jump_hosts, vec![by_alias("bastion").id.clone()]);
+        assert_eq!(by_alias("bastion-copy").duplicate_of, Some(by_alias("bastion").id.clone()));
+
+        // 未定义的跳板机补为新会话
+        let gateway = by_alias("deploy@gw.example.com:2222");
+        assert_eq!((gateway.config.host.as_str(), gateway.config.port), ("gw.example.com", 2222));
+        assert_eq!(gateway.config.username, "deploy");
+        assert_eq!(by_alias("web").config.jump_hosts, vec![gateway.id.clone()]);
+
+        assert_eq!(plan.new_sessions().count(), 3);
+    }
+
+    #[test]
+    fn test_parse_jump_reference() {
+        assert_eq!(parse_jump_reference("gw"), (None, "gw".to_string(), 22));
+        assert_eq!(parse_jump_reference("me@gw:2200"), (Some("me".to_string()), "gw".to_string(), 2200));
+        assert_eq!(parse_jump_reference("[fe80::1]:2200"), (None, "fe80::1".to_string(), 2200));
+        assert_eq!(parse_jump_reference("fe80::1"), (None, "fe80::1".to_string(), 22));
+    }
+}
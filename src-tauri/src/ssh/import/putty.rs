@@ -0,0 +1,266 @@
+// PuTTY 会话导入
+//
+// 读取 `reg export HKCU\Software\SimonTatham\PuTTY\Sessions` 导出的 .reg 文件。
+// PuTTY 没有分组，导入的会话统一放在 IMPORT_GROUP；PuTTY 不保存密码。
+
+use super::{parse_ini, percent_decode, ssh_config, ImportedHost, IniSection};
+use crate::ssh::forwarding::{PortForwardKind, PortForwardRule};
+use crate::ssh::session::{
+    whoami_fallback, AuthMethod, ConnectionType, SerialConfig, SerialFlowControl, SerialParity,
+    SerialStopBits, SessionConfig,
+};
+
+const SESSIONS_KEY: &str = "\\Software\\SimonTatham\\PuTTY\\Sessions\\";
+
+/// 导入的会话所在分组
+pub const IMPORT_GROUP: &str = "PuTTY";
+
+/// 解析 .reg 文件，返回可导入的主机和被跳过的会话
+pub fn parse(content: &str) -> (Vec<ImportedHost>, Vec<String>) {
+    let mut hosts = Vec::new();
+    let mut warnings = Vec::new();
+
+    for section in parse_ini(content) {
+        let Some(index) = section.name.find(SESSIONS_KEY) else {
+            continue;
+        };
+        let name = percent_decode(&section.name[index + SESSIONS_KEY.len()..]);
+        if name == "Default Settings" {
+            continue;
+        }
+
+        let values = RegValues(&section);
+        match session(&name, &values) {
+            Ok(host) => hosts.push(host),
+            Err(reason) => warnings.push(format!("{}: {}，已跳过", name, reason)),
+        }
+    }
+
+    (hosts, warnings)
+}
+
+/// 注册表值：字符串去掉引号和转义，dword 转为十进制
+struct RegValues<'a>(&'a IniSection);
+
+impl RegValues<'_> {
+    fn string(&self, key: &str) -> Option<String> {
+        let value = self.0.get(&format!("\"{}\"", key))?;
+        let value = value.strip_prefix('"')?.strip_suffix('"')?;
+        let value = value.replace("\\\"", "\"").replace("\\\\", "\\");
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn number(&self, key: &str) -> Option<u32> {
+        let value = self.0.get(&format!("\"{}\"", key))?;
+        u32::from_str_radix(value.strip_prefix("dword:")?, 16).ok()
+    }
+}
+
+fn session(name: &str, values: &RegValues) -> Result<ImportedHost, String> {
+    let protocol = values.string("Protocol").unwrap_or_else(|| "ssh".to_string());
+    let mut unsupported = Vec::new();
+
+    let mut config = if protocol == "serial" {
+        let serial = serial_config(values, &mut unsupported).ok_or("缺少串口设备")?;
+        SessionConfig::serial(name.to_string(), serial)
+    } else {
+        // HostName 可以写成 user@host
+        let host = values.string("HostName").ok_or("缺少主机名")?;
+        let (username, host) = match host.rsplit_once('@') {
+            Some((username, host)) => (Some(username.to_string()), host.to_string()),
+            None => (values.string("UserName"), host),
+        };
+        let port = values
+            .number("PortNumber")
+            .and_then(|port| u16::try_from(port).ok())
+            .unwrap_or(22);
+
+        let auth_method = match values.string("PublicKeyFile") {
+            Some(private_key_path) => AuthMethod::PublicKey {
+                private_key_path,
+                passphrase: None,
+                certificate_path: values.string("DetachedCertificate"),
+            },
+            None => AuthMethod::Agent { identity: None },
+        };
+
+        let mut config = SessionConfig::ssh(
+            name.to_string(),
+            host,
+            port,
+            username.unwrap_or_else(whoami_fallback),
+            auth_method,
+        );
+        config.connection_type = match protocol.as_str() {
+            "ssh" => ConnectionType::Ssh,
+            "telnet" => ConnectionType::Telnet,
+            "raw" => ConnectionType::RawTcp,
+            other => return Err(format!("不支持 {} 协议", other)),
+        };
+        config
+    };
+    config.group = IMPORT_GROUP.to_string();
+
+    config.terminal_type = values.string("TerminalType");
+    config.columns = values.number("TermWidth").and_then(|cols| u16::try_from(cols).ok());
+    config.rows = values.number("TermHeight").and_then(|rows| u16::try_from(rows).ok());
+    // 旧版本只有以分钟为单位的 PingInterval
+    let ping_interval = values
+        .number("PingIntervalSecs")
+        .or_else(|| values.number("PingInterval").map(|minutes| minutes * 60));
+    if let Some(interval) = ping_interval {
+        config.keep_alive_interval = interval as u64;
+    }
+
+    if let Some(forwards) = values.string("PortForwardings") {
+        for forward in forwards.split(',').filter(|forward| !forward.is_empty()) {
+            match parse_forward(forward) {
+                Some(rule) => config.port_forwards.push(rule),
+                None => unsupported.push(format!("端口转发 {}", forward)),
+            }
+        }
+    }
+
+    let mut proxy_jump = Vec::new();
+    match values.number("ProxyMethod").unwrap_or(0) {
+        0 => {}
+        // PuTTY 0.77 起支持 SSH 跳板机
+        6 => {
+            if let Some(proxy_host) = values.string("ProxyHost") {
+                let port = values.number("ProxyPort").unwrap_or(22);
+                let reference = match values.string("ProxyUsername") {
+                    Some(username) => format!("{}@{}:{}", username, proxy_host, port),
+                    None => format!("{}:{}", proxy_host, port),
+                };
+                proxy_jump.push(reference);
+            }
+        }
+        method => unsupported.push(format!("代理（ProxyMethod={}）", method)),
+    }
+
+    if values.number("AgentFwd") == Some(1) {
+        unsupported.push("Agent 转发".to_string());
+    }
+    if values.number("X11Forward") == Some(1) {
+        unsupported.push("X11 转发".to_string());
+    }
+    if values.string("RemoteCommand").is_some() {
+        unsupported.push("远程命令".to_string());
+    }
+
+    let mut host = ImportedHost::new(name.to_string(), config);
+    host.proxy_jump = proxy_jump;
+    host.unsupported = unsupported;
+    Ok(host)
+}
+
+fn serial_config(values: &RegValues, unsupported: &mut Vec<String>) -> Option<SerialConfig> {
+    let parity = match values.number("SerialParity").unwrap_or(0) {
+        0 => SerialParity::None,
+        1 => SerialParity::Odd,
+        2 => SerialParity::Even,
+        _ => {
+            unsupported.push("Mark/Space 校验".to_string());
+            SerialParity::None
+        }
+    };
+    // 以半个停止位为单位：2 = 1 位，3 = 1.5 位，4 = 2 位
+    let stop_bits = match values.number("SerialStopHalfbits").unwrap_or(2) {
+        2 => SerialStopBits::One,
+        4 => SerialStopBits::Two,
+        _ => {
+            unsupported.push("1.5 个停止位".to_string());
+            SerialStopBits::One
+        }
+    };
+    let flow_control = match values.number("SerialFlowControl").unwrap_or(1) {
+        0 => SerialFlowControl::None,
+        1 => SerialFlowControl::Software,
+        2 => SerialFlowControl::Hardware,
+        _ => {
+            unsupported.push("DSR/DTR 流控".to_string());
+            SerialFlowControl::None
+        }
+    };
+
+    Some(SerialConfig {
+        path: values.string("SerialLine")?,
+        baud_rate: values.number("SerialSpeed").unwrap_or(9600),
+        data_bits: values
+            .number("SerialDataBits")
+            .and_then(|bits| u8::try_from(bits).ok())
+            .unwrap_or(8),
+        parity,
+        stop_bits,
+        flow_control,
+    })
+}
+
+/// 解析 PortForwardings 中的一项：`[4|6]L[addr:]port=host:port`、`R...`、`D[addr:]port`
+fn parse_forward(forward: &str) -> Option<PortForwardRule> {
+    let forward = forward.trim_start_matches(['4', '6']);
+    let mut chars = forward.chars();
+    let kind = match chars.next()? {
+        'L' => PortForwardKind::Local,
+        'R' => PortForwardKind::Remote,
+        'D' => PortForwardKind::Dynamic,
+        _ => return None,
+    };
+    let args: Vec<String> = chars.as_str().splitn(2, '=').map(str::to_string).collect();
+    ssh_config::parse_forward(kind, &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reg_export() {
+        let content = r#"Windows Registry Editor Version 5.00
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Default%20Settings]
+"HostName"=""
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Prod%20DB]
+"HostName"="deploy@db.example.com"
+"PortNumber"=dword:00000893
+"Protocol"="ssh"
+"PublicKeyFile"="C:\\Users\\me\\.ssh\\db.ppk"
+"PortForwardings"="L5432=localhost:5432,D1080,X9"
+"ProxyMethod"=dword:00000006
+"ProxyHost"="bastion.example.com"
+"ProxyPort"=dword:00000016
+"ProxyUsername"="ops"
+"AgentFwd"=dword:00000001
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Switch]
+"HostName"=""
+"Protocol"="serial"
+"SerialLine"="COM3"
+"SerialSpeed"=dword:00002580
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Old]
+"HostName"="old.example.com"
+"Protocol"="rlogin"
+"#;
+        let (hosts, warnings) = parse(content);
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(warnings, vec!["Old: 不支持 rlogin 协议，已跳过".to_string()]);
+
+        let db = &hosts[0];
+        assert_eq!(db.alias, "Prod DB");
+        assert_eq!((db.config.host.as_str(), db.config.port, db.config.username.as_str()), ("db.example.com", 2195, "deploy"));
+        assert!(matches!(
+            &db.config.auth_method,
+            AuthMethod::PublicKey { private_key_path, .. } if private_key_path == "C:\\Users\\me\\.ssh\\db.ppk"
+        ));
+        assert_eq!(db.config.port_forwards.len(), 2);
+        assert_eq!(db.proxy_jump, vec!["ops@bastion.example.com:22".to_string()]);
+        assert_eq!(db.unsupported, vec!["端口转发 X9".to_string(), "Agent 转发".to_string()]);
+
+        match &hosts[1].config.connection_type {
+            ConnectionType::Serial(serial) => assert_eq!((serial.path.as_str(), serial.baud_rate), ("COM3", 9600)),
+            other => panic!("unexpected connection type: {:?}", other),
+        }
+    }
+}
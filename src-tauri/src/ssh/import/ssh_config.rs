@@ -0,0 +1,685 @@
+// OpenSSH 客户端配置（~/.ssh/config）的导入与导出
+//
+// 导入支持 Host 块、Include（文件名可含通配符）和常用的连接选项。
+// 与 ssh 一样，同一选项以第一次出现的值为准，IdentityFile 和各类 Forward 可以出现多次。
+// 只导入不含通配符的 Host 别名，通配符块（如 `Host *`）中的选项按匹配规则合并进来；
+// Match 块无法在导入时求值，直接忽略。
+//
+// 导出只写出 SSH 会话的连接选项，不包含密码和私钥口令。
+
+use super::ImportedHost;
+use crate::error::{Result, SSHError};
+use crate::ssh::forwarding::{PortForwardKind, PortForwardRule};
+use crate::ssh::session::{whoami_fallback, AuthMethod, SessionConfig};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Include 最大嵌套深度，防止循环引用
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// 导入的会话所在分组
+pub const IMPORT_GROUP: &str = "ssh_config";
+
+/// 可以出现多次、全部生效的选项
+const CUMULATIVE_OPTIONS: &[&str] = &["identityfile", "certificatefile", "localforward", "remoteforward", "dynamicforward"];
+
+/// ~/.ssh/config 的默认位置
+pub fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh").join("config"))
+}
+
+/// 解析配置文件，返回可导入的主机和解析过程中的提示
+pub fn load(path: &Path) -> Result<(Vec<ImportedHost>, Vec<String>)> {
+    let config = SshConfig::load(path, dirs::home_dir().unwrap_or_default())?;
+    Ok(config.into_hosts())
+}
+
+enum Condition {
+    /// 第一个 Host 之前的全局选项
+    All,
+    Host(Vec<String>),
+    Match,
+}
+
+struct Block {
+    condition: Condition,
+    options: Vec<(String, Vec<String>)>,
+}
+
+struct SshConfig {
+    blocks: Vec<Block>,
+    warnings: Vec<String>,
+    home: PathBuf,
+}
+
+impl SshConfig {
+    fn load(path: &Path, home: PathBuf) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SSHError::Io(format!("无法读取 {}: {}", path.display(), e)))?;
+        let mut config = Self {
+            blocks: vec![Block {
+                condition: Condition::All,
+                options: Vec::new(),
+            }],
+            warnings: Vec::new(),
+            home,
+        };
+        config.parse(&content, path, 0);
+        Ok(config)
+    }
+
+    fn parse(&mut self, content: &str, source: &Path, depth: usize) {
+        for (index, line) in content.lines().enumerate() {
+            let Some((keyword, args)) = split_line(line) else {
+                continue;
+            };
+            let location = format!("{}:{}", source.display(), index + 1);
+
+            match keyword.as_str() {
+                "host" => self.blocks.push(Block {
+                    condition: Condition::Host(args),
+                    options: Vec::new(),
+                }),
+                "match" => {
+                    self.warnings.push(format!("{}: 不支持 Match 块，已忽略", location));
+                    self.blocks.push(Block {
+                        condition: Condition::Match,
+                        options: Vec::new(),
+                    });
+                }
+                "include" if depth >= MAX_INCLUDE_DEPTH => {
+                    self.warnings.push(format!("{}: Include 嵌套过深，已忽略", location));
+                }
+                "include" => {
+                    for pattern in &args {
+                        for path in self.include_paths(pattern) {
+                            match std::fs::read_to_string(&path) {
+                                Ok(content) => self.parse(&content, &path, depth + 1),
+                                Err(e) => self.warnings.push(format!("{}: 无法读取 {}: {}", location, path.display(), e)),
+                            }
+                        }
+                    }
+                }
+                _ if args.is_empty() => {
+                    self.warnings.push(format!("{}: {} 缺少参数", location, keyword));
+                }
+                _ => {
+                    if let Some(block) = self.blocks.last_mut() {
+                        block.options.push((keyword, args));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Include 的文件列表：相对路径基于 ~/.ssh，文件名中的通配符按字典序展开
+    fn include_paths(&self, pattern: &str) -> Vec<PathBuf> {
+        let path = match pattern.strip_prefix("~/") {
+            Some(rest) => self.home.join(rest),
+            None if Path::new(pattern).is_absolute() => PathBuf::from(pattern),
+            None => self.home.join(".ssh").join(pattern),
+        };
+
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy().into_owned()) else {
+            return Vec::new();
+        };
+        if !name.contains(['*', '?']) {
+            return vec![path];
+        }
+
+        let Some(parent) = path.parent() else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(parent)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| wildcard_match(&name, &entry.file_name().to_string_lossy()))
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        paths
+    }
+
+    /// 合并适用于该别名的所有选项
+    fn options_for(&self, alias: &str) -> Vec<(&str, &[String])> {
+        let mut resolved: Vec<(&str, &[String])> = Vec::new();
+        for block in &self.blocks {
+            let applies = match &block.condition {
+                Condition::All => true,
+                Condition::Host(patterns) => host_matches(patterns, alias),
+                Condition::Match => false,
+            };
+            if !applies {
+                continue;
+            }
+            for (keyword, args) in &block.options {
+                let keyword = keyword.as_str();
+                if CUMULATIVE_OPTIONS.contains(&keyword) || !resolved.iter().any(|(key, _)| *key == keyword) {
+                    resolved.push((keyword, args.as_slice()));
+                }
+            }
+        }
+        resolved
+    }
+
+    /// 所有可以导入的别名（不含通配符和否定模式），按出现顺序去重
+    fn aliases(&self) -> Vec<String> {
+        let mut aliases: Vec<String> = Vec::new();
+        for block in &self.blocks {
+            if let Condition::Host(patterns) = &block.condition {
+                for pattern in patterns {
+                    if !pattern.contains(['*', '?', '!']) && !aliases.contains(pattern) {
+                        aliases.push(pattern.clone());
+                    }
+                }
+            }
+        }
+        aliases
+    }
+
+    fn into_hosts(self) -> (Vec<ImportedHost>, Vec<String>) {
+        let hosts = self.aliases().iter().map(|alias| self.host(alias)).collect();
+        (hosts, self.warnings)
+    }
+
+    fn host(&self, alias: &str) -> ImportedHost {
+        let options = self.options_for(alias);
+        let first = |keyword: &str| {
+            options
+                .iter()
+                .find(|(key, _)| *key == keyword)
+                .map(|(_, args)| args[0].as_str())
+        };
+        let all = |keyword: &'static str| {
+            options
+                .iter()
+                .filter(move |(key, _)| *key == keyword)
+                .map(|(_, args)| *args)
+        };
+        let mut unsupported = Vec::new();
+
+        let hostname = first("hostname")
+            .map(|hostname| expand_tokens(hostname, &[('h', alias)]))
+            .unwrap_or_else(|| alias.to_string());
+        let port = match first("port") {
+            Some(port) => port.parse().unwrap_or_else(|_| {
+                unsupported.push(format!("无效的端口 {}，使用 22", port));
+                22
+            }),
+            None => 22,
+        };
+        let username = first("user").map(str::to_string).unwrap_or_else(whoami_fallback);
+
+        let home = self.home.to_string_lossy().into_owned();
+        let local_user = whoami_fallback();
+        let expand_path = |path: &str| {
+            let path = match path.strip_prefix("~/") {
+                Some(rest) => format!("{}/{}", home.trim_end_matches('/'), rest),
+                None => path.to_string(),
+            };
+            expand_tokens(&path, &[('d', &home), ('u', &local_user), ('r', &username), ('h', &hostname)])
+        };
+        let identity = all("identityfile")
+            .map(|args| args[0].as_str())
+            .find(|path| !path.eq_ignore_ascii_case("none"))
+            .map(expand_path);
+        let certificate = all("certificatefile")
+            .map(|args| args[0].as_str())
+            .find(|path| !path.eq_ignore_ascii_case("none"))
+            .map(expand_path);
+
+        let auth_method = match identity {
+            Some(private_key_path) => AuthMethod::PublicKey {
+                private_key_path,
+                passphrase: None,
+                certificate_path: certificate,
+            },
+            None => AuthMethod::Agent { identity: None },
+        };
+
+        let proxy_jump: Vec<String> = match first("proxyjump") {
+            Some(jumps) if !jumps.eq_ignore_ascii_case("none") => jumps
+                .split(',')
+                .map(|jump| jump.trim().trim_start_matches("ssh://").to_string())
+                .filter(|jump| !jump.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        };
+        if proxy_jump.is_empty() && first("proxycommand").is_some_and(|command| !command.eq_ignore_ascii_case("none")) {
+            unsupported.push("ProxyCommand".to_string());
+        }
+
+        let mut config = SessionConfig::ssh(alias.to_string(), hostname, port, username, auth_method);
+        config.group = IMPORT_GROUP.to_string();
+        if let Some(interval) = first("serveraliveinterval").and_then(|interval| interval.parse().ok()) {
+            config.keep_alive_interval = interval;
+        }
+        if first("stricthostkeychecking").is_some_and(|value| matches!(value.to_lowercase().as_str(), "no" | "off")) {
+            config.strict_host_key_checking = false;
+        }
+
+        for (keyword, kind) in [
+            ("localforward", PortForwardKind::Local),
+            ("remoteforward", PortForwardKind::Remote),
+            ("dynamicforward", PortForwardKind::Dynamic),
+        ] {
+            for args in all(keyword) {
+                match parse_forward(kind, args) {
+                    Some(rule) => config.port_forwards.push(rule),
+                    None => unsupported.push(format!("{} {}", keyword, args.join(" "))),
+                }
+            }
+        }
+
+        let mut host = ImportedHost::new(alias.to_string(), config);
+        host.proxy_jump = proxy_jump;
+        host.unsupported = unsupported;
+        host
+    }
+}
+
+/// 导出为 OpenSSH 配置
+///
+/// 别名由会话名生成（空白替换为 `-`，重名时追加序号），跳板机链转换为 ProxyJump，
+/// 引用的会话不在导出范围内时省略。非 SSH 会话只留一行注释。
+pub fn render(sessions: &[(String, SessionConfig)]) -> String {
+    let mut aliases: HashMap<&str, String> = HashMap::new();
+    let mut used = HashSet::new();
+    for (id, config) in sessions.iter().filter(|(_, config)| config.connection_type.is_ssh()) {
+        let base = alias_for(&config.name);
+        let mut alias = base.clone();
+        let mut suffix = 2;
+        while !used.insert(alias.clone()) {
+            alias = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        aliases.insert(id.as_str(), alias);
+    }
+
+    let mut output = String::from("# Exported by SSH Terminal (passwords and passphrases are not included)\n");
+    for (id, config) in sessions {
+        let Some(alias) = aliases.get(id.as_str()) else {
+            output.push_str(&format!("\n# Skipped non-SSH session: {}\n", config.name));
+            continue;
+        };
+
+        output.push_str(&format!("\nHost {}\n", alias));
+        push_option(&mut output, "HostName", &config.host);
+        if config.port != 22 {
+            push_option(&mut output, "Port", &config.port.to_string());
+        }
+        if !config.username.is_empty() {
+            push_option(&mut output, "User", &config.username);
+        }
+
+        match &config.auth_method {
+            AuthMethod::PublicKey { private_key_path, certificate_path, .. } => {
+                push_option(&mut output, "IdentityFile", private_key_path);
+                if let Some(certificate_path) = certificate_path {
+                    push_option(&mut output, "CertificateFile", certificate_path);
+                }
+            }
+            AuthMethod::Password { .. } => {
+                push_option(&mut output, "PreferredAuthentications", "keyboard-interactive,password");
+            }
+            AuthMethod::Agent { .. } => {}
+            // 私钥保存在应用数据库中，没有可引用的文件
+            AuthMethod::ManagedKey { key_id, .. } => {
+                output.push_str(&format!("    # Uses managed key {}, set IdentityFile manually\n", key_id));
+            }
+        }
+
+        let jumps: Vec<&str> = config
+            .jump_hosts
+            .iter()
+            .filter_map(|jump| aliases.get(jump.as_str()).map(String::as_str))
+            .collect();
+        if !jumps.is_empty() {
+            push_option(&mut output, "ProxyJump", &jumps.join(","));
+        }
+
+        for rule in config.port_forwards.iter().filter(|rule| rule.enabled) {
+            let listen = format_listen(&rule.bind_host, rule.bind_port);
+            let target = format_address(&rule.target_host, rule.target_port);
+            match rule.kind {
+                PortForwardKind::Local => push_option(&mut output, "LocalForward", &format!("{} {}", listen, target)),
+                PortForwardKind::Remote => push_option(&mut output, "RemoteForward", &format!("{} {}", listen, target)),
+                PortForwardKind::Dynamic => push_option(&mut output, "DynamicForward", &listen),
+            }
+        }
+
+        if config.keep_alive_interval > 0 {
+            push_option(&mut output, "ServerAliveInterval", &config.keep_alive_interval.to_string());
+        }
+        if !config.strict_host_key_checking {
+            push_option(&mut output, "StrictHostKeyChecking", "no");
+        }
+    }
+    output
+}
+
+/// 由会话名生成 Host 别名，去掉 ssh_config 中有特殊含义的字符
+fn alias_for(name: &str) -> String {
+    let alias: String = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .filter(|c| !matches!(c, '*' | '?' | '!' | '#' | '"' | '\'' | ','))
+        .collect();
+    if alias.is_empty() {
+        "host".to_string()
+    } else {
+        alias
+    }
+}
+
+fn push_option(output: &mut String, keyword: &str, value: &str) {
+    if value.contains(char::is_whitespace) && !matches!(keyword, "LocalForward" | "RemoteForward") {
+        output.push_str(&format!("    {} \"{}\"\n", keyword, value));
+    } else {
+        output.push_str(&format!("    {} {}\n", keyword, value));
+    }
+}
+
+fn format_address(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// 监听地址为本机回环时省略，与 ssh 的默认行为一致
+fn format_listen(host: &str, port: u16) -> String {
+    match host {
+        "" | "127.0.0.1" | "localhost" => port.to_string(),
+        "0.0.0.0" => format!("*:{}", port),
+        _ => format_address(host, port),
+    }
+}
+
+/// 拆分一行为关键字（小写）和参数，支持 `Key=Value` 写法和引号
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let end = line.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(line.len());
+    let keyword = line[..end].to_lowercase();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.peek() {
+            None | Some('#') => break,
+            _ => {}
+        }
+
+        let mut arg = String::new();
+        let mut quote = None;
+        while let Some(&c) = chars.peek() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => arg.push(c),
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c.is_whitespace() => break,
+                None => arg.push(c),
+            }
+            chars.next();
+        }
+        args.push(arg);
+    }
+
+    Some((keyword, args))
+}
+
+/// Host 模式匹配：任一否定模式匹配则不适用，否则需要至少一个模式匹配
+fn host_matches(patterns: &[String], host: &str) -> bool {
+    let host = host.to_lowercase();
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(&negated.to_lowercase(), &host) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(&pattern.to_lowercase(), &host),
+        }
+    }
+    matched
+}
+
+/// `*` 匹配任意字符串，`?` 匹配单个字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 替换 `%x` 形式的令牌，`%%` 表示 `%`，未知令牌原样保留
+fn expand_tokens(value: &str, tokens: &[(char, &str)]) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some(token) => match tokens.iter().find(|(key, _)| *key == token) {
+                Some((_, replacement)) => result.push_str(replacement),
+                None => {
+                    result.push('%');
+                    result.push(token);
+                }
+            },
+            None => result.push('%'),
+        }
+    }
+    result
+}
+
+/// 拆分 `host:port`，IPv6 地址写成 `[addr]:port`
+fn split_host_port(value: &str) -> Option<(String, u16)> {
+    let (host, port) = match value.strip_prefix('[') {
+        Some(rest) => rest.split_once("]:")?,
+        None => value.rsplit_once(':')?,
+    };
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+/// 解析 `[bind_address:]port`，`*` 表示监听所有地址
+fn parse_listen(listen: &str, default_bind: &str) -> Option<(String, u16)> {
+    if let Ok(port) = listen.parse() {
+        return Some((default_bind.to_string(), port));
+    }
+    let (host, port) = split_host_port(listen)?;
+    let host = if host == "*" || host.is_empty() { "0.0.0.0".to_string() } else { host };
+    Some((host, port))
+}
+
+/// 解析 LocalForward / RemoteForward / DynamicForward，不支持 Unix 套接字
+pub(super) fn parse_forward(kind: PortForwardKind, args: &[String]) -> Option<PortForwardRule> {
+    if args.iter().any(|arg| arg.contains('/')) {
+        return None;
+    }
+    let default_bind = match kind {
+        PortForwardKind::Remote => "localhost",
+        _ => "127.0.0.1",
+    };
+    let (bind_host, bind_port) = parse_listen(args.first()?, default_bind)?;
+
+    let (target_host, target_port) = match kind {
+        PortForwardKind::Dynamic => (String::new(), 0),
+        _ => split_host_port(args.get(1)?)?,
+    };
+
+    Some(PortForwardRule {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        bind_host,
+        bind_port,
+        target_host,
+        target_port,
+        enabled: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::session::ConnectionType;
+
+    #[test]
+    fn test_wildcard_and_split_line() {
+        assert!(wildcard_match("*.example.com", "db.example.com"));
+        assert!(wildcard_match("web-??", "web-01"));
+        assert!(!wildcard_match("web-??", "web-001"));
+        assert!(host_matches(&["*.internal".to_string(), "!secret.internal".to_string()], "DB.internal"));
+        assert!(!host_matches(&["*.internal".to_string(), "!secret.internal".to_string()], "secret.internal"));
+
+        assert_eq!(
+            split_line("  IdentityFile=\"~/.ssh/my key\" # comment"),
+            Some(("identityfile".to_string(), vec!["~/.ssh/my key".to_string()]))
+        );
+        assert_eq!(split_line("# comment"), None);
+        assert_eq!(expand_tokens("%h.example.com:100%%", &[('h', "db")]), "db.example.com:100%");
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let mut bastion = SessionConfig::ssh(
+            "bastion".to_string(),
+            "bastion.example.com".to_string(),
+            2222,
+            "ops".to_string(),
+            AuthMethod::PublicKey {
+                private_key_path: "/keys/my key".to_string(),
+                passphrase: Some("secret".to_string()),
+                certificate_path: None,
+            },
+        );
+        bastion.keep_alive_interval = 0;
+        let mut db = SessionConfig::ssh(
+            "prod db".to_string(),
+            "db.internal".to_string(),
+            22,
+            "admin".to_string(),
+            AuthMethod::Password { password: "secret".to_string() },
+        );
+        db.jump_hosts = vec!["b".to_string(), "missing".to_string()];
+        db.port_forwards = parse_forward(PortForwardKind::Local, &["5432".to_string(), "localhost:5432".to_string()])
+            .into_iter()
+            .collect();
+        let mut telnet = SessionConfig::ssh("switch".to_string(), "10.0.0.1".to_string(), 23, String::new(), AuthMethod::Agent { identity: None });
+        telnet.connection_type = ConnectionType::Telnet;
+
+        let sessions = vec![("b".to_string(), bastion), ("d".to_string(), db), ("t".to_string(), telnet)];
+        let rendered = render(&sessions);
+        assert!(!rendered.contains("secret"));
+
+        let home = std::env::temp_dir().join(format!("ssh-config-render-{}", std::process::id()));
+        std::fs::create_dir_all(&home).unwrap();
+        let path = home.join("config");
+        std::fs::write(&path, &rendered).unwrap();
+        let (hosts, warnings) = SshConfig::load(&path, home.clone()).unwrap().into_hosts();
+        let _ = std::fs::remove_dir_all(&home);
+
+        assert!(warnings.is_empty());
+        assert_eq!(hosts.len(), 2);
+        assert_eq!((hosts[0].alias.as_str(), hosts[0].config.port), ("bastion", 2222));
+        assert!(matches!(
+            &hosts[0].config.auth_method,
+            AuthMethod::PublicKey { private_key_path, .. } if private_key_path == "/keys/my key"
+        ));
+        assert_eq!(hosts[1].alias, "prod-db");
+        assert_eq!(hosts[1].proxy_jump, vec!["bastion".to_string()]);
+        assert_eq!(hosts[1].config.port_forwards[0].bind_port, 5432);
+    }
+
+    #[test]
+    fn test_load_config_with_include() {
+        let home = std::env::temp_dir().join(format!("ssh-config-test-{}", std::process::id()));
+        let ssh_dir = home.join(".ssh");
+        std::fs::create_dir_all(ssh_dir.join("conf.d")).unwrap();
+        std::fs::write(
+            ssh_dir.join("config"),
+            "User global\n\
+             Include conf.d/*.conf\n\
+             \n\
+             Host bastion\n\
+             \tHostName bastion.example.com\n\
+             \tPort 2222\n\
+             \tIdentityFile ~/.ssh/id_bastion\n\
+             \n\
+             Host db web\n\
+             \tHostName %h.internal\n\
+             \tProxyJump bastion\n\
+             \tLocalForward 5432 localhost:5432\n\
+             \tDynamicForward *:1080\n\
+             \n\
+             Match host *.internal\n\
+             \tUser ignored\n\
+             \n\
+             Host *\n\
+             \tServerAliveInterval 15\n\
+             \tUser fallback\n",
+        )
+        .unwrap();
+        std::fs::write(ssh_dir.join("conf.d").join("app.conf"), "Host app\n  HostName 10.0.0.5\n  User deploy\n").unwrap();
+
+        let config = SshConfig::load(&ssh_dir.join("config"), home.clone()).unwrap();
+        let (hosts, warnings) = config.into_hosts();
+        let _ = std::fs::remove_dir_all(&home);
+
+        let aliases: Vec<&str> = hosts.iter().map(|host| host.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["app", "bastion", "db", "web"]);
+        assert_eq!(warnings.len(), 1);
+
+        // 第一个 Host 之前的选项对所有主机生效，并且优先于后面的设置
+        let app = &hosts[0].config;
+        assert_eq!((app.host.as_str(), app.username.as_str()), ("10.0.0.5", "global"));
+        let bastion = &hosts[1].config;
+        assert_eq!((bastion.port, bastion.username.as_str(), bastion.keep_alive_interval), (2222, "global", 15));
+        match &bastion.auth_method {
+            AuthMethod::PublicKey { private_key_path, .. } => {
+                assert_eq!(Path::new(private_key_path), ssh_dir.join("id_bastion"));
+            }
+            other => panic!("unexpected auth method: {:?}", other),
+        }
+
+        let db = &hosts[2];
+        assert_eq!(db.config.host, "db.internal");
+        assert_eq!(db.proxy_jump, vec!["bastion".to_string()]);
+        assert_eq!(db.config.port_forwards.len(), 2);
+        assert_eq!((db.config.port_forwards[0].target_host.as_str(), db.config.port_forwards[0].target_port), ("localhost", 5432));
+        assert_eq!((db.config.port_forwards[1].bind_host.as_str(), db.config.port_forwards[1].bind_port), ("0.0.0.0", 1080));
+        assert!(matches!(db.config.auth_method, AuthMethod::Agent { .. }));
+    }
+}
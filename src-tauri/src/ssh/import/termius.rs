@@ -0,0 +1,190 @@
+// Termius 会话导入
+//
+// Termius 的 JSON 导出没有公开的格式说明，不同版本字段名也不一致，
+// 这里按 serde_json::Value 宽松解析：顶层可以是主机数组，也可以是 `{ hosts, groups }`。
+
+use super::ImportedHost;
+use crate::error::{Result, SSHError};
+use crate::ssh::session::{whoami_fallback, AuthMethod, SessionConfig};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 已识别的主机字段，其余字段作为不支持项报告
+const HOST_FIELDS: &[&str] = &[
+    "label", "name", "address", "host", "hostname", "port", "username", "user", "password",
+    "group", "group_id", "ssh_config",
+];
+
+/// 元数据字段，不报告
+const METADATA_FIELDS: &[&str] = &["id", "local_id", "remote_id", "created_at", "updated_at", "updated_by"];
+
+/// 分组嵌套的最大深度，防止 parent 形成环
+const MAX_GROUP_DEPTH: usize = 16;
+
+/// 解析 Termius 导出的 JSON，返回可导入的主机和被跳过的条目
+pub fn parse(content: &str) -> Result<(Vec<ImportedHost>, Vec<String>)> {
+    let root: Value = serde_json::from_str(content)
+        .map_err(|e| SSHError::Storage(format!("不是有效的 Termius 导出文件: {}", e)))?;
+
+    let (hosts, groups) = match &root {
+        Value::Array(hosts) => (hosts.as_slice(), &[][..]),
+        Value::Object(root) => (
+            root.get("hosts").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default(),
+            root.get("groups").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default(),
+        ),
+        _ => return Err(SSHError::Storage("不是有效的 Termius 导出文件".to_string())),
+    };
+    let groups = Groups::new(groups);
+
+    let mut imported = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, host) in hosts.iter().enumerate() {
+        let Some(host) = host.as_object() else {
+            warnings.push(format!("第 {} 项不是主机，已跳过", index + 1));
+            continue;
+        };
+        match session(host, &groups) {
+            Ok(host) => imported.push(host),
+            Err(reason) => {
+                let label = string(host, &["label", "name"]).unwrap_or_else(|| format!("第 {} 项", index + 1));
+                warnings.push(format!("{}: {}，已跳过", label, reason));
+            }
+        }
+    }
+
+    Ok((imported, warnings))
+}
+
+/// 分组 ID 到名称和父分组的映射
+struct Groups(HashMap<String, (String, Option<String>)>);
+
+impl Groups {
+    fn new(groups: &[Value]) -> Self {
+        let groups = groups
+            .iter()
+            .filter_map(Value::as_object)
+            .filter_map(|group| {
+                let id = id_of(group.get("id")?)?;
+                let label = string(group, &["label", "name"])?;
+                let parent = ["parent_group", "parent_id", "parent"]
+                    .iter()
+                    .find_map(|key| group.get(*key).and_then(id_of));
+                Some((id, (label, parent)))
+            })
+            .collect();
+        Self(groups)
+    }
+
+    /// 分组路径，如 `生产/数据库`
+    fn path(&self, id: &str) -> Option<String> {
+        let mut labels = Vec::new();
+        let mut current = Some(id.to_string());
+        while let Some(id) = current {
+            if labels.len() >= MAX_GROUP_DEPTH {
+                break;
+            }
+            let (label, parent) = self.0.get(&id)?;
+            labels.push(label.as_str());
+            current = parent.clone();
+        }
+        labels.reverse();
+        Some(labels.join("/"))
+    }
+}
+
+fn session(host: &Map<String, Value>, groups: &Groups) -> std::result::Result<ImportedHost, String> {
+    // 新版本把端口、用户名等放在嵌套的 ssh_config 中
+    let ssh_config = host.get("ssh_config").and_then(Value::as_object);
+    let field = |keys: &[&str]| string(host, keys).or_else(|| ssh_config.and_then(|config| string(config, keys)));
+
+    let address = field(&["address", "host", "hostname"]).ok_or("缺少主机地址")?;
+    let label = field(&["label", "name"]).unwrap_or_else(|| address.clone());
+    let port = field(&["port"]).and_then(|port| port.parse().ok()).unwrap_or(22);
+    let username = field(&["username", "user"]).unwrap_or_else(whoami_fallback);
+    let auth_method = match field(&["password"]) {
+        Some(password) => AuthMethod::Password { password },
+        None => AuthMethod::Agent { identity: None },
+    };
+
+    let mut config = SessionConfig::ssh(label.clone(), address, port, username, auth_method);
+    let group = match host.get("group") {
+        Some(Value::String(group)) => Some(group.clone()),
+        Some(Value::Object(group)) => string(group, &["label", "name"]),
+        _ => host.get("group_id").and_then(id_of).and_then(|id| groups.path(&id)),
+    };
+    if let Some(group) = group.filter(|group| !group.is_empty()) {
+        config.group = group;
+    }
+
+    let mut unsupported: Vec<String> = host
+        .keys()
+        .filter(|key| !HOST_FIELDS.contains(&key.as_str()) && !METADATA_FIELDS.contains(&key.as_str()))
+        .map(|key| format!("字段 {}", key))
+        .collect();
+    if let Some(ssh_config) = ssh_config {
+        unsupported.extend(
+            ssh_config
+                .keys()
+                .filter(|key| !HOST_FIELDS.contains(&key.as_str()) && !METADATA_FIELDS.contains(&key.as_str()))
+                .map(|key| format!("字段 ssh_config.{}", key)),
+        );
+    }
+
+    let mut host = ImportedHost::new(label, config);
+    host.unsupported = unsupported;
+    Ok(host)
+}
+
+/// 取第一个存在的字段，数字也按字符串返回（端口有时是字符串）
+fn string(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match object.get(*key)? {
+        Value::String(value) if !value.is_empty() => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    })
+}
+
+/// 分组 ID 可能是数字或字符串，也可能是 `{ id }`
+fn id_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        Value::Object(object) => object.get("id").and_then(id_of),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hosts_and_groups() {
+        let content = r#"{
+            "groups": [
+                { "id": 1, "label": "生产" },
+                { "id": 2, "label": "数据库", "parent_group": 1 }
+            ],
+            "hosts": [
+                { "id": 10, "label": "db", "address": "db.internal", "port": "2222", "username": "admin", "group_id": 2, "os_name": "ubuntu" },
+                { "label": "web", "address": "web.internal", "group": { "label": "测试" },
+                  "ssh_config": { "port": 22, "username": "deploy", "password": "secret", "mosh": true } },
+                { "label": "broken" }
+            ]
+        }"#;
+        let (hosts, warnings) = parse(content).unwrap();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(warnings, vec!["broken: 缺少主机地址，已跳过".to_string()]);
+
+        let db = &hosts[0];
+        assert_eq!(db.config.group, "生产/数据库");
+        assert_eq!((db.config.port, db.config.username.as_str()), (2222, "admin"));
+        assert_eq!(db.unsupported, vec!["字段 os_name".to_string()]);
+
+        let web = &hosts[1];
+        assert_eq!(web.config.group, "测试");
+        assert_eq!(web.config.username, "deploy");
+        assert!(matches!(&web.config.auth_method, AuthMethod::Password { password } if password == "secret"));
+        assert_eq!(web.unsupported, vec!["字段 ssh_config.mosh".to_string()]);
+    }
+}
@@ -0,0 +1,97 @@
+// WinSCP 会话导入
+//
+// 读取 WinSCP.ini（或「导出配置」得到的 ini）。每个 [Sessions\文件夹/名称] 节是
+// 一个站点，节名和取值都经过 `%XX` 编码。只导入 SCP/SFTP 站点。
+
+use super::{parse_ini, percent_decode, ImportedHost, IniSection};
+use crate::ssh::session::{whoami_fallback, AuthMethod, SessionConfig};
+
+const SESSIONS_PREFIX: &str = "Sessions\\";
+
+/// 解析 WinSCP.ini，返回可导入的主机和被跳过的站点
+pub fn parse(content: &str) -> (Vec<ImportedHost>, Vec<String>) {
+    let mut hosts = Vec::new();
+    let mut warnings = Vec::new();
+
+    for section in parse_ini(content) {
+        let Some(path) = section.name.strip_prefix(SESSIONS_PREFIX) else {
+            continue;
+        };
+        let path = percent_decode(path);
+        if path == "Default Settings" {
+            continue;
+        }
+
+        match session(&path, &section) {
+            Ok(host) => hosts.push(host),
+            Err(reason) => warnings.push(format!("{}: {}，已跳过", path, reason)),
+        }
+    }
+
+    (hosts, warnings)
+}
+
+fn session(path: &str, section: &IniSection) -> Result<ImportedHost, String> {
+    let value = |key: &str| section.get(key).map(percent_decode).filter(|value| !value.is_empty());
+
+    // 0 = SCP，1 = SFTP（可回退 SCP），2 = SFTP，5 = FTP，6 = WebDAV，7 = S3
+    match value("FSProtocol").as_deref() {
+        None | Some("0") | Some("1") | Some("2") => {}
+        Some("5") => return Err("不支持 FTP 站点".to_string()),
+        Some("6") => return Err("不支持 WebDAV 站点".to_string()),
+        Some("7") => return Err("不支持 S3 站点".to_string()),
+        Some(other) => return Err(format!("不支持的协议 {}", other)),
+    }
+
+    let (group, name) = match path.rsplit_once('/') {
+        Some((group, name)) => (Some(group), name),
+        None => (None, path),
+    };
+    let host = value("HostName").ok_or("缺少主机名")?;
+    let port = value("PortNumber").and_then(|port| port.parse().ok()).unwrap_or(22);
+    let username = value("UserName").unwrap_or_else(whoami_fallback);
+    let auth_method = match value("PublicKeyFile") {
+        Some(private_key_path) => AuthMethod::PublicKey {
+            private_key_path,
+            passphrase: None,
+            certificate_path: None,
+        },
+        None => AuthMethod::Agent { identity: None },
+    };
+
+    let mut config = SessionConfig::ssh(name.to_string(), host, port, username, auth_method);
+    if let Some(group) = group {
+        config.group = group.to_string();
+    }
+
+    let mut unsupported = Vec::new();
+    let mut proxy_jump = Vec::new();
+
+    // WinSCP 的密码用自有算法混淆，不做还原
+    if value("Password").is_some() {
+        unsupported.push("密码未导入".to_string());
+    }
+    if value("Tunnel").as_deref() == Some("1") {
+        if let Some(tunnel_host) = value("TunnelHostName") {
+            let port = value("TunnelPortNumber").unwrap_or_else(|| "22".to_string());
+            proxy_jump.push(match value("TunnelUserName") {
+                Some(username) => format!("{}@{}:{}", username, tunnel_host, port),
+                None => format!("{}:{}", tunnel_host, port),
+            });
+        }
+        if value("TunnelPublicKeyFile").is_some() || value("TunnelPassword").is_some() {
+            unsupported.push("隧道认证信息".to_string());
+        }
+    }
+    if value("ProxyMethod").is_some_and(|method| method != "0") {
+        unsupported.push("代理".to_string());
+    }
+    if value("RemoteDirectory").is_some() || value("LocalDirectory").is_some() {
+        unsupported.push("初始目录".to_string());
+    }
+
+    let mut host = ImportedHost::new(name.to_string(), config);
+    host.proxy_jump = proxy_jump;
+    host.unsupported = unsupported;
+    Ok(host)
+}
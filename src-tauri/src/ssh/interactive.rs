@@ -0,0 +1,146 @@
+// 键盘交互认证（keyboard-interactive）
+//
+// 服务器下发的提示（如 TOTP/OTP 验证码）通过事件发送到前端，
+// 前端通过 `session_keyboard_interactive_respond` 命令回传答案
+
+use crate::error::{Result, SSHError};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+/// 等待用户输入的最长时间
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 单个提示项
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InteractivePromptItem {
+    pub prompt: String,
+    /// 用户输入是否可以回显（false 表示应作为密码输入）
+    pub echo: bool,
+}
+
+/// 发送到前端的键盘交互认证请求
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InteractivePromptEvent {
+    pub request_id: String,
+    pub connection_id: String,
+    pub name: String,
+    pub instructions: String,
+    pub prompts: Vec<InteractivePromptItem>,
+}
+
+/// 键盘交互认证应答器
+///
+/// 后端在收到服务器的 InfoRequest 时调用，返回与提示一一对应的答案
+#[async_trait]
+pub trait InteractiveResponder: Send + Sync {
+    async fn respond(
+        &self,
+        name: String,
+        instructions: String,
+        prompts: Vec<InteractivePromptItem>,
+    ) -> Result<Vec<String>>;
+}
+
+type PendingResponses = Arc<StdMutex<HashMap<String, oneshot::Sender<Option<Vec<String>>>>>>;
+
+/// 通过 Tauri 事件与前端交互的键盘交互认证代理
+///
+/// 请求以 `ssh-keyboard-interactive-<connectionId>` 事件发出，
+/// 答案通过 [`KeyboardInteractiveBroker::submit`] 回传
+#[derive(Clone)]
+pub struct KeyboardInteractiveBroker {
+    app_handle: AppHandle,
+    pending: PendingResponses,
+}
+
+impl KeyboardInteractiveBroker {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            pending: Arc::new(StdMutex::new(HashMap::new())),
+        }
+    }
+
+    /// 为指定连接创建应答器
+    pub fn responder(&self, connection_id: &str) -> Arc<dyn InteractiveResponder> {
+        Arc::new(ConnectionResponder {
+            broker: self.clone(),
+            connection_id: connection_id.to_string(),
+        })
+    }
+
+    /// 提交前端的答案，responses 为 None 表示用户取消
+    pub fn submit(&self, request_id: &str, responses: Option<Vec<String>>) -> Result<()> {
+        let sender = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(request_id)
+            .ok_or_else(|| SSHError::NotFound(format!("键盘交互认证请求 {}", request_id)))?;
+
+        // 接收方已超时退出时忽略
+        let _ = sender.send(responses);
+        Ok(())
+    }
+}
+
+/// 绑定到单个连接的应答器
+struct ConnectionResponder {
+    broker: KeyboardInteractiveBroker,
+    connection_id: String,
+}
+
+#[async_trait]
+impl InteractiveResponder for ConnectionResponder {
+    async fn respond(
+        &self,
+        name: String,
+        instructions: String,
+        prompts: Vec<InteractivePromptItem>,
+    ) -> Result<Vec<String>> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (sender, receiver) = oneshot::channel();
+
+        self.broker
+            .pending
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), sender);
+
+        let event = InteractivePromptEvent {
+            request_id: request_id.clone(),
+            connection_id: self.connection_id.clone(),
+            name,
+            instructions,
+            prompts,
+        };
+        let event_name = format!("ssh-keyboard-interactive-{}", self.connection_id);
+        if let Err(e) = self.broker.app_handle.emit(&event_name, event) {
+            self.broker.pending.lock().unwrap().remove(&request_id);
+            return Err(SSHError::AuthenticationFailed(format!(
+                "无法发送键盘交互认证请求: {}",
+                e
+            )));
+        }
+
+        let result = tokio::time::timeout(RESPONSE_TIMEOUT, receiver).await;
+        self.broker.pending.lock().unwrap().remove(&request_id);
+
+        match result {
+            Ok(Ok(Some(responses))) => Ok(responses),
+            Ok(Ok(None)) | Ok(Err(_)) => Err(SSHError::AuthenticationFailed(
+                "用户取消了键盘交互认证".to_string(),
+            )),
+            Err(_) => Err(SSHError::AuthenticationFailed(
+                "等待键盘交互认证输入超时".to_string(),
+            )),
+        }
+    }
+}
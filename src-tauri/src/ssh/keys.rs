@@ -0,0 +1,194 @@
+//! 托管 SSH 密钥
+//!
+//! 密钥管理器生成或导入的私钥统一保存为 OpenSSH 格式（设置了密码时用 bcrypt-pbkdf + AES-256-CTR 加密），
+//! 再由数据库层用设备密钥加密存储。会话通过 `AuthMethod::ManagedKey` 按 ID 引用密钥，
+//! 连接时经 ManagedKeyStore 取出，SSH 层不直接访问数据库。
+
+use crate::error::{Result, SSHError};
+use rand::rngs::OsRng;
+use russh::keys::ssh_key::private::{KeypairData, RsaKeypair};
+use russh::keys::ssh_key::LineEnding;
+use russh::keys::{decode_secret_key, Algorithm, EcdsaCurve, HashAlg, PrivateKey, PublicKey};
+use serde::{Deserialize, Serialize};
+
+/// RSA 密钥默认长度
+pub const DEFAULT_RSA_BITS: usize = 4096;
+
+/// RSA 密钥最小长度（依赖开启了 hazmat-allow-insecure-rsa-keys，需要自行检查）
+const MIN_RSA_BITS: usize = 2048;
+
+/// 可生成的密钥类型
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyType {
+    Ed25519,
+    EcdsaP256,
+    EcdsaP384,
+    EcdsaP521,
+    Rsa,
+}
+
+/// 按 ID 加载托管密钥，由数据库层实现
+pub trait ManagedKeyStore: Send + Sync {
+    /// 返回解密后的私钥，passphrase 为会话中保存的密钥密码
+    fn load_private_key(&self, key_id: &str, passphrase: Option<&str>) -> Result<PrivateKey>;
+}
+
+/// 生成新密钥，bits 只对 RSA 有效
+pub fn generate(key_type: KeyType, bits: Option<usize>, comment: &str) -> Result<PrivateKey> {
+    let mut key = match key_type {
+        KeyType::Ed25519 => PrivateKey::random(&mut OsRng, Algorithm::Ed25519),
+        KeyType::EcdsaP256 => PrivateKey::random(&mut OsRng, Algorithm::Ecdsa { curve: EcdsaCurve::NistP256 }),
+        KeyType::EcdsaP384 => PrivateKey::random(&mut OsRng, Algorithm::Ecdsa { curve: EcdsaCurve::NistP384 }),
+        KeyType::EcdsaP521 => PrivateKey::random(&mut OsRng, Algorithm::Ecdsa { curve: EcdsaCurve::NistP521 }),
+        KeyType::Rsa => {
+            let bits = bits.unwrap_or(DEFAULT_RSA_BITS);
+            if bits < MIN_RSA_BITS {
+                return Err(SSHError::Crypto(format!("RSA 密钥长度不能小于 {} 位", MIN_RSA_BITS)));
+            }
+            RsaKeypair::random(&mut OsRng, bits)
+                .and_then(|keypair| PrivateKey::new(KeypairData::from(keypair), ""))
+        }
+    }
+    .map_err(|e| SSHError::Crypto(format!("生成密钥失败: {}", e)))?;
+
+    key.set_comment(comment);
+    Ok(key)
+}
+
+/// 解析导入的私钥（OpenSSH、PEM、PKCS#8 或 PuTTY .ppk），加密的私钥需要提供密码
+pub fn parse(content: &str, passphrase: Option<&str>) -> Result<PrivateKey> {
+    decode_secret_key(content, passphrase).map_err(|e| match e {
+        russh::keys::Error::KeyIsEncrypted => SSHError::Crypto("私钥已加密，请输入密码".to_string()),
+        e => SSHError::Crypto(format!("无法解析私钥: {}", e)),
+    })
+}
+
+/// 编码为 OpenSSH 格式，passphrase 非空时加密
+pub fn encode(key: &PrivateKey, passphrase: Option<&str>) -> Result<String> {
+    let key = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => key
+            .encrypt(&mut OsRng, passphrase)
+            .map_err(|e| SSHError::Crypto(format!("加密私钥失败: {}", e)))?,
+        None => key.clone(),
+    };
+
+    key.to_openssh(LineEnding::LF)
+        .map(|pem| pem.to_string())
+        .map_err(|e| SSHError::Crypto(format!("编码私钥失败: {}", e)))
+}
+
+/// 解码 encode 的结果
+pub fn decode(pem: &str, passphrase: Option<&str>) -> Result<PrivateKey> {
+    let key = PrivateKey::from_openssh(pem)
+        .map_err(|e| SSHError::Crypto(format!("无法解析私钥: {}", e)))?;
+    if !key.is_encrypted() {
+        return Ok(key);
+    }
+
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| SSHError::Crypto("私钥已加密，请输入密码".to_string()))?;
+    key.decrypt(passphrase)
+        .map_err(|_| SSHError::Crypto("私钥密码错误".to_string()))
+}
+
+/// 修改私钥密码，new_passphrase 为空时移除密码
+pub fn change_passphrase(pem: &str, old_passphrase: Option<&str>, new_passphrase: Option<&str>) -> Result<String> {
+    encode(&decode(pem, old_passphrase)?, new_passphrase)
+}
+
+/// 公钥（authorized_keys 格式，含注释）
+pub fn public_key_line(key: &PrivateKey) -> Result<String> {
+    key.public_key()
+        .to_openssh()
+        .map_err(|e| SSHError::Crypto(format!("编码公钥失败: {}", e)))
+}
+
+/// SHA256 指纹
+pub fn fingerprint(key: &PrivateKey) -> String {
+    key.fingerprint(HashAlg::Sha256).to_string()
+}
+
+/// 计算需要追加到 authorized_keys 末尾的内容，已存在相同密钥（忽略选项和注释）时返回 None
+///
+/// 公钥必须是单行的 OpenSSH 格式（`算法 密钥 [注释]`）
+pub fn add_authorized_key(content: &str, public_key: &str) -> Result<Option<String>> {
+    let public_key = public_key.trim();
+    if public_key.contains(['\n', '\r']) {
+        return Err(SSHError::Crypto("无效的公钥: 只能包含一行".to_string()));
+    }
+    let key = PublicKey::from_openssh(public_key)
+        .map_err(|e| SSHError::Crypto(format!("无效的公钥: {}", e)))?;
+    let blob = key
+        .to_openssh()
+        .map_err(|e| SSHError::Crypto(format!("编码公钥失败: {}", e)))?
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+
+    // 选项中可能带引号和空格，但密钥本身总是独立的一段
+    let exists = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .any(|line| line.split_whitespace().any(|token| token == blob));
+    if exists {
+        return Ok(None);
+    }
+
+    let mut addition = String::new();
+    if !content.is_empty() && !content.ends_with('\n') {
+        addition.push('\n');
+    }
+    addition.push_str(public_key);
+    addition.push('\n');
+    Ok(Some(addition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_encode_and_change_passphrase() {
+        let key = generate(KeyType::Ed25519, None, "me@laptop").unwrap();
+        assert!(public_key_line(&key).unwrap().starts_with("ssh-ed25519 "));
+        assert!(public_key_line(&key).unwrap().ends_with(" me@laptop"));
+
+        let pem = encode(&key, Some("old")).unwrap();
+        assert!(decode(&pem, None).is_err());
+        assert!(decode(&pem, Some("wrong")).is_err());
+        assert_eq!(fingerprint(&decode(&pem, Some("old")).unwrap()), fingerprint(&key));
+
+        // 移除密码后无需密码即可解码
+        let pem = change_passphrase(&pem, Some("old"), None).unwrap();
+        let restored = decode(&pem, None).unwrap();
+        assert_eq!(fingerprint(&restored), fingerprint(&key));
+        assert_eq!(restored.comment(), "me@laptop");
+
+        // 导入时同样接受 OpenSSH 格式
+        assert_eq!(fingerprint(&parse(&pem, None).unwrap()), fingerprint(&key));
+        assert!(generate(KeyType::Rsa, Some(1024), "").is_err());
+    }
+
+    #[test]
+    fn test_add_authorized_key() {
+        let key = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIH2b2C9qwB5nRbxmELoYf79Vk8BDIELF3i0rea5Tw3ys me@laptop";
+        // 只返回需要追加的内容，原文件缺少结尾换行时先补上
+        let addition = add_authorized_key("ssh-rsa AAAAB3Other other", key).unwrap().unwrap();
+        assert_eq!(addition, format!("\n{}\n", key));
+        assert_eq!(add_authorized_key("", key).unwrap().unwrap(), format!("{}\n", key));
+
+        // 已存在（带选项、不同注释）时不重复添加
+        let existing = "# keys\nfrom=\"10.0.0.1\",no-pty ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIH2b2C9qwB5nRbxmELoYf79Vk8BDIELF3i0rea5Tw3ys old\n";
+        assert!(add_authorized_key(existing, key).unwrap().is_none());
+
+        assert!(add_authorized_key("", "garbage").is_err());
+        assert!(add_authorized_key("", "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIKey me@laptop").is_err());
+        // 多行输入可能注入额外的密钥
+        let injected = format!("{}\nssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHI7vlVq9aLGwIKlhp1BqZql8ZxaUXESWNhrKiAMhWsj evil", key);
+        assert!(add_authorized_key("", &injected).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+//! known_hosts 主机密钥信任库
+//!
+//! 解析 OpenSSH 格式的 known_hosts 文件，支持：
+//! - 明文主机名和通配符模式（`*`、`?`、`!` 取反）
+//! - 哈希主机名（`|1|salt|hash`，HMAC-SHA1）
+//! - 非默认端口（`[host]:port`）
+//! - `@revoked` 标记；`@cert-authority` 行只做识别，不会被当作主机密钥
+//!
+//! 已知限制：不支持主机证书验证。russh 不协商 `*-cert-v01@openssh.com` 主机密钥算法，
+//! 服务器出示的始终是普通主机密钥，无法按 `@cert-authority` 验证。
+//! 只通过 `@cert-authority` 信任的主机会得到 [`HostKeyStatus::CertAuthorityOnly`]，
+//! 严格模式下以 [`SSHError::HostCertificateUnsupported`] 拒绝，而不是当作未知主机请用户确认指纹
+//!
+//! 读取用户的 `~/.ssh/known_hosts`（只读）和应用自己的 known_hosts 文件，
+//! 新接受的主机密钥只写入应用自己的文件。
+
+use crate::error::{Result, SSHError};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use russh::keys::{HashAlg, PublicKey};
+use sha1::Sha1;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// known_hosts 行标记
+#[derive(Debug, Clone, PartialEq)]
+pub enum KnownHostMarker {
+    /// 普通主机密钥
+    None,
+    /// `@revoked`：该密钥已被吊销，任何主机出示都必须拒绝
+    Revoked,
+    /// `@cert-authority`：该密钥是主机证书的签发 CA（不参与主机密钥匹配）
+    CertAuthority,
+}
+
+/// 主机匹配规则
+#[derive(Debug, Clone)]
+pub enum HostPatterns {
+    /// 逗号分隔的明文模式列表
+    Plain(Vec<String>),
+    /// 哈希主机名：HMAC-SHA1(salt, host)
+    Hashed { salt: Vec<u8>, hash: Vec<u8> },
+}
+
+/// known_hosts 中的一条记录
+#[derive(Debug, Clone)]
+pub struct KnownHostEntry {
+    pub marker: KnownHostMarker,
+    pub hosts: HostPatterns,
+    pub key: PublicKey,
+}
+
+/// 主机密钥验证结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostKeyStatus {
+    /// 已知且匹配
+    Trusted,
+    /// 没有该主机的记录
+    Unknown,
+    /// 已有记录但密钥不同（可能存在中间人攻击）
+    Changed { expected_fingerprint: String },
+    /// 密钥已被吊销
+    Revoked,
+    /// 没有该主机的密钥记录，但有匹配的 `@cert-authority` 行（不支持主机证书验证）
+    CertAuthorityOnly,
+}
+
+/// 计算公钥的 SHA256 指纹（与 `ssh-keygen -l` 输出格式一致）
+pub fn fingerprint(key: &PublicKey) -> String {
+    key.fingerprint(HashAlg::Sha256).to_string()
+}
+
+/// 生成 known_hosts 中使用的主机名（非 22 端口使用 `[host]:port`）
+pub fn host_key_name(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{}]:{}", host, port)
+    }
+}
+
+impl HostPatterns {
+    /// 检查主机名是否匹配
+    ///
+    /// `!` 开头的取反模式一旦匹配，整行视为不匹配
+    pub fn matches(&self, host_name: &str) -> bool {
+        match self {
+            HostPatterns::Plain(patterns) => {
+                let mut matched = false;
+                for pattern in patterns {
+                    if let Some(negated) = pattern.strip_prefix('!') {
+                        if wildcard_match(negated, host_name) {
+                            return false;
+                        }
+                    } else if wildcard_match(pattern, host_name) {
+                        matched = true;
+                    }
+                }
+                matched
+            }
+            HostPatterns::Hashed { salt, hash } => {
+                let mut mac = match Hmac::<Sha1>::new_from_slice(salt) {
+                    Ok(mac) => mac,
+                    Err(_) => return false,
+                };
+                mac.update(host_name.as_bytes());
+                mac.verify_slice(hash).is_ok()
+            }
+        }
+    }
+
+    /// 解析主机字段
+    fn parse(field: &str) -> Option<Self> {
+        if let Some(rest) = field.strip_prefix("|1|") {
+            let (salt_b64, hash_b64) = rest.split_once('|')?;
+            let engine = base64::engine::general_purpose::STANDARD;
+            let salt = engine.decode(salt_b64).ok()?;
+            let hash = engine.decode(hash_b64).ok()?;
+            Some(HostPatterns::Hashed { salt, hash })
+        } else {
+            let patterns = field
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.to_lowercase())
+                .collect::<Vec<_>>();
+            if patterns.is_empty() {
+                None
+            } else {
+                Some(HostPatterns::Plain(patterns))
+            }
+        }
+    }
+}
+
+/// OpenSSH 风格的通配符匹配（`*` 匹配任意串，`?` 匹配单个字符）
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut pi, mut ti) = (0usize, 0usize);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = star {
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            star = Some((star_pi, star_ti + 1));
+        } else {
+            return false;
+        }
+    }
+
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+/// 解析 known_hosts 的一行，无法识别的行返回 None
+pub fn parse_line(line: &str) -> Option<KnownHostEntry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut fields = line.split_whitespace();
+    let mut first = fields.next()?;
+
+    let marker = match first {
+        "@revoked" => {
+            first = fields.next()?;
+            KnownHostMarker::Revoked
+        }
+        "@cert-authority" => {
+            first = fields.next()?;
+            KnownHostMarker::CertAuthority
+        }
+        other if other.starts_with('@') => return None,
+        _ => KnownHostMarker::None,
+    };
+
+    let hosts = HostPatterns::parse(first)?;
+    let key_type = fields.next()?;
+    let key_data = fields.next()?;
+    let key = PublicKey::from_openssh(&format!("{} {}", key_type, key_data)).ok()?;
+
+    Some(KnownHostEntry { marker, hosts, key })
+}
+
+/// 主机密钥信任库
+pub struct KnownHosts {
+    entries: Vec<KnownHostEntry>,
+    /// 新密钥写入的文件
+    store_path: PathBuf,
+}
+
+impl KnownHosts {
+    /// 从默认位置加载：用户的 `~/.ssh/known_hosts` 与应用的 known_hosts
+    pub fn load_default() -> Result<Self> {
+        let store_path = Self::app_known_hosts_path()?;
+        let mut sources = Vec::new();
+        if let Some(home) = dirs::home_dir() {
+            sources.push(home.join(".ssh").join("known_hosts"));
+        }
+        sources.push(store_path.clone());
+        Ok(Self::load(&sources, store_path))
+    }
+
+    /// 从指定的文件列表加载，不存在的文件会被忽略
+    pub fn load(sources: &[PathBuf], store_path: PathBuf) -> Self {
+        let mut entries = Vec::new();
+        for path in sources {
+            match fs::read_to_string(path) {
+                Ok(content) => {
+                    let before = entries.len();
+                    entries.extend(content.lines().filter_map(parse_line));
+                    tracing::debug!("Loaded {} known host entries from {:?}", entries.len() - before, path);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    tracing::warn!("Failed to read known_hosts file {:?}: {}", path, e);
+                }
+            }
+        }
+        Self { entries, store_path }
+    }
+
+    /// 应用自己的 known_hosts 文件路径
+    pub fn app_known_hosts_path() -> Result<PathBuf> {
+        Ok(crate::config::Storage::get_app_storage_dir()?.join("known_hosts"))
+    }
+
+    /// 验证服务器出示的主机密钥
+    pub fn verify(&self, host: &str, port: u16, key: &PublicKey) -> HostKeyStatus {
+        let host_name = host_key_name(host, port);
+
+        // 吊销检查优先于一切匹配
+        if self.entries.iter().any(|e| {
+            e.marker == KnownHostMarker::Revoked && e.key.key_data() == key.key_data()
+        }) {
+            return HostKeyStatus::Revoked;
+        }
+
+        let mut expected: Option<&PublicKey> = None;
+        for entry in self.entries.iter().filter(|e| e.marker == KnownHostMarker::None) {
+            if !entry.hosts.matches(&host_name) {
+                continue;
+            }
+            if entry.key.key_data() == key.key_data() {
+                return HostKeyStatus::Trusted;
+            }
+            // 只有同算法的不同密钥才算变更，其他算法的记录不影响判断
+            if entry.key.algorithm() == key.algorithm() && expected.is_none() {
+                expected = Some(&entry.key);
+            }
+        }
+
+        match expected {
+            Some(old) => HostKeyStatus::Changed {
+                expected_fingerprint: fingerprint(old),
+            },
+            None if self.entries.iter().any(|e| {
+                e.marker == KnownHostMarker::CertAuthority && e.hosts.matches(&host_name)
+            }) => HostKeyStatus::CertAuthorityOnly,
+            None => HostKeyStatus::Unknown,
+        }
+    }
+
+    /// 用户确认指纹后信任未知主机出示的密钥
+    ///
+    /// `public_key` 为 `SSHError::HostKeyUnknown` 中保存的公钥；
+    /// 指纹与用户确认的不一致、密钥已被吊销或该主机已有不同密钥时拒绝
+    pub fn trust(&mut self, host: &str, port: u16, public_key: &str, confirmed_fingerprint: &str) -> Result<()> {
+        let key = PublicKey::from_openssh(public_key)
+            .map_err(|e| SSHError::Storage(format!("Invalid host key: {}", e)))?;
+        let actual_fingerprint = fingerprint(&key);
+        if actual_fingerprint != confirmed_fingerprint {
+            return Err(SSHError::Storage(format!(
+                "主机密钥指纹不一致（确认的指纹: {}，服务器指纹: {}）",
+                confirmed_fingerprint, actual_fingerprint
+            )));
+        }
+
+        match self.verify(host, port, &key) {
+            HostKeyStatus::Trusted => Ok(()),
+            HostKeyStatus::Unknown | HostKeyStatus::CertAuthorityOnly => self.learn(host, port, &key),
+            HostKeyStatus::Changed { expected_fingerprint } => Err(SSHError::HostKeyMismatch {
+                host: host.to_string(),
+                port,
+                expected_fingerprint,
+                actual_fingerprint,
+            }),
+            HostKeyStatus::Revoked => Err(SSHError::HostKeyRevoked {
+                host: host.to_string(),
+                port,
+                fingerprint: actual_fingerprint,
+            }),
+        }
+    }
+
+    /// 记住新接受的主机密钥（追加到应用的 known_hosts）
+    pub fn learn(&mut self, host: &str, port: u16, key: &PublicKey) -> Result<()> {
+        let host_name = host_key_name(host, port);
+        let key_line = encode_key(key)?;
+
+        append_line(&self.store_path, &format!("{} {}", host_name, key_line))?;
+
+        self.entries.push(KnownHostEntry {
+            marker: KnownHostMarker::None,
+            hosts: HostPatterns::Plain(vec![host_name.to_lowercase()]),
+            key: key.clone(),
+        });
+        Ok(())
+    }
+}
+
+/// 连接时的主机密钥检查
+///
+/// - 已知且匹配：接受
+/// - 未知主机：严格模式下拒绝（等待用户确认指纹），否则接受并写入 known_hosts（首次使用信任）
+/// - 密钥变更：严格模式下拒绝，否则仅记录警告
+/// - 已吊销：始终拒绝
+/// - 只通过 `@cert-authority` 信任：不支持主机证书验证，严格模式下拒绝，否则同未知主机
+pub struct HostKeyVerifier {
+    host: String,
+    port: u16,
+    strict: bool,
+    /// 指定的 known_hosts 来源和写入文件，None 时使用默认位置
+    files: Option<(Vec<PathBuf>, PathBuf)>,
+}
+
+impl HostKeyVerifier {
+    pub fn new(host: &str, port: u16, strict: bool) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            strict,
+            files: None,
+        }
+    }
+
+    /// 使用指定的 known_hosts 文件，不读写用户目录
+    #[cfg(test)]
+    pub fn with_files(mut self, sources: Vec<PathBuf>, store_path: PathBuf) -> Self {
+        self.files = Some((sources, store_path));
+        self
+    }
+
+    fn load(&self) -> Result<KnownHosts> {
+        match &self.files {
+            Some((sources, store_path)) => Ok(KnownHosts::load(sources, store_path.clone())),
+            None => KnownHosts::load_default(),
+        }
+    }
+
+    /// 检查服务器出示的主机密钥，返回 Err 表示拒绝连接
+    pub fn check(&self, key: &PublicKey) -> Result<()> {
+        let actual_fingerprint = fingerprint(key);
+
+        let mut store = match self.load() {
+            Ok(store) => store,
+            Err(e) if self.strict => return Err(e),
+            Err(e) => {
+                tracing::warn!("Failed to load known_hosts, accepting host key {}: {}", actual_fingerprint, e);
+                return Ok(());
+            }
+        };
+
+        match store.verify(&self.host, self.port, key) {
+            HostKeyStatus::Trusted => {
+                tracing::debug!("Host key for {}:{} verified: {}", self.host, self.port, actual_fingerprint);
+                Ok(())
+            }
+            HostKeyStatus::Unknown if self.strict => {
+                // 严格模式下不自动信任，由前端向用户确认指纹后写入 known_hosts 再重连
+                Err(SSHError::HostKeyUnknown {
+                    host: self.host.clone(),
+                    port: self.port,
+                    fingerprint: actual_fingerprint,
+                    public_key: encode_key(key).unwrap_or_default(),
+                })
+            }
+            HostKeyStatus::CertAuthorityOnly if self.strict => Err(SSHError::HostCertificateUnsupported {
+                host: self.host.clone(),
+                port: self.port,
+                fingerprint: actual_fingerprint,
+            }),
+            status @ (HostKeyStatus::CertAuthorityOnly | HostKeyStatus::Unknown) => {
+                if status == HostKeyStatus::CertAuthorityOnly {
+                    tracing::warn!(
+                        "{}:{} is only trusted through @cert-authority, host certificates are not supported",
+                        self.host, self.port
+                    );
+                }
+                tracing::info!(
+                    "Permanently adding host key for {}:{} to known_hosts: {}",
+                    self.host, self.port, actual_fingerprint
+                );
+                if let Err(e) = store.learn(&self.host, self.port, key) {
+                    tracing::warn!("Failed to save host key to known_hosts: {}", e);
+                }
+                Ok(())
+            }
+            HostKeyStatus::Changed { expected_fingerprint } if self.strict => Err(SSHError::HostKeyMismatch {
+                host: self.host.clone(),
+                port: self.port,
+                expected_fingerprint,
+                actual_fingerprint,
+            }),
+            HostKeyStatus::Changed { expected_fingerprint } => {
+                tracing::warn!(
+                    "Host key for {}:{} changed ({} -> {}), accepting because strict host key checking is disabled",
+                    self.host, self.port, expected_fingerprint, actual_fingerprint
+                );
+                Ok(())
+            }
+            HostKeyStatus::Revoked => Err(SSHError::HostKeyRevoked {
+                host: self.host.clone(),
+                port: self.port,
+                fingerprint: actual_fingerprint,
+            }),
+        }
+    }
+}
+
+/// 编码为 known_hosts 中使用的 `算法 密钥` 格式
+pub fn encode_key(key: &PublicKey) -> Result<String> {
+    let key_line = key
+        .to_openssh()
+        .map_err(|e| SSHError::Storage(format!("Failed to encode host key: {}", e)))?;
+    // to_openssh 会带上注释，known_hosts 中只保留算法和密钥
+    Ok(key_line.split_whitespace().take(2).collect::<Vec<_>>().join(" "))
+}
+
+/// 追加一行到 known_hosts 文件（必要时创建目录）
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| SSHError::Storage(format!("Failed to create known_hosts directory: {}", e)))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| SSHError::Storage(format!("Failed to open known_hosts: {}", e)))?;
+
+    writeln!(file, "{}", line)
+        .map_err(|e| SSHError::Storage(format!("Failed to write known_hosts: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIH2b2C9qwB5nRbxmELoYf79Vk8BDIELF3i0rea5Tw3ys";
+    const KEY_B: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHI7vlVq9aLGwIKlhp1BqZql8ZxaUXESWNhrKiAMhWsj";
+
+    fn key(s: &str) -> PublicKey {
+        PublicKey::from_openssh(s).unwrap()
+    }
+
+    fn store(lines: &[String]) -> KnownHosts {
+        KnownHosts {
+            entries: lines.iter().filter_map(|l| parse_line(l)).collect(),
+            store_path: PathBuf::from("/nonexistent/known_hosts"),
+        }
+    }
+
+    #[test]
+    fn test_plain_and_port_entries() {
+        let kh = store(&[
+            format!("example.com,10.0.0.1 {}", KEY_A),
+            format!("[example.com]:2222 {}", KEY_B),
+        ]);
+
+        assert_eq!(kh.verify("example.com", 22, &key(KEY_A)), HostKeyStatus::Trusted);
+        assert_eq!(kh.verify("10.0.0.1", 22, &key(KEY_A)), HostKeyStatus::Trusted);
+        assert_eq!(kh.verify("example.com", 2222, &key(KEY_B)), HostKeyStatus::Trusted);
+        assert_eq!(kh.verify("other.com", 22, &key(KEY_A)), HostKeyStatus::Unknown);
+    }
+
+    #[test]
+    fn test_hashed_entries() {
+        // 由 `ssh-keygen -H` 生成
+        let kh = store(&[
+            format!("|1|KOkdZARUoMn8tp8OWJZGL3P08po=|g9AJtj5WUemWL/DOEsel320TXcM= {}", KEY_A),
+            format!("|1|cdcpTBlMMkhBSDLTZcSM9KbFPtM=|cMLdtRAVAo4q0JlGtpxoHM1urCM= {}", KEY_A),
+        ]);
+
+        assert_eq!(kh.verify("example.com", 22, &key(KEY_A)), HostKeyStatus::Trusted);
+        assert_eq!(kh.verify("example.com", 2222, &key(KEY_A)), HostKeyStatus::Trusted);
+        assert_eq!(kh.verify("example.org", 22, &key(KEY_A)), HostKeyStatus::Unknown);
+    }
+
+    #[test]
+    fn test_changed_key_reports_old_fingerprint() {
+        let kh = store(&[format!("example.com {}", KEY_A)]);
+
+        assert_eq!(
+            kh.verify("example.com", 22, &key(KEY_B)),
+            HostKeyStatus::Changed {
+                expected_fingerprint: "SHA256:fqUZsOMHMdkww0YQc5IXGVQmBOl9VXOw28dc0z5mAvA".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_revoked_and_cert_authority() {
+        let kh = store(&[
+            format!("@revoked * {}", KEY_B),
+            format!("@cert-authority *.example.com {}", KEY_A),
+            format!("host.example.com {}", KEY_B),
+        ]);
+
+        assert_eq!(kh.verify("host.example.com", 22, &key(KEY_B)), HostKeyStatus::Revoked);
+        // CA 公钥不应被当作普通主机密钥；只有 CA 记录的主机单独报告，不当作未知主机
+        assert_eq!(kh.verify("db.example.com", 22, &key(KEY_A)), HostKeyStatus::CertAuthorityOnly);
+        assert_eq!(kh.verify("db.example.org", 22, &key(KEY_A)), HostKeyStatus::Unknown);
+        assert_eq!(kh.entries[1].marker, KnownHostMarker::CertAuthority);
+    }
+
+    #[test]
+    fn test_cert_authority_only_host_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("known-hosts-ca-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let store_path = dir.join("known_hosts");
+        fs::write(&store_path, format!("@cert-authority *.example.com {}\n", KEY_A)).unwrap();
+        let verifier = |strict| {
+            HostKeyVerifier::new("db.example.com", 22, strict).with_files(vec![store_path.clone()], store_path.clone())
+        };
+
+        assert!(matches!(
+            verifier(true).check(&key(KEY_B)),
+            Err(SSHError::HostCertificateUnsupported { .. })
+        ));
+        // 非严格模式下同未知主机，记住出示的主机密钥
+        verifier(false).check(&key(KEY_B)).unwrap();
+        assert!(verifier(true).check(&key(KEY_B)).is_ok());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_trust_confirmed_host_key() {
+        let dir = std::env::temp_dir().join(format!("known-hosts-test-{}", std::process::id()));
+        let store_path = dir.join("known_hosts");
+        let mut kh = KnownHosts::load(&[], store_path.clone());
+        kh.entries.push(parse_line(&format!("@revoked * {}", KEY_B)).unwrap());
+
+        let fingerprint_a = fingerprint(&key(KEY_A));
+        assert!(kh.trust("new.example.com", 2222, KEY_A, "SHA256:wrong").is_err());
+        assert!(kh.trust("new.example.com", 2222, KEY_B, &fingerprint(&key(KEY_B))).is_err());
+        kh.trust("new.example.com", 2222, KEY_A, &fingerprint_a).unwrap();
+
+        assert_eq!(kh.verify("new.example.com", 2222, &key(KEY_A)), HostKeyStatus::Trusted);
+        let saved = fs::read_to_string(&store_path).unwrap();
+        assert_eq!(saved.trim(), format!("[new.example.com]:2222 {}", KEY_A));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_wildcard_and_negation() {
+        let patterns = HostPatterns::parse("*.example.com,!bad.example.com").unwrap();
+        assert!(patterns.matches("good.example.com"));
+        assert!(!patterns.matches("bad.example.com"));
+        assert!(!patterns.matches("example.org"));
+        assert!(wildcard_match("host?", "host1"));
+        assert!(!wildcard_match("host?", "host12"));
+    }
+}
@@ -1,16 +1,47 @@
 use crate::error::{Result, SSHError};
-use crate::ssh::session::{SessionConfig, SessionConfigUpdate, SessionStatus, SessionInfo};
+use crate::ssh::session::{
+    AuthMethod, ConnectionType, DisconnectReason, LifecycleEvent, SessionConfig, SessionConfigUpdate, SessionEnd,
+    SessionInfo, SessionStatus, SessionStatusEvent,
+};
 use crate::ssh::connection::ConnectionInstance;
 use crate::ssh::backend::SSHBackend;
+use crate::ssh::interactive::KeyboardInteractiveBroker;
+use crate::ssh::output::{OutputBatcher, IO_TRACE_TARGET};
+use crate::ssh::forwarding::{PortForwardEvent, PortForwardRule, PortForwardStatus};
+use crate::ssh::exec::{ExecOutputEvent, ExecRequest, ExecResult};
+use crate::ssh::screen::{OutputChunk, OutputReplay};
+use crate::ssh::certificate;
+use crate::ssh::keys::ManagedKeyStore;
+use crate::ssh::known_hosts::{host_key_name, KnownHosts};
 #[cfg(not(target_os = "android"))]
 use crate::ssh::backends::DefaultBackend;
+#[cfg(not(target_os = "android"))]
+use crate::ssh::backends::russh::SshTransport;
+#[cfg(not(target_os = "android"))]
+use crate::ssh::backends::serial::SerialBackend;
+#[cfg(not(target_os = "android"))]
+use crate::ssh::backends::tcp::TcpBackend;
+#[cfg(not(target_os = "android"))]
+use crate::ssh::pty::LocalPtyBackend;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex, Weak};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tauri::{AppHandle, Emitter};
 
-// 常量定义
-const BUFFER_SIZE: usize = 8192;
+
+/// 同一会话的多个连接实例共享的 SSH 连接
+#[cfg(not(target_os = "android"))]
+struct SharedTransport {
+    transport: Weak<SshTransport>,
+    /// 建立连接时的配置摘要，会话配置修改后不再复用
+    fingerprint: u64,
+    /// 共享该连接的连接实例ID，端口转发事件发送给每个实例
+    subscribers: Subscribers,
+}
+
+/// 接收同一 SSH 连接端口转发事件的连接实例ID
+type Subscribers = Arc<StdMutex<Vec<String>>>;
 
 /// SSH管理器：维护Session配置和Connection实例
 #[derive(Clone)]
@@ -19,6 +50,21 @@ pub struct SSHManager {
     sessions: Arc<RwLock<HashMap<String, SessionConfig>>>,
     /// 连接实例：connectionId -> ConnectionInstance
     connections: Arc<RwLock<HashMap<String, ConnectionInstance>>>,
+    /// 键盘交互认证代理：转发服务器提示并接收前端答案
+    interactive: KeyboardInteractiveBroker,
+    /// 已认证的 SSH 连接：sessionId -> 连接，同一会话的多个 channel 共享
+    #[cfg(not(target_os = "android"))]
+    transports: Arc<RwLock<HashMap<String, SharedTransport>>>,
+    /// 建立 SSH 连接的会话锁：sessionId -> 锁，多个标签页同时连接或重连时只建立一个连接
+    #[cfg(not(target_os = "android"))]
+    transport_locks: Arc<StdMutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    /// 正在执行的命令：execId -> 取消令牌
+    execs: Arc<StdMutex<HashMap<String, CancellationToken>>>,
+    /// 托管密钥存储，ManagedKey 认证时按 ID 加载私钥
+    #[cfg_attr(target_os = "android", allow(dead_code))]
+    key_store: Option<Arc<dyn ManagedKeyStore>>,
+    /// 严格模式下被拒绝的未知主机密钥：known_hosts 主机名 -> 公钥，等待用户确认指纹
+    pending_host_keys: Arc<StdMutex<HashMap<String, String>>>,
     app_handle: AppHandle,
 }
 
@@ -27,10 +73,23 @@ impl SSHManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             connections: Arc::new(RwLock::new(HashMap::new())),
+            interactive: KeyboardInteractiveBroker::new(app_handle.clone()),
+            #[cfg(not(target_os = "android"))]
+            transports: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(not(target_os = "android"))]
+            transport_locks: Arc::new(StdMutex::new(HashMap::new())),
+            execs: Arc::new(StdMutex::new(HashMap::new())),
+            key_store: None,
+            pending_host_keys: Arc::new(StdMutex::new(HashMap::new())),
             app_handle,
         }
     }
 
+    /// 设置托管密钥存储（应用启动时由数据库层提供）
+    pub fn set_key_store(&mut self, key_store: Arc<dyn ManagedKeyStore>) {
+        self.key_store = Some(key_store);
+    }
+
     // ============= Session配置管理 =============
 
     /// 创建新的会话配置（持久化）
@@ -124,6 +183,21 @@ impl SSHManager {
         if let Some(keep_alive_interval) = updates.keep_alive_interval {
             session.keep_alive_interval = keep_alive_interval;
         }
+        if let Some(jump_hosts) = updates.jump_hosts {
+            session.jump_hosts = jump_hosts;
+        }
+        if let Some(port_forwards) = updates.port_forwards {
+            session.port_forwards = port_forwards;
+        }
+        if let Some(reconnect) = updates.reconnect {
+            session.reconnect = reconnect;
+        }
+        if let Some(connection_type) = updates.connection_type {
+            session.connection_type = connection_type;
+        }
+        if let Some(persistent_shell) = updates.persistent_shell {
+            session.persistent_shell = persistent_shell;
+        }
 
         println!("Updated session config: {} ({})", id, session.name);
         Ok(())
@@ -157,6 +231,8 @@ impl SSHManager {
                 println!("Removed connection: {}", conn_id);
             }
 
+            #[cfg(not(target_os = "android"))]
+            self.transport_locks.lock().unwrap().remove(id);
             println!("Deleted session config: {}", id);
             Ok(())
         } else {
@@ -208,21 +284,134 @@ impl SSHManager {
             .ok_or_else(|| SSHError::SessionNotFound(id.to_string()))
     }
 
+    /// 查找会话配置下已连接的连接实例ID
+    pub async fn find_connected_connection(&self, session_id: &str) -> Option<String> {
+        let connections = self.connections.read().await;
+        for connection in connections.values() {
+            if connection.session_id == session_id && matches!(connection.status().await, SessionStatus::Connected) {
+                return Some(connection.id.clone());
+            }
+        }
+        None
+    }
+
+    /// 复制连接：在同一 SSH 连接上打开新的终端 channel
+    ///
+    /// 原连接仍存活时不再重新认证，返回新的连接实例ID
+    pub async fn duplicate_connection(&self, connection_id: &str) -> Result<String> {
+        let source = self.get_connection(connection_id).await?;
+
+        let new_id = uuid::Uuid::new_v4().to_string();
+        let connection = ConnectionInstance::new(new_id.clone(), source.session_id.clone(), source.config.clone());
+        *connection.terminal_size.lock().await = *source.terminal_size.lock().await;
+
+        {
+            let mut connections = self.connections.write().await;
+            connections.insert(new_id.clone(), connection);
+        }
+
+        tracing::info!("Duplicating connection {} as {}", connection_id, new_id);
+        self.connect_connection(&new_id).await?;
+        Ok(new_id)
+    }
+
+    /// 获取会话仍然存活的 SSH 连接
+    ///
+    /// 连接建立后会话配置被修改（配置摘要不同）时不再复用
+    #[cfg(not(target_os = "android"))]
+    async fn shared_transport(&self, session_id: &str, fingerprint: u64) -> Option<(Arc<SshTransport>, Subscribers)> {
+        let (transport, subscribers) = match self.transports.read().await.get(session_id)? {
+            shared if shared.fingerprint == fingerprint => (shared.transport.upgrade()?, shared.subscribers.clone()),
+            _ => return None,
+        };
+        if transport.is_closed().await {
+            return None;
+        }
+        Some((transport, subscribers))
+    }
+
+    /// 获取会话建立 SSH 连接的锁
+    #[cfg(not(target_os = "android"))]
+    fn transport_lock(&self, session_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.transport_locks
+            .lock()
+            .unwrap()
+            .entry(session_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// 解析跳板机链为会话配置（按连接顺序）
+    async fn resolve_jump_hosts(&self, config: &SessionConfig) -> Result<Vec<SessionConfig>> {
+        let mut jump_hosts = Vec::with_capacity(config.jump_hosts.len());
+
+        for (index, jump_id) in config.jump_hosts.iter().enumerate() {
+            if config.jump_hosts[..index].contains(jump_id) {
+                return Err(SSHError::ConnectionFailed(format!("跳板机链中存在重复的会话: {}", jump_id)));
+            }
+
+            let jump_config = self.get_session_config(jump_id).await
+                .map_err(|_| SSHError::SessionNotFound(format!("跳板机会话 {}", jump_id)))?;
+            jump_hosts.push(jump_config);
+        }
+
+        Ok(jump_hosts)
+    }
+
     /// 连接指定的连接实例
     pub async fn connect_connection(&self, connection_id: &str) -> Result<()> {
         let connection = self.get_connection(connection_id).await?;
-        connection.set_status(SessionStatus::Connecting).await;
+        connection.set_closing(false);
+        self.set_connection_status(&connection, SessionStatus::Connecting).await;
 
         println!("Starting SSH connection for: {}", connection_id);
 
+        if let Err(e) = self.open_backend(&connection).await {
+            self.set_connection_status(&connection, SessionStatus::Error(e.to_string())).await;
+            return Err(e);
+        }
+
+        self.on_connected(&connection).await;
+        Ok(())
+    }
+
+    /// 创建后端并建立连接，保存到连接实例
+    async fn open_backend(&self, connection: &ConnectionInstance) -> Result<()> {
+        self.emit_lifecycle(&connection.id, LifecycleEvent::Connecting);
+
         #[cfg(not(target_os = "android"))]
         {
-            // 桌面平台：使用实际的 SSH 后端
-            // 创建后端实例
-            let mut backend = Box::new(DefaultBackend::new());
+            // 桌面平台：按连接类型创建后端并建立连接
+            let config = connection.connect_config().await;
+
+            // 同一会话串行建立 SSH 连接（查找可复用连接 -> 连接 -> 登记），
+            // 多个标签页同时重连时只有第一个建立新连接，其余复用
+            let _transport_guard = match &config.connection_type {
+                ConnectionType::Ssh => Some(self.transport_lock(&connection.session_id).lock_owned().await),
+                _ => None,
+            };
+
+            let (mut backend, new_transport): (Box<dyn SSHBackend>, Option<(u64, Subscribers)>) = match &config.connection_type {
+                ConnectionType::Ssh => self.ssh_backend(connection, &config).await?,
+                ConnectionType::Local(_) => (Box::new(LocalPtyBackend::new()), None),
+                ConnectionType::Serial(_) => (Box::new(SerialBackend::new()), None),
+                ConnectionType::Telnet | ConnectionType::RawTcp => (Box::new(TcpBackend::new()), None),
+            };
 
             // 建立连接
-            backend.connect(&connection.config).await?;
+            if let Err(e) = backend.connect(&config).await {
+                if let SSHError::HostKeyUnknown { host, port, public_key, .. } = &e {
+                    // 记住服务器出示的公钥，用户确认指纹后由 trust_host_key 写入
+                    self.pending_host_keys
+                        .lock()
+                        .unwrap()
+                        .insert(host_key_name(host, *port), public_key.clone());
+                }
+                return Err(e);
+            }
+            if let Some((fingerprint, subscribers)) = new_transport {
+                self.register_transport(&connection.session_id, fingerprint, subscribers, backend.as_ref()).await;
+            }
 
             // 取出 reader
             let reader = backend.reader()
@@ -241,7 +430,96 @@ impl SSHManager {
             }
         }
 
-        connection.set_status(SessionStatus::Connected).await;
+        Ok(())
+    }
+
+    /// 使用连接的配置和指定的认证方式重新登录一次，确认认证方式可用
+    #[cfg(not(target_os = "android"))]
+    pub async fn check_authentication(&self, connection_id: &str, auth_method: AuthMethod) -> Result<()> {
+        let connection = self.get_connection(connection_id).await?;
+        let mut config = connection.connect_config().await;
+        config.auth_method = auth_method;
+
+        let mut backend = DefaultBackend::new();
+        backend.set_jump_hosts(self.resolve_jump_hosts(&config).await?);
+        if let Some(key_store) = &self.key_store {
+            backend.set_key_store(key_store.clone());
+        }
+        backend.check_authentication(&config).await
+    }
+
+    #[cfg(target_os = "android")]
+    pub async fn check_authentication(&self, _connection_id: &str, _auth_method: AuthMethod) -> Result<()> {
+        Err(SSHError::NotSupported("当前平台不支持 SSH 连接".to_string()))
+    }
+
+    /// 创建 SSH 后端，同一会话已有存活的连接时只打开新的 channel
+    ///
+    /// 需要建立新连接时同时返回配置摘要和端口转发事件的订阅者，连接成功后用于登记
+    #[cfg(not(target_os = "android"))]
+    async fn ssh_backend(
+        &self,
+        connection: &ConnectionInstance,
+        config: &SessionConfig,
+    ) -> Result<(Box<dyn SSHBackend>, Option<(u64, Subscribers)>)> {
+        let mut backend = Box::new(DefaultBackend::new());
+        backend.set_lifecycle_events(self.lifecycle_event_sender(&connection.id));
+
+        let jump_hosts = self.resolve_jump_hosts(config).await?;
+        let fingerprint = config.transport_fingerprint(&jump_hosts);
+        if let Some((transport, subscribers)) = self.shared_transport(&connection.session_id, fingerprint).await {
+            {
+                let mut subscribers = subscribers.lock().unwrap();
+                if !subscribers.contains(&connection.id) {
+                    subscribers.push(connection.id.clone());
+                }
+            }
+            backend.set_transport(transport);
+            return Ok((backend, None));
+        }
+
+        backend.set_interactive_responder(self.interactive.responder(&connection.id));
+        backend.set_jump_hosts(jump_hosts);
+        if let Some(key_store) = &self.key_store {
+            backend.set_key_store(key_store.clone());
+        }
+        let subscribers: Subscribers = Arc::new(StdMutex::new(vec![connection.id.clone()]));
+        backend.set_port_forward_events(self.port_forward_event_sender(subscribers.clone()));
+
+        Ok((backend, Some((fingerprint, subscribers))))
+    }
+
+    /// 记录新建立的 SSH 连接，供同一会话的其他 channel 复用
+    #[cfg(not(target_os = "android"))]
+    async fn register_transport(
+        &self,
+        session_id: &str,
+        fingerprint: u64,
+        subscribers: Subscribers,
+        backend: &dyn SSHBackend,
+    ) {
+        let Some(transport) = backend.as_any()
+            .downcast_ref::<DefaultBackend>()
+            .and_then(|backend| backend.transport())
+        else {
+            return;
+        };
+
+        let mut transports = self.transports.write().await;
+        transports.retain(|_, shared| shared.transport.strong_count() > 0);
+        transports.insert(
+            session_id.to_string(),
+            SharedTransport {
+                transport: Arc::downgrade(&transport),
+                fingerprint,
+                subscribers,
+            },
+        );
+    }
+
+    /// 连接建立后更新状态并启动读取器
+    async fn on_connected(&self, connection: &ConnectionInstance) {
+        self.set_connection_status(connection, SessionStatus::Connected).await;
 
         // 设置连接时间
         {
@@ -249,17 +527,276 @@ impl SSHManager {
             *connected_at = Some(chrono::Utc::now());
         }
 
-        println!("Starting SSH reader for connection: {}", connection_id);
+        println!("Starting SSH reader for connection: {}", connection.id);
 
         // 启动读取器
-        self.start_backend_reader(connection_id.to_string(), connection.clone());
+        self.start_backend_reader(connection.id.clone(), connection.clone());
+    }
+
+    /// 更新连接状态并发送 `ssh-status-<connectionId>` 事件
+    async fn set_connection_status(&self, connection: &ConnectionInstance, status: SessionStatus) {
+        self.emit_status(SessionStatusEvent {
+            connection_id: connection.id.clone(),
+            status: status.clone(),
+            attempt: None,
+            max_attempts: None,
+            retry_in_ms: None,
+        });
+        connection.set_status(status).await;
+    }
+
+    fn emit_status(&self, event: SessionStatusEvent) {
+        let event_name = format!("ssh-status-{}", event.connection_id);
+        if let Err(e) = self.app_handle.emit(&event_name, event) {
+            eprintln!("Failed to emit event {}: {}", event_name, e);
+        }
+    }
+
+    /// 发送 `ssh-lifecycle-<connectionId>` 事件
+    fn emit_lifecycle(&self, connection_id: &str, event: LifecycleEvent) {
+        emit_lifecycle_event(&self.app_handle, connection_id, event);
+    }
+
+    /// 创建后端生命周期事件通道
+    fn lifecycle_event_sender(&self, connection_id: &str) -> tokio::sync::mpsc::UnboundedSender<LifecycleEvent> {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<LifecycleEvent>();
+        let app_handle = self.app_handle.clone();
+        let connection_id = connection_id.to_string();
 
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                emit_lifecycle_event(&app_handle, &connection_id, event);
+            }
+        });
+
+        sender
+    }
+
+    /// 会话结束后更新状态，连接意外中断时按重连策略重新建立后端
+    ///
+    /// 只有连接中断（非远程退出、非用户断开）才会重连，连接已删除或策略禁用时不重连
+    async fn handle_session_end(&self, connection: ConnectionInstance) {
+        if connection.is_closing() || self.get_connection(&connection.id).await.is_err() {
+            return;
+        }
+
+        // 清理失效的后端，读取会话结束原因
+        let end = match connection.backend.lock().await.take() {
+            Some(mut backend) => {
+                let end = backend.session_end();
+                let _ = backend.disconnect().await;
+                end
+            }
+            None => None,
+        }
+        .unwrap_or_else(|| SessionEnd::new(DisconnectReason::ConnectionLost));
+        connection.backend_reader.lock().await.take();
+        *connection.connected_at.lock().await = None;
+
+        tracing::info!("Session ended: {} ({:?})", connection.id, end);
+        self.emit_lifecycle(&connection.id, LifecycleEvent::Disconnected(end.clone()));
+
+        let policy = connection.config.reconnect.clone();
+        if end.reason != DisconnectReason::ConnectionLost || !policy.enabled || policy.max_attempts == 0 {
+            self.set_connection_status(&connection, SessionStatus::Closed(end)).await;
+            return;
+        }
+
+        for attempt in 1..=policy.max_attempts {
+            let delay = policy.backoff(attempt, rand::random::<f64>());
+            connection.set_status(SessionStatus::Reconnecting).await;
+            self.emit_status(SessionStatusEvent {
+                connection_id: connection.id.clone(),
+                status: SessionStatus::Reconnecting,
+                attempt: Some(attempt),
+                max_attempts: Some(policy.max_attempts),
+                retry_in_ms: Some(delay.as_millis() as u64),
+            });
+
+            tokio::time::sleep(delay).await;
+            if connection.is_closing() {
+                return;
+            }
+
+            tracing::info!("Reconnecting {} (attempt {}/{})", connection.id, attempt, policy.max_attempts);
+            match self.open_backend(&connection).await {
+                Ok(()) => {
+                    // 重连过程中用户断开了连接
+                    if connection.is_closing() {
+                        if let Some(mut backend) = connection.backend.lock().await.take() {
+                            let _ = backend.disconnect().await;
+                        }
+                        return;
+                    }
+                    self.on_connected(&connection).await;
+                    if connection.config.persistent_shell.enabled {
+                        self.emit_lifecycle(&connection.id, LifecycleEvent::Reattached {
+                            name: connection.persistent_shell_name(),
+                        });
+                    }
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!("Reconnect attempt {} for {} failed: {}", attempt, connection.id, e);
+                    // 主机密钥或认证问题重试也无法解决
+                    if matches!(
+                        e,
+                        SSHError::HostKeyMismatch { .. }
+                            | SSHError::HostKeyRevoked { .. }
+                            | SSHError::HostKeyUnknown { .. }
+                            | SSHError::HostCertificateUnsupported { .. }
+                            | SSHError::AuthenticationFailed(_)
+                    ) {
+                        self.set_connection_status(&connection, SessionStatus::Error(e.to_string())).await;
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.set_connection_status(
+            &connection,
+            SessionStatus::Error(format!("重连失败：已重试 {} 次", policy.max_attempts)),
+        ).await;
+    }
+
+    /// 回放连接在 since 位置之后的输出，since 为空时回放当前屏幕
+    pub async fn replay_output(&self, connection_id: &str, since: Option<u64>) -> Result<OutputReplay> {
+        let connection = self.get_connection(connection_id).await?;
+        let replay = connection.screen.lock().unwrap().replay(since);
+        Ok(replay)
+    }
+
+    /// 提交键盘交互认证的答案
+    ///
+    /// responses 为 None 表示用户取消认证
+    pub fn respond_keyboard_interactive(&self, request_id: &str, responses: Option<Vec<String>>) -> Result<()> {
+        self.interactive.submit(request_id, responses)
+    }
+
+    /// 用户确认指纹后信任严格模式下被拒绝的未知主机密钥，之后重新连接即可
+    pub fn trust_host_key(&self, host: &str, port: u16, fingerprint: &str) -> Result<()> {
+        let host_name = host_key_name(host, port);
+        let public_key = self.pending_host_keys
+            .lock()
+            .unwrap()
+            .get(&host_name)
+            .cloned()
+            .ok_or_else(|| SSHError::NotFound(format!("没有等待确认的主机密钥: {}", host_name)))?;
+
+        KnownHosts::load_default()?.trust(host, port, &public_key, fingerprint)?;
+        self.pending_host_keys.lock().unwrap().remove(&host_name);
+        tracing::info!("Trusted host key for {} ({})", host_name, fingerprint);
         Ok(())
     }
 
+    /// 创建端口转发事件通道
+    ///
+    /// 端口转发属于 SSH 连接而不是单个标签页，事件以 `ssh-port-forward-<connectionId>`
+    /// 发送给共享该连接的每个连接实例，已删除的实例自动退订
+    fn port_forward_event_sender(&self, subscribers: Subscribers) -> tokio::sync::mpsc::UnboundedSender<PortForwardEvent> {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<PortForwardEvent>();
+        let app_handle = self.app_handle.clone();
+        let connections = self.connections.clone();
+
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let connection_ids = {
+                    let connections = connections.read().await;
+                    let mut subscribers = subscribers.lock().unwrap();
+                    subscribers.retain(|id| connections.contains_key(id));
+                    subscribers.clone()
+                };
+                for connection_id in connection_ids {
+                    let event_name = format!("ssh-port-forward-{}", connection_id);
+                    if let Err(e) = app_handle.emit(&event_name, event.clone()) {
+                        tracing::warn!("Failed to emit port forward event {}: {}", event_name, e);
+                    }
+                }
+            }
+        });
+
+        sender
+    }
+
+    // ============= 命令执行 =============
+
+    /// 在连接上执行非交互式命令，不经过终端 shell
+    ///
+    /// 输出通过 `ssh-exec-<execId>` 事件实时推送，可通过 cancel_exec 取消
+    pub async fn exec_command(&self, connection_id: &str, exec_id: &str, request: ExecRequest) -> Result<ExecResult> {
+        let connection = self.get_connection(connection_id).await?;
+        // 只在获取连接时短暂持有后端锁，执行期间不阻塞终端输入
+        let transport = connection.transport().await?;
+
+        let cancel = CancellationToken::new();
+        self.execs.lock().unwrap().insert(exec_id.to_string(), cancel.clone());
+
+        let app_handle = self.app_handle.clone();
+        let event_name = format!("ssh-exec-{}", exec_id);
+        let result = transport
+            .exec(&request, cancel, |stream, data| {
+                let event = ExecOutputEvent {
+                    exec_id: exec_id.to_string(),
+                    stream,
+                    data: data.to_vec(),
+                };
+                if let Err(e) = app_handle.emit(&event_name, event) {
+                    tracing::warn!("Failed to emit event {}: {}", event_name, e);
+                }
+            })
+            .await;
+
+        self.execs.lock().unwrap().remove(exec_id);
+        result
+    }
+
+    /// 取消正在执行的命令
+    pub fn cancel_exec(&self, exec_id: &str) -> Result<()> {
+        let token = self.execs.lock().unwrap().remove(exec_id)
+            .ok_or_else(|| SSHError::NotFound(format!("命令执行 {}", exec_id)))?;
+        token.cancel();
+        Ok(())
+    }
+
+    // ============= 端口转发 =============
+
+    /// 列出连接上的端口转发及其状态
+    pub async fn list_port_forwards(&self, connection_id: &str) -> Result<Vec<PortForwardStatus>> {
+        let connection = self.get_connection(connection_id).await?;
+        let backend_guard = connection.backend.lock().await;
+        let backend = backend_guard.as_ref().ok_or(SSHError::NotConnected)?;
+        Ok(backend.port_forwards().await)
+    }
+
+    /// 在已连接的会话上添加端口转发
+    pub async fn add_port_forward(&self, connection_id: &str, rule: PortForwardRule) -> Result<()> {
+        let connection = self.get_connection(connection_id).await?;
+        let mut backend_guard = connection.backend.lock().await;
+        let backend = backend_guard.as_mut().ok_or(SSHError::NotConnected)?;
+        backend.add_port_forward(rule).await
+    }
+
+    /// 移除端口转发
+    pub async fn remove_port_forward(&self, connection_id: &str, rule_id: &str) -> Result<()> {
+        let connection = self.get_connection(connection_id).await?;
+        let mut backend_guard = connection.backend.lock().await;
+        let backend = backend_guard.as_mut().ok_or(SSHError::NotConnected)?;
+        backend.remove_port_forward(rule_id).await
+    }
+
+    /// 启用或停用端口转发
+    pub async fn set_port_forward_enabled(&self, connection_id: &str, rule_id: &str, enabled: bool) -> Result<()> {
+        let connection = self.get_connection(connection_id).await?;
+        let mut backend_guard = connection.backend.lock().await;
+        let backend = backend_guard.as_mut().ok_or(SSHError::NotConnected)?;
+        backend.set_port_forward_enabled(rule_id, enabled).await
+    }
+
     /// 断开连接实例
     pub async fn disconnect_connection(&self, id: &str) -> Result<()> {
         let connection = self.get_connection(id).await?;
+        connection.set_closing(true);
 
         // 使用后端断开连接
         {
@@ -270,7 +807,9 @@ impl SSHManager {
             *backend_guard = None;
         }
 
-        connection.set_status(SessionStatus::Disconnected).await;
+        let end = SessionEnd::new(DisconnectReason::UserRequested);
+        self.emit_lifecycle(&connection.id, LifecycleEvent::Disconnected(end.clone()));
+        self.set_connection_status(&connection, SessionStatus::Closed(end)).await;
 
         // 清除连接时间
         let mut connected_at = connection.connected_at.lock().await;
@@ -283,12 +822,8 @@ impl SSHManager {
     pub async fn write_to_connection(&self, id: &str, data: Vec<u8>) -> Result<()> {
         let connection = self.get_connection(id).await?;
 
-        // 记录写入的详细信息
-        let text = String::from_utf8_lossy(&data);
-        let data_len = data.len();
-        println!("[SSH Write] Writing {} bytes to connection: {}", data_len, id);
-        println!("[SSH Write] Data content: {:?}", text);
-        println!("[SSH Write] Data bytes (first 50): {:?}", &data[..data_len.min(50)]);
+        // 输入可能包含密码，只记录长度
+        tracing::trace!(target: IO_TRACE_TARGET, "Writing {} bytes to connection: {}", data.len(), id);
 
         // 使用后端的 write 方法
         let mut backend_guard = connection.backend.lock().await;
@@ -298,9 +833,6 @@ impl SSHManager {
             return Err(SSHError::NotConnected);
         }
 
-        println!("[SSH Write] Successfully wrote {} bytes to connection: {}", data_len, id);
-        println!("---------------");
-
         Ok(())
     }
 
@@ -308,11 +840,14 @@ impl SSHManager {
     pub async fn resize_connection(&self, id: &str, rows: u16, cols: u16) -> Result<()> {
         let connection = self.get_connection(id).await?;
 
+        // 记录终端大小，重连时恢复
+        *connection.terminal_size.lock().await = Some((rows, cols));
+
         // 使用后端的 resize 方法
         let mut backend_guard = connection.backend.lock().await;
         if let Some(ref mut backend) = *backend_guard {
             backend.resize(rows, cols).await?;
-        } else {
+        } else if !matches!(connection.status().await, SessionStatus::Reconnecting) {
             return Err(SSHError::NotConnected);
         }
 
@@ -322,6 +857,7 @@ impl SSHManager {
     /// 启动后端读取器
     fn start_backend_reader(&self, connection_id: String, connection: ConnectionInstance) {
         let app_handle = self.app_handle.clone();
+        let manager = self.clone();
 
         println!("Starting backend reader task for connection: {}", connection_id);
 
@@ -338,8 +874,8 @@ impl SSHManager {
 
             println!("Backend reader acquired for connection: {}", connection_id);
 
-            let mut buffer = [0u8; BUFFER_SIZE];
-            let mut read_count = 0;
+            let event_name = format!("ssh-output-{}", connection_id);
+            let mut batcher = OutputBatcher::default();
 
             loop {
                 // 每次循环都重新获取 reader
@@ -352,31 +888,20 @@ impl SSHManager {
                     }
                 };
 
-                use tokio::io::AsyncReadExt;
-
-                match reader.read(&mut buffer).await {
-                    Ok(n) if n > 0 => {
-                        read_count += 1;
-                        let data = buffer[..n].to_vec();
-                        let text = String::from_utf8_lossy(&data);
-                        
-                        // 记录读取的详细信息（不打印 Raw bytes）
-                        println!("[SSH Read] Read {} bytes from connection: {} (read #{})", n, connection_id, read_count);
-                        println!("[SSH Read] Text content: {:?}", text);
-
+                match batcher.next_batch(reader).await {
+                    Ok(Some(data)) => {
                         // 释放锁后再发送事件
                         drop(reader_guard);
 
-                        // 发送事件到前端（使用connectionId）
-                        let event_name = format!("ssh-output-{}", connection_id);
-                        if let Err(e) = app_handle.emit(&event_name, data) {
+                        tracing::trace!(target: IO_TRACE_TARGET, "Emitting {} bytes to {}", data.len(), event_name);
+                        let offset = connection.screen.lock().unwrap().push(&data);
+
+                        // 发送事件到前端（使用connectionId），带上起始位置供前端发现遗漏的输出
+                        if let Err(e) = app_handle.emit(&event_name, OutputChunk { offset, data }) {
                             eprintln!("[SSH Read] Failed to emit event {}: {}", event_name, e);
-                        } else {
-                            println!("[SSH Read] Successfully emitted event: {} ({} bytes)", event_name, n);
                         }
-                        println!("---------------");
                     }
-                    Ok(_) => {
+                    Ok(None) => {
                         // EOF，连接关闭
                         println!("EOF received, stopping reader for connection: {}", connection_id);
                         break;
@@ -390,6 +915,9 @@ impl SSHManager {
             }
 
             println!("Backend reader task ended for connection: {}", connection_id);
+
+            // 非用户主动断开时尝试自动重连
+            manager.handle_session_end(connection).await;
         });
     }
 
@@ -416,6 +944,7 @@ impl SSHManager {
                 group: config.group.clone(),
                 connection_session_id: None,  // 配置本身
                 connection_id: None,  // 配置本身没有 connection_id
+                certificate: certificate::inspect_user_certificate(&config.auth_method),
             });
         }
 
@@ -471,3 +1000,23 @@ impl SSHManager {
         self.resize_connection(id, rows, cols).await
     }
 }
+
+/// 生命周期事件负载，在事件内容中附带 connectionId
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LifecyclePayload {
+    connection_id: String,
+    #[serde(flatten)]
+    event: LifecycleEvent,
+}
+
+fn emit_lifecycle_event(app_handle: &AppHandle, connection_id: &str, event: LifecycleEvent) {
+    let event_name = format!("ssh-lifecycle-{}", connection_id);
+    let payload = LifecyclePayload {
+        connection_id: connection_id.to_string(),
+        event,
+    };
+    if let Err(e) = app_handle.emit(&event_name, payload) {
+        eprintln!("Failed to emit event {}: {}", event_name, e);
+    }
+}
@@ -3,4 +3,13 @@ pub mod session;
 pub mod connection;
 pub mod backend;
 pub mod backends;
+pub mod known_hosts;
+pub mod certificate;
+pub mod keys;
+pub mod interactive;
 pub mod pty;
+pub mod forwarding;
+pub mod output;
+pub mod exec;
+pub mod screen;
+pub mod import;
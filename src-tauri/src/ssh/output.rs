@@ -0,0 +1,257 @@
+// 终端输出管道
+//
+// 后端输出经有界 channel 传给读取器，读取器在短时间窗口内合并多个数据块后
+// 一次性发送事件，减少高吞吐输出（如 cat 大文件）时的事件数量。
+//
+// 输出内容可能包含回显的密码，日志中只记录字节数。
+// 需要排查时通过 RUST_LOG=ssh_io=trace 开启。
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// 输出/IO 跟踪日志的 target
+pub const IO_TRACE_TARGET: &str = "ssh_io";
+
+/// 后端输出 channel 容量（数据块个数），写满后会话循环暂停读取，由 SSH 窗口向服务器施加背压
+pub const OUTPUT_CHANNEL_CAPACITY: usize = 256;
+
+/// 后端命令 channel 容量
+pub const COMMAND_CHANNEL_CAPACITY: usize = 64;
+
+/// 单次读取的缓冲区大小
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// 单批最大字节数
+const DEFAULT_MAX_BATCH: usize = 64 * 1024;
+
+/// 收到第一块数据后继续等待合并的时间
+const DEFAULT_LINGER: Duration = Duration::from_millis(4);
+
+/// 基于 mpsc channel 的异步读取器
+///
+/// 后端在后台任务中接收输出并发送到 channel，读取器将其转换为 AsyncRead，
+/// 发送端全部释放后返回 EOF
+pub struct ChannelReader {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    buffer: Option<Vec<u8>>,
+    position: usize,
+}
+
+impl ChannelReader {
+    pub fn new(receiver: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self {
+            receiver,
+            buffer: None,
+            position: 0,
+        }
+    }
+}
+
+impl AsyncRead for ChannelReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        // 如果当前 buffer 还有数据，先消费
+        if self.buffer.is_some() {
+            let buffer_len = {
+                let buffer = self.buffer.as_ref().unwrap();
+                buffer.len()
+            };
+
+            if self.position < buffer_len {
+                let mut temp_buffer = None;
+                std::mem::swap(&mut self.buffer, &mut temp_buffer);
+
+                if let Some(buffer) = temp_buffer {
+                    let remaining = &buffer[self.position..];
+                    let to_copy = std::cmp::min(remaining.len(), buf.capacity());
+                    buf.put_slice(&remaining[..to_copy]);
+                    self.position += to_copy;
+
+                    if self.position >= buffer.len() {
+                        self.buffer = None;
+                        self.position = 0;
+                    } else {
+                        self.buffer = Some(buffer);
+                    }
+                }
+                return Poll::Ready(Ok(()));
+            }
+        }
+
+        // 尝试从 channel 接收新数据
+        match self.receiver.poll_recv(cx) {
+            Poll::Ready(Some(data)) => {
+                let to_copy = std::cmp::min(data.len(), buf.capacity());
+                buf.put_slice(&data[..to_copy]);
+                if data.len() > to_copy {
+                    self.buffer = Some(data[to_copy..].to_vec());
+                    self.position = 0;
+                }
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(None) => {
+                // Channel 关闭
+                debug!("ChannelReader channel closed");
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// 输出合并器
+///
+/// 先等待第一块数据，之后在 linger 时间内继续合并后续数据，直到达到 max_batch
+pub struct OutputBatcher {
+    scratch: Box<[u8]>,
+    max_batch: usize,
+    linger: Duration,
+}
+
+impl OutputBatcher {
+    pub fn new(max_batch: usize, linger: Duration) -> Self {
+        Self {
+            scratch: vec![0u8; READ_CHUNK_SIZE].into_boxed_slice(),
+            max_batch,
+            linger,
+        }
+    }
+
+    /// 读取下一批输出，返回 None 表示 EOF
+    ///
+    /// 合并窗口内遇到 EOF 或错误时先返回已读取的数据，下一次调用再报告
+    pub async fn next_batch<R>(&mut self, reader: &mut R) -> io::Result<Option<Vec<u8>>>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        let n = reader.read(&mut self.scratch).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+
+        let mut batch = Vec::with_capacity(n.max(READ_CHUNK_SIZE));
+        batch.extend_from_slice(&self.scratch[..n]);
+
+        let deadline = Instant::now() + self.linger;
+        while batch.len() < self.max_batch {
+            match tokio::time::timeout_at(deadline, reader.read(&mut self.scratch)).await {
+                Ok(Ok(n)) if n > 0 => batch.extend_from_slice(&self.scratch[..n]),
+                // EOF、错误或超时：先发送已合并的数据
+                _ => break,
+            }
+        }
+
+        Ok(Some(batch))
+    }
+}
+
+impl Default for OutputBatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BATCH, DEFAULT_LINGER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_output_batcher_coalesces_chunks() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        client.write_all(b"hello ").await.unwrap();
+        client.write_all(b"world").await.unwrap();
+        drop(client);
+
+        let mut batcher = OutputBatcher::new(1024, Duration::from_millis(20));
+        let batch = batcher.next_batch(&mut server).await.unwrap();
+        assert_eq!(batch.as_deref(), Some(&b"hello world"[..]));
+        assert!(batcher.next_batch(&mut server).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_output_batcher_respects_max_batch() {
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        client.write_all(&[b'x'; 20000]).await.unwrap();
+
+        let mut batcher = OutputBatcher::new(10000, Duration::from_millis(20));
+        let first = batcher.next_batch(&mut server).await.unwrap().unwrap();
+        assert!(first.len() >= 10000 && first.len() < 20000);
+
+        let mut total = first.len();
+        while total < 20000 {
+            total += batcher.next_batch(&mut server).await.unwrap().unwrap().len();
+        }
+        assert_eq!(total, 20000);
+    }
+
+    /// 输出吞吐量基准测试（需要本地 SSH 服务器）
+    ///
+    /// SSH_BENCH_HOST=127.0.0.1 SSH_BENCH_USER=me SSH_BENCH_PASSWORD=secret \
+    ///     cargo test --release bench_output_throughput -- --ignored --nocapture
+    ///
+    /// 可选：SSH_BENCH_PORT（默认 22）、SSH_BENCH_KEY（私钥路径，替代密码）、
+    /// SSH_BENCH_MB（输出大小，默认 64）
+    #[cfg(not(target_os = "android"))]
+    #[tokio::test]
+    #[ignore]
+    async fn bench_output_throughput() {
+        use crate::ssh::backend::SSHBackend;
+        use crate::ssh::backends::DefaultBackend;
+        use crate::ssh::session::SessionConfig;
+
+        let Ok(host) = std::env::var("SSH_BENCH_HOST") else {
+            eprintln!("SSH_BENCH_HOST not set, skipping");
+            return;
+        };
+        let env = |name: &str| std::env::var(name).ok();
+        let auth_method = match env("SSH_BENCH_KEY") {
+            Some(path) => serde_json::json!({ "PublicKey": { "privateKeyPath": path } }),
+            None => serde_json::json!({ "Password": { "password": env("SSH_BENCH_PASSWORD").unwrap_or_default() } }),
+        };
+        let config: SessionConfig = serde_json::from_value(serde_json::json!({
+            "name": "bench",
+            "host": host,
+            "port": env("SSH_BENCH_PORT").and_then(|p| p.parse::<u16>().ok()).unwrap_or(22),
+            "username": env("SSH_BENCH_USER").unwrap_or_else(|| "root".to_string()),
+            "authMethod": auth_method,
+            "strictHostKeyChecking": false,
+        }))
+        .unwrap();
+        let megabytes: u64 = env("SSH_BENCH_MB").and_then(|m| m.parse().ok()).unwrap_or(64);
+
+        let mut backend = DefaultBackend::new();
+        backend.connect(&config).await.unwrap();
+        let mut reader = backend.reader().unwrap();
+
+        let command = format!("head -c {} /dev/zero | base64; exit\n", megabytes * 1024 * 1024);
+        backend.write(command.as_bytes()).await.unwrap();
+
+        let start = std::time::Instant::now();
+        let mut batcher = OutputBatcher::default();
+        let (mut bytes, mut batches) = (0usize, 0usize);
+        while let Some(batch) = batcher.next_batch(&mut reader).await.unwrap() {
+            bytes += batch.len();
+            batches += 1;
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        println!(
+            "{} bytes in {:.2}s ({:.1} MB/s), {} batches, avg {} bytes/batch",
+            bytes,
+            elapsed,
+            bytes as f64 / elapsed / 1024.0 / 1024.0,
+            batches,
+            bytes / batches.max(1)
+        );
+    }
+}
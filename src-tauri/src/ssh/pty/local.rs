@@ -0,0 +1,268 @@
+// 本地终端后端 - 基于 portable-pty（仅桌面平台）
+
+use crate::error::{Result, SSHError};
+use crate::ssh::backend::{BackendReader, SSHBackend};
+use crate::ssh::output::{ChannelReader, IO_TRACE_TARGET, OUTPUT_CHANNEL_CAPACITY};
+use crate::ssh::session::{ConnectionType, DisconnectReason, SessionConfig, SessionEnd};
+use async_trait::async_trait;
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, trace, warn};
+
+type SharedChild = Arc<StdMutex<Box<dyn Child + Send + Sync>>>;
+
+/// 本地终端后端
+///
+/// 在本地 PTY 中启动 shell，输出由后台线程读取后经 channel 转为异步读取器
+pub struct LocalPtyBackend {
+    master: Option<Box<dyn MasterPty + Send>>,
+    writer: Option<Box<dyn Write + Send>>,
+    child: Option<SharedChild>,
+    receiver: Option<mpsc::Receiver<Vec<u8>>>,
+    /// 会话结束信息，读取线程在 shell 退出后写入
+    session_end: Arc<StdMutex<Option<SessionEnd>>>,
+}
+
+impl LocalPtyBackend {
+    pub fn new() -> Self {
+        Self {
+            master: None,
+            writer: None,
+            child: None,
+            receiver: None,
+            session_end: Arc::new(StdMutex::new(None)),
+        }
+    }
+
+    /// 系统默认 shell
+    fn default_shell() -> String {
+        #[cfg(windows)]
+        {
+            std::env::var("COMSPEC").unwrap_or_else(|_| "powershell.exe".to_string())
+        }
+        #[cfg(not(windows))]
+        {
+            std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
+        }
+    }
+
+    /// 启动读取线程：PTY 读取是阻塞的，不能放在异步任务中
+    ///
+    /// 读到 EOF 后回收子进程并记录退出码，再释放发送端让读取器收到 EOF
+    fn spawn_reader(
+        mut reader: Box<dyn Read + Send>,
+        sender: mpsc::Sender<Vec<u8>>,
+        child: SharedChild,
+        session_end: Arc<StdMutex<Option<SessionEnd>>>,
+    ) {
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        trace!(target: IO_TRACE_TARGET, "PTY -> channel: {} bytes", n);
+                        if sender.blocking_send(buffer[..n].to_vec()).is_err() {
+                            debug!("PTY output receiver dropped");
+                            return;
+                        }
+                    }
+                    // Linux 上 shell 退出后读取 master 返回 EIO
+                    Err(e) => {
+                        debug!("PTY read ended: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            // 轮询而不是 wait()，避免持有锁阻塞 disconnect 中的 kill
+            let end = loop {
+                let status = child.lock().unwrap().try_wait();
+                match status {
+                    Ok(Some(status)) => {
+                        break SessionEnd {
+                            reason: DisconnectReason::RemoteExit,
+                            exit_code: Some(status.exit_code()),
+                            message: None,
+                        }
+                    }
+                    Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+                    Err(e) => {
+                        break SessionEnd {
+                            reason: DisconnectReason::Error,
+                            exit_code: None,
+                            message: Some(e.to_string()),
+                        }
+                    }
+                }
+            };
+            debug!("Local shell ended: {:?}", end);
+
+            // 用户主动断开时已写入结束原因
+            session_end.lock().unwrap().get_or_insert(end);
+            drop(sender);
+        });
+    }
+}
+
+impl Default for LocalPtyBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pty_error(context: &str, e: impl std::fmt::Display) -> SSHError {
+    SSHError::ConnectionFailed(format!("{}: {}", context, e))
+}
+
+#[async_trait]
+impl SSHBackend for LocalPtyBackend {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn connect(&mut self, config: &SessionConfig) -> Result<()> {
+        let ConnectionType::Local(shell_config) = &config.connection_type else {
+            return Err(SSHError::NotSupported("本地终端后端只支持本地会话".to_string()));
+        };
+
+        let size = PtySize {
+            rows: config.rows.unwrap_or(24),
+            cols: config.columns.unwrap_or(80),
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        let pair = native_pty_system()
+            .openpty(size)
+            .map_err(|e| pty_error("Failed to open PTY", e))?;
+
+        let program = shell_config.shell.clone().unwrap_or_else(Self::default_shell);
+        info!("Starting local shell: {}", program);
+
+        let mut command = CommandBuilder::new(&program);
+        command.args(&shell_config.args);
+        if let Some(cwd) = &shell_config.cwd {
+            command.cwd(cwd);
+        }
+        command.env(
+            "TERM",
+            config.terminal_type.as_deref().unwrap_or("xterm-256color"),
+        );
+        command.env("COLORTERM", "truecolor");
+        for (key, value) in &shell_config.env {
+            command.env(key, value);
+        }
+
+        let child = pair
+            .slave
+            .spawn_command(command)
+            .map_err(|e| pty_error("Failed to start shell", e))?;
+        // 释放 slave 端，子进程退出后 master 才能读到 EOF
+        drop(pair.slave);
+
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| pty_error("Failed to open PTY reader", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| pty_error("Failed to open PTY writer", e))?;
+
+        let (sender, receiver) = mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
+        let child: SharedChild = Arc::new(StdMutex::new(child));
+        *self.session_end.lock().unwrap() = None;
+        Self::spawn_reader(reader, sender, child.clone(), self.session_end.clone());
+
+        self.master = Some(pair.master);
+        self.writer = Some(writer);
+        self.child = Some(child);
+        self.receiver = Some(receiver);
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        let writer = self.writer.as_mut().ok_or(SSHError::NotConnected)?;
+        trace!(target: IO_TRACE_TARGET, "channel -> PTY: {} bytes", data.len());
+        writer.write_all(data)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    async fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
+        let master = self.master.as_ref().ok_or(SSHError::NotConnected)?;
+        master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| pty_error("Failed to resize PTY", e))
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.session_end
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| SessionEnd::new(DisconnectReason::UserRequested));
+
+        if let Some(child) = self.child.take() {
+            if let Err(e) = child.lock().unwrap().kill() {
+                // shell 已经退出
+                debug!("Failed to kill local shell: {}", e);
+            }
+        }
+
+        self.writer = None;
+        self.master = None;
+        info!("Local shell closed");
+        Ok(())
+    }
+
+    fn session_end(&self) -> Option<SessionEnd> {
+        self.session_end.lock().unwrap().clone()
+    }
+
+    fn reader(&mut self) -> Result<Box<dyn BackendReader + Send>> {
+        match self.receiver.take() {
+            Some(receiver) => Ok(Box::new(ChannelReader::new(receiver))),
+            None => {
+                warn!("PTY reader already taken");
+                Err(SSHError::NotConnected)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::ssh::session::LocalShellConfig;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_local_shell_output_and_exit_code() {
+        let config = SessionConfig::local("sh".to_string(), LocalShellConfig {
+            shell: Some("/bin/sh".to_string()),
+            args: vec!["-c".to_string(), "echo $GREETING; exit 3".to_string()],
+            env: [("GREETING".to_string(), "hello".to_string())].into(),
+            ..Default::default()
+        });
+
+        let mut backend = LocalPtyBackend::new();
+        backend.connect(&config).await.unwrap();
+        let mut reader = backend.reader().unwrap();
+
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output).await.unwrap();
+        assert!(String::from_utf8_lossy(&output).contains("hello"));
+
+        let end = backend.session_end().unwrap();
+        assert_eq!(end.reason, DisconnectReason::RemoteExit);
+        assert_eq!(end.exit_code, Some(3));
+    }
+}
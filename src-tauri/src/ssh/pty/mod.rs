@@ -1,2 +1,9 @@
 // PTY 模块
+//
+// 本地终端后端，与远程会话共用 SSHManager、录制和快捷键逻辑
 
+#[cfg(not(target_os = "android"))]
+pub mod local;
+
+#[cfg(not(target_os = "android"))]
+pub use local::LocalPtyBackend;
@@ -0,0 +1,236 @@
+// 终端屏幕状态
+//
+// 每个连接在本地保留最近的终端输出，并记录累计输出字节数作为位置；
+// 同时跟踪当前屏幕从哪里开始绘制（最近一次清屏、终端复位或切换备用屏幕），
+// 从这个位置回放即可在新挂载的终端中还原屏幕。
+//
+// 输出事件带有起始位置，前端发现位置不连续（挂载前、断线重连期间漏掉的输出）时，
+// 带上自己写入的最后位置调用 `session_replay_output` 补齐；位置过旧时从仍保留的屏幕开始处回放。
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// 每个连接保留的输出字节数
+pub const SCREEN_HISTORY_BYTES: usize = 512 * 1024;
+
+/// 终端输出事件（`ssh-output-<connectionId>`）
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputChunk {
+    /// 本批数据的起始位置
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+/// 输出回放结果
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputReplay {
+    pub data: Vec<u8>,
+    /// 回放数据的结束位置，作为下次回放的 since
+    pub offset: u64,
+    /// 请求位置之后的部分输出已被丢弃，回放从保留的屏幕开始处或最早一行开始
+    pub truncated: bool,
+}
+
+/// 影响屏幕内容的控制序列
+#[derive(Clone, Copy)]
+enum ScreenAction {
+    /// 清屏（`ESC [ 2 J`）
+    Clear,
+    /// 终端复位（`ESC c`），同时退出备用屏幕
+    Reset,
+    /// 进入备用屏幕（vim、less、tmux 等全屏程序）
+    EnterAlternate,
+    /// 退出备用屏幕，恢复主屏幕
+    LeaveAlternate,
+}
+
+const SCREEN_SEQUENCES: &[(&[u8], ScreenAction)] = &[
+    (b"\x1b[2J", ScreenAction::Clear),
+    (b"\x1bc", ScreenAction::Reset),
+    (b"\x1b[?1049h", ScreenAction::EnterAlternate),
+    (b"\x1b[?1047h", ScreenAction::EnterAlternate),
+    (b"\x1b[?47h", ScreenAction::EnterAlternate),
+    (b"\x1b[?1049l", ScreenAction::LeaveAlternate),
+    (b"\x1b[?1047l", ScreenAction::LeaveAlternate),
+    (b"\x1b[?47l", ScreenAction::LeaveAlternate),
+];
+
+/// 连接的本地屏幕状态
+pub struct ScreenState {
+    buffer: VecDeque<u8>,
+    capacity: usize,
+    /// 累计输出字节数（包括已丢弃的部分）
+    total: u64,
+    /// 主屏幕最近一次清屏的位置
+    main_start: u64,
+    /// 进入备用屏幕的位置，不在备用屏幕时为空
+    alternate_start: Option<u64>,
+    /// 上一批输出末尾尚未完整的控制序列
+    partial: Vec<u8>,
+}
+
+impl ScreenState {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: VecDeque::with_capacity(capacity.min(64 * 1024)),
+            capacity,
+            total: 0,
+            main_start: 0,
+            alternate_start: None,
+            partial: Vec::new(),
+        }
+    }
+
+    /// 记录一批输出，返回这批输出的起始位置
+    pub fn push(&mut self, data: &[u8]) -> u64 {
+        let offset = self.total;
+        self.scan(data);
+        self.total += data.len() as u64;
+
+        let data = &data[data.len().saturating_sub(self.capacity)..];
+        let overflow = (self.buffer.len() + data.len()).saturating_sub(self.capacity);
+        self.buffer.drain(..overflow);
+        self.buffer.extend(data);
+        offset
+    }
+
+    /// 当前屏幕的开始位置
+    fn screen_start(&self) -> u64 {
+        self.alternate_start.unwrap_or(self.main_start)
+    }
+
+    /// 查找影响屏幕内容的控制序列，更新屏幕开始位置
+    fn scan(&mut self, data: &[u8]) {
+        let mut input = std::mem::take(&mut self.partial);
+        input.extend_from_slice(data);
+        let base = self.total - (input.len() - data.len()) as u64;
+
+        let mut i = 0;
+        while let Some(found) = input[i..].iter().position(|&b| b == 0x1b) {
+            i += found;
+            let rest = &input[i..];
+            let position = base + i as u64;
+
+            if let Some((sequence, action)) = SCREEN_SEQUENCES.iter().find(|(seq, _)| rest.starts_with(seq)) {
+                match action {
+                    ScreenAction::Clear => match self.alternate_start {
+                        Some(_) => self.alternate_start = Some(position),
+                        None => self.main_start = position,
+                    },
+                    ScreenAction::Reset => {
+                        self.main_start = position;
+                        self.alternate_start = None;
+                    }
+                    ScreenAction::EnterAlternate => self.alternate_start = Some(position),
+                    ScreenAction::LeaveAlternate => self.alternate_start = None,
+                }
+                i += sequence.len();
+            } else if SCREEN_SEQUENCES.iter().any(|(seq, _)| seq.len() > rest.len() && seq.starts_with(rest)) {
+                // 序列被拆到了下一批输出中
+                self.partial = rest.to_vec();
+                return;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// 返回 since 之后的输出，since 为空时从当前屏幕的开始处回放
+    pub fn replay(&self, since: Option<u64>) -> OutputReplay {
+        let start = self.total - self.buffer.len() as u64;
+        let since = since.unwrap_or_else(|| self.screen_start()).min(self.total);
+
+        if since >= start {
+            let skip = (since - start) as usize;
+            return OutputReplay {
+                data: self.buffer.range(skip..).copied().collect(),
+                offset: self.total,
+                truncated: false,
+            };
+        }
+
+        // 请求位置已被丢弃：屏幕开始处仍保留时从那里回放，
+        // 否则最早的数据可能从转义序列或多字节字符中间开始，跳到第一个换行之后
+        let skip = if self.screen_start() >= start {
+            (self.screen_start() - start) as usize
+        } else {
+            self.buffer
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|i| i + 1)
+                .unwrap_or(0)
+        };
+        OutputReplay {
+            data: self.buffer.range(skip..).copied().collect(),
+            offset: self.total,
+            truncated: true,
+        }
+    }
+}
+
+impl Default for ScreenState {
+    fn default() -> Self {
+        Self::new(SCREEN_HISTORY_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_since_offset() {
+        let mut screen = ScreenState::new(64);
+        assert_eq!(screen.push(b"$ ls\r\n"), 0);
+        let seen = screen.push(b"a.txt b.txt\r\n$ ");
+        assert_eq!(seen, 6);
+
+        let replay = screen.replay(Some(seen));
+        assert_eq!(replay.data, b"a.txt b.txt\r\n$ ");
+        assert_eq!(replay.offset, 21);
+        assert!(!replay.truncated);
+
+        assert!(screen.replay(Some(replay.offset)).data.is_empty());
+        assert_eq!(screen.replay(None).data, b"$ ls\r\na.txt b.txt\r\n$ ");
+    }
+
+    #[test]
+    fn test_replay_after_history_dropped() {
+        let mut screen = ScreenState::new(16);
+        screen.push(b"line one\nline two\nline three\n");
+        screen.push(b"$ ");
+
+        let replay = screen.replay(Some(4));
+        assert!(replay.truncated);
+        assert_eq!(replay.data, b"line three\n$ ");
+        assert_eq!(replay.offset, 31);
+    }
+
+    #[test]
+    fn test_replay_current_screen() {
+        let mut screen = ScreenState::new(1024);
+        screen.push(b"old output\r\n\x1b[H\x1b");
+        // 清屏序列被拆到两批输出中
+        screen.push(b"[2J$ vim\r\n");
+        assert_eq!(screen.replay(None).data, b"\x1b[2J$ vim\r\n");
+
+        // 全屏程序运行期间只回放备用屏幕的内容，退出后回放主屏幕
+        screen.push(b"\x1b[?1049hfile contents");
+        assert_eq!(screen.replay(None).data, b"\x1b[?1049hfile contents");
+        screen.push(b"\x1b[?1049l$ ");
+        assert_eq!(screen.replay(None).data, b"\x1b[2J$ vim\r\n\x1b[?1049hfile contents\x1b[?1049l$ ");
+    }
+
+    #[test]
+    fn test_replay_from_retained_screen_start() {
+        let mut screen = ScreenState::new(16);
+        screen.push(b"line one\nline two\n\x1bcprompt$ ");
+
+        // 请求位置已被丢弃，但屏幕开始处仍保留
+        let replay = screen.replay(Some(0));
+        assert!(replay.truncated);
+        assert_eq!(replay.data, b"\x1bcprompt$ ");
+    }
+}
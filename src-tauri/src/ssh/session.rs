@@ -1,4 +1,9 @@
+use crate::ssh::certificate::CertificateInfo;
+use crate::ssh::forwarding::PortForwardRule;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +28,295 @@ pub struct SessionConfig {
     /// 心跳间隔（秒），0表示禁用
     #[serde(default = "default_keep_alive_interval")]
     pub keep_alive_interval: u64,
+    /// 跳板机链：按顺序经过的已保存会话 ID（类似 ProxyJump）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jump_hosts: Vec<String>,
+    /// 端口转发规则（-L / -R / -D），连接成功后启动已启用的规则
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForwardRule>,
+    /// 断线自动重连策略
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    /// 连接类型，默认为 SSH
+    #[serde(default, skip_serializing_if = "ConnectionType::is_ssh")]
+    pub connection_type: ConnectionType,
+    /// 用 tmux/screen 保持远程会话，断线重连后重新附加
+    #[serde(default)]
+    pub persistent_shell: PersistentShell,
+}
+
+impl SessionConfig {
+    /// SSH 会话配置，其余字段取默认值
+    pub fn ssh(name: String, host: String, port: u16, username: String, auth_method: AuthMethod) -> Self {
+        let mut config = Self::without_ssh(name, host, ConnectionType::Ssh);
+        config.port = port;
+        config.username = username;
+        config.auth_method = auth_method;
+        config.keep_alive_interval = default_keep_alive_interval();
+        config
+    }
+
+    /// 本地终端会话配置，host/username 等 SSH 字段只用于显示
+    pub fn local(name: String, shell: LocalShellConfig) -> Self {
+        let mut config = Self::without_ssh(name, "localhost".to_string(), ConnectionType::Local(shell));
+        config.username = whoami_fallback();
+        // shell 退出即会话结束，不需要重连
+        config.reconnect.enabled = false;
+        config
+    }
+
+    /// 串口会话配置，设备断开后按重连策略重新打开
+    pub fn serial(name: String, serial: SerialConfig) -> Self {
+        Self::without_ssh(name, serial.path.clone(), ConnectionType::Serial(serial))
+    }
+
+    /// 建立 SSH 连接所用配置的摘要，用于判断已有连接能否被新的标签页复用
+    ///
+    /// 只包含影响 SSH 连接本身的字段（含跳板机配置），终端大小、会话保持等单个 channel 的设置不参与计算
+    pub fn transport_fingerprint(&self, jump_hosts: &[SessionConfig]) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for config in std::iter::once(self).chain(jump_hosts) {
+            let fields = serde_json::json!([
+                config.host,
+                config.port,
+                config.username,
+                config.auth_method,
+                config.strict_host_key_checking,
+                config.keep_alive_interval,
+                config.port_forwards,
+            ]);
+            fields.to_string().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn without_ssh(name: String, host: String, connection_type: ConnectionType) -> Self {
+        Self {
+            name,
+            host,
+            port: 0,
+            username: String::new(),
+            auth_method: AuthMethod::Agent { identity: None },
+            terminal_type: None,
+            columns: None,
+            rows: None,
+            strict_host_key_checking: default_strict_host_key_checking(),
+            group: default_group(),
+            keep_alive_interval: 0,
+            jump_hosts: Vec::new(),
+            port_forwards: Vec::new(),
+            reconnect: ReconnectPolicy::default(),
+            connection_type,
+            persistent_shell: PersistentShell::default(),
+        }
+    }
+}
+
+pub(crate) fn whoami_fallback() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+/// 连接类型，决定使用哪个后端
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ConnectionType {
+    #[default]
+    Ssh,
+    /// 本地终端（bash/zsh/fish/PowerShell 等）
+    Local(LocalShellConfig),
+    /// 串口控制台（交换机、开发板等）
+    Serial(SerialConfig),
+    /// Telnet，使用 host/port 连接（老旧网络设备）
+    Telnet,
+    /// 原始 TCP 套接字，不做任何协议处理
+    RawTcp,
+}
+
+impl ConnectionType {
+    pub fn is_ssh(&self) -> bool {
+        matches!(self, ConnectionType::Ssh)
+    }
+}
+
+/// 本地终端配置
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalShellConfig {
+    /// 启动的程序，为空时使用系统默认 shell（$SHELL / %COMSPEC%）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// 工作目录，为空时使用用户主目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// 额外的环境变量
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// 串口配置
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialConfig {
+    /// 设备路径（/dev/ttyUSB0、COM3 等）
+    pub path: String,
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    /// 数据位（5 ~ 8）
+    #[serde(default = "default_data_bits")]
+    pub data_bits: u8,
+    #[serde(default)]
+    pub parity: SerialParity,
+    #[serde(default)]
+    pub stop_bits: SerialStopBits,
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+}
+
+fn default_baud_rate() -> u32 {
+    115200
+}
+
+fn default_data_bits() -> u8 {
+    8
+}
+
+/// 可用的串口设备（`session_serial_ports` 返回）
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialPortEntry {
+    pub path: String,
+    /// USB 设备的厂商和产品名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// 校验位
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+/// 停止位
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialStopBits {
+    #[default]
+    One,
+    Two,
+}
+
+/// 流控
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    /// XON/XOFF
+    Software,
+    /// RTS/CTS
+    Hardware,
+}
+
+/// 断线自动重连策略
+///
+/// 心跳超时或 channel 意外关闭后按指数退避重新建立连接
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReconnectPolicy {
+    /// 是否启用自动重连
+    pub enabled: bool,
+    /// 最大重试次数
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间（毫秒）
+    pub initial_delay_ms: u64,
+    /// 重试等待时间上限（毫秒）
+    pub max_delay_ms: u64,
+    /// 每次重试等待时间的增长倍数
+    pub multiplier: f64,
+    /// 随机抖动比例（0 ~ 1），避免多个会话同时重连
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 5,
+            initial_delay_ms: 1000,
+            max_delay_ms: 30000,
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// 第 attempt 次重试（从 1 开始）前的等待时间
+    ///
+    /// random 为 [0, 1) 内的随机数，抖动后的等待时间落在 base * (1 ± jitter) 之间
+    pub fn backoff(&self, attempt: u32, random: f64) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = (self.initial_delay_ms as f64 * self.multiplier.max(1.0).powi(exponent))
+            .min(self.max_delay_ms as f64);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 - jitter + 2.0 * jitter * random.clamp(0.0, 1.0);
+        Duration::from_millis((base * factor) as u64)
+    }
+}
+
+/// 远程会话保持
+///
+/// 启用后不直接启动登录 shell，而是执行 `tmux new -A -s <name>`（或 screen 的等价命令），
+/// 网络中断重连后会附加到同一个远程会话，断线期间的输出由 tmux/screen 重绘
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersistentShell {
+    pub enabled: bool,
+    pub multiplexer: Multiplexer,
+    /// 远程会话名，为空时按连接 ID 生成
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// 终端复用程序
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Multiplexer {
+    #[default]
+    Tmux,
+    Screen,
+}
+
+impl PersistentShell {
+    /// 远程会话名，只保留字母、数字、`-` 和 `_`，无需再做 shell 转义
+    pub fn session_name(&self, fallback: &str) -> String {
+        let name = self.name.as_deref().filter(|name| !name.trim().is_empty()).unwrap_or(fallback);
+        name.trim()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect()
+    }
+
+    /// 在 PTY 中执行的命令，远程未安装 tmux/screen 时退回登录 shell
+    pub fn command(&self, name: &str) -> String {
+        let (program, attach) = match self.multiplexer {
+            Multiplexer::Tmux => ("tmux", format!("tmux new -A -s {}", name)),
+            // -D -R：在其他地方已附加时先分离，不存在时新建
+            Multiplexer::Screen => ("screen", format!("screen -D -R -S {}", name)),
+        };
+        format!(
+            "command -v {} >/dev/null 2>&1 && exec {} || exec \"$SHELL\" -l",
+            program, attach
+        )
+    }
 }
 
 /// 用于部分更新会话配置的结构体
@@ -51,6 +345,16 @@ pub struct SessionConfigUpdate {
     pub group: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_alive_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_hosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_forwards: Option<Vec<PortForwardRule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<ReconnectPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_type: Option<ConnectionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistent_shell: Option<PersistentShell>,
 }
 
 fn default_strict_host_key_checking() -> bool {
@@ -69,7 +373,29 @@ fn default_keep_alive_interval() -> u64 {
 pub enum AuthMethod {
     Password { password: String },
     #[serde(rename_all = "camelCase")]
-    PublicKey { private_key_path: String, passphrase: Option<String> },
+    PublicKey {
+        private_key_path: String,
+        passphrase: Option<String>,
+        /// OpenSSH 用户证书路径，为空时自动查找 `<private_key_path>-cert.pub`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        certificate_path: Option<String>,
+    },
+    /// 通过 ssh-agent（SSH_AUTH_SOCK）认证
+    ///
+    /// identity 为空时依次尝试 agent 中的所有密钥，
+    /// 否则只尝试指纹（SHA256:...）或注释与之匹配的密钥
+    Agent {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        identity: Option<String>,
+    },
+    /// 使用密钥管理器中保存的密钥，按 ID 引用（不依赖本机文件路径）
+    #[serde(rename_all = "camelCase")]
+    ManagedKey {
+        key_id: String,
+        /// 私钥设置了密码时需要
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        passphrase: Option<String>,
+    },
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -78,9 +404,94 @@ pub enum SessionStatus {
     Disconnected,
     Connecting,
     Connected,
+    /// 连接意外断开，正在自动重连
+    Reconnecting,
+    /// 会话已结束，包含结束原因和远程退出码
+    Closed(SessionEnd),
     Error(String),
 }
 
+/// 会话结束原因
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DisconnectReason {
+    /// 用户主动断开
+    UserRequested,
+    /// 远程 shell 退出
+    RemoteExit,
+    /// 服务器关闭了 channel
+    ClosedByServer,
+    /// 网络中断或心跳超时
+    ConnectionLost,
+    /// 本地读写出错
+    Error,
+}
+
+/// 会话结束信息
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnd {
+    pub reason: DisconnectReason,
+    /// 远程进程退出码（ChannelMsg::ExitStatus）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<u32>,
+    /// 附加说明（退出信号、错误信息等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl SessionEnd {
+    pub fn new(reason: DisconnectReason) -> Self {
+        Self {
+            reason,
+            exit_code: None,
+            message: None,
+        }
+    }
+}
+
+/// 连接生命周期事件
+///
+/// 以 `ssh-lifecycle-<connectionId>` 事件发送到前端
+#[derive(Clone, Serialize, Debug)]
+#[serde(tag = "stage", rename_all = "camelCase")]
+pub enum LifecycleEvent {
+    /// 开始建立连接
+    Connecting,
+    /// 完成认证（经过跳板机时每一跳都会发送）
+    #[serde(rename_all = "camelCase")]
+    Authenticated { host: String, port: u16 },
+    /// PTY 和 shell 已就绪
+    ShellReady,
+    /// 重连后重新附加到 tmux/screen 会话
+    #[serde(rename_all = "camelCase")]
+    Reattached { name: String },
+    /// 远程进程退出
+    #[serde(rename_all = "camelCase")]
+    ExitStatus { exit_code: u32 },
+    /// 会话结束
+    Disconnected(SessionEnd),
+}
+
+/// 连接状态变更事件
+///
+/// 以 `ssh-status-<connectionId>` 事件发送到前端
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatusEvent {
+    pub connection_id: String,
+    pub status: SessionStatus,
+    /// 当前重连次数（仅 Reconnecting 状态）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
+    /// 最大重连次数（仅 Reconnecting 状态）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// 距离下次重连的等待时间（毫秒，仅 Reconnecting 状态）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_ms: Option<u64>,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionInfo {
@@ -99,4 +510,110 @@ pub struct SessionInfo {
     /// 如果是连接实例且已连接，这个字段存储实际的connectionId
     #[serde(skip_serializing_if = "Option::is_none")]
     pub connection_id: Option<String>,
+    /// 使用的 OpenSSH 用户证书（含有效期）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let policy = ReconnectPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(1, 0.5), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_millis(2000));
+        assert_eq!(policy.backoff(4, 0.5), Duration::from_millis(8000));
+        // 不超过上限
+        assert_eq!(policy.backoff(10, 0.5), Duration::from_millis(30000));
+    }
+
+    #[test]
+    fn test_reconnect_backoff_jitter() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(800));
+        assert_eq!(policy.backoff(1, 0.5), Duration::from_millis(1000));
+        assert!(policy.backoff(1, 0.999) <= Duration::from_millis(1200));
+    }
+
+    #[test]
+    fn test_transport_fingerprint() {
+        let config = SessionConfig::ssh(
+            "web".to_string(),
+            "example.com".to_string(),
+            22,
+            "root".to_string(),
+            AuthMethod::Agent { identity: None },
+        );
+
+        // 终端大小不影响 SSH 连接的复用
+        let mut resized = config.clone();
+        resized.rows = Some(50);
+        resized.columns = Some(200);
+        assert_eq!(config.transport_fingerprint(&[]), resized.transport_fingerprint(&[]));
+
+        let mut edited = config.clone();
+        edited.username = "admin".to_string();
+        assert_ne!(config.transport_fingerprint(&[]), edited.transport_fingerprint(&[]));
+
+        // 跳板机配置修改后同样不能复用
+        let jump = config.clone();
+        let mut jump_edited = config.clone();
+        jump_edited.port = 2222;
+        assert_ne!(
+            config.transport_fingerprint(std::slice::from_ref(&jump)),
+            config.transport_fingerprint(&[jump_edited])
+        );
+    }
+
+    #[test]
+    fn test_connection_type_serde() {
+        // 旧配置没有 connectionType 字段，按 SSH 处理
+        let config: SessionConfig = serde_json::from_value(serde_json::json!({
+            "name": "web",
+            "host": "example.com",
+            "port": 22,
+            "username": "root",
+            "authMethod": { "Password": { "password": "secret" } },
+        }))
+        .unwrap();
+        assert_eq!(config.connection_type, ConnectionType::Ssh);
+        assert!(serde_json::to_value(&config).unwrap().get("connectionType").is_none());
+
+        let local = SessionConfig::local("zsh".to_string(), LocalShellConfig {
+            shell: Some("/bin/zsh".to_string()),
+            ..Default::default()
+        });
+        let value = serde_json::to_value(&local).unwrap();
+        assert_eq!(value["connectionType"], serde_json::json!({ "type": "local", "shell": "/bin/zsh" }));
+        let parsed: SessionConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.connection_type, local.connection_type);
+    }
+
+    #[test]
+    fn test_persistent_shell_command() {
+        let shell = PersistentShell {
+            enabled: true,
+            name: Some("work; rm -rf ~".to_string()),
+            ..Default::default()
+        };
+        let name = shell.session_name("ssh-terminal-1");
+        assert_eq!(name, "work__rm_-rf__");
+        assert_eq!(
+            shell.command(&name),
+            "command -v tmux >/dev/null 2>&1 && exec tmux new -A -s work__rm_-rf__ || exec \"$SHELL\" -l"
+        );
+
+        let screen = PersistentShell {
+            enabled: true,
+            multiplexer: Multiplexer::Screen,
+            name: None,
+        };
+        assert_eq!(screen.session_name("ssh-terminal-1"), "ssh-terminal-1");
+        assert!(screen.command("main").contains("exec screen -D -R -S main"));
+    }
 }
@@ -4,7 +4,7 @@ import { Terminal, Trash2, Play, Edit, Wifi, WifiOff } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Card, CardContent, CardFooter, CardHeader, CardTitle } from '@/components/ui/card';
 import { Badge } from '@/components/ui/badge';
-import type { SessionInfo } from '@/types/ssh';
+import { sessionStatusKind, type SessionInfo } from '@/types/ssh';
 import { useSessionStore } from '@/store/sessionStore';
 import { useTerminalStore } from '@/store/terminalStore';
 import { useNavigate } from 'react-router-dom';
@@ -35,7 +35,7 @@ export function MobileSessionCard({ sessionId, onEdit }: MobileSessionCardProps)
   // 计算实际的连接状态
   const getDisplayStatus = () => {
     if (session.connectionSessionId) {
-      return session.status;
+      return sessionStatusKind(session.status);
     }
     const connections = sessions.filter(s => s.connectionSessionId === sessionId && s.status === 'connected');
     return connections.length > 0 ? 'connected' : 'disconnected';
@@ -1,5 +1,4 @@
 import { useState } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import {
   Dialog,
   DialogContent,
@@ -11,7 +10,7 @@ import {
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
-import { useSessionStore } from '@/store/sessionStore';
+import { connectWithHostKeyPrompt, useSessionStore } from '@/store/sessionStore';
 
 interface QuickConnectProps {
   open: boolean;
@@ -58,7 +57,7 @@ export function QuickConnect({ open, onOpenChange, onConnect }: QuickConnectProp
 
       // 2. 尝试连接
       try {
-        await invoke('session_connect', { sessionId: connectionId });
+        await connectWithHostKeyPrompt(connectionId);
         console.log('SSH connected successfully');
 
         // 连接成功，关闭对话框并通知
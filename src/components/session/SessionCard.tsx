@@ -4,7 +4,7 @@ import { Terminal, Trash2, Play, Edit } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Card, CardContent, CardFooter, CardHeader, CardTitle } from '@/components/ui/card';
 import { ConnectionStatusBadge } from '@/components/ssh/ConnectionStatusBadge';
-import type { SessionInfo } from '@/types/ssh';
+import { sessionStatusKind, type SessionInfo } from '@/types/ssh';
 import { useSessionStore } from '@/store/sessionStore';
 import { useTerminalStore } from '@/store/terminalStore';
 import { useNavigate } from 'react-router-dom';
@@ -36,7 +36,7 @@ export function SessionCard({ sessionId, onEdit }: SessionCardProps) {
   const getDisplayStatus = () => {
     // 如果是连接实例本身，直接返回其状态
     if (session.connectionSessionId) {
-      return session.status;
+      return sessionStatusKind(session.status);
     }
     // 如果是会话配置，查找是否有活跃的连接实例
     const connections = sessions.filter(s => s.connectionSessionId === sessionId && s.status === 'connected');
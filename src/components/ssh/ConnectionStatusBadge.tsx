@@ -1,11 +1,11 @@
 import { Badge } from '@/components/ui/badge';
-import { SessionStatus } from '@/types/ssh';
+import { sessionStatusKind, type SessionStatusValue } from '@/types/ssh';
 import { cn } from '@/lib/utils';
 import { memo } from 'react';
 import { useTranslation } from 'react-i18next';
 
 interface ConnectionStatusBadgeProps {
-  status: SessionStatus;
+  status: SessionStatusValue;
   className?: string;
 }
 
@@ -19,7 +19,7 @@ export const ConnectionStatusBadge = memo(function ConnectionStatusBadge({ statu
     error: { label: t('session.status.error'), className: 'badge-error' },
   };
 
-  const config = statusConfig[status] || statusConfig.disconnected;
+  const config = statusConfig[sessionStatusKind(status)] || statusConfig.disconnected;
 
   return (
     <Badge variant="outline" className={cn(config.className, className)}>
@@ -35,6 +35,8 @@ interface DownloadRecord {
   errorMessage: string | null;
   createdAt: number;
   updatedAt: number;
+  /** 续传起点（字节） */
+  resumeOffset: number;
 }
 
 interface PaginatedDownloadRecords {
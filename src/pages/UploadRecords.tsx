@@ -35,6 +35,10 @@ interface UploadRecord {
   errorMessage: string | null;
   createdAt: number;
   updatedAt: number;
+  /** 续传起点（字节） */
+  resumeOffset: number;
+  /** 服务器之间传输时的源连接 ID，此时 localPath 为源服务器上的路径 */
+  sourceConnectionId?: string | null;
 }
 
 interface PaginatedUploadRecords {
@@ -312,7 +316,7 @@ export function UploadRecords() {
                     {record.taskId}
                   </div>
                   <div className="col-span-2 truncate" title={record.localPath}>
-                    {record.localPath}
+                    {record.sourceConnectionId ? `${record.sourceConnectionId}:${record.localPath}` : record.localPath}
                   </div>
                   <div className="col-span-2 truncate" title={record.remotePath}>
                     {record.remotePath}
@@ -111,6 +111,17 @@ export const useAuthStore = create<AuthState>((set, get) => ({
           // 迁移失败不影响登录成功
         }
 
+        // 迁移匿名用户的 SSH 密钥到当前登录用户
+        try {
+          const migratedKeys = await invoke<number>('db_ssh_key_migrate_to_user');
+          if (migratedKeys > 0) {
+            console.log(`[authStore] Migrated ${migratedKeys} keys from anonymous to user ${user.id}`);
+          }
+        } catch (migrateError) {
+          console.error('[authStore] Failed to migrate keys:', migrateError);
+          // 迁移失败不影响登录成功
+        }
+
         await syncStore.syncNow();
         await sessionStore.reloadSessions();
         await authStore.getCurrentUser();
@@ -196,6 +207,17 @@ export const useAuthStore = create<AuthState>((set, get) => ({
           // 迁移失败不影响注册成功
         }
 
+        // 迁移匿名用户的 SSH 密钥到当前注册用户
+        try {
+          const migratedKeys = await invoke<number>('db_ssh_key_migrate_to_user');
+          if (migratedKeys > 0) {
+            console.log(`[authStore] Migrated ${migratedKeys} keys from anonymous to user ${user.id}`);
+          }
+        } catch (migrateError) {
+          console.error('[authStore] Failed to migrate keys:', migrateError);
+          // 迁移失败不影响注册成功
+        }
+
         await userProfileStore.loadProfile();
         await authStore.getCurrentUser();
         console.log('[authStore] Register user profile loaded');
@@ -1,7 +1,9 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
-import type { SessionConfig, SessionInfo } from '@/types/ssh';
+import * as Dialog from '@tauri-apps/plugin-dialog';
+import i18n from '@/i18n/config';
+import { isHostKeyError, type SessionConfig, type SessionInfo } from '@/types/ssh';
 import { useAIStore } from './aiStore';
 
 interface SessionStore {
@@ -29,6 +31,57 @@ interface SessionStore {
   getSessionConfig: (id: string) => SessionConfig | undefined; // 获取完整的会话配置
 }
 
+/**
+ * 连接会话；遇到未知主机密钥时请用户确认指纹，确认后信任该密钥并重新连接
+ *
+ * 主机密钥变更或已吊销时提示可能存在中间人攻击，不允许继续连接
+ */
+export async function connectWithHostKeyPrompt(sessionId: string): Promise<string> {
+  try {
+    return await invoke<string>('session_connect', { sessionId });
+  } catch (error) {
+    if (!isHostKeyError(error)) {
+      throw error;
+    }
+
+    const { host, port } = error;
+    if (error.kind === 'hostKeyMismatch') {
+      await Dialog.message(
+        i18n.t('session.hostKey.mismatch', {
+          host,
+          port,
+          expectedFingerprint: error.expectedFingerprint,
+          actualFingerprint: error.actualFingerprint,
+        }),
+        { title: i18n.t('session.hostKey.mismatchTitle'), kind: 'error' }
+      );
+      throw error.message;
+    }
+    if (error.kind === 'hostKeyRevoked') {
+      await Dialog.message(
+        i18n.t('session.hostKey.revoked', { host, port, fingerprint: error.actualFingerprint }),
+        { title: i18n.t('session.hostKey.mismatchTitle'), kind: 'error' }
+      );
+      throw error.message;
+    }
+
+    const fingerprint = error.actualFingerprint;
+    const confirmed = await Dialog.confirm(
+      i18n.t('session.hostKey.unknownConfirm', { host, port, fingerprint }),
+      {
+        title: i18n.t('session.hostKey.title'),
+        kind: 'warning',
+      }
+    );
+    if (!confirmed) {
+      throw error.message;
+    }
+
+    await invoke('session_trust_host_key', { host, port, fingerprint });
+    return await invoke<string>('session_connect', { sessionId });
+  }
+}
+
 export const useSessionStore = create<SessionStore>()(
   persist(
     (set, get) => ({
@@ -165,7 +218,7 @@ export const useSessionStore = create<SessionStore>()(
           });
         }
 
-        const connectionId = await invoke<string>('session_connect', { sessionId });
+        const connectionId = await connectWithHostKeyPrompt(sessionId);
 
         // 重新加载sessions列表
         await get().loadSessions();
@@ -257,7 +310,7 @@ export const useSessionStore = create<SessionStore>()(
           });
         }
 
-        const connectionId = await invoke<string>('session_connect', { sessionId: id });
+        const connectionId = await connectWithHostKeyPrompt(id);
 
         // 重新加载sessions列表，包含新创建的连接实例
         await get().loadSessions();
@@ -6,6 +6,7 @@ import { WebglAddon } from '@xterm/addon-webgl';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
 import { useRecordingStore } from '@/store/recordingStore';
+import type { OutputChunk, OutputReplay, SessionStatusEvent } from '@/types/ssh';
 
 export interface TerminalTab {
   id: string;
@@ -190,50 +191,125 @@ export const useTerminalStore = create<TerminalStore>((set, get) => ({
     let outputBuffer = '';
     let dialogShown = false;
 
-    // 设置监听器
-    listen<number[]>(eventName, (event) => {
-      const data = new Uint8Array(event.payload);
-      const text = new TextDecoder().decode(data);
+    // 已写入终端的输出位置（与后端 ScreenState 的累计字节数对应），null 表示尚未写入
+    let renderedOffset: number | null = null;
+    // 回放进行中时收到的输出先排队，回放完成后按位置去重写入
+    let replaying = false;
+    const queued: OutputChunk[] = [];
 
+    const writeOutput = (data: Uint8Array): boolean => {
       // 从 store 获取最新的终端实例
       const currentInstance = get().terminalInstances.get(connectionId);
-      if (currentInstance?.terminal) {
-        currentInstance.terminal.write(data);
+      if (!currentInstance?.terminal) {
+        console.warn(`[TerminalStore] Terminal instance not found for ${connectionId}, dropping ${data.length} bytes`);
+        return false;
+      }
+
+      currentInstance.terminal.write(data);
 
-        // 录制输出事件
-        useRecordingStore.getState().recordOutput(connectionId, data);
+      // 录制输出事件
+      useRecordingStore.getState().recordOutput(connectionId, data);
 
-        // 更新缓冲区用于检测
-        outputBuffer += text;
-        if (outputBuffer.length > 2000) {
-          outputBuffer = outputBuffer.slice(-2000);
+      // 更新缓冲区用于检测
+      outputBuffer += new TextDecoder().decode(data);
+      if (outputBuffer.length > 2000) {
+        outputBuffer = outputBuffer.slice(-2000);
+      }
+
+      // 检测主机密钥确认提示（使用缓冲区检测）
+      if (!dialogShown && currentInstance.onHostKeyDetect &&
+          outputBuffer.includes("The authenticity of host") &&
+          outputBuffer.includes("can't be established")) {
+        // 提取主机信息
+        const hostMatch = outputBuffer.match(/The authenticity of host '([^']+)'/);
+        const fingerprintMatch = outputBuffer.match(/fingerprint is (SHA256:[^\s]+)/);
+        const keyTypeMatch = outputBuffer.match(/(ED25519|RSA|ECDSA) key fingerprint/);
+
+        if (hostMatch && fingerprintMatch && keyTypeMatch) {
+          dialogShown = true;
+          currentInstance.onHostKeyDetect(hostMatch[1], fingerprintMatch[1], keyTypeMatch[1]);
         }
+      }
+      return true;
+    };
 
-        // 检测主机密钥确认提示（使用缓冲区检测）
-        if (!dialogShown && currentInstance.onHostKeyDetect &&
-            outputBuffer.includes("The authenticity of host") &&
-            outputBuffer.includes("can't be established")) {
-          // 提取主机信息
-          const hostMatch = outputBuffer.match(/The authenticity of host '([^']+)'/);
-          const fingerprintMatch = outputBuffer.match(/fingerprint is (SHA256:[^\s]+)/);
-          const keyTypeMatch = outputBuffer.match(/(ED25519|RSA|ECDSA) key fingerprint/);
-
-          if (hostMatch && fingerprintMatch && keyTypeMatch) {
-            dialogShown = true;
-            currentInstance.onHostKeyDetect(hostMatch[1], fingerprintMatch[1], keyTypeMatch[1]);
+    // 写入一批输出，跳过已经通过回放写入的部分
+    const writeChunk = (chunk: OutputChunk) => {
+      const end = chunk.offset + chunk.data.length;
+      if (renderedOffset !== null && end <= renderedOffset) {
+        return;
+      }
+      const skip = renderedOffset !== null && chunk.offset < renderedOffset ? renderedOffset - chunk.offset : 0;
+      if (writeOutput(new Uint8Array(chunk.data.slice(skip)))) {
+        renderedOffset = end;
+      }
+    };
+
+    // 从后端回放 renderedOffset 之后错过的输出（为空时回放当前屏幕）
+    const replayMissed = async () => {
+      if (replaying) {
+        return;
+      }
+      replaying = true;
+      try {
+        const replay = await invoke<OutputReplay>('session_replay_output', {
+          connectionId,
+          since: renderedOffset,
+        });
+        if (replay.data.length === 0 || writeOutput(new Uint8Array(replay.data))) {
+          renderedOffset = replay.offset;
+        }
+      } catch (error) {
+        console.error(`[TerminalStore] Failed to replay output for ${connectionId}:`, error);
+      } finally {
+        replaying = false;
+        for (const chunk of queued.splice(0)) {
+          // 回放失败时不再等待缺失的部分，避免反复回放
+          if (renderedOffset !== null && chunk.offset > renderedOffset) {
+            renderedOffset = chunk.offset;
           }
+          writeChunk(chunk);
         }
-      } else {
-        console.warn(`[TerminalStore] Terminal instance not found for ${connectionId}, dropping ${data.length} bytes`);
       }
-    }).then((unlisten) => {
+    };
+
+    const handleChunk = (chunk: OutputChunk) => {
+      if (replaying) {
+        queued.push(chunk);
+        return;
+      }
+      // 位置不连续：监听器建立前、终端实例不存在或断线期间错过了输出，先补齐再写入
+      if (renderedOffset === null || chunk.offset > renderedOffset) {
+        queued.push(chunk);
+        void replayMissed();
+        return;
+      }
+      writeChunk(chunk);
+    };
+
+    // 设置监听器
+    Promise.all([
+      listen<OutputChunk>(eventName, (event) => handleChunk(event.payload)),
+      // 断线重连成功后补齐输出（重连前最后一批输出可能没有写入终端）
+      listen<SessionStatusEvent>(`ssh-status-${connectionId}`, (event) => {
+        if (event.payload.status === 'connected') {
+          void replayMissed();
+        }
+      }),
+    ]).then(([unlistenOutput, unlistenStatus]) => {
+      // 补齐监听器建立前的输出
+      void replayMissed();
+
       // 保存 unlisten 函数到 store，并标记监听器已激活
       const updatedInstance = get().terminalInstances.get(connectionId);
       if (updatedInstance) {
         const newInstances = new Map(get().terminalInstances);
         newInstances.set(connectionId, {
           ...updatedInstance,
-          outputUnlisten: unlisten,
+          outputUnlisten: () => {
+            unlistenOutput();
+            unlistenStatus();
+          },
           outputListenerActive: true,
         });
         set({ terminalInstances: newInstances });
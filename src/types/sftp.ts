@@ -38,17 +38,18 @@ export type TransferOperation = 'upload' | 'download' | 'remoteToRemote';
  */
 export type TransferSource =
   | { type: 'local'; path: string }
-  | { type: 'remote'; connectionId: string; path: string };
+  | { type: 'remote'; connectionId: string; sessionId?: string; path: string };
 
 /**
  * 传输状态
  */
 export type TransferStatus =
-  | 'pending'
-  | 'inProgress'
-  | 'completed'
-  | 'failed'
-  | 'cancelled';
+  | { state: 'pending' }
+  | { state: 'inProgress' }
+  | { state: 'paused' }
+  | { state: 'completed' }
+  | { state: 'failed'; reason: string }
+  | { state: 'cancelled' };
 
 /**
  * 文件传输进度
@@ -62,6 +63,10 @@ export interface TransferProgress {
   source: TransferSource;
   /** 目标 */
   destination: TransferSource;
+  /** 是否为目录 */
+  isDir: boolean;
+  /** 优先级（数值越大越先执行） */
+  priority: number;
   /** 文件总大小 */
   fileSize: number;
   /** 已传输字节数 */
@@ -70,6 +75,112 @@ export interface TransferProgress {
   speed: number;
   /** 传输状态 */
   status: TransferStatus;
+  /** 入队时间（Unix 时间戳，秒） */
+  createdAt: number;
+  /** 更新时间（Unix 时间戳，秒） */
+  updatedAt: number;
+}
+
+/**
+ * 传输队列入队请求
+ */
+export interface TransferRequest {
+  source: TransferSource;
+  destination: TransferSource;
+  /** 优先级（默认 0） */
+  priority?: number;
+}
+
+/**
+ * 传输队列并发限制
+ */
+export interface TransferQueueLimits {
+  /** 全局最大并发传输数 */
+  maxConcurrent: number;
+  /** 单个连接最大并发传输数 */
+  maxPerConnection: number;
+}
+
+/**
+ * 目录同步方向
+ */
+export type SyncDirection = 'upload' | 'download';
+
+/**
+ * 目录同步选项
+ */
+export interface SyncOptions {
+  direction: SyncDirection;
+  localDir: string;
+  remoteDir: string;
+  /** 大小相同的文件按 SHA-256 比较（远程端需要 sha256sum） */
+  checksum?: boolean;
+  /** 删除目标端多余的文件和目录 */
+  delete?: boolean;
+  /** 只同步匹配的文件（gitignore 语法） */
+  include?: string[];
+  /** 跳过匹配的文件和目录（gitignore 语法） */
+  exclude?: string[];
+  /** 遵循源端的 .gitignore 并跳过 .git 目录 */
+  useGitignore?: boolean;
+}
+
+/**
+ * 目录同步操作
+ */
+export type SyncAction = 'create' | 'update' | 'delete' | 'conflict';
+
+/**
+ * 同步计划中的一项
+ */
+export interface SyncEntry {
+  /** 相对于同步根目录的路径 */
+  path: string;
+  action: SyncAction;
+  isDir: boolean;
+  size: number;
+  /** 源端修改时间（Unix 时间戳，秒） */
+  modified: number;
+}
+
+/**
+ * 目录同步计划
+ */
+export interface SyncPlan {
+  direction: SyncDirection;
+  localDir: string;
+  remoteDir: string;
+  entries: SyncEntry[];
+  transferFiles: number;
+  transferBytes: number;
+  deleteCount: number;
+  conflictCount: number;
+  unchangedFiles: number;
+}
+
+/**
+ * 目录同步结果
+ */
+export interface SyncResult {
+  filesTransferred: number;
+  bytesTransferred: number;
+  dirsCreated: number;
+  deleted: number;
+  elapsedTimeMs: number;
+}
+
+/**
+ * 目录同步进度事件（sftp-sync-progress）
+ */
+export interface SyncProgressEvent {
+  taskId: string;
+  connectionId: string;
+  currentFile: string;
+  filesCompleted: number;
+  totalFiles: number;
+  bytesTransferred: number;
+  totalBytes: number;
+  speedBytesPerSec: number;
 }
 
 /**
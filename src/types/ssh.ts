@@ -7,11 +7,26 @@ export type AuthMethodPassword = {
 export type AuthMethodPublicKey = {
   privateKeyPath: string;
   passphrase?: string;
+  /** OpenSSH 用户证书路径，不传时自动查找 `<privateKeyPath>-cert.pub` */
+  certificatePath?: string;
+};
+
+// ssh-agent 认证，identity 可按指纹（SHA256:...）或注释过滤密钥
+export type AuthMethodAgent = {
+  identity?: string;
+};
+
+// 密钥管理器中的密钥，按 ID 引用，passphrase 为该密钥的密码
+export type AuthMethodManagedKey = {
+  keyId: string;
+  passphrase?: string;
 };
 
 export type AuthMethod =
   | { Password: AuthMethodPassword }
-  | { PublicKey: AuthMethodPublicKey };
+  | { PublicKey: AuthMethodPublicKey }
+  | { Agent: AuthMethodAgent }
+  | { ManagedKey: AuthMethodManagedKey };
 
 export interface SessionConfig {
   id?: string;
@@ -29,9 +44,196 @@ export interface SessionConfig {
   group?: string;
   /** 心跳间隔（秒），0表示禁用（默认30秒） */
   keepAliveInterval?: number;
+  /** 跳板机链：按顺序经过的已保存会话 ID（类似 ProxyJump） */
+  jumpHosts?: string[];
+  /** 端口转发规则，连接成功后自动启动已启用的规则 */
+  portForwards?: PortForwardRule[];
+  /** 断线自动重连策略（默认启用） */
+  reconnect?: ReconnectPolicy;
+  /** 连接类型（默认 SSH） */
+  connectionType?: ConnectionType;
+  /** 用 tmux/screen 保持远程会话，断线重连后重新附加（默认关闭） */
+  persistentShell?: PersistentShell;
+}
+
+/** 本地终端配置（`session_create_local`） */
+export interface LocalShellConfig {
+  /** 启动的程序，不传时使用系统默认 shell */
+  shell?: string;
+  args?: string[];
+  /** 工作目录，不传时使用用户主目录 */
+  cwd?: string;
+  env?: Record<string, string>;
+}
+
+/** 串口配置（`session_create_serial`） */
+export interface SerialConfig {
+  /** 设备路径，如 /dev/ttyUSB0、COM3 */
+  path: string;
+  /** 波特率（默认 115200） */
+  baudRate?: number;
+  /** 数据位 5 ~ 8（默认 8） */
+  dataBits?: number;
+  parity?: 'none' | 'odd' | 'even';
+  stopBits?: 'one' | 'two';
+  /** 流控：software 为 XON/XOFF，hardware 为 RTS/CTS */
+  flowControl?: 'none' | 'software' | 'hardware';
+}
+
+/** 可用的串口设备（`session_serial_ports` 返回） */
+export interface SerialPortEntry {
+  path: string;
+  description?: string;
+}
+
+/** 连接类型，按 type 字段区分 */
+export type ConnectionType =
+  | { type: 'ssh' }
+  | ({ type: 'local' } & LocalShellConfig)
+  | ({ type: 'serial' } & SerialConfig)
+  /** Telnet，使用 host/port 连接 */
+  | { type: 'telnet' }
+  /** 原始 TCP 套接字 */
+  | { type: 'rawTcp' };
+
+export interface ReconnectPolicy {
+  enabled: boolean;
+  /** 最大重试次数 */
+  maxAttempts: number;
+  /** 首次重试前的等待时间（毫秒） */
+  initialDelayMs: number;
+  /** 重试等待时间上限（毫秒） */
+  maxDelayMs: number;
+  /** 每次重试等待时间的增长倍数 */
+  multiplier: number;
+  /** 随机抖动比例（0 ~ 1） */
+  jitter: number;
+}
+
+/** 远程会话保持：执行 `tmux new -A -s <name>` 代替登录 shell */
+export interface PersistentShell {
+  enabled: boolean;
+  multiplexer?: 'tmux' | 'screen';
+  /** 远程会话名，不传时按连接 ID 生成 */
+  name?: string;
+}
+
+/** 终端输出事件（事件 `ssh-output-<connectionId>`） */
+export interface OutputChunk {
+  /** 本批数据的起始位置（连接累计输出字节数） */
+  offset: number;
+  data: number[];
+}
+
+/** 输出回放结果（`session_replay_output`） */
+export interface OutputReplay {
+  data: number[];
+  /** 回放数据的结束位置，作为下次回放的 since */
+  offset: number;
+  /** 请求位置之后的部分输出已被丢弃 */
+  truncated: boolean;
+}
+
+/** 端口转发类型：local（-L）、remote（-R）、dynamic（-D，SOCKS5） */
+export type PortForwardKind = 'local' | 'remote' | 'dynamic';
+
+export interface PortForwardRule {
+  id: string;
+  kind: PortForwardKind;
+  /** 监听地址（默认 127.0.0.1），remote 规则为服务器上的地址 */
+  bindHost?: string;
+  /** 监听端口，remote 规则为 0 时由服务器分配 */
+  bindPort: number;
+  /** 目标地址（dynamic 不需要） */
+  targetHost?: string;
+  targetPort?: number;
+  /** 连接时是否自动启动（默认 true） */
+  enabled?: boolean;
+}
+
+/** 端口转发运行状态（`session_port_forward_list` 返回） */
+export interface PortForwardStatus extends PortForwardRule {
+  active: boolean;
+  boundPort?: number;
+  bytesSent: number;
+  bytesReceived: number;
+  activeConnections: number;
+  lastError?: string;
+}
+
+/** 端口转发事件（事件 `ssh-port-forward-<connectionId>`） */
+export interface PortForwardEvent {
+  ruleId: string;
+  active: boolean;
+  error?: string;
+}
+
+export type SessionStatus = 'disconnected' | 'connecting' | 'connected' | 'reconnecting' | 'error';
+
+/** 会话结束原因 */
+export type DisconnectReason =
+  | 'userRequested'
+  | 'remoteExit'
+  | 'closedByServer'
+  | 'connectionLost'
+  | 'error';
+
+/** 会话结束信息 */
+export interface SessionEnd {
+  reason: DisconnectReason;
+  /** 远程进程退出码 */
+  exitCode?: number;
+  message?: string;
+}
+
+/** 后端返回的连接状态：Closed/Error 序列化为对象 */
+export type SessionStatusValue = SessionStatus | { closed: SessionEnd } | { error: string };
+
+/** 归类为界面显示用的状态：已结束视为断开，错误信息见 `error` 字段 */
+export function sessionStatusKind(status: SessionStatusValue): SessionStatus {
+  if (typeof status === 'string') {
+    return status;
+  }
+  return 'closed' in status ? 'disconnected' : 'error';
+}
+
+/** 主机密钥验证失败时后端返回的结构化错误（见 SSHError 的序列化），其他错误为字符串 */
+export interface HostKeyError {
+  kind: 'hostKeyUnknown' | 'hostKeyMismatch' | 'hostKeyRevoked';
+  /** 本地化的错误信息 */
+  message: string;
+  host: string;
+  port: number;
+  /** known_hosts 中保存的指纹（仅 hostKeyMismatch） */
+  expectedFingerprint?: string;
+  /** 服务器出示的密钥指纹 */
+  actualFingerprint: string;
+}
+
+export function isHostKeyError(error: unknown): error is HostKeyError {
+  return typeof error === 'object' && error !== null && 'kind' in error && 'actualFingerprint' in error;
+}
+
+/** 连接状态变更事件（事件 `ssh-status-<connectionId>`） */
+export interface SessionStatusEvent {
+  connectionId: string;
+  status: SessionStatusValue;
+  /** 当前重连次数（仅 reconnecting） */
+  attempt?: number;
+  maxAttempts?: number;
+  /** 距离下次重连的等待时间（毫秒） */
+  retryInMs?: number;
 }
 
-export type SessionStatus = 'disconnected' | 'connecting' | 'connected' | 'error';
+/** 连接生命周期事件（事件 `ssh-lifecycle-<connectionId>`） */
+export type LifecycleEvent = { connectionId: string } & (
+  | { stage: 'connecting' }
+  | { stage: 'authenticated'; host: string; port: number }
+  | { stage: 'shellReady' }
+  | { stage: 'reattached'; name: string }
+  | { stage: 'exitStatus'; exitCode: number }
+  | ({ stage: 'disconnected' } & SessionEnd)
+);
 
 export interface SessionInfo {
   id: string;
@@ -39,7 +241,7 @@ export interface SessionInfo {
   host: string;
   port: number;
   username: string;
-  status: SessionStatus;
+  status: SessionStatusValue;
   connectedAt?: string;
   error?: string;
   group: string;
@@ -47,4 +249,120 @@ export interface SessionInfo {
   connectionSessionId?: string;
   /** 如果是连接实例且已连接，这个字段存储实际的connectionId */
   connectionId?: string;
+  /** 使用的 OpenSSH 用户证书 */
+  certificate?: CertificateInfo;
+}
+
+/** OpenSSH 证书信息 */
+export interface CertificateInfo {
+  keyId: string;
+  serial: number;
+  /** 允许使用的用户名，为空表示不限制 */
+  principals: string[];
+  /** 签发 CA 的 SHA256 指纹 */
+  caFingerprint: string;
+  /** 生效时间（Unix 秒） */
+  validAfter: number;
+  /** 过期时间（Unix 秒），不传表示永不过期 */
+  validBefore?: number;
+  expired: boolean;
+}
+
+/** 键盘交互认证提示项 */
+export interface KeyboardInteractivePrompt {
+  prompt: string;
+  /** 为 false 时应作为密码输入（不回显） */
+  echo: boolean;
+}
+
+/**
+ * 键盘交互认证请求（事件 `ssh-keyboard-interactive-<connectionId>`）
+ * 通过 `session_keyboard_interactive_respond` 命令回复，responses 传 null 表示取消
+ */
+export interface KeyboardInteractiveRequest {
+  requestId: string;
+  connectionId: string;
+  name: string;
+  instructions: string;
+  prompts: KeyboardInteractivePrompt[];
+}
+
+/** 命令执行请求（`session_exec`） */
+export interface ExecRequest {
+  command: string;
+  /** 超时时间（毫秒），不传表示不限制 */
+  timeoutMs?: number;
+}
+
+/** 命令输出事件（事件 `ssh-exec-<execId>`），data 为原始字节 */
+export interface ExecOutputEvent {
+  execId: string;
+  stream: 'stdout' | 'stderr';
+  data: number[];
+}
+
+/** 命令执行结果 */
+export interface ExecResult {
+  /** 退出码，被信号终止或超时/取消时为空 */
+  exitCode?: number;
+  exitSignal?: string;
+  stdout: string;
+  stderr: string;
+  /** 输出过大被截断（完整输出可通过事件获取） */
+  truncated: boolean;
+  timedOut: boolean;
+  cancelled: boolean;
+}
+
+/** 导入计划中的一个会话 */
+export interface ImportCandidate {
+  /** 导入后使用的会话 ID，重复项为已有会话的 ID */
+  id: string;
+  /** 导入源中的名称（如 ssh_config 的 Host 别名） */
+  alias: string;
+  config: SessionConfig;
+  /** 与已有会话重复（主机、端口、用户名相同），导入时跳过 */
+  duplicateOf?: string;
+  /** 无法导入的设置 */
+  unsupported?: string[];
+}
+
+/** 导入源格式（`db_ssh_session_import_file`） */
+export type ImportSource = 'sshConfig' | 'putty' | 'mobaXterm' | 'winScp' | 'termius';
+
+/** 导入结果（`db_ssh_session_import_ssh_config` / `db_ssh_session_import_file`），dryRun 时只是预览 */
+export interface ImportPlan {
+  candidates: ImportCandidate[];
+  warnings: string[];
+  /** 实际写入的会话数，预览时为 0 */
+  imported: number;
+}
+
+/** 加密导出文件的导入结果（`db_ssh_session_import_bundle`），会话按 ID 合并 */
+export interface BundleImportResult {
+  created: number;
+  updated: number;
+  /** 新导入的托管密钥数 */
+  keys: number;
+  keybindings: boolean;
+  terminalConfig: boolean;
+  warnings: string[];
+}
+
+/** 密钥管理器可生成的密钥类型，RSA 默认 4096 位 */
+export type KeyType = 'ed25519' | 'ecdsaP256' | 'ecdsaP384' | 'ecdsaP521' | 'rsa';
+
+/** 密钥管理器中的密钥（`db_ssh_key_*`），不含私钥 */
+export interface SshKeyInfo {
+  id: string;
+  name: string;
+  /** ssh-ed25519、ecdsa-sha2-nistp256、ssh-rsa 等 */
+  algorithm: string;
+  /** SHA256 指纹 */
+  fingerprint: string;
+  /** authorized_keys 格式的公钥 */
+  publicKey: string;
+  hasPassphrase: boolean;
+  createdAt: number;
+  updatedAt: number;
 }
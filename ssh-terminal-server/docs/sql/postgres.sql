@@ -40,8 +40,22 @@ CREATE INDEX IF NOT EXISTS idx_ssh_sessions_group ON ssh_sessions(group_name);
 CREATE INDEX IF NOT EXISTS idx_ssh_sessions_deleted ON ssh_sessions(deleted_at);
 CREATE INDEX IF NOT EXISTS idx_ssh_sessions_server_ver ON ssh_sessions(server_ver);
 
+-- SSH 密钥表索引
+CREATE INDEX IF NOT EXISTS idx_ssh_keys_user_id ON ssh_keys(user_id);
+CREATE INDEX IF NOT EXISTS idx_ssh_keys_deleted ON ssh_keys(deleted_at);
+
 -- 邮件日志表索引
 CREATE INDEX IF NOT EXISTS idx_email_logs_user_id ON email_logs(user_id);
 CREATE INDEX IF NOT EXISTS idx_email_logs_email ON email_logs(email);
 CREATE INDEX IF NOT EXISTS idx_email_logs_status ON email_logs(status);
 CREATE INDEX IF NOT EXISTS idx_email_logs_created_at ON email_logs(created_at);
+
+-- ============================================
+-- 3. 字段迁移（旧版本数据库补充新增字段，已存在时忽略）
+-- ============================================
+
+-- SSH 会话跳板机链（JSON 数组）
+ALTER TABLE ssh_sessions ADD COLUMN IF NOT EXISTS jump_hosts TEXT;
+
+-- SSH 会话连接类型（JSON 对象，NULL 表示 SSH）
+ALTER TABLE ssh_sessions ADD COLUMN IF NOT EXISTS connection_type TEXT;
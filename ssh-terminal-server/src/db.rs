@@ -330,12 +330,13 @@ async fn create_tables(db: &DatabaseConnection) -> anyhow::Result<()> {
     let schema = Schema::new(builder);
 
     // 导入所有 entities
-    use crate::domain::entities::{users, user_profiles, ssh_sessions, email_logs};
+    use crate::domain::entities::{users, user_profiles, ssh_sessions, ssh_keys, email_logs};
 
     // 创建所有表（添加新表只需一行！）
     create_single_table(db, &schema, &builder, users::Entity, "用户表").await?;
     create_single_table(db, &schema, &builder, user_profiles::Entity, "用户资料表").await?;
     create_single_table(db, &schema, &builder, ssh_sessions::Entity, "SSH会话表").await?;
+    create_single_table(db, &schema, &builder, ssh_keys::Entity, "SSH密钥表").await?;
     create_single_table(db, &schema, &builder, email_logs::Entity, "邮件日志表").await?;
 
     tracing::info!("✅ 数据库表结构检查完成");
@@ -370,19 +371,22 @@ async fn execute_sql_script(
     backend: DbBackend,
     sql: &str,
 ) -> anyhow::Result<()> {
-    // 按分号分割SQL语句
-    let statements: Vec<&str> = sql
+    // 按分号分割SQL语句，并去掉语句前的注释行
+    let statements: Vec<String> = sql
         .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && !s.starts_with("--"))
+        .map(|s| {
+            s.lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .filter(|s| !s.is_empty())
         .collect();
 
     for statement in statements {
-        if statement.is_empty() {
-            continue;
-        }
-
-        match db.execute(Statement::from_string(backend, statement.to_string())).await {
+        match db.execute(Statement::from_string(backend, statement.clone())).await {
             Ok(_) => {
                 tracing::debug!("✅ SQL执行成功");
             }
@@ -22,6 +22,14 @@ pub struct CreateSshSessionRequest {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+
+    /// 跳板机链（会话 ID 列表）
+    #[serde(default)]
+    pub jump_hosts: Vec<String>,
+
+    /// 连接类型（为空表示 SSH）
+    #[serde(default)]
+    pub connection_type: Option<serde_json::Value>,
     
     #[validate(length(min = 1))]
     pub auth_method_encrypted: String,
@@ -43,6 +51,8 @@ pub struct UpdateSshSessionRequest {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    pub jump_hosts: Option<Vec<String>>,
+    pub connection_type: Option<serde_json::Value>,
     pub auth_method_encrypted: Option<String>,
     pub auth_nonce: Option<String>,
     pub auth_key_salt: Option<String>,
@@ -19,6 +19,14 @@ pub struct SyncRequest {
 
     /// 删除的会话 ID
     pub deleted_session_ids: Vec<String>,
+
+    /// 托管密钥更新（旧客户端不发送此字段）
+    #[serde(default)]
+    pub ssh_keys: Vec<SshKeyPushItem>,
+
+    /// 删除的密钥 ID（旧客户端不发送此字段）
+    #[serde(default)]
+    pub deleted_key_ids: Vec<String>,
 }
 
 /// SSH 会话推送项
@@ -33,6 +41,12 @@ pub struct SshSessionPushItem {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    /// 跳板机链（会话 ID 列表，旧客户端不发送此字段）
+    #[serde(default)]
+    pub jump_hosts: Vec<String>,
+    /// 连接类型（为空表示 SSH，旧客户端不发送此字段）
+    #[serde(default)]
+    pub connection_type: Option<serde_json::Value>,
     pub auth_method_encrypted: String,
     pub auth_nonce: String,
     pub auth_key_salt: Option<String>,
@@ -41,6 +55,23 @@ pub struct SshSessionPushItem {
     pub updated_at: i64,
 }
 
+/// 托管密钥推送项
+///
+/// 私钥由客户端使用用户的 device_id 加密，服务器原样保存
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SshKeyPushItem {
+    pub id: String,
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    pub has_passphrase: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
 /// 更新用户资料请求
 #[derive(Debug, Deserialize, Validate, Clone)]
 pub struct UpdateProfileRequest {
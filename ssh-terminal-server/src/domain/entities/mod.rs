@@ -1,5 +1,6 @@
 pub mod users;
 pub mod user_profiles;
 pub mod ssh_sessions;
+pub mod ssh_keys;
 pub mod email_logs;
 
@@ -0,0 +1,67 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+
+/// 托管 SSH 密钥实体
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ssh_keys")]
+#[serde(rename_all = "camelCase")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    pub user_id: String,
+
+    // 密钥基本信息
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    #[sea_orm(column_type = "Text")]
+    pub public_key: String,
+
+    // 私钥（客户端使用用户的 device_id 加密后上传，服务器不解密）
+    #[sea_orm(column_type = "Text")]
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    pub has_passphrase: bool,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+
+    // 软删除（保留记录以便其他设备拉取删除）
+    pub deleted_at: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut this = self;
+        let now = chrono::Utc::now().timestamp();
+
+        if insert {
+            this.created_at = Set(now);
+        }
+        this.updated_at = Set(now);
+
+        Ok(this)
+    }
+}
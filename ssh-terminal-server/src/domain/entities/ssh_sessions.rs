@@ -21,6 +21,15 @@ pub struct Model {
     pub columns: Option<u16>,
     pub rows: Option<u16>,
 
+    // 跳板机链（JSON 数组，元素为同一用户的其他会话 ID，按跳转顺序排列）
+    #[sea_orm(column_type = "Text", nullable)]
+    pub jump_hosts: Option<String>,
+
+    // 连接类型（JSON 对象，type 字段区分 telnet/rawTcp/serial/local，NULL 表示 SSH）
+    // 服务器不解析具体内容，原样返回给客户端
+    #[sea_orm(column_type = "Text", nullable)]
+    pub connection_type: Option<String>,
+
     // 认证信息（加密存储）
     // 客户端使用用户密码加密后上传，服务器使用服务器密钥二次加密
     pub auth_method_encrypted: String,
@@ -38,6 +47,42 @@ pub struct Model {
     pub deleted_at: Option<i64>,
 }
 
+impl Model {
+    /// 解析跳板机链
+    pub fn jump_host_ids(&self) -> Vec<String> {
+        self.jump_hosts
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// 解析连接类型（SSH 会话返回 None）
+    pub fn connection_type_value(&self) -> Option<serde_json::Value> {
+        self.connection_type
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+}
+
+/// 将跳板机链编码为 JSON（空链存储为 NULL）
+pub fn encode_jump_hosts(jump_hosts: &[String]) -> Option<String> {
+    if jump_hosts.is_empty() {
+        None
+    } else {
+        serde_json::to_string(jump_hosts).ok()
+    }
+}
+
+/// 将连接类型编码为 JSON（SSH 存储为 NULL）
+pub fn encode_connection_type(connection_type: Option<&serde_json::Value>) -> Option<String> {
+    match connection_type {
+        Some(value) if value.is_object() && value.get("type").and_then(|t| t.as_str()) != Some("ssh") => {
+            serde_json::to_string(value).ok()
+        }
+        _ => None,
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
@@ -12,6 +12,8 @@ pub struct SshSessionVO {
     pub terminal_type: Option<String>,
     pub columns: Option<u16>,
     pub rows: Option<u16>,
+    pub jump_hosts: Vec<String>,
+    pub connection_type: Option<serde_json::Value>,
     pub auth_method_encrypted: String,
     pub auth_nonce: String,
     pub auth_key_salt: Option<String>,
@@ -23,6 +25,21 @@ pub struct SshSessionVO {
     pub deleted_at: Option<i64>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct SshKeyVO {
+    pub id: String,
+    pub name: String,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub public_key: String,
+    pub private_key_encrypted: String,
+    pub private_key_nonce: String,
+    pub has_passphrase: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub deleted_at: Option<i64>,
+}
+
 /// 分页响应
 #[derive(Debug, Serialize)]
 pub struct PaginatedSshSessions {
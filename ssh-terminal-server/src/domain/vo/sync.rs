@@ -16,6 +16,9 @@ pub struct SyncResponse {
     /// 成功删除的会话 ID（客户端请求删除的）
     pub deleted_session_ids: Vec<String>,
 
+    /// 成功保存的密钥 ID（包括删除）
+    pub updated_key_ids: Vec<String>,
+
     /// 服务器版本号映射（id -> server_ver）
     pub server_versions: std::collections::HashMap<String, i32>,
 
@@ -26,6 +29,9 @@ pub struct SyncResponse {
     /// SSH 会话列表（从服务器拉取的新数据）
     pub ssh_sessions: Vec<super::ssh::SshSessionVO>,
 
+    /// 托管密钥列表（包含已删除的密钥，deleted_at 不为空）
+    pub ssh_keys: Vec<super::ssh::SshKeyVO>,
+
     /// === 冲突信息 ===
     /// 需要解决的冲突
     pub conflicts: Vec<ConflictInfo>,
@@ -34,6 +34,8 @@ pub async fn create_session_handler(
         terminal_type: request.terminal_type,
         columns: request.columns,
         rows: request.rows,
+        jump_hosts: ssh_sessions::encode_jump_hosts(&request.jump_hosts),
+        connection_type: ssh_sessions::encode_connection_type(request.connection_type.as_ref()),
         auth_method_encrypted: request.auth_method_encrypted,
         auth_nonce: request.auth_nonce,
         auth_key_salt: request.auth_key_salt,
@@ -146,6 +148,8 @@ pub async fn update_session_handler(
     updated.terminal_type = request.terminal_type.or(existing.terminal_type);
     updated.columns = request.columns.or(existing.columns);
     updated.rows = request.rows.or(existing.rows);
+    if let Some(jump_hosts) = request.jump_hosts { updated.jump_hosts = ssh_sessions::encode_jump_hosts(&jump_hosts); }
+    if let Some(connection_type) = request.connection_type { updated.connection_type = ssh_sessions::encode_connection_type(Some(&connection_type)); }
     updated.auth_method_encrypted = request.auth_method_encrypted.unwrap_or(existing.auth_method_encrypted);
     updated.auth_nonce = request.auth_nonce.unwrap_or(existing.auth_nonce);
     updated.auth_key_salt = request.auth_key_salt.or(existing.auth_key_salt);
@@ -185,6 +189,8 @@ pub async fn delete_session_handler(
 
 /// 将 Model 转换为 VO
 fn session_to_vo(session: ssh_sessions::Model) -> SshSessionVO {
+    let jump_hosts = session.jump_host_ids();
+    let connection_type = session.connection_type_value();
     SshSessionVO {
         id: session.id,
         user_id: session.user_id,
@@ -196,6 +202,8 @@ fn session_to_vo(session: ssh_sessions::Model) -> SshSessionVO {
         terminal_type: session.terminal_type,
         columns: session.columns,
         rows: session.rows,
+        jump_hosts,
+        connection_type,
         auth_method_encrypted: session.auth_method_encrypted,
         auth_nonce: session.auth_nonce,
         auth_key_salt: session.auth_key_salt,
@@ -1,5 +1,6 @@
 pub mod user_repository;
 pub mod user_profile_repository;
 pub mod ssh_session_repository;
+pub mod ssh_key_repository;
 pub mod email_log_repository;
 
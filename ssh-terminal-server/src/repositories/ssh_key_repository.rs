@@ -0,0 +1,90 @@
+use anyhow::Result;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, QueryOrder};
+use crate::domain::entities::ssh_keys::{self, Entity as SshKey};
+
+pub struct SshKeyRepository {
+    db: DatabaseConnection,
+}
+
+impl SshKeyRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 根据 user_id 查找所有密钥
+    pub async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<ssh_keys::Model>> {
+        let keys = SshKey::find()
+            .filter(ssh_keys::Column::UserId.eq(user_id))
+            .filter(ssh_keys::Column::DeletedAt.is_null())
+            .order_by_asc(ssh_keys::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(keys)
+    }
+
+    /// 根据 user_id 查找指定时间之后更新的密钥（增量查询，包含已删除的密钥）
+    pub async fn find_by_user_id_updated_after(&self, user_id: &str, after: i64) -> Result<Vec<ssh_keys::Model>> {
+        let keys = SshKey::find()
+            .filter(ssh_keys::Column::UserId.eq(user_id))
+            .filter(ssh_keys::Column::UpdatedAt.gt(after))
+            .order_by_asc(ssh_keys::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(keys)
+    }
+
+    /// 根据 ID 查找密钥（包含已删除的密钥）
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<ssh_keys::Model>> {
+        let key = SshKey::find_by_id(id.to_string())
+            .one(&self.db)
+            .await?;
+
+        Ok(key)
+    }
+
+    /// 创建或覆盖密钥，已删除的密钥会被恢复
+    /// 注意：由于 id 是 TEXT 类型，新建时使用 Entity::insert() 而非 ActiveModel::insert()
+    pub async fn upsert(&self, key: ssh_keys::Model) -> Result<()> {
+        let exists = self.find_by_id(&key.id).await?.is_some();
+
+        let active_model = ssh_keys::ActiveModel {
+            id: sea_orm::Set(key.id),
+            user_id: sea_orm::Set(key.user_id),
+            name: sea_orm::Set(key.name),
+            algorithm: sea_orm::Set(key.algorithm),
+            fingerprint: sea_orm::Set(key.fingerprint),
+            public_key: sea_orm::Set(key.public_key),
+            private_key_encrypted: sea_orm::Set(key.private_key_encrypted),
+            private_key_nonce: sea_orm::Set(key.private_key_nonce),
+            has_passphrase: sea_orm::Set(key.has_passphrase),
+            created_at: sea_orm::Set(key.created_at),
+            // 使用调用方设置的同步时间（Entity::insert/update 不会触发 ActiveModelBehavior）
+            updated_at: sea_orm::Set(key.updated_at),
+            deleted_at: sea_orm::Set(None),
+        };
+
+        if exists {
+            SshKey::update(active_model).exec(&self.db).await?;
+        } else {
+            SshKey::insert(active_model).exec(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 软删除密钥（使用指定时间戳）
+    pub async fn soft_delete_with_time(&self, id: &str, delete_time: i64) -> Result<()> {
+        let Some(existing) = self.find_by_id(id).await? else {
+            return Ok(());
+        };
+
+        let mut active_model: ssh_keys::ActiveModel = existing.into();
+        active_model.updated_at = sea_orm::Set(delete_time);
+        active_model.deleted_at = sea_orm::Set(Some(delete_time));
+        SshKey::update(active_model).exec(&self.db).await?;
+
+        Ok(())
+    }
+}
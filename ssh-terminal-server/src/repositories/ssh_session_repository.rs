@@ -65,6 +65,8 @@ impl SshSessionRepository {
             terminal_type: sea_orm::Set(session.terminal_type),
             columns: sea_orm::Set(session.columns),
             rows: sea_orm::Set(session.rows),
+            jump_hosts: sea_orm::Set(session.jump_hosts),
+            connection_type: sea_orm::Set(session.connection_type),
             auth_method_encrypted: sea_orm::Set(session.auth_method_encrypted),
             auth_nonce: sea_orm::Set(session.auth_nonce),
             auth_key_salt: sea_orm::Set(session.auth_key_salt),
@@ -111,6 +113,8 @@ impl SshSessionRepository {
             terminal_type: sea_orm::Set(session.terminal_type),
             columns: sea_orm::Set(session.columns),
             rows: sea_orm::Set(session.rows),
+            jump_hosts: sea_orm::Set(session.jump_hosts),
+            connection_type: sea_orm::Set(session.connection_type),
             auth_method_encrypted: sea_orm::Set(session.auth_method_encrypted),
             auth_nonce: sea_orm::Set(session.auth_nonce),
             auth_key_salt: sea_orm::Set(session.auth_key_salt),
@@ -144,6 +148,8 @@ impl SshSessionRepository {
             terminal_type: sea_orm::Set(existing.terminal_type),
             columns: sea_orm::Set(existing.columns),
             rows: sea_orm::Set(existing.rows),
+            jump_hosts: sea_orm::Set(existing.jump_hosts),
+            connection_type: sea_orm::Set(existing.connection_type),
             auth_method_encrypted: sea_orm::Set(existing.auth_method_encrypted),
             auth_nonce: sea_orm::Set(existing.auth_nonce),
             auth_key_salt: sea_orm::Set(existing.auth_key_salt),
@@ -191,6 +197,8 @@ impl SshSessionRepository {
             terminal_type: sea_orm::Set(existing.terminal_type),
             columns: sea_orm::Set(existing.columns),
             rows: sea_orm::Set(existing.rows),
+            jump_hosts: sea_orm::Set(existing.jump_hosts),
+            connection_type: sea_orm::Set(existing.connection_type),
             auth_method_encrypted: sea_orm::Set(existing.auth_method_encrypted),
             auth_nonce: sea_orm::Set(existing.auth_nonce),
             auth_key_salt: sea_orm::Set(existing.auth_key_salt),
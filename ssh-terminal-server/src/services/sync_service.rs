@@ -2,6 +2,7 @@ use anyhow::Result;
 use sea_orm::DatabaseConnection;
 use crate::domain::dto::sync::*;
 use crate::domain::vo::sync::*;
+use crate::repositories::ssh_key_repository::SshKeyRepository;
 use crate::repositories::ssh_session_repository::SshSessionRepository;
 use crate::repositories::user_profile_repository::UserProfileRepository;
 use crate::repositories::user_repository::UserRepository;
@@ -35,6 +36,7 @@ impl SyncService {
 
         let ssh_repo = SshSessionRepository::new(self.db.clone());
         let profile_repo = UserProfileRepository::new(self.db.clone());
+        let key_repo = SshKeyRepository::new(self.db.clone());
 
         // === 统一的服务器时间 ===
         let server_time = Utc::now().timestamp();
@@ -169,6 +171,8 @@ impl SyncService {
                             terminal_type: session_item.terminal_type.clone(),
                             columns: session_item.columns,
                             rows: session_item.rows,
+                            jump_hosts: crate::domain::entities::ssh_sessions::encode_jump_hosts(&session_item.jump_hosts),
+                            connection_type: crate::domain::entities::ssh_sessions::encode_connection_type(session_item.connection_type.as_ref()),
                             auth_method_encrypted: session_item.auth_method_encrypted.clone(),
                             auth_nonce: session_item.auth_nonce.clone(),
                             auth_key_salt: session_item.auth_key_salt.clone(),
@@ -204,6 +208,8 @@ impl SyncService {
                         terminal_type: session_item.terminal_type.clone(),
                         columns: session_item.columns,
                         rows: session_item.rows,
+                        jump_hosts: crate::domain::entities::ssh_sessions::encode_jump_hosts(&session_item.jump_hosts),
+                        connection_type: crate::domain::entities::ssh_sessions::encode_connection_type(session_item.connection_type.as_ref()),
                         auth_method_encrypted: session_item.auth_method_encrypted.clone(),
                         auth_nonce: session_item.auth_nonce.clone(),
                         auth_key_salt: session_item.auth_key_salt.clone(),
@@ -243,6 +249,55 @@ impl SyncService {
             }
         }
 
+        // 4. 处理托管密钥（密钥内容不可变，名称和密码按最后写入生效）
+        let mut updated_key_ids = Vec::new();
+        for key_item in &request.ssh_keys {
+            match key_repo.find_by_id(&key_item.id).await {
+                Ok(Some(existing)) if existing.user_id != user_id => {
+                    tracing::warn!("Skipping SSH key {} owned by another user", key_item.id);
+                }
+                Ok(existing) => {
+                    let key = crate::domain::entities::ssh_keys::Model {
+                        id: key_item.id.clone(),
+                        user_id: user_id.to_string(),
+                        name: key_item.name.clone(),
+                        algorithm: key_item.algorithm.clone(),
+                        fingerprint: key_item.fingerprint.clone(),
+                        public_key: key_item.public_key.clone(),
+                        private_key_encrypted: key_item.private_key_encrypted.clone(),
+                        private_key_nonce: key_item.private_key_nonce.clone(),
+                        has_passphrase: key_item.has_passphrase,
+                        created_at: existing.map_or(key_item.created_at, |k| k.created_at),
+                        updated_at: last_sync_at,
+                        deleted_at: None,
+                    };
+
+                    match key_repo.upsert(key).await {
+                        Ok(()) => updated_key_ids.push(key_item.id.clone()),
+                        Err(e) => tracing::error!("Failed to save SSH key {}: {}", key_item.id, e),
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to find SSH key {}: {}", key_item.id, e);
+                }
+            }
+        }
+
+        for key_id in &request.deleted_key_ids {
+            match key_repo.find_by_id(key_id).await {
+                Ok(Some(existing)) if existing.user_id != user_id => {
+                    tracing::warn!("Skipping deletion of SSH key {} owned by another user", key_id);
+                }
+                Ok(_) => match key_repo.soft_delete_with_time(key_id, last_sync_at).await {
+                    Ok(()) => updated_key_ids.push(key_id.clone()),
+                    Err(e) => tracing::error!("Failed to delete SSH key {}: {}", key_id, e),
+                },
+                Err(e) => {
+                    tracing::error!("Failed to find SSH key {}: {}", key_id, e);
+                }
+            }
+        }
+
         // === 第二阶段：Pull - 拉取最新的服务器数据 ===
         // 增量拉取 SSH 会话：
         // - 如果有 last_sync_at，只返回该时间之后更新的会话
@@ -273,6 +328,15 @@ impl SyncService {
                 .collect()
         };
 
+        // 拉取托管密钥：增量同步包含已删除的密钥，让其他设备同步删除
+        let ssh_keys_vo = match request.last_sync_at {
+            Some(last_sync) => key_repo.find_by_user_id_updated_after(user_id, last_sync).await?,
+            None => key_repo.find_by_user_id(user_id).await?,
+        }
+        .into_iter()
+        .map(|k| self.key_to_vo(k))
+        .collect();
+
         // 增量拉取用户资料：
         // - 如果有 last_sync_at，只返回该时间之后更新的资料
         // - 首次同步（last_sync_at 为 None）返回所有资料
@@ -341,9 +405,11 @@ impl SyncService {
             last_sync_at,
             updated_session_ids,
             deleted_session_ids,
+            updated_key_ids,
             server_versions,
             user_profile: user_profile_vo,
             ssh_sessions: ssh_sessions_vo,
+            ssh_keys: ssh_keys_vo,
             conflicts,
             message,
         })
@@ -471,6 +537,8 @@ impl SyncService {
 
     /// 将 SSH Session Model 转换为 VO
     fn session_to_vo(&self, session: crate::domain::entities::ssh_sessions::Model) -> crate::domain::vo::ssh::SshSessionVO {
+        let jump_hosts = session.jump_host_ids();
+        let connection_type = session.connection_type_value();
         crate::domain::vo::ssh::SshSessionVO {
             id: session.id,
             user_id: session.user_id,
@@ -482,6 +550,8 @@ impl SyncService {
             terminal_type: session.terminal_type,
             columns: session.columns,
             rows: session.rows,
+            jump_hosts,
+            connection_type,
             auth_method_encrypted: session.auth_method_encrypted,
             auth_nonce: session.auth_nonce,
             auth_key_salt: session.auth_key_salt,
@@ -494,6 +564,23 @@ impl SyncService {
         }
     }
 
+    /// 将 SSH Key Model 转换为 VO
+    fn key_to_vo(&self, key: crate::domain::entities::ssh_keys::Model) -> crate::domain::vo::ssh::SshKeyVO {
+        crate::domain::vo::ssh::SshKeyVO {
+            id: key.id,
+            name: key.name,
+            algorithm: key.algorithm,
+            fingerprint: key.fingerprint,
+            public_key: key.public_key,
+            private_key_encrypted: key.private_key_encrypted,
+            private_key_nonce: key.private_key_nonce,
+            has_passphrase: key.has_passphrase,
+            created_at: key.created_at,
+            updated_at: key.updated_at,
+            deleted_at: key.deleted_at,
+        }
+    }
+
     /// 将 User Profile Model 转换为 VO
     fn profile_to_vo(&self, profile: crate::domain::entities::user_profiles::Model, email: String) -> crate::domain::vo::user::UserProfileVO {
         crate::domain::vo::user::UserProfileVO {